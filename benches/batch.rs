@@ -0,0 +1,45 @@
+//! compares reading the calendar fields of each DateTime in a log-like
+//! series of timestamps with converting them through a batch::Converter
+//!
+//! run with `cargo bench --bench batch`
+use datetime::batch::Converter;
+use datetime::{CivilTime, DateTime};
+use std::hint::black_box;
+use std::time::Instant;
+
+const SAMPLES: u64 = 10_000_000;
+
+fn bench<F: FnMut(&DateTime) -> CivilTime>(name: &str, dates: &[DateTime], mut f: F) {
+    let start = Instant::now();
+    for date in dates {
+        black_box(f(date));
+    }
+    let elapsed = start.elapsed();
+
+    let per_item = elapsed / dates.len() as u32;
+    println!("{:<40} {:>10?} total, {:>8?} each", name, elapsed, per_item);
+}
+
+fn main() {
+    // a busy log: a few lines a second, so about four months of them
+    let secs: Vec<u64> = (0..SAMPLES).map(|i| 1_700_000_000 + i).collect();
+
+    let start = Instant::now();
+    let dates = DateTime::from_secs_slice(&secs);
+    println!("{:<40} {:>10?} total", "from_secs_slice", start.elapsed());
+
+    bench("CivilTime::from, every line", &dates, |date| {
+        CivilTime::from(date)
+    });
+
+    let mut converter = Converter::new();
+    bench("Converter::to_civil, every line", &dates, |date| {
+        converter.to_civil(date)
+    });
+
+    // make sure the two agree before trusting the numbers
+    let mut converter = Converter::new();
+    for date in dates.iter().step_by(997) {
+        assert_eq!(converter.to_civil(date), CivilTime::from(date));
+    }
+}
@@ -0,0 +1,83 @@
+//! compares decomposing timestamps into calendar fields with the
+//! constant-time algorithm used by DateTime against the year-by-year loop
+//! it replaced
+//!
+//! run with `cargo bench --bench decompose`
+use datetime::DateTime;
+use std::hint::black_box;
+use std::time::Instant;
+
+const SAMPLES: i64 = 1_000_000;
+
+fn is_leap_year(year: usize) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+// the old implementation: walks forward a year, then a month, at a time
+fn loop_year_month_date(secs: usize) -> (usize, usize, usize) {
+    let mut days = secs / 86_400;
+
+    let mut year = 1970;
+    loop {
+        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
+        if days < days_in_year {
+            break;
+        }
+        days -= days_in_year;
+        year += 1;
+    }
+
+    let mut month = 1;
+    loop {
+        let days_in_month = match month {
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        if days < days_in_month {
+            break;
+        }
+        days -= days_in_month;
+        month += 1;
+    }
+
+    (year, month, days + 1)
+}
+
+fn bench<F: FnMut(i64)>(name: &str, step: i64, mut f: F) {
+    let start = Instant::now();
+    for i in 0..SAMPLES {
+        f(i * step);
+    }
+    let elapsed = start.elapsed();
+
+    let per_item = elapsed / SAMPLES as u32;
+    println!("{:<40} {:>10?} total, {:>8?} each", name, elapsed, per_item);
+}
+
+fn main() {
+    // one sample every ~15 minutes from 1970 covers about 30 years; one
+    // every ~2.6 days reaches about 9000 years out
+    for &(span, step) in &[("1970-2000", 947), ("1970-9000", 221_471)] {
+        bench(
+            &format!("DateTime construction + fields, {}", span),
+            step,
+            |secs| {
+                let date = DateTime::from_signed_secs(secs);
+                black_box((date.year(), date.month(), date.date()));
+            },
+        );
+
+        bench(&format!("year-by-year loop, {}", span), step, |secs| {
+            black_box(loop_year_month_date(secs as usize));
+        });
+    }
+
+    // make sure the two agree before trusting the numbers
+    for secs in (0..SAMPLES).map(|i| i * 221_471) {
+        let date = DateTime::from_signed_secs(secs);
+        let fields = (date.year(), date.month() as usize + 1, date.date());
+        assert_eq!(fields, loop_year_month_date(secs as usize));
+    }
+}
@@ -0,0 +1,358 @@
+//! changing a single calendar field of a DateTime
+//!
+//! each `with_*` method returns a DateTime with one field replaced and the
+//! rest kept as they were, as read in the DateTime's offset. they return
+//! None if the result doesn't exist, such as February 30, or is outside
+//! the range a DateTime can represent
+//! ```
+//! # use datetime::{DateTime, Month};
+//! // 2000-02-29 12:00
+//! let date = DateTime::from_secs(951825600);
+//!
+//! assert_eq!(date.with_hour(0).unwrap().as_time_stamp(), "Tue Feb 29, 2000  0:00:00 (UTC)");
+//! assert_eq!(date.with_month(Month::March).unwrap().date(), 29);
+//! // there was no February 29 in 2001
+//! assert!(date.with_year(2001).is_none());
+//! ```
+use crate::convert::{days_from_civil, days_in_month, is_leap_year};
+use crate::{DateTime, Month, MAX_SECS, MIN_SECS};
+use core::convert::TryFrom;
+
+/// where [`DateTime::checked_add_years`] moves February 29 to in a year
+/// that doesn't have one
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LeapDay {
+    /// to February 28, staying in the same month
+    Clamp,
+    /// to March 1, the day after February 28
+    RollOver,
+}
+
+// the calendar fields of a DateTime, with the month as 1-12
+struct Fields {
+    year: usize,
+    month: usize,
+    date: usize,
+    hour: usize,
+    minute: usize,
+    second: usize,
+    nanos: u32,
+}
+
+impl DateTime {
+    fn editable_fields(&self) -> Fields {
+        Fields {
+            year: self.year(),
+            month: self.month() as usize + 1,
+            date: self.date(),
+            hour: self.hour(),
+            minute: self.minute(),
+            second: self.second(),
+            nanos: self.nanos,
+        }
+    }
+
+    // the DateTime with the given fields in self's offset, or None if they
+    // don't make a valid time in range
+    fn with_fields(&self, fields: Fields) -> Option<DateTime> {
+        let Fields {
+            year,
+            month,
+            date,
+            hour,
+            minute,
+            second,
+            nanos,
+        } = fields;
+        if year < 1 || year > DateTime::MAX.year() || date < 1 || date > days_in_month(year, month)
+        {
+            return None;
+        }
+        if hour > 23 || minute > 59 || second > 59 || nanos >= 1_000_000_000 {
+            return None;
+        }
+
+        let days = days_from_civil(i64::try_from(year).ok()?, month, date);
+        let secs = days
+            .checked_mul(86_400)?
+            .checked_add((hour * 3600 + minute * 60 + second) as i64)?
+            .checked_sub(self.offset.seconds() as i64)?;
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(secs, nanos, self.offset))
+        } else {
+            None
+        }
+    }
+
+    /// returns the DateTime with its year replaced
+    pub fn with_year(&self, year: usize) -> Option<DateTime> {
+        self.with_fields(Fields {
+            year,
+            ..self.editable_fields()
+        })
+    }
+
+    /// returns the DateTime with its month replaced
+    pub fn with_month(&self, month: Month) -> Option<DateTime> {
+        self.with_fields(Fields {
+            month: month as usize + 1,
+            ..self.editable_fields()
+        })
+    }
+
+    /// returns the DateTime with its day of the month replaced
+    pub fn with_date(&self, date: usize) -> Option<DateTime> {
+        self.with_fields(Fields {
+            date,
+            ..self.editable_fields()
+        })
+    }
+
+    /// returns the DateTime with its hour replaced
+    pub fn with_hour(&self, hour: usize) -> Option<DateTime> {
+        self.with_fields(Fields {
+            hour,
+            ..self.editable_fields()
+        })
+    }
+
+    /// returns the DateTime with its minute replaced
+    pub fn with_minute(&self, minute: usize) -> Option<DateTime> {
+        self.with_fields(Fields {
+            minute,
+            ..self.editable_fields()
+        })
+    }
+
+    /// returns the DateTime with its second replaced
+    pub fn with_second(&self, second: usize) -> Option<DateTime> {
+        self.with_fields(Fields {
+            second,
+            ..self.editable_fields()
+        })
+    }
+
+    /// returns the DateTime the given number of calendar months later (or
+    /// earlier, if negative), keeping the time of day and the day of the
+    /// month, or None if that is out of range
+    ///
+    /// if the new month is too short for the day, the result is on its last
+    /// day instead, so January 31 plus one month is February 28 (or 29)
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc3339("2024-01-31T09:30:00Z").unwrap();
+    ///
+    /// assert_eq!(date.checked_add_months(1).unwrap().to_rfc3339(), "2024-02-29T09:30:00Z");
+    /// assert_eq!(date.checked_add_months(2).unwrap().to_rfc3339(), "2024-03-31T09:30:00Z");
+    /// assert_eq!(date.checked_add_months(-2).unwrap().to_rfc3339(), "2023-11-30T09:30:00Z");
+    /// ```
+    pub fn checked_add_months(&self, months: i64) -> Option<DateTime> {
+        let fields = self.editable_fields();
+        let months = (fields.year as i64 * 12 + fields.month as i64 - 1).checked_add(months)?;
+        let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as usize + 1);
+        // well past MAX_SECS, and small enough not to overflow in
+        // with_fields
+        if !(1..=1 << 40).contains(&year) {
+            return None;
+        }
+
+        let year = year as usize;
+        self.with_fields(Fields {
+            year,
+            month,
+            date: fields.date.min(days_in_month(year, month)),
+            ..fields
+        })
+    }
+
+    /// returns the DateTime the given number of calendar years later (or
+    /// earlier, if negative), keeping the time of day, month and day of
+    /// the month, or None if that is out of range
+    ///
+    /// February 29 moved to a year without one becomes February 28 or March
+    /// 1 as `leap_day` says, so anniversaries and expiry dates land where
+    /// they should
+    /// ```
+    /// # use datetime::adjust::LeapDay;
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc3339("2024-02-29T12:00:00Z").unwrap();
+    ///
+    /// let clamped = date.checked_add_years(1, LeapDay::Clamp).unwrap();
+    /// assert_eq!(clamped.to_rfc3339(), "2025-02-28T12:00:00Z");
+    /// let rolled = date.checked_add_years(1, LeapDay::RollOver).unwrap();
+    /// assert_eq!(rolled.to_rfc3339(), "2025-03-01T12:00:00Z");
+    /// let leap = date.checked_add_years(4, LeapDay::RollOver).unwrap();
+    /// assert_eq!(leap.to_rfc3339(), "2028-02-29T12:00:00Z");
+    /// ```
+    pub fn checked_add_years(&self, years: i64, leap_day: LeapDay) -> Option<DateTime> {
+        let fields = self.editable_fields();
+        let year = (fields.year as i64).checked_add(years)?;
+        // well past MAX_SECS, and small enough not to overflow in
+        // with_fields
+        if !(1..=1 << 40).contains(&year) {
+            return None;
+        }
+
+        let year = year as usize;
+        let (month, date) = match (fields.month, fields.date, leap_day) {
+            (2, 29, LeapDay::Clamp) if !is_leap_year(year) => (2, 28),
+            (2, 29, LeapDay::RollOver) if !is_leap_year(year) => (3, 1),
+            (month, date, _) => (month, date),
+        };
+        self.with_fields(Fields {
+            year,
+            month,
+            date,
+            ..fields
+        })
+    }
+
+    /// returns the DateTime the given number of calendar years earlier, as
+    /// [`checked_add_years`](DateTime::checked_add_years) does
+    pub fn checked_sub_years(&self, years: i64, leap_day: LeapDay) -> Option<DateTime> {
+        self.checked_add_years(years.checked_neg()?, leap_day)
+    }
+
+    /// returns the DateTime the given number of calendar months earlier,
+    /// as [`checked_add_months`](DateTime::checked_add_months) does
+    pub fn checked_sub_months(&self, months: i64) -> Option<DateTime> {
+        self.checked_add_months(months.checked_neg()?)
+    }
+
+    /// returns the DateTime with the fraction of its second replaced, or
+    /// None if `nanos` is not less than a second
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624).with_nanosecond(500_000_000).unwrap();
+    ///
+    /// assert_eq!(date.millisecond(), 500);
+    /// ```
+    pub fn with_nanosecond(&self, nanos: u32) -> Option<DateTime> {
+        self.with_fields(Fields {
+            nanos,
+            ..self.editable_fields()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LeapDay;
+    use crate::{DateTime, FixedOffset, Month, MAX_SECS};
+
+    #[test]
+    fn test_with_fields() {
+        // 1996-09-09 11:23:44.123 at -04:00
+        let offset = FixedOffset::west(4 * 3600).unwrap();
+        let date = DateTime::from_millis(842282624123).with_offset(offset);
+
+        let changed = date
+            .with_year(2024)
+            .and_then(|date| date.with_month(Month::February))
+            .and_then(|date| date.with_date(29))
+            .and_then(|date| date.with_minute(0))
+            .and_then(|date| date.with_second(0))
+            .unwrap();
+        assert_eq!(
+            changed.as_time_stamp_with_fraction(3),
+            "Thu Feb 29, 2024  11:00:00.123 (UTC-04:00)"
+        );
+        assert_eq!(changed.offset(), offset);
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let date = DateTime::from_secs(0);
+
+        assert!(date
+            .with_month(Month::February)
+            .unwrap()
+            .with_date(30)
+            .is_none());
+        assert!(date.with_date(0).is_none());
+        assert!(date.with_hour(24).is_none());
+        assert!(date.with_nanosecond(1_000_000_000).is_none());
+        assert!(date.with_year(0).is_none());
+
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert!(date.with_year(last.year() + 1).is_none());
+        #[cfg(target_pointer_width = "64")]
+        assert!(date.with_year(1 << 62).is_none());
+        assert!(date.with_year(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn test_month_arithmetic() {
+        // 2000-01-31 23:30 at +02:00
+        let offset = FixedOffset::east(2 * 3600).unwrap();
+        let date = DateTime::from_secs(949354200).with_offset(offset);
+        assert_eq!(date.date(), 31);
+
+        let stamp = |date: Option<DateTime>| date.map(|date| date.as_time_stamp());
+        assert_eq!(
+            stamp(date.checked_add_months(1)),
+            Some("Tue Feb 29, 2000  23:30:00 (UTC+02:00)".into())
+        );
+        assert_eq!(
+            stamp(date.checked_add_months(13)),
+            Some("Wed Feb 28, 2001  23:30:00 (UTC+02:00)".into())
+        );
+        assert_eq!(
+            stamp(date.checked_sub_months(2)),
+            Some("Tue Nov 30, 1999  23:30:00 (UTC+02:00)".into())
+        );
+        assert_eq!(date.checked_add_months(0), Some(date));
+
+        assert!(date.checked_sub_months(2000 * 12).is_none());
+        assert!(date.checked_add_months(i64::MAX).is_none());
+        assert!(date.checked_sub_months(i64::MIN).is_none());
+        assert!(DateTime::from_signed_secs(MAX_SECS)
+            .checked_add_months(1)
+            .is_none());
+    }
+
+    #[test]
+    fn test_year_arithmetic() {
+        // 2000-02-29 23:30 at -02:00
+        let offset = FixedOffset::west(2 * 3600).unwrap();
+        let date = DateTime::from_secs(951874200).with_offset(offset);
+        assert_eq!((date.month(), date.date()), (Month::February, 29));
+
+        let moved = |years, leap_day| {
+            date.checked_add_years(years, leap_day)
+                .map(|date| (date.year(), date.month(), date.date(), date.hour()))
+        };
+        assert_eq!(
+            moved(1, LeapDay::Clamp),
+            Some((2001, Month::February, 28, 23))
+        );
+        assert_eq!(
+            moved(-1, LeapDay::RollOver),
+            Some((1999, Month::March, 1, 23))
+        );
+        assert_eq!(
+            moved(100, LeapDay::RollOver),
+            Some((2100, Month::March, 1, 23))
+        );
+        assert_eq!(
+            moved(400, LeapDay::Clamp),
+            Some((2400, Month::February, 29, 23))
+        );
+        assert_eq!(
+            date.checked_sub_years(4, LeapDay::Clamp),
+            date.checked_add_years(-4, LeapDay::RollOver)
+        );
+
+        // other days are unaffected by the policy
+        let march = date.checked_add_months(1).unwrap();
+        assert_eq!(
+            march.checked_add_years(1, LeapDay::Clamp),
+            march.checked_add_years(1, LeapDay::RollOver)
+        );
+
+        assert!(moved(-2000, LeapDay::Clamp).is_none());
+        assert!(moved(i64::MAX, LeapDay::Clamp).is_none());
+        assert!(date.checked_sub_years(i64::MIN, LeapDay::Clamp).is_none());
+    }
+}
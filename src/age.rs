@@ -0,0 +1,121 @@
+//! the freshness and age calculations of HTTP caching
+//!
+//! a cached response is fresh while its current age is less than its
+//! freshness lifetime. these functions follow
+//! [RFC 9111 §4.2](https://www.rfc-editor.org/rfc/rfc9111#section-4.2),
+//! taking header values already parsed (for example with
+//! [`DateTime::parse_http_date`])
+//! ```
+//! # use datetime::age::{current_age, freshness_lifetime};
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let date = DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+//! let requested = DateTime::from_secs(784111777);
+//! let received = DateTime::from_secs(784111778);
+//! let now = DateTime::from_secs(784111837);
+//!
+//! let lifetime = freshness_lifetime(&date, None, Some(Duration::from_secs(300))).unwrap();
+//! let age = current_age(&date, Duration::ZERO, &requested, &received, &now);
+//!
+//! assert_eq!(age, Duration::from_secs(60));
+//! assert!(age < lifetime);
+//! ```
+use crate::DateTime;
+use core::time::Duration;
+
+/// returns how long a response stays fresh, from its `Date` and `Expires`
+/// headers and its `max-age` (or, for shared caches, `s-maxage`)
+/// directive
+///
+/// `max_age` takes precedence over `expires`, and an `expires` before
+/// `date` gives a lifetime of zero. without either, the lifetime is None
+/// and a cache may use a heuristic instead
+pub fn freshness_lifetime(
+    date: &DateTime,
+    expires: Option<&DateTime>,
+    max_age: Option<Duration>,
+) -> Option<Duration> {
+    match (max_age, expires) {
+        (Some(max_age), _) => Some(max_age),
+        (None, Some(expires)) => Some(since(date, expires)),
+        (None, None) => None,
+    }
+}
+
+/// returns the current age of a cached response
+///
+/// `date` is its `Date` header and `age_value` its `Age` header (zero if it
+/// had none), `request_time` and `response_time` are when the request was
+/// sent and the response received, and `now` is the current time
+pub fn current_age(
+    date: &DateTime,
+    age_value: Duration,
+    request_time: &DateTime,
+    response_time: &DateTime,
+    now: &DateTime,
+) -> Duration {
+    let apparent_age = since(date, response_time);
+    let response_delay = since(request_time, response_time);
+    let corrected_age_value = age_value.saturating_add(response_delay);
+
+    let corrected_initial_age = apparent_age.max(corrected_age_value);
+    let resident_time = since(response_time, now);
+
+    corrected_initial_age.saturating_add(resident_time)
+}
+
+// the time from `start` to `end`, or zero if `end` is earlier
+fn since(start: &DateTime, end: &DateTime) -> Duration {
+    start.ttl_until(end).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_age, freshness_lifetime};
+    use crate::DateTime;
+    use std::time::Duration;
+
+    #[test]
+    fn test_freshness_lifetime() {
+        let date = DateTime::from_secs(1000);
+        let expires = DateTime::from_secs(4600);
+
+        assert_eq!(
+            freshness_lifetime(&date, Some(&expires), None),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(
+            freshness_lifetime(&date, Some(&expires), Some(Duration::from_secs(60))),
+            Some(Duration::from_secs(60))
+        );
+        // an Expires in the past means the response is already stale
+        assert_eq!(
+            freshness_lifetime(&expires, Some(&date), None),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(freshness_lifetime(&date, None, None), None);
+    }
+
+    #[test]
+    fn test_current_age() {
+        let request_time = DateTime::from_secs(1000);
+        let response_time = DateTime::from_secs(1002);
+        let now = DateTime::from_secs(1010);
+
+        // the Age header plus the delay wins over the apparent age
+        let date = DateTime::from_secs(999);
+        let age = current_age(
+            &date,
+            Duration::from_secs(30),
+            &request_time,
+            &response_time,
+            &now,
+        );
+        assert_eq!(age, Duration::from_secs(30 + 2 + 8));
+
+        // the origin's clock runs slow, so the apparent age wins
+        let date = DateTime::from_secs(900);
+        let age = current_age(&date, Duration::ZERO, &request_time, &response_time, &now);
+        assert_eq!(age, Duration::from_secs(102 + 8));
+    }
+}
@@ -0,0 +1,121 @@
+//! converting many timestamps at once
+//!
+//! reading the calendar fields of a DateTime works them out from scratch
+//! each time, which is quick but adds up over tens of millions of log
+//! lines. [`Converter`] remembers the month of the last timestamp it
+//! converted, so that the next one, which in a log is almost always in the
+//! same month, only needs its day and time of day worked out
+//! ```
+//! # use datetime::batch::Converter;
+//! # use datetime::DateTime;
+//! let dates = DateTime::from_secs_slice(&[842282624, 842282625, 842369024]);
+//!
+//! let mut converter = Converter::new();
+//! let fields: Vec<_> = dates.iter().map(|date| converter.to_civil(date)).collect();
+//! assert_eq!(fields[2].to_string(), "1996-09-10T15:23:44");
+//! ```
+use crate::convert::{civil_from_days, days_in_month};
+use crate::{CivilTime, DateTime};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+impl DateTime {
+    /// returns DateTimes for the given numbers of seconds after the Unix
+    /// epoch, as [`DateTime::from_secs`] does for each
+    ///
+    /// # Panics
+    ///
+    /// panics if any of them is too far in the future for a DateTime
+    pub fn from_secs_slice(secs: &[u64]) -> Vec<DateTime> {
+        trace_span!("from_secs_slice", count = secs.len());
+        secs.iter()
+            .map(|&secs| {
+                let secs = i64::try_from(secs).unwrap_or(i64::MAX);
+                DateTime::from_signed_secs(secs)
+            })
+            .collect()
+    }
+}
+
+/// works out the calendar fields of many DateTimes, reusing the month of
+/// the last one
+///
+/// timestamps can come in any order; ones in a different month from the
+/// last are just as quick as reading a DateTime's fields directly
+#[derive(Debug, Clone, Default)]
+pub struct Converter {
+    // the (year, month) of the last month seen, and the range of day
+    // numbers it covers
+    month: Option<(usize, usize)>,
+    first_day: i64,
+    last_day: i64,
+}
+
+impl Converter {
+    /// returns a converter with nothing remembered yet
+    pub fn new() -> Self {
+        Converter::default()
+    }
+
+    /// returns the DateTime's calendar fields as reported in its own
+    /// offset, the same as `CivilTime::from(date)`
+    pub fn to_civil(&mut self, date: &DateTime) -> CivilTime {
+        let local = date.secs + date.offset.seconds() as i64;
+        let day = local.div_euclid(86_400);
+        let secs = local.rem_euclid(86_400) as usize;
+
+        let (year, month) = match self.month {
+            Some(month) if (self.first_day..=self.last_day).contains(&day) => month,
+            _ => {
+                let (year, month, date) = civil_from_days(day);
+                let year = year as usize;
+                self.month = Some((year, month));
+                self.first_day = day - date as i64 + 1;
+                self.last_day = self.first_day + days_in_month(year, month) as i64 - 1;
+                (year, month)
+            }
+        };
+
+        CivilTime::from_fields(
+            (year, month, (day - self.first_day) as usize + 1),
+            (secs / 3600, secs / 60 % 60, secs % 60),
+            date.nanos,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Converter;
+    use crate::{CivilTime, DateTime, FixedOffset, MAX_SECS, MIN_SECS};
+
+    #[test]
+    fn test_matches_fields() {
+        let mut converter = Converter::new();
+        let offset = FixedOffset::west(5 * 3600).unwrap();
+
+        // forwards in small steps, across month and year boundaries, and
+        // then jumping about
+        let steps = (0..200_000).map(|i| 1_700_000_000 + i * 997);
+        let jumps = [MIN_SECS, MAX_SECS, 0, -1, 951_782_400, 951_868_799];
+        for secs in steps.chain(jumps.iter().copied()) {
+            let date = DateTime::from_signed_secs(secs);
+            assert_eq!(converter.to_civil(&date), CivilTime::from(&date));
+
+            let date = date.with_offset(offset);
+            if secs > MIN_SECS + 86_400 {
+                assert_eq!(converter.to_civil(&date), CivilTime::from(&date));
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_secs_slice() {
+        let dates = DateTime::from_secs_slice(&[0, 842282624]);
+        assert_eq!(
+            dates,
+            [DateTime::from_secs(0), DateTime::from_secs(842282624)]
+        );
+        assert!(DateTime::from_secs_slice(&[]).is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+//! conversions to and from `bson::DateTime`, enabled with the `bson`
+//! feature
+//!
+//! BSON datetimes are Unix milliseconds, so converting a DateTime drops
+//! any finer fraction, and DateTimes too far from the epoch saturate.
+//! BSON datetimes before the year 1 fail to convert with
+//! [`RangeError`]
+//! ```
+//! # use datetime::DateTime;
+//! # use std::convert::TryFrom;
+//! let ours = DateTime::from_millis(842282624123);
+//! let theirs = bson::DateTime::from(&ours);
+//!
+//! assert_eq!(theirs.timestamp_millis(), 842282624123);
+//! assert_eq!(DateTime::try_from(theirs).unwrap(), ours);
+//! ```
+use crate::{AbsoluteTime, DateTime, FixedOffset, RangeError};
+use core::convert::TryFrom;
+
+impl From<&DateTime> for ::bson::DateTime {
+    fn from(date: &DateTime) -> Self {
+        let millis = date
+            .secs
+            .saturating_mul(1000)
+            .saturating_add((date.nanos / 1_000_000) as i64);
+
+        ::bson::DateTime::from_millis(millis)
+    }
+}
+
+impl TryFrom<::bson::DateTime> for DateTime {
+    type Error = RangeError;
+
+    /// converts a BSON datetime into a DateTime in UTC
+    fn try_from(date: ::bson::DateTime) -> Result<Self, Self::Error> {
+        let millis = date.timestamp_millis();
+        let secs = millis.div_euclid(1000);
+        let nanos = millis.rem_euclid(1000) as u32 * 1_000_000;
+
+        let time = AbsoluteTime::new(secs, nanos).ok_or(RangeError)?;
+        Ok(time.to_datetime(FixedOffset::utc()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, RangeError};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_round_trip() {
+        let date = DateTime::from_nanos(-1);
+        let bson = ::bson::DateTime::from(&date);
+
+        assert_eq!(bson.timestamp_millis(), -1);
+        let back = DateTime::try_from(bson).unwrap();
+        assert_eq!((back.secs, back.nanos), (-1, 999_000_000));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(DateTime::try_from(::bson::DateTime::MIN), Err(RangeError));
+        let far = DateTime::MAX;
+        assert_eq!(::bson::DateTime::from(&far), ::bson::DateTime::MAX);
+    }
+}
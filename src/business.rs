@@ -0,0 +1,869 @@
+//! business-day arithmetic
+//!
+//! business days are Monday to Friday, less any holidays. dates are taken
+//! in the DateTime's own offset, and moving by business days keeps the time
+//! of day
+//! ```
+//! # use datetime::{DateTime, Day};
+//! // Friday 1996-09-13 15:23:44
+//! let friday = DateTime::from_secs(842282624 + 4 * 86_400);
+//!
+//! let due = friday.add_business_days(1).unwrap();
+//! assert_eq!(due.day(), Day::Monday);
+//! assert!(!due.is_weekend());
+//! assert_eq!(friday.business_days_between(&due), 1);
+//! ```
+//!
+//! holidays are given as a [`Holidays`], which is implemented for slices
+//! and sets of [`PackedDate`]s, for the built-in calendars [`UsFederal`]
+//! and [`WeekendsOnly`], and for pairs of calendars, which observe the
+//! holidays of both
+//! ```
+//! # use datetime::{DateTime, PackedDate};
+//! // Monday 1996-09-09, before a holiday on the Tuesday
+//! let monday = DateTime::from_secs(842282624);
+//! let holidays = [PackedDate::new(1996, 9, 10).unwrap()];
+//!
+//! let due = monday.add_business_days_with(1, &holidays[..]).unwrap();
+//! assert_eq!(due.date(), 11);
+//! ```
+//! ```
+//! # use datetime::business::{Holidays, UsFederal};
+//! # use datetime::{DateTime, PackedDate};
+//! // Wednesday 2023-11-22, the day before Thanksgiving
+//! let wednesday = DateTime::parse_rfc3339("2023-11-22T12:00:00Z").unwrap();
+//! let due = wednesday.add_business_days_with(1, &UsFederal).unwrap();
+//! assert_eq!(due.date(), 24);
+//!
+//! // a company's own day off on top of the federal holidays
+//! let calendar = (UsFederal, vec![PackedDate::new(2023, 11, 24).unwrap()]);
+//! let due = wednesday.add_business_days_with(1, &calendar).unwrap();
+//! assert_eq!(due.date(), 27);
+//! assert!(calendar.is_holiday_at(&due.checked_sub_secs(3 * 86_400).unwrap()));
+//! ```
+//!
+//! [`working_duration_between`] counts only the time within the working
+//! hours of a [`WorkCalendar`], such as the time taken to respond to a
+//! ticket
+//! ```
+//! # use datetime::business::{working_duration_between, WorkCalendar};
+//! # use datetime::{DateTime, Span};
+//! // opened on Friday 1996-09-13 at 15:23:44, answered on Monday at 10:00
+//! let opened = DateTime::from_secs(842282624 + 4 * 86_400);
+//! let answered = DateTime::parse_rfc3339("1996-09-16T10:00:00Z").unwrap();
+//!
+//! // Monday to Friday, 9 to 5
+//! let calendar = WorkCalendar::new();
+//! let taken = working_duration_between(&opened, &answered, &calendar);
+//! // 1h 36m 16s on Friday and an hour on Monday
+//! assert_eq!(taken, Span::from_secs(5776 + 3600));
+//! ```
+use crate::convert::{civil_from_days, days_from_civil, days_in_month};
+use crate::{DateTime, Day, PackedDate, Span, MAX_SECS, MIN_SECS};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+/// a set of dates which aren't business days
+pub trait Holidays {
+    /// returns true if the given date is a holiday
+    fn is_holiday(&self, date: PackedDate) -> bool;
+
+    /// returns true if the DateTime's date, as reported in its offset, is
+    /// a holiday
+    fn is_holiday_at(&self, date: &DateTime) -> bool {
+        matches!(PackedDate::from_datetime(date), Some(date) if self.is_holiday(date))
+    }
+
+    /// returns whether each day of the given year is a holiday, indexed
+    /// from 0 for 1 January; the last entry is false outside leap years
+    ///
+    /// looking a day up in this is cheaper than asking the calendar, for
+    /// code that checks many dates in the same year
+    /// ```
+    /// # use datetime::business::{Holidays, UsFederal};
+    /// let holidays = UsFederal.year_bitmap(2023);
+    ///
+    /// // New Year's Day fell on a Sunday, so was observed on Monday
+    /// assert_eq!(&holidays[..3], &[false, true, false]);
+    /// assert_eq!(holidays.iter().filter(|&&holiday| holiday).count(), 11);
+    /// ```
+    fn year_bitmap(&self, year: usize) -> [bool; 366] {
+        let mut bitmap = [false; 366];
+        let days = (1..=12).flat_map(|month| {
+            (1..=days_in_month(year, month)).map(move |date| PackedDate::new(year, month, date))
+        });
+
+        for (holiday, date) in bitmap.iter_mut().zip(days) {
+            // dates a PackedDate can't hold are in no list of holidays
+            *holiday = matches!(date, Ok(date) if self.is_holiday(date));
+        }
+        bitmap
+    }
+}
+
+impl Holidays for [PackedDate] {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl Holidays for Vec<PackedDate> {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl Holidays for BTreeSet<PackedDate> {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Holidays for HashSet<PackedDate> {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl<A: Holidays, B: Holidays> Holidays for (A, B) {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        self.0.is_holiday(date) || self.1.is_holiday(date)
+    }
+}
+
+impl<H: Holidays + ?Sized> Holidays for &H {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        (**self).is_holiday(date)
+    }
+}
+
+/// a calendar with no holidays, so that only weekends aren't business days
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WeekendsOnly;
+
+impl Holidays for WeekendsOnly {
+    fn is_holiday(&self, _: PackedDate) -> bool {
+        false
+    }
+}
+
+/// the federal holidays of the United States, on the days they are
+/// observed
+///
+/// a holiday falling on a Saturday is observed on the Friday before, and
+/// one falling on a Sunday on the Monday after. the current holidays are
+/// used for every year, except that Martin Luther King Jr. Day is only a
+/// holiday from 1986 and Juneteenth from 2021
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct UsFederal;
+
+impl UsFederal {
+    // the days since the epoch on which the year's holidays are observed,
+    // or None for those not yet created
+    fn holidays(year: i64) -> [Option<i64>; 12] {
+        // a fixed date, moved off a weekend
+        let fixed = |year: i64, month: usize, date: usize| {
+            let day = days_from_civil(year, month, date);
+            match weekday(day) {
+                5 => day - 1,
+                6 => day + 1,
+                _ => day,
+            }
+        };
+        // the nth given weekday of a month, counting Monday as 0, or the
+        // last if n is 0
+        let nth = |month: usize, target: i64, n: i64| {
+            let first = days_from_civil(year, month, 1);
+            if n > 0 {
+                first + (target - weekday(first)).rem_euclid(7) + (n - 1) * 7
+            } else {
+                let last = first + days_in_month(year as usize, month) as i64 - 1;
+                last - (weekday(last) - target).rem_euclid(7)
+            }
+        };
+
+        [
+            Some(fixed(year, 1, 1)),
+            // New Year's Day of the next year may be observed in this one
+            Some(fixed(year + 1, 1, 1)),
+            Some(nth(1, 0, 3)).filter(|_| year >= 1986),
+            Some(nth(2, 0, 3)),
+            Some(nth(5, 0, 0)),
+            Some(fixed(year, 6, 19)).filter(|_| year >= 2021),
+            Some(fixed(year, 7, 4)),
+            Some(nth(9, 0, 1)),
+            Some(nth(10, 0, 2)),
+            Some(fixed(year, 11, 11)),
+            Some(nth(11, 3, 4)),
+            Some(fixed(year, 12, 25)),
+        ]
+    }
+}
+
+impl Holidays for UsFederal {
+    fn is_holiday(&self, date: PackedDate) -> bool {
+        let year = date.year() as i64;
+        let day = days_from_civil(year, date.month() as usize + 1, date.date());
+        UsFederal::holidays(year).contains(&Some(day))
+    }
+}
+
+// the day of the week of a local day, counting from Monday as 0
+fn weekday(day: i64) -> i64 {
+    (day + 3).rem_euclid(7)
+}
+
+// the number of weekdays before a local day, counting from 1970-01-05 (a
+// Monday) and negative before it
+fn weekdays_before(day: i64) -> i64 {
+    let days = day - 4;
+    days.div_euclid(7) * 5 + days.rem_euclid(7).min(5)
+}
+
+// whether a local day is a business day
+fn is_business_day<H: Holidays + ?Sized>(day: i64, holidays: &H) -> bool {
+    if weekday(day) >= 5 {
+        return false;
+    }
+
+    let (year, month, date) = civil_from_days(day);
+    match PackedDate::new(year as usize, month, date) {
+        Ok(date) => !holidays.is_holiday(date),
+        // too late to be in any list of holidays
+        Err(_) => true,
+    }
+}
+
+impl DateTime {
+    // the date in the DateTime's own offset, in days since the epoch
+    fn local_days(&self) -> i64 {
+        (self.secs + self.offset.seconds() as i64).div_euclid(86_400)
+    }
+
+    // the DateTime moved by whole days, or None if that is out of range
+    fn add_days(&self, days: i64) -> Option<DateTime> {
+        let secs = days.checked_mul(86_400)?.checked_add(self.secs)?;
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(secs, self.nanos, self.offset))
+        } else {
+            None
+        }
+    }
+
+    /// returns true if the DateTime falls on a Saturday or a Sunday
+    pub fn is_weekend(&self) -> bool {
+        weekday(self.local_days()) >= 5
+    }
+
+    /// returns the DateTime `n` business days later (or earlier, if `n` is
+    /// negative), or None if that is outside the range a DateTime can
+    /// represent
+    ///
+    /// a weekend counts as part of the business day before it when moving
+    /// forwards, and the one after it when moving backwards, so one
+    /// business day after a Saturday is the Monday
+    pub fn add_business_days(&self, n: i64) -> Option<DateTime> {
+        if n == 0 {
+            return self.add_days(0);
+        }
+
+        let day = self.local_days();
+        let weekday = weekday(day);
+
+        let moved = if n >= 0 {
+            // count from the Friday before a weekend
+            let (day, weekday) = (day - (weekday - 4).max(0), weekday.min(4));
+            let (weeks, rest) = (n / 5, n % 5);
+            let skip = if weekday + rest > 4 { 2 } else { 0 };
+
+            weeks
+                .checked_mul(7)?
+                .checked_add(rest + skip)?
+                .checked_add(day)?
+        } else {
+            // count from the Monday after a weekend
+            let (day, weekday) = if weekday >= 5 {
+                (day + 7 - weekday, 0)
+            } else {
+                (day, weekday)
+            };
+            let n = n.checked_neg()?;
+            let (weeks, rest) = (n / 5, n % 5);
+            let skip = if weekday - rest < 0 { 2 } else { 0 };
+
+            day.checked_sub(weeks.checked_mul(7)?)?
+                .checked_sub(rest + skip)?
+        };
+
+        self.add_days(moved - self.local_days())
+    }
+
+    /// like [`add_business_days`](DateTime::add_business_days), but also
+    /// skipping the given holidays
+    ///
+    /// this takes time in proportion to `n`
+    pub fn add_business_days_with<H: Holidays + ?Sized>(
+        &self,
+        n: i64,
+        holidays: &H,
+    ) -> Option<DateTime> {
+        let step = if n >= 0 { 1 } else { -1 };
+        let mut day = self.local_days();
+        let mut remaining = n.unsigned_abs();
+
+        while remaining > 0 {
+            day = day.checked_add(step)?;
+            // give up once past the range of a DateTime
+            if !(MIN_SECS / 86_400 - 1..=MAX_SECS / 86_400 + 1).contains(&day) {
+                return None;
+            }
+            if is_business_day(day, holidays) {
+                remaining -= 1;
+            }
+        }
+
+        self.add_days(day - self.local_days())
+    }
+
+    /// returns the number of business days from the DateTime's date up to
+    /// but not including `other`'s, which is negative if `other` is
+    /// earlier; both dates are taken in this DateTime's offset
+    /// ```
+    /// # use datetime::DateTime;
+    /// // Monday 1996-09-09 and the Monday after
+    /// let monday = DateTime::from_secs(842282624);
+    /// let next = DateTime::from_secs(842282624 + 7 * 86_400);
+    ///
+    /// assert_eq!(monday.business_days_between(&next), 5);
+    /// assert_eq!(next.business_days_between(&monday), -5);
+    /// ```
+    pub fn business_days_between(&self, other: &DateTime) -> i64 {
+        let other = other.with_offset(self.offset);
+        weekdays_before(other.local_days()) - weekdays_before(self.local_days())
+    }
+
+    /// like [`business_days_between`](DateTime::business_days_between),
+    /// but not counting the given holidays
+    ///
+    /// this takes time in proportion to the number of days between them
+    pub fn business_days_between_with<H: Holidays + ?Sized>(
+        &self,
+        other: &DateTime,
+        holidays: &H,
+    ) -> i64 {
+        let (start, end) = (
+            self.local_days(),
+            other.with_offset(self.offset).local_days(),
+        );
+        let (from, to, sign) = if start <= end {
+            (start, end, 1)
+        } else {
+            (end, start, -1)
+        };
+
+        let count = (from..to)
+            .filter(|&day| is_business_day(day, holidays))
+            .count();
+        count as i64 * sign
+    }
+
+    /// describes the business days left from this DateTime until
+    /// `deadline`, not counting the given holidays, like "3 business days
+    /// remaining" or "overdue by 1 business day"
+    ///
+    /// days are counted as by
+    /// [`business_days_between_with`](DateTime::business_days_between_with);
+    /// a deadline later on the same business day is "due today", and one
+    /// that has passed without a business day in between is "overdue"
+    /// ```
+    /// # use datetime::{DateTime, PackedDate};
+    /// // Thursday 1996-09-12, with the deadline on the Tuesday after
+    /// let now = DateTime::from_secs(842282624 + 3 * 86_400);
+    /// let deadline = DateTime::from_secs(842282624 + 8 * 86_400);
+    ///
+    /// let none: &[PackedDate] = &[];
+    /// assert_eq!(now.business_time_until(&deadline, none), "3 business days remaining");
+    ///
+    /// let holidays = [PackedDate::new(1996, 9, 16).unwrap()];
+    /// assert_eq!(now.business_time_until(&deadline, &holidays[..]), "2 business days remaining");
+    /// assert_eq!(deadline.business_time_until(&now, none), "overdue by 3 business days");
+    /// ```
+    pub fn business_time_until<H: Holidays + ?Sized>(
+        &self,
+        deadline: &DateTime,
+        holidays: &H,
+    ) -> String {
+        let days = self.business_days_between_with(deadline, holidays);
+        let count = days.unsigned_abs();
+        let plural = if count == 1 { "" } else { "s" };
+
+        if *deadline < *self {
+            match count {
+                0 => "overdue".to_string(),
+                _ => format!("overdue by {} business day{}", count, plural),
+            }
+        } else if days == 0 && self.local_days() == deadline.with_offset(self.offset).local_days() {
+            "due today".to_string()
+        } else {
+            format!("{} business day{} remaining", count, plural)
+        }
+    }
+}
+
+/// the working hours of a week, less any holidays, for
+/// [`working_duration_between`]
+///
+/// the default is Monday to Friday, 09:00 to 17:00, with no holidays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkCalendar {
+    // a bit for each working day, indexed by `Day as usize`
+    days: u8,
+    // the start and end of the working day, in seconds after midnight
+    start: u32,
+    end: u32,
+    holidays: BTreeSet<PackedDate>,
+}
+
+impl Default for WorkCalendar {
+    fn default() -> Self {
+        WorkCalendar {
+            days: 0b0011_1110,
+            start: 9 * 3600,
+            end: 17 * 3600,
+            holidays: BTreeSet::new(),
+        }
+    }
+}
+
+impl WorkCalendar {
+    /// returns a calendar of Monday to Friday, 09:00 to 17:00
+    pub fn new() -> Self {
+        WorkCalendar::default()
+    }
+
+    /// returns the calendar working only on the given days of the week
+    pub fn with_days(self, days: &[Day]) -> Self {
+        let days = days.iter().fold(0, |mask, &day| mask | 1 << day as usize);
+        WorkCalendar { days, ..self }
+    }
+
+    /// returns the calendar working from `start` to `end` each working
+    /// day, both given as (hour, minute)
+    ///
+    /// # Panics
+    ///
+    /// panics if `start` isn't before `end`, or `end` is after 24:00
+    /// ```
+    /// # use datetime::business::WorkCalendar;
+    /// let calendar = WorkCalendar::new().with_hours((8, 30), (16, 30));
+    /// ```
+    pub fn with_hours(self, start: (u32, u32), end: (u32, u32)) -> Self {
+        let seconds = |(hour, minute): (u32, u32)| {
+            assert!(minute < 60, "minute must be 0-59");
+            hour * 3600 + minute * 60
+        };
+        let (start, end) = (seconds(start), seconds(end));
+        assert!(start < end, "working hours must start before they end");
+        assert!(end <= 86_400, "working hours must end by 24:00");
+
+        WorkCalendar { start, end, ..self }
+    }
+
+    /// returns the calendar with the given dates added as holidays
+    pub fn with_holidays<I: IntoIterator<Item = PackedDate>>(mut self, holidays: I) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    // whether a local day, in days since the epoch, has working hours
+    fn is_working_day(&self, day: i64) -> bool {
+        // 1970-01-01 was a Thursday
+        let weekday = (day + 4).rem_euclid(7);
+        if self.days & 1 << weekday == 0 {
+            return false;
+        }
+
+        let (year, month, date) = civil_from_days(day);
+        match PackedDate::new(year as usize, month, date) {
+            Ok(date) => !self.holidays.contains(&date),
+            Err(_) => true,
+        }
+    }
+}
+
+/// returns the time from `a` to `b` that falls within the working hours of
+/// `calendar`, which is negative if `b` is earlier; both are taken in
+/// `a`'s offset
+///
+/// this takes time in proportion to the number of days between them
+pub fn working_duration_between(a: &DateTime, b: &DateTime, calendar: &WorkCalendar) -> Span {
+    // nanoseconds since the epoch in a's offset
+    let local = |date: &DateTime| {
+        (date.secs + a.offset.seconds() as i64) as i128 * 1_000_000_000 + date.nanos as i128
+    };
+    let (start, end) = (local(a), local(b));
+    let (from, to, sign) = if start <= end {
+        (start, end, 1)
+    } else {
+        (end, start, -1)
+    };
+
+    let day_nanos = 86_400 * 1_000_000_000;
+    let total: i128 = (from.div_euclid(day_nanos) as i64..=to.div_euclid(day_nanos) as i64)
+        .filter(|&day| calendar.is_working_day(day))
+        .map(|day| {
+            let midnight = day as i128 * day_nanos;
+            let open = midnight + calendar.start as i128 * 1_000_000_000;
+            let close = midnight + calendar.end as i128 * 1_000_000_000;
+            (close.min(to) - open.max(from)).max(0)
+        })
+        .sum();
+
+    let span = Span::new(
+        (total / 1_000_000_000) as i64,
+        (total % 1_000_000_000) as u32,
+    )
+    .expect("working time is shorter than the time between two DateTimes");
+    if sign < 0 {
+        -span
+    } else {
+        span
+    }
+}
+
+/// returns the DateTime once `duration` of working time in `calendar` has
+/// passed after `date`, in `date`'s offset, or None if that is out of
+/// range or the calendar has no working days
+///
+/// a duration ending exactly at the end of the working day gives that
+/// time, rather than the start of the next working day
+/// ```
+/// # use datetime::business::{add_working_duration, WorkCalendar};
+/// # use datetime::DateTime;
+/// # use std::time::Duration;
+/// // Friday 1996-09-13 at 16:00
+/// let friday = DateTime::parse_rfc3339("1996-09-13T16:00:00Z").unwrap();
+///
+/// let due = add_working_duration(&friday, Duration::from_secs(4 * 3600), &WorkCalendar::new());
+/// assert_eq!(due.unwrap().to_rfc3339(), "1996-09-16T12:00:00Z");
+/// ```
+pub fn add_working_duration(
+    date: &DateTime,
+    duration: Duration,
+    calendar: &WorkCalendar,
+) -> Option<DateTime> {
+    const NANOS: i128 = 1_000_000_000;
+    const DAY: i128 = 86_400 * NANOS;
+
+    // working time passes no faster than real time, so this rules out the
+    // durations it would take longest to find
+    let mut remaining = duration.as_nanos() as i128;
+    if calendar.days == 0 || remaining > (MAX_SECS - date.secs + 1) as i128 * NANOS {
+        return None;
+    }
+
+    // nanoseconds since the epoch in the DateTime's offset
+    let offset = date.offset.seconds() as i64;
+    let from = (date.secs + offset) as i128 * NANOS + date.nanos as i128;
+    if remaining == 0 {
+        return Some(*date);
+    }
+
+    let mut day = from.div_euclid(DAY) as i64;
+    loop {
+        if day > MAX_SECS / 86_400 + 1 {
+            return None;
+        }
+
+        if calendar.is_working_day(day) {
+            let midnight = day as i128 * DAY;
+            let open = (midnight + calendar.start as i128 * NANOS).max(from);
+            let close = midnight + calendar.end as i128 * NANOS;
+
+            if open < close {
+                if remaining <= close - open {
+                    let local = open + remaining;
+                    let secs = local.div_euclid(NANOS) as i64 - offset;
+                    let nanos = local.rem_euclid(NANOS) as u32;
+                    return if secs <= MAX_SECS {
+                        Some(DateTime::from_parts(secs, nanos, date.offset))
+                    } else {
+                        None
+                    };
+                }
+                remaining -= close - open;
+            }
+        }
+        day += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_working_duration, working_duration_between, Holidays, UsFederal, WeekendsOnly,
+        WorkCalendar,
+    };
+    use crate::convert::days_from_civil;
+    use crate::{DateTime, Day, FixedOffset, PackedDate, Span};
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    // 1996-09-09 15:23:44, a Monday, moved by whole days
+    fn day(days: i64) -> DateTime {
+        DateTime::from_signed_secs(842282624 + days * 86_400)
+    }
+
+    #[test]
+    fn test_add_business_days() {
+        let saturday = day(5);
+        assert!(saturday.is_weekend() && !day(4).is_weekend());
+
+        assert_eq!(saturday.add_business_days(1).unwrap(), day(7));
+        assert_eq!(saturday.add_business_days(0).unwrap(), saturday);
+        assert_eq!(saturday.add_business_days(-1).unwrap(), day(4));
+        assert_eq!(day(0).add_business_days(-1).unwrap(), day(-3));
+        assert_eq!(day(3).add_business_days(2).unwrap(), day(7));
+        assert_eq!(day(0).add_business_days(10).unwrap(), day(14));
+        assert_eq!(day(2).add_business_days(-7).unwrap(), day(-7));
+
+        // agrees with counting one day at a time
+        for start in -7..7 {
+            for n in -12..12 {
+                let none: &[PackedDate] = &[];
+                assert_eq!(
+                    day(start).add_business_days(n),
+                    day(start).add_business_days_with(n, none)
+                );
+            }
+        }
+        assert!(day(0).add_business_days(i64::MAX).is_none());
+    }
+
+    #[test]
+    fn test_business_days_between() {
+        assert_eq!(day(0).business_days_between(&day(0)), 0);
+        assert_eq!(day(4).business_days_between(&day(7)), 1);
+        assert_eq!(day(5).business_days_between(&day(6)), 0);
+        assert_eq!(day(-400).business_days_between(&day(0)), 285);
+
+        for start in -7..7 {
+            for end in -7..14 {
+                let none: &[PackedDate] = &[];
+                assert_eq!(
+                    day(start).business_days_between(&day(end)),
+                    day(start).business_days_between_with(&day(end), none)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_holidays() {
+        let holidays: BTreeSet<_> = [(1996, 9, 10), (1996, 9, 16)]
+            .iter()
+            .map(|&(y, m, d)| PackedDate::new(y, m, d).unwrap())
+            .collect();
+        assert!(holidays.is_holiday(PackedDate::new(1996, 9, 16).unwrap()));
+
+        assert_eq!(
+            day(0).add_business_days_with(1, &holidays).unwrap().day(),
+            Day::Wednesday
+        );
+        assert_eq!(day(0).add_business_days_with(4, &holidays).unwrap(), day(8));
+        assert_eq!(
+            day(8).add_business_days_with(-4, &holidays).unwrap(),
+            day(0)
+        );
+        assert_eq!(day(0).business_days_between_with(&day(9), &holidays), 5);
+    }
+
+    #[test]
+    fn test_year_bitmap() {
+        let holidays = [
+            PackedDate::new(2024, 2, 29).unwrap(),
+            PackedDate::new(2024, 12, 31).unwrap(),
+        ];
+        let bitmap = holidays[..].year_bitmap(2024);
+        assert!(bitmap[59] && bitmap[365]);
+        assert_eq!(bitmap.iter().filter(|&&holiday| holiday).count(), 2);
+
+        // the bitmap agrees with the calendar on every day
+        let bitmap = UsFederal.year_bitmap(2021);
+        let start = days_from_civil(2021, 1, 1);
+        for (i, &holiday) in bitmap.iter().enumerate().take(365) {
+            let midnight = DateTime::from_signed_secs((start + i as i64) * 86_400);
+            let date = PackedDate::from_datetime(&midnight).unwrap();
+            assert_eq!(holiday, UsFederal.is_holiday(date));
+        }
+        assert!(!bitmap[365]);
+        assert_eq!(WeekendsOnly.year_bitmap(2021), [false; 366]);
+    }
+
+    #[test]
+    fn test_us_federal() {
+        let date = |y, m, d| PackedDate::new(y, m, d).unwrap();
+        let in_2023: Vec<_> = (1..=12)
+            .flat_map(|month| (1..=crate::days_in_month(2023, month)).map(move |d| (month, d)))
+            .filter(|&(month, d)| UsFederal.is_holiday(date(2023, month, d)))
+            .collect();
+        assert_eq!(
+            in_2023,
+            [
+                (1, 2),
+                (1, 16),
+                (2, 20),
+                (5, 29),
+                (6, 19),
+                (7, 4),
+                (9, 4),
+                (10, 9),
+                (11, 10),
+                (11, 23),
+                (12, 25)
+            ]
+        );
+
+        // New Year's Day 2022 was a Saturday
+        assert!(UsFederal.is_holiday(date(2021, 12, 31)));
+        assert!(!UsFederal.is_holiday(date(2022, 1, 3)));
+        assert!(!UsFederal.is_holiday(date(1985, 1, 21)));
+        assert!(!UsFederal.is_holiday(date(2020, 6, 19)));
+
+        assert!(!WeekendsOnly.is_holiday(date(2023, 12, 25)));
+        let both = (WeekendsOnly, &UsFederal);
+        assert!(both.is_holiday(date(2023, 12, 25)));
+
+        // Christmas 2023 at 20:00 in New York is already the 26th in UTC
+        let offset = FixedOffset::west(5 * 3600).unwrap();
+        let evening = DateTime::parse_rfc3339("2023-12-26T01:00:00Z").unwrap();
+        assert!(UsFederal.is_holiday_at(&evening.with_offset(offset)));
+        assert!(!UsFederal.is_holiday_at(&evening));
+    }
+
+    #[test]
+    fn test_business_time_until() {
+        let none: &[PackedDate] = &[];
+        let holidays = [PackedDate::new(1996, 9, 10).unwrap()];
+        let until = |from: i64, to: i64| day(from).business_time_until(&day(to), none);
+
+        assert_eq!(until(0, 1), "1 business day remaining");
+        assert_eq!(until(4, 7), "1 business day remaining");
+        assert_eq!(until(5, 7), "0 business days remaining");
+        assert_eq!(until(0, 0), "due today");
+        assert_eq!(until(7, 4), "overdue by 1 business day");
+        assert_eq!(until(7, 5), "overdue");
+        assert_eq!(
+            day(0).business_time_until(&day(2), &holidays[..]),
+            "1 business day remaining"
+        );
+
+        // later on the same day is still due today, earlier is overdue
+        let later = DateTime::from_secs(842282624 + 3600);
+        assert_eq!(day(0).business_time_until(&later, none), "due today");
+        assert_eq!(later.business_time_until(&day(0), none), "overdue");
+    }
+
+    #[test]
+    fn test_working_duration_between() {
+        let calendar = WorkCalendar::new();
+        let at = |input: &str| DateTime::parse_rfc3339(input).unwrap();
+        let hours = |a: &str, b: &str, calendar: &WorkCalendar| {
+            working_duration_between(&at(a), &at(b), calendar)
+        };
+
+        // Friday afternoon to Monday morning
+        let friday = "1996-09-13T16:00:00Z";
+        let monday = "1996-09-16T10:30:00Z";
+        assert_eq!(hours(friday, monday, &calendar), Span::from_secs(9000));
+        assert_eq!(hours(monday, friday, &calendar), Span::from_secs(-9000));
+        // entirely out of hours
+        assert_eq!(
+            hours("1996-09-13T18:00:00Z", "1996-09-16T08:00:00Z", &calendar),
+            Span::ZERO
+        );
+        // a whole week, and a half-second within hours
+        assert_eq!(
+            hours("1996-09-09T00:00:00Z", "1996-09-16T00:00:00Z", &calendar),
+            Span::from_secs(5 * 8 * 3600)
+        );
+        assert_eq!(
+            hours("1996-09-09T12:00:00Z", "1996-09-09T12:00:00.5Z", &calendar),
+            Span::from_millis(500)
+        );
+
+        let holiday = PackedDate::new(1996, 9, 16).unwrap();
+        let custom = WorkCalendar::new()
+            .with_days(&[Day::Saturday, Day::Monday])
+            .with_hours((8, 30), (12, 0))
+            .with_holidays([holiday].iter().copied());
+        assert_eq!(
+            hours("1996-09-09T00:00:00Z", "1996-09-17T00:00:00Z", &custom),
+            Span::from_secs(2 * 12600)
+        );
+
+        // working hours are in the first DateTime's offset, so this is
+        // 16:00 on Friday to 12:30 on Monday
+        let offset = FixedOffset::east(2 * 3600).unwrap();
+        let start = at("1996-09-13T14:00:00Z").with_offset(offset);
+        assert_eq!(
+            working_duration_between(&start, &at(monday), &calendar),
+            Span::from_secs(3600 + 12600)
+        );
+    }
+
+    #[test]
+    fn test_add_working_duration() {
+        let calendar = WorkCalendar::new();
+        let at = |input: &str| DateTime::parse_rfc3339(input).unwrap();
+        let add = |input: &str, secs: u64| {
+            add_working_duration(&at(input), Duration::from_secs(secs), &calendar)
+                .map(|date| date.to_rfc3339())
+        };
+
+        assert_eq!(
+            add("1996-09-13T16:00:00Z", 3600).unwrap(),
+            "1996-09-13T17:00:00Z"
+        );
+        assert_eq!(
+            add("1996-09-13T16:00:00Z", 3601).unwrap(),
+            "1996-09-16T09:00:01Z"
+        );
+        assert_eq!(
+            add("1996-09-14T12:00:00Z", 0).unwrap(),
+            "1996-09-14T12:00:00Z"
+        );
+        assert_eq!(
+            add("1996-09-14T12:00:00Z", 60).unwrap(),
+            "1996-09-16T09:01:00Z"
+        );
+        assert_eq!(
+            add("1996-09-09T20:00:00Z", 5 * 8 * 3600).unwrap(),
+            "1996-09-16T17:00:00Z"
+        );
+        assert!(add("1996-09-09T00:00:00Z", u64::MAX).is_none());
+        assert!(add_working_duration(
+            &at("1996-09-09T00:00:00Z"),
+            Duration::from_secs(1),
+            &WorkCalendar::new().with_days(&[])
+        )
+        .is_none());
+
+        // the inverse of working_duration_between
+        let start = at("1996-09-12T10:15:30.25+02:00");
+        for &secs in &[1, 3600, 20_000, 100_000, 500_000] {
+            let end = add_working_duration(&start, Duration::from_secs(secs), &calendar).unwrap();
+            assert_eq!(
+                working_duration_between(&start, &end, &calendar),
+                Span::from_secs(secs as i64)
+            );
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! month calendars laid out in weeks
+//!
+//! [`MonthCalendar`] arranges a month's days into rows of seven, one per
+//! week, for drawing calendars and date pickers. its Display output is
+//! laid out like the Unix `cal` command
+//! ```
+//! # use datetime::calendar::MonthCalendar;
+//! # use datetime::{Day, Month};
+//! let september = MonthCalendar::new(2024, Month::September).with_week_start(Day::Monday);
+//!
+//! let weeks = september.weeks();
+//! assert_eq!(weeks.len(), 6);
+//! assert_eq!(weeks[0], [None, None, None, None, None, None, Some(1)]);
+//!
+//! assert_eq!(september.to_string(), "   September 2024
+//! Mo Tu We Th Fr Sa Su
+//!                    1
+//!  2  3  4  5  6  7  8
+//!  9 10 11 12 13 14 15
+//! 16 17 18 19 20 21 22
+//! 23 24 25 26 27 28 29
+//! 30
+//! ");
+//! ```
+use crate::convert::weekday;
+use crate::{Day, Month};
+use alloc::vec::Vec;
+use core::fmt;
+
+// the days of the week, in order from Sunday
+const DAYS: [Day; 7] = [
+    Day::Sunday,
+    Day::Monday,
+    Day::Tuesday,
+    Day::Wednesday,
+    Day::Thursday,
+    Day::Friday,
+    Day::Saturday,
+];
+
+/// a month of a year, laid out as weeks starting on a given day
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MonthCalendar {
+    year: usize,
+    month: Month,
+    week_start: Day,
+}
+
+impl MonthCalendar {
+    /// returns the calendar for the month, with weeks starting on Sunday
+    pub fn new(year: usize, month: Month) -> Self {
+        MonthCalendar {
+            year,
+            month,
+            week_start: Day::Sunday,
+        }
+    }
+
+    /// returns the calendar with weeks starting on the given day
+    pub fn with_week_start(self, week_start: Day) -> Self {
+        MonthCalendar { week_start, ..self }
+    }
+
+    /// returns the year
+    pub fn year(&self) -> usize {
+        self.year
+    }
+
+    /// returns the month
+    pub fn month(&self) -> Month {
+        self.month
+    }
+
+    /// returns the day weeks start on
+    pub fn week_start(&self) -> Day {
+        self.week_start
+    }
+
+    /// returns the days of the week in the order the calendar's columns
+    /// show them
+    /// ```
+    /// # use datetime::calendar::MonthCalendar;
+    /// # use datetime::{Day, Month};
+    /// let calendar = MonthCalendar::new(2024, Month::May).with_week_start(Day::Saturday);
+    /// assert_eq!(calendar.weekdays()[..2], [Day::Saturday, Day::Sunday]);
+    /// ```
+    pub fn weekdays(&self) -> [Day; 7] {
+        let mut days = DAYS;
+        days.rotate_left(self.week_start as usize);
+        days
+    }
+
+    /// returns the month's weeks, each a row of seven days of the month in
+    /// the order of [`weekdays`](Self::weekdays), with None for the days
+    /// that fall in the month before or after
+    ///
+    /// a month spans four to six weeks
+    pub fn weeks(&self) -> Vec<[Option<usize>; 7]> {
+        let first = weekday(self.year, self.month as usize + 1, 1);
+        let lead = (first as usize + 7 - self.week_start as usize) % 7;
+        let length = self.month.days(self.year);
+
+        let mut weeks = Vec::with_capacity(6);
+        for cell in (0..lead + length).step_by(7) {
+            let mut week = [None; 7];
+            for (column, slot) in week.iter_mut().enumerate() {
+                let date = cell + column + 1;
+                if date > lead && date - lead <= length {
+                    *slot = Some(date - lead);
+                }
+            }
+            weeks.push(week);
+        }
+
+        weeks
+    }
+}
+
+impl fmt::Display for MonthCalendar {
+    /// writes the calendar like the Unix `cal` command: the month and year
+    /// centered over two-letter day headings and a line per week, with no
+    /// trailing spaces
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let title = alloc::format!("{} {}", self.month.name(), self.year);
+        writeln!(f, "{}", alloc::format!("{:^20}", title).trim_end())?;
+
+        let headings: Vec<_> = self
+            .weekdays()
+            .iter()
+            .map(|day| &day.abbreviation()[..2])
+            .collect();
+        writeln!(f, "{}", headings.join(" "))?;
+
+        for week in self.weeks() {
+            let cells: Vec<_> = week
+                .iter()
+                .map(|date| match date {
+                    Some(date) => alloc::format!("{:>2}", date),
+                    None => alloc::string::String::from("  "),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MonthCalendar;
+    use crate::{Day, Month};
+
+    #[test]
+    fn test_weeks() {
+        // February 2015 started on a Sunday and had 28 days
+        let february = MonthCalendar::new(2015, Month::February);
+        let weeks = february.weeks();
+        assert_eq!(weeks.len(), 4);
+        assert_eq!(weeks[0][0], Some(1));
+        assert_eq!(weeks[3][6], Some(28));
+
+        let weeks = february.with_week_start(Day::Monday).weeks();
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0], [None, None, None, None, None, None, Some(1)]);
+        assert_eq!(
+            weeks[4],
+            [
+                Some(23),
+                Some(24),
+                Some(25),
+                Some(26),
+                Some(27),
+                Some(28),
+                None
+            ]
+        );
+
+        // years far past 9999 still fall on the right days
+        assert_eq!(
+            MonthCalendar::new(2024 + 400 * 1_000_000, Month::September).weeks(),
+            MonthCalendar::new(2024, Month::September).weeks()
+        );
+        assert_eq!(MonthCalendar::new(0, Month::January).weeks()[0][6], Some(1));
+    }
+
+    #[test]
+    fn test_display() {
+        let calendar = MonthCalendar::new(2000, Month::February);
+        assert_eq!(
+            calendar.to_string(),
+            "   February 2000\n\
+             Su Mo Tu We Th Fr Sa\n\
+             \x20      1  2  3  4  5\n\
+             \x206  7  8  9 10 11 12\n\
+             13 14 15 16 17 18 19\n\
+             20 21 22 23 24 25 26\n\
+             27 28 29\n"
+        );
+    }
+}
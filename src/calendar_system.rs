@@ -0,0 +1,303 @@
+//! the Julian calendar, and calendar systems in general
+//!
+//! DateTime's own fields use the proleptic Gregorian calendar, but most
+//! records from before 1582 (and many from later, in countries that took
+//! longer to switch) are dated in the Julian calendar, whose only leap year
+//! rule is every fourth year. [`Calendar`] describes how a calendar
+//! numbers its days, and [`DateTime::to_calendar`],
+//! [`DateTime::to_rfc3339_in`] and [`DateTime::parse_rfc3339_in`] read and
+//! write dates in any calendar, such as those of [`CalendarSystem`]
+//! ```
+//! # use datetime::calendar_system::CalendarSystem;
+//! # use datetime::DateTime;
+//! // the day the Gregorian calendar was first used
+//! let date = DateTime::parse_rfc3339("1582-10-15T12:00:00Z").unwrap();
+//!
+//! assert_eq!(date.to_calendar(CalendarSystem::Julian).to_string(), "1582-10-05");
+//! assert_eq!(date.to_rfc3339_in(CalendarSystem::Julian), "1582-10-05T12:00:00Z");
+//!
+//! let parsed = DateTime::parse_rfc3339_in("1582-10-05T12:00:00Z", CalendarSystem::Julian);
+//! assert_eq!(parsed, Ok(date));
+//! ```
+use crate::convert::{self, month_table};
+use crate::parse::{rfc3339_in, Tolerances};
+use crate::{DateTime, FixedOffset, Month, ParseError, MONTHS};
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+/// a calendar of twelve months with the Gregorian month lengths, which
+/// differs only in which years are leap years
+///
+/// days are counted from 1970-01-01 in the proleptic Gregorian calendar,
+/// so the same day count is the same day in every calendar, and months are
+/// numbered 1-12
+pub trait Calendar {
+    /// returns true if the given year has a 29th of February
+    fn is_leap_year(&self, year: i64) -> bool;
+
+    /// returns the number of days in the given month of the given year
+    ///
+    /// # Panics
+    ///
+    /// panics if `month` is not 1-12
+    fn days_in_month(&self, year: i64, month: usize) -> usize {
+        assert!((1..=12).contains(&month), "month must be 1-12");
+        let table = month_table(self.is_leap_year(year));
+        (table[month] - table[month - 1]) as usize
+    }
+
+    /// returns the number of days between 1970-01-01 and the given date,
+    /// which is negative for dates before it
+    fn days_from_civil(&self, year: i64, month: usize, day: usize) -> i64;
+
+    /// returns the (year, month, day) of the date the given number of days
+    /// after 1970-01-01; the inverse of
+    /// [`days_from_civil`](Self::days_from_civil)
+    fn civil_from_days(&self, days: i64) -> (i64, usize, usize);
+}
+
+/// the proleptic Gregorian calendar that DateTime uses
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Gregorian;
+
+impl Calendar for Gregorian {
+    fn is_leap_year(&self, year: i64) -> bool {
+        // leap years repeat every 400 years
+        convert::is_leap_year(year.rem_euclid(400) as usize)
+    }
+
+    fn days_from_civil(&self, year: i64, month: usize, day: usize) -> i64 {
+        convert::days_from_civil(year, month, day)
+    }
+
+    fn civil_from_days(&self, days: i64) -> (i64, usize, usize) {
+        convert::civil_from_days(days)
+    }
+}
+
+// the number of days from 0000-03-01 in the Julian calendar, which was
+// 0000-02-28 in the Gregorian one, to 1970-01-01
+const JULIAN_EPOCH_DAYS: i64 = 719_470;
+
+/// the proleptic Julian calendar, with a leap year every fourth year
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Julian;
+
+impl Calendar for Julian {
+    fn is_leap_year(&self, year: i64) -> bool {
+        year.rem_euclid(4) == 0
+    }
+
+    fn days_from_civil(&self, year: i64, month: usize, day: usize) -> i64 {
+        // like the Gregorian version, counts from March so that leap days
+        // fall at the end of four-year cycles
+        let year = if month <= 2 { year - 1 } else { year };
+        let month = month as i64;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+
+        year * 365 + year.div_euclid(4) + doy - JULIAN_EPOCH_DAYS
+    }
+
+    fn civil_from_days(&self, days: i64) -> (i64, usize, usize) {
+        let days = days + JULIAN_EPOCH_DAYS;
+        let cycle = days.div_euclid(1461);
+        let doc = days.rem_euclid(1461);
+        let yoc = (doc - doc / 1460) / 365;
+        let doy = doc - 365 * yoc;
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = cycle * 4 + yoc + if month <= 2 { 1 } else { 0 };
+
+        (year, month as usize, day as usize)
+    }
+}
+
+/// the calendar systems this crate knows, for choosing one at runtime
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum CalendarSystem {
+    #[default]
+    Gregorian,
+    Julian,
+}
+
+impl Calendar for CalendarSystem {
+    fn is_leap_year(&self, year: i64) -> bool {
+        match self {
+            CalendarSystem::Gregorian => Gregorian.is_leap_year(year),
+            CalendarSystem::Julian => Julian.is_leap_year(year),
+        }
+    }
+
+    fn days_from_civil(&self, year: i64, month: usize, day: usize) -> i64 {
+        match self {
+            CalendarSystem::Gregorian => Gregorian.days_from_civil(year, month, day),
+            CalendarSystem::Julian => Julian.days_from_civil(year, month, day),
+        }
+    }
+
+    fn civil_from_days(&self, days: i64) -> (i64, usize, usize) {
+        match self {
+            CalendarSystem::Gregorian => Gregorian.civil_from_days(days),
+            CalendarSystem::Julian => Julian.civil_from_days(days),
+        }
+    }
+}
+
+/// a date in some calendar, as returned by [`DateTime::to_calendar`]
+///
+/// its Display output is `YYYY-MM-DD`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CalendarDate {
+    year: i64,
+    month: Month,
+    date: usize,
+}
+
+impl CalendarDate {
+    /// returns the year
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    /// returns the month
+    pub fn month(&self) -> Month {
+        self.month
+    }
+
+    /// returns the day of the month, starting from 1
+    pub fn date(&self) -> usize {
+        self.date
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            self.year,
+            self.month as usize + 1,
+            self.date
+        )
+    }
+}
+
+impl DateTime {
+    /// returns the DateTime's date, in its own offset, in the given
+    /// calendar
+    /// ```
+    /// # use datetime::calendar_system::{CalendarSystem, Julian};
+    /// # use datetime::{DateTime, Month};
+    /// let date = DateTime::from_secs(0).to_calendar(Julian);
+    /// assert_eq!((date.year(), date.month(), date.date()), (1969, Month::December, 19));
+    ///
+    /// let date = DateTime::from_secs(0).to_calendar(CalendarSystem::Gregorian);
+    /// assert_eq!(date.to_string(), "1970-01-01");
+    /// ```
+    pub fn to_calendar<C: Calendar>(&self, calendar: C) -> CalendarDate {
+        let local = self.secs + self.offset.seconds() as i64;
+        let (year, month, date) = calendar.civil_from_days(local.div_euclid(86_400));
+
+        CalendarDate {
+            year,
+            month: MONTHS[month - 1],
+            date,
+        }
+    }
+
+    /// formats the DateTime as an RFC 3339 timestamp like
+    /// [`DateTime::to_rfc3339`], but with the date in the given calendar
+    pub fn to_rfc3339_in<C: Calendar>(&self, calendar: C) -> String {
+        let gregorian = self.to_rfc3339();
+        // everything from the time on is the same in any calendar
+        let time = gregorian.find('T').expect("RFC 3339 timestamps have a T");
+        format!("{}{}", self.to_calendar(calendar), &gregorian[time..])
+    }
+
+    /// parses an RFC 3339 timestamp like [`DateTime::parse_rfc3339`], but
+    /// with the date in the given calendar
+    /// ```
+    /// # use datetime::calendar_system::Julian;
+    /// # use datetime::{DateTime, ParseError};
+    /// // 1500 was a leap year in the Julian calendar, but not the Gregorian
+    /// let date = DateTime::parse_rfc3339_in("1500-02-29T00:00:00Z", Julian).unwrap();
+    /// assert_eq!(date.to_rfc3339(), "1500-03-10T00:00:00Z");
+    ///
+    /// let error = DateTime::parse_rfc3339("1500-02-29T00:00:00Z");
+    /// assert_eq!(error, Err(ParseError::OutOfRange));
+    /// ```
+    pub fn parse_rfc3339_in<C: Calendar>(input: &str, calendar: C) -> Result<DateTime, ParseError> {
+        rfc3339_in(input, &calendar, Tolerances::RFC3339).map(|(date, _)| date)
+    }
+
+    /// parses an RFC 3339 timestamp like
+    /// [`DateTime::parse_rfc3339_preserving_offset`], but with the date in
+    /// the given calendar
+    pub fn parse_rfc3339_in_preserving_offset<C: Calendar>(
+        input: &str,
+        calendar: C,
+    ) -> Result<(DateTime, FixedOffset), ParseError> {
+        rfc3339_in(input, &calendar, Tolerances::RFC3339)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Calendar, CalendarSystem, Gregorian, Julian};
+    use crate::{DateTime, FixedOffset, Month, ParseError, MAX_SECS, MIN_SECS};
+
+    #[test]
+    fn test_julian_round_trip() {
+        for days in (-800_000..800_000).step_by(97) {
+            let (year, month, date) = Julian.civil_from_days(days);
+            assert!((1..=12).contains(&month));
+            assert!((1..=Julian.days_in_month(year, month)).contains(&date));
+            assert_eq!(Julian.days_from_civil(year, month, date), days);
+        }
+
+        assert_eq!(Julian.civil_from_days(-1), (1969, 12, 18));
+        assert_eq!(Julian.days_in_month(1900, 2), 29);
+        assert_eq!(Gregorian.days_in_month(1900, 2), 28);
+        assert_eq!(Gregorian.days_in_month(-4, 2), 29);
+        assert!(!Julian.is_leap_year(-1) && Julian.is_leap_year(-4));
+
+        // the first supported second is 0001-01-03 in the Julian calendar
+        let first = DateTime::from_signed_secs(MIN_SECS).to_calendar(Julian);
+        assert_eq!(first.to_string(), "0001-01-03");
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        let (year, _, _) = Gregorian.civil_from_days(MAX_SECS / 86_400);
+        assert_eq!(last.to_calendar(CalendarSystem::Gregorian).year(), year);
+        assert!(last.to_calendar(Julian).year() < year);
+    }
+
+    #[test]
+    fn test_render_and_parse() {
+        let offset = FixedOffset::east(3600).unwrap();
+        let date = DateTime::parse_rfc3339("1066-10-14T08:30:00.25Z")
+            .unwrap()
+            .with_offset(offset);
+
+        let julian = date.to_calendar(CalendarSystem::Julian);
+        assert_eq!((julian.year(), julian.month()), (1066, Month::October));
+        assert_eq!(julian.date(), 8);
+        let text = date.to_rfc3339_in(Julian);
+        assert_eq!(text, "1066-10-08T09:30:00.250+01:00");
+        assert_eq!(
+            DateTime::parse_rfc3339_in_preserving_offset(&text, Julian),
+            Ok((date, offset))
+        );
+
+        assert_eq!(
+            DateTime::parse_rfc3339_in("1500-02-30T00:00:00Z", Julian),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339_in("0001-01-02T23:59:59Z", Julian),
+            Err(ParseError::OutOfRange)
+        );
+        assert!(DateTime::parse_rfc3339_in("0001-01-03T00:00:00Z", Julian).is_ok());
+    }
+}
@@ -0,0 +1,287 @@
+//! the validity periods of X.509 certificates
+//!
+//! a certificate's `notBefore` and `notAfter` times are ASN.1 UTCTime
+//! (`YYMMDDHHMMSSZ`) for years up to 2049, and GeneralizedTime
+//! (`YYYYMMDDHHMMSSZ`) after that
+//! ([RFC 5280 §4.1.2.5](https://www.rfc-editor.org/rfc/rfc5280#section-4.1.2.5)).
+//! [`CertValidity`] reads either form and answers the questions monitoring
+//! tools ask
+//! ```
+//! # use datetime::cert::{CertStatus, CertValidity};
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let validity = CertValidity::from_asn1("960909000000Z", "20500101000000Z").unwrap();
+//! let now = DateTime::from_secs(842282624);
+//!
+//! assert!(validity.is_valid_at(&now));
+//! assert_eq!(validity.days_until_expiry(&now), 19_471);
+//!
+//! let warn_within = Duration::from_secs(30 * 86_400);
+//! let status = validity.status(&DateTime::from_secs(2524608000 - 86_400 * 3), warn_within);
+//! assert_eq!(status, CertStatus::ExpiringSoon(3));
+//! assert_eq!(status.to_string(), "expires in 3 days");
+//! ```
+use crate::convert::{days_from_civil, is_valid_date, is_valid_time};
+use crate::{AbsoluteTime, DateTime, FixedOffset, Interval, ParseError, MAX_SECS};
+use core::fmt;
+use core::time::Duration;
+
+impl DateTime {
+    /// parses an ASN.1 UTCTime as used in certificates, `YYMMDDHHMMSSZ`;
+    /// two-digit years from 50 onwards are taken to be in the 1900s, and
+    /// earlier ones in the 2000s
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_utc_time("960909152344Z").unwrap();
+    ///
+    /// assert_eq!(date.to_rfc3339(), "1996-09-09T15:23:44Z");
+    /// ```
+    pub fn parse_utc_time(input: &str) -> Result<DateTime, ParseError> {
+        let time = asn1_time(input, 2)?;
+        Ok(time.to_datetime(FixedOffset::utc()))
+    }
+
+    /// parses an ASN.1 GeneralizedTime as used in certificates,
+    /// `YYYYMMDDHHMMSSZ`, without fractional seconds
+    pub fn parse_generalized_time(input: &str) -> Result<DateTime, ParseError> {
+        let time = asn1_time(input, 4)?;
+        Ok(time.to_datetime(FixedOffset::utc()))
+    }
+}
+
+// reads a UTCTime (with two year digits) or GeneralizedTime (with four)
+fn asn1_time(input: &str, year_digits: usize) -> Result<AbsoluteTime, ParseError> {
+    let bytes = input.as_bytes();
+    let len = year_digits + 11;
+    if let Some(pos) = bytes.iter().take(len - 1).position(|b| !b.is_ascii_digit()) {
+        return Err(ParseError::Invalid(pos));
+    }
+    if bytes.len() < len {
+        return Err(ParseError::TooShort);
+    }
+    if bytes[len - 1] != b'Z' {
+        return Err(ParseError::Invalid(len - 1));
+    }
+    if bytes.len() > len {
+        return Err(ParseError::TooLong);
+    }
+
+    let field = |from: usize, digits: usize| {
+        bytes[from..from + digits]
+            .iter()
+            .fold(0, |n, &b| n * 10 + (b - b'0') as usize)
+    };
+    let year = match (year_digits, field(0, year_digits)) {
+        (2, year) if year >= 50 => 1900 + year,
+        (2, year) => 2000 + year,
+        (_, year) => year,
+    };
+    let at = year_digits;
+    let (month, date) = (field(at, 2), field(at + 2, 2));
+    let (hour, minute, second) = (field(at + 4, 2), field(at + 6, 2), field(at + 8, 2));
+
+    if is_valid_date(year, month, date).is_err() || is_valid_time(hour, minute, second).is_err() {
+        return Err(ParseError::OutOfRange);
+    }
+
+    let days = days_from_civil(year as i64, month, date);
+    let secs = days * 86_400 + (hour * 3600 + minute * 60 + second) as i64;
+    AbsoluteTime::new(secs, 0).ok_or(ParseError::OutOfRange)
+}
+
+/// the period a certificate is valid for, including both ends
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CertValidity {
+    pub not_before: AbsoluteTime,
+    pub not_after: AbsoluteTime,
+}
+
+impl CertValidity {
+    /// returns the validity period between two DateTimes
+    pub fn new(not_before: &DateTime, not_after: &DateTime) -> Self {
+        CertValidity {
+            not_before: AbsoluteTime::from(not_before),
+            not_after: AbsoluteTime::from(not_after),
+        }
+    }
+
+    /// returns the validity period from `notBefore` and `notAfter` values,
+    /// each either a UTCTime or a GeneralizedTime
+    pub fn from_asn1(not_before: &str, not_after: &str) -> Result<Self, ParseError> {
+        let parse = |input: &str| match input.len() {
+            13 => asn1_time(input, 2),
+            _ => asn1_time(input, 4),
+        };
+
+        Ok(CertValidity {
+            not_before: parse(not_before)?,
+            not_after: parse(not_after)?,
+        })
+    }
+
+    /// returns true if the certificate is valid at the given time
+    pub fn is_valid_at(&self, time: &DateTime) -> bool {
+        let time = AbsoluteTime::from(time);
+        self.not_before <= time && time <= self.not_after
+    }
+
+    /// returns the number of whole days from `now` until the certificate
+    /// expires, which is negative once it has
+    pub fn days_until_expiry(&self, now: &DateTime) -> i64 {
+        let secs = self.not_after.seconds() - now.secs;
+        secs.div_euclid(86_400)
+    }
+
+    /// returns the validity period as a half-open [`Interval`] of whole
+    /// seconds
+    pub fn interval(&self) -> Interval {
+        // an Interval can't end past the last DateTime
+        let end = (self.not_after.seconds() + 1).min(MAX_SECS);
+        Interval::from_signed_secs(self.not_before.seconds(), end)
+    }
+
+    /// returns the certificate's status at `now`, warning if it expires
+    /// within `warn_within`
+    pub fn status(&self, now: &DateTime, warn_within: Duration) -> CertStatus {
+        let now_time = AbsoluteTime::from(now);
+        if now_time < self.not_before {
+            let secs = self.not_before.seconds() - now.secs;
+            return CertStatus::NotYetValid((secs + 86_399) / 86_400);
+        }
+
+        let days = self.days_until_expiry(now);
+        if now_time > self.not_after {
+            let secs = now.secs - self.not_after.seconds();
+            CertStatus::Expired(secs / 86_400)
+        } else if (self.not_after.seconds() - now.secs) as u64 <= warn_within.as_secs() {
+            CertStatus::ExpiringSoon(days)
+        } else {
+            CertStatus::Valid(days)
+        }
+    }
+}
+
+/// the state of a certificate at some time, with a count of whole days
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CertStatus {
+    /// not valid yet, for this many more days (rounded up)
+    NotYetValid(i64),
+    /// valid, for this many more whole days
+    Valid(i64),
+    /// valid, but expiring soon, after this many more whole days
+    ExpiringSoon(i64),
+    /// expired this many whole days ago
+    Expired(i64),
+}
+
+impl CertStatus {
+    /// returns true if the certificate is valid, even if it expires soon
+    pub fn is_valid(&self) -> bool {
+        matches!(self, CertStatus::Valid(_) | CertStatus::ExpiringSoon(_))
+    }
+}
+
+// "1 day" or "n days"
+struct Days(i64);
+
+impl fmt::Display for Days {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            1 => f.write_str("1 day"),
+            n => write!(f, "{} days", n),
+        }
+    }
+}
+
+impl fmt::Display for CertStatus {
+    /// writes a short warning such as `expires in 3 days`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CertStatus::NotYetValid(days) => write!(f, "not valid for another {}", Days(days)),
+            CertStatus::Valid(days) => write!(f, "valid for {}", Days(days)),
+            CertStatus::ExpiringSoon(0) => f.write_str("expires today"),
+            CertStatus::ExpiringSoon(days) => write!(f, "expires in {}", Days(days)),
+            CertStatus::Expired(0) => f.write_str("expired today"),
+            CertStatus::Expired(days) => write!(f, "expired {} ago", Days(days)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CertStatus, CertValidity};
+    use crate::{DateTime, ParseError};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_asn1_times() {
+        let date = DateTime::parse_utc_time("491231235959Z").unwrap();
+        assert_eq!(date.to_rfc3339(), "2049-12-31T23:59:59Z");
+        let date = DateTime::parse_utc_time("500101000000Z").unwrap();
+        assert_eq!(date.to_rfc3339(), "1950-01-01T00:00:00Z");
+        let date = DateTime::parse_generalized_time("20500101000000Z").unwrap();
+        assert_eq!(date.to_rfc3339(), "2050-01-01T00:00:00Z");
+
+        assert_eq!(
+            DateTime::parse_utc_time("9609091523Z"),
+            Err(ParseError::Invalid(10))
+        );
+        assert_eq!(
+            DateTime::parse_utc_time("960909152344"),
+            Err(ParseError::TooShort)
+        );
+        assert_eq!(
+            DateTime::parse_utc_time("960909152344+0000"),
+            Err(ParseError::Invalid(12))
+        );
+        assert_eq!(
+            DateTime::parse_utc_time("960230000000Z"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_generalized_time("20500101000000.5Z"),
+            Err(ParseError::Invalid(14))
+        );
+    }
+
+    #[test]
+    fn test_validity() {
+        let validity = CertValidity::new(&DateTime::from_secs(1000), &DateTime::from_secs(2000));
+        assert!(validity.is_valid_at(&DateTime::from_secs(2000)));
+        assert!(!validity.is_valid_at(&DateTime::from_millis(2_000_001)));
+        assert!(!validity.is_valid_at(&DateTime::from_secs(999)));
+        assert_eq!(validity.interval().duration(), Duration::from_secs(1001));
+
+        assert_eq!(validity.days_until_expiry(&DateTime::from_secs(2001)), -1);
+        assert_eq!(
+            validity.days_until_expiry(&DateTime::from_signed_secs(2000 - 86_400)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_status() {
+        let day = |days: i64| DateTime::from_signed_secs(days * 86_400);
+        let validity = CertValidity::new(&day(10), &day(40));
+        let warn = Duration::from_secs(7 * 86_400);
+
+        let status = validity.status(&day(9), warn);
+        assert_eq!(status, CertStatus::NotYetValid(1));
+        assert_eq!(status.to_string(), "not valid for another 1 day");
+
+        assert_eq!(
+            validity.status(&day(20), warn).to_string(),
+            "valid for 20 days"
+        );
+        assert!(validity.status(&day(35), warn).is_valid());
+        assert_eq!(validity.status(&day(40), warn).to_string(), "expires today");
+
+        let status = validity.status(&day(42), warn);
+        assert_eq!(status.to_string(), "expired 2 days ago");
+        assert!(!status.is_valid());
+        assert_eq!(
+            validity.status(&day(40).saturating_add_secs(1), warn),
+            CertStatus::Expired(0)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! conversions to and from the `chrono` crate, enabled with the `chrono`
+//! feature
+//!
+//! - `chrono::DateTime<Tz>` converts into a DateTime with `TryFrom`,
+//!   keeping its offset, and a DateTime converts into a
+//!   `chrono::DateTime<Utc>` the same way. either fails with
+//!   [`RangeError`] if the other side can't hold the instant
+//! - [`Day`] and [`Month`] convert to and from `chrono::Weekday` and
+//!   `chrono::Month` with `From`
+//! ```
+//! # use datetime::DateTime;
+//! # use std::convert::TryFrom;
+//! use chrono::{TimeZone, Utc};
+//!
+//! let theirs = Utc.timestamp_opt(842282624, 0).unwrap();
+//! let ours = DateTime::try_from(theirs).unwrap();
+//!
+//! assert_eq!(ours.as_time_stamp(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+//! assert_eq!(chrono::DateTime::<Utc>::try_from(&ours).unwrap(), theirs);
+//! ```
+use crate::{AbsoluteTime, DateTime, Day, FixedOffset, Month, RangeError, MONTHS};
+use ::chrono::{Offset, TimeZone, Utc, Weekday};
+use core::convert::TryFrom;
+
+impl<Tz: TimeZone> TryFrom<::chrono::DateTime<Tz>> for DateTime {
+    type Error = RangeError;
+
+    /// converts a chrono DateTime, keeping its offset from UTC. chrono
+    /// shows a leap second as an extra second of nanoseconds, which is
+    /// rolled over into the next second here
+    fn try_from(date: ::chrono::DateTime<Tz>) -> Result<Self, Self::Error> {
+        let offset = date.offset().fix().local_minus_utc();
+        let offset = FixedOffset::east(offset).ok_or(RangeError)?;
+
+        let mut secs = date.timestamp();
+        let mut nanos = date.timestamp_subsec_nanos();
+        if nanos >= 1_000_000_000 {
+            secs = secs.checked_add(1).ok_or(RangeError)?;
+            nanos -= 1_000_000_000;
+        }
+
+        let instant = AbsoluteTime::new(secs, nanos).ok_or(RangeError)?;
+        Ok(instant.to_datetime(offset))
+    }
+}
+
+impl TryFrom<&DateTime> for ::chrono::DateTime<Utc> {
+    type Error = RangeError;
+
+    /// converts a DateTime into a chrono DateTime in UTC
+    fn try_from(date: &DateTime) -> Result<Self, Self::Error> {
+        Utc.timestamp_opt(date.secs, date.nanos)
+            .single()
+            .ok_or(RangeError)
+    }
+}
+
+impl From<Weekday> for Day {
+    fn from(day: Weekday) -> Self {
+        match day {
+            Weekday::Sun => Day::Sunday,
+            Weekday::Mon => Day::Monday,
+            Weekday::Tue => Day::Tuesday,
+            Weekday::Wed => Day::Wednesday,
+            Weekday::Thu => Day::Thursday,
+            Weekday::Fri => Day::Friday,
+            Weekday::Sat => Day::Saturday,
+        }
+    }
+}
+
+impl From<Day> for Weekday {
+    fn from(day: Day) -> Self {
+        match day {
+            Day::Sunday => Weekday::Sun,
+            Day::Monday => Weekday::Mon,
+            Day::Tuesday => Weekday::Tue,
+            Day::Wednesday => Weekday::Wed,
+            Day::Thursday => Weekday::Thu,
+            Day::Friday => Weekday::Fri,
+            Day::Saturday => Weekday::Sat,
+        }
+    }
+}
+
+impl From<::chrono::Month> for Month {
+    fn from(month: ::chrono::Month) -> Self {
+        MONTHS[month.number_from_month() as usize - 1]
+    }
+}
+
+impl From<Month> for ::chrono::Month {
+    fn from(month: Month) -> Self {
+        ::chrono::Month::try_from(month as u8 + 1).expect("every Month is a chrono Month")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, Day, FixedOffset, Month, RangeError};
+    use ::chrono::{FixedOffset as ChronoOffset, TimeZone, Utc, Weekday};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_datetime_round_trip() {
+        let zone = ChronoOffset::west_opt(16200).unwrap();
+        let theirs = zone.timestamp_opt(-1, 500).unwrap();
+
+        let ours = DateTime::try_from(theirs).unwrap();
+        assert_eq!((ours.secs, ours.nanos), (-1, 500));
+        assert_eq!(ours.offset(), FixedOffset::west(16200).unwrap());
+        assert_eq!(
+            ::chrono::DateTime::<Utc>::try_from(&ours).unwrap(),
+            theirs.with_timezone(&Utc)
+        );
+
+        // chrono can't reach the far future a DateTime can
+        let far = DateTime::MAX;
+        assert_eq!(::chrono::DateTime::<Utc>::try_from(&far), Err(RangeError));
+        // nor can a DateTime reach before the year 1
+        let early = Utc.with_ymd_and_hms(0, 12, 31, 0, 0, 0).unwrap();
+        assert!(DateTime::try_from(early).is_err());
+    }
+
+    #[test]
+    fn test_day_and_month() {
+        assert_eq!(Day::from(Weekday::Sun), Day::Sunday);
+        assert_eq!(Weekday::from(Day::Saturday), Weekday::Sat);
+        assert_eq!(Month::from(::chrono::Month::January), Month::January);
+        assert_eq!(
+            ::chrono::Month::from(Month::December),
+            ::chrono::Month::December
+        );
+    }
+}
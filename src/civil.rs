@@ -0,0 +1,340 @@
+//! separate types for instants and wall-clock times
+//!
+//! a [`DateTime`] carries both an instant and the offset its calendar
+//! fields are reported in, which makes it easy to read local fields as if
+//! they were UTC. [`AbsoluteTime`] is only an instant, with no calendar
+//! fields at all, and [`CivilTime`] is only a set of wall-clock fields, with
+//! no instant; converting between the two always takes an explicit offset
+//! (or, with the `tz` feature, a time zone)
+//! ```
+//! # use datetime::{AbsoluteTime, CivilTime, FixedOffset};
+//! let meeting = CivilTime::new(1996, 9, 9, 17, 23, 44).unwrap();
+//! let berlin = FixedOffset::east(7200).unwrap();
+//!
+//! let instant = meeting.to_absolute(berlin);
+//! assert_eq!(instant.seconds(), 842282624);
+//! assert_eq!(instant.to_civil(FixedOffset::utc()).hour(), 15);
+//! ```
+use crate::convert::{days_from_civil, is_valid_date, is_valid_time};
+use crate::{DateTime, FixedOffset, InvalidDate, Month, MAX_SECS, MIN_SECS, MONTHS};
+use core::fmt;
+
+/// an instant in time, with no calendar fields or offset
+///
+/// AbsoluteTimes compare and hash by the instant they represent
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AbsoluteTime {
+    secs: i64,
+    nanos: u32,
+}
+
+impl AbsoluteTime {
+    /// returns the instant the given number of seconds (and nanoseconds
+    /// into the following second) after the Unix epoch, or None if it is
+    /// outside the range of a DateTime or `nanos` is not less than a second
+    pub fn new(secs: i64, nanos: u32) -> Option<Self> {
+        if !(MIN_SECS..=MAX_SECS).contains(&secs) || nanos >= 1_000_000_000 {
+            None
+        } else {
+            Some(AbsoluteTime { secs, nanos })
+        }
+    }
+
+    /// returns the number of whole seconds since the Unix epoch, rounded
+    /// towards the past
+    pub fn seconds(&self) -> i64 {
+        self.secs
+    }
+
+    /// returns the fraction of the second in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+
+    /// returns the wall-clock time at this instant in a zone `offset`
+    /// ahead of UTC
+    pub fn to_civil(&self, offset: FixedOffset) -> CivilTime {
+        CivilTime::from(&self.to_datetime(offset))
+    }
+
+    /// returns a DateTime for this instant with its fields reported in
+    /// the given offset
+    pub fn to_datetime(&self, offset: FixedOffset) -> DateTime {
+        DateTime::from_parts(self.secs, self.nanos, offset)
+    }
+}
+
+impl From<&DateTime> for AbsoluteTime {
+    fn from(date: &DateTime) -> Self {
+        AbsoluteTime {
+            secs: date.secs,
+            nanos: date.nanos,
+        }
+    }
+}
+
+/// a wall-clock date and time, with no offset or time zone
+///
+/// CivilTimes compare by their fields, so two CivilTimes from different
+/// zones are ordered as their clocks read, not by when they happened
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilTime {
+    year: usize,
+    // 1-12, kept as a number so that CivilTimes can be ordered
+    month: usize,
+    date: usize,
+    hour: usize,
+    minute: usize,
+    second: usize,
+    nanos: u32,
+}
+
+impl CivilTime {
+    /// returns the wall-clock time with the given fields, where `month` is
+    /// 1-12, or the first field that is out of range; the year can't be 0
+    /// ```
+    /// # use datetime::{CivilTime, InvalidDate};
+    /// assert!(CivilTime::new(2000, 2, 29, 23, 59, 59).is_ok());
+    /// assert_eq!(CivilTime::new(1900, 2, 29, 0, 0, 0), Err(InvalidDate::LeapDay));
+    /// assert_eq!(CivilTime::new(1900, 2, 30, 0, 0, 0), Err(InvalidDate::Date));
+    /// ```
+    pub fn new(
+        year: usize,
+        month: usize,
+        date: usize,
+        hour: usize,
+        minute: usize,
+        second: usize,
+    ) -> Result<Self, InvalidDate> {
+        is_valid_date(year, month, date)?;
+        is_valid_time(hour, minute, second)?;
+
+        Ok(CivilTime {
+            year,
+            month,
+            date,
+            hour,
+            minute,
+            second,
+            nanos: 0,
+        })
+    }
+
+    // the wall-clock time with the given fields, which must be in range
+    pub(crate) const fn from_fields(
+        (year, month, date): (usize, usize, usize),
+        (hour, minute, second): (usize, usize, usize),
+        nanos: u32,
+    ) -> Self {
+        CivilTime {
+            year,
+            month,
+            date,
+            hour,
+            minute,
+            second,
+            nanos,
+        }
+    }
+
+    /// returns the same wall-clock time with the given fraction of a
+    /// second, or None if `nanos` is not less than a second
+    pub fn with_nanosecond(&self, nanos: u32) -> Option<Self> {
+        if nanos >= 1_000_000_000 {
+            return None;
+        }
+
+        Some(CivilTime { nanos, ..*self })
+    }
+
+    /// returns the year
+    pub fn year(&self) -> usize {
+        self.year
+    }
+
+    /// returns the month
+    pub fn month(&self) -> Month {
+        MONTHS[self.month - 1]
+    }
+
+    /// returns the day of the month
+    pub fn date(&self) -> usize {
+        self.date
+    }
+
+    /// returns the hour
+    pub fn hour(&self) -> usize {
+        self.hour
+    }
+
+    /// returns the minute
+    pub fn minute(&self) -> usize {
+        self.minute
+    }
+
+    /// returns the second
+    pub fn second(&self) -> usize {
+        self.second
+    }
+
+    /// returns the fraction of the second in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+
+    /// returns the instant at which clocks in a zone `offset` ahead of UTC
+    /// show this time
+    ///
+    /// # Panics
+    ///
+    /// panics if that instant is outside the range of a DateTime, i.e.
+    /// before the year 1 or after [`DateTime::MAX`] in UTC; see
+    /// [`CivilTime::checked_to_absolute`] for a version that doesn't
+    pub fn to_absolute(&self, offset: FixedOffset) -> AbsoluteTime {
+        self.checked_to_absolute(offset)
+            .expect("time is outside the range of a DateTime")
+    }
+
+    /// returns the instant at which clocks in a zone `offset` ahead of UTC
+    /// show this time, or None if it is outside the range of a DateTime
+    /// ```
+    /// # use datetime::{CivilTime, DateTime, FixedOffset};
+    /// let last = DateTime::MAX.year();
+    /// let time = CivilTime::new(last, 12, 31, 0, 0, 0).unwrap();
+    ///
+    /// assert!(time.checked_to_absolute(FixedOffset::utc()).is_none());
+    /// ```
+    pub fn checked_to_absolute(&self, offset: FixedOffset) -> Option<AbsoluteTime> {
+        // days_from_civil overflows for years far past the last DateTime
+        if self.year > DateTime::MAX.year() {
+            return None;
+        }
+
+        let days = days_from_civil(self.year as i64, self.month, self.date);
+        let secs = days
+            .checked_mul(86_400)?
+            .checked_add((self.hour * 3600 + self.minute * 60 + self.second) as i64)?
+            .checked_sub(offset.seconds() as i64)?;
+
+        AbsoluteTime::new(secs, self.nanos)
+    }
+
+    /// resolves this wall-clock time in a time zone; see
+    /// [`TimeZone::from_local`](crate::tz::TimeZone::from_local)
+    #[cfg(feature = "tz")]
+    pub fn in_zone<Z: crate::tz::TimeZone + ?Sized>(
+        &self,
+        tz: &Z,
+    ) -> Option<crate::tz::LocalResult> {
+        tz.from_local(
+            self.year,
+            self.month,
+            self.date,
+            self.hour,
+            self.minute,
+            self.second,
+        )
+    }
+}
+
+impl From<&DateTime> for CivilTime {
+    /// returns the DateTime's fields as reported in its own offset
+    fn from(date: &DateTime) -> Self {
+        CivilTime {
+            year: date.year(),
+            month: date.month() as usize + 1,
+            date: date.date(),
+            hour: date.hour(),
+            minute: date.minute(),
+            second: date.second(),
+            nanos: date.nanos,
+        }
+    }
+}
+
+impl fmt::Display for CivilTime {
+    /// formats the time as `yyyy-mm-ddThh:mm:ss`, with a fraction if it
+    /// is not a whole second
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.date, self.hour, self.minute, self.second
+        )?;
+        if self.nanos != 0 {
+            write!(f, ".{:09}", self.nanos)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AbsoluteTime, CivilTime};
+    use crate::{DateTime, FixedOffset, InvalidDate, Month};
+
+    #[test]
+    fn test_round_trip() {
+        let offset = FixedOffset::west(16200).unwrap();
+        let instant = AbsoluteTime::new(-1, 500).unwrap();
+
+        let civil = instant.to_civil(offset);
+        assert_eq!(civil.to_string(), "1969-12-31T19:29:59.000000500");
+        assert_eq!(civil.month(), Month::December);
+        assert_eq!(civil.to_absolute(offset), instant);
+
+        let date = DateTime::from_millis(842282624123);
+        assert_eq!(AbsoluteTime::from(&date).to_datetime(offset), date);
+    }
+
+    #[test]
+    fn test_civil_ordering() {
+        let morning = CivilTime::new(1996, 9, 9, 9, 0, 0).unwrap();
+        let evening = CivilTime::new(1996, 9, 9, 21, 0, 0).unwrap();
+        assert!(morning < evening);
+
+        // 09:00 in Tokyo happens before 21:00 the previous day in New York
+        let tokyo = morning.to_absolute(FixedOffset::east(9 * 3600).unwrap());
+        let new_york = CivilTime::new(1996, 9, 8, 21, 0, 0).unwrap();
+        let new_york = new_york.to_absolute(FixedOffset::west(4 * 3600).unwrap());
+        assert!(tokyo < new_york);
+
+        assert_eq!(CivilTime::new(0, 1, 1, 0, 0, 0), Err(InvalidDate::Year));
+        assert!(morning.with_nanosecond(1_000_000_000).is_none());
+    }
+
+    #[test]
+    fn test_checked_to_absolute() {
+        let utc = FixedOffset::utc();
+        let first = CivilTime::new(1, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            first.checked_to_absolute(utc),
+            Some(AbsoluteTime::from(&DateTime::MIN))
+        );
+        assert!(first
+            .checked_to_absolute(FixedOffset::east(1).unwrap())
+            .is_none());
+
+        let last = CivilTime::new(DateTime::MAX.year(), 12, 30, 23, 59, 59).unwrap();
+        assert!(last.checked_to_absolute(utc).is_some());
+        assert!(last
+            .checked_to_absolute(FixedOffset::west(1).unwrap())
+            .is_none());
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let far = CivilTime::new(1 << 62, 1, 1, 0, 0, 0).unwrap();
+            assert!(far.checked_to_absolute(utc).is_none());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the range of a DateTime")]
+    fn test_to_absolute_panics() {
+        let last = DateTime::MAX.year();
+        CivilTime::new(last, 12, 31, 0, 0, 0)
+            .unwrap()
+            .to_absolute(FixedOffset::utc());
+    }
+}
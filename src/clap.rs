@@ -0,0 +1,188 @@
+//! command-line parsing with `clap`, enabled with the `clap` feature
+//!
+//! [`Day`] and [`Month`] implement `ValueEnum`, accepting their lowercase
+//! English names or three-letter abbreviations, and listing them in help
+//! text. [`AbsoluteTime`] can be taken as an argument with
+//! `value_parser!(AbsoluteTime)`, accepting an RFC 3339 timestamp or a
+//! bare `yyyy-mm-dd` date meaning midnight UTC
+//!
+//! clap needs argument values to be `Clone + Send + Sync`, which DateTime
+//! isn't, so arguments are AbsoluteTimes; convert them with
+//! [`AbsoluteTime::to_datetime`]
+//! ```
+//! # use datetime::{AbsoluteTime, Day, FixedOffset};
+//! use clap::{value_parser, Arg, Command};
+//!
+//! let cmd = Command::new("report")
+//!     .arg(Arg::new("since").long("since").value_parser(value_parser!(AbsoluteTime)))
+//!     .arg(Arg::new("weekday").long("weekday").value_parser(value_parser!(Day)));
+//!
+//! let matches = cmd.get_matches_from(["report", "--since", "2024-01-01", "--weekday", "fri"]);
+//!
+//! let since = matches.get_one::<AbsoluteTime>("since").unwrap();
+//! assert_eq!(since.to_datetime(FixedOffset::utc()).year(), 2024);
+//! assert_eq!(matches.get_one::<Day>("weekday"), Some(&Day::Friday));
+//! ```
+use crate::{AbsoluteTime, DateTime, Day, Month, ParseError, DAYS, MONTHS};
+use ::clap::builder::{PossibleValue, TypedValueParser, ValueParserFactory};
+use ::clap::error::ErrorKind;
+use ::clap::{Arg, Command, Error, ValueEnum};
+use std::ffi::OsStr;
+
+// the lowercase names and abbreviations clap accepts, in the order of the
+// enums
+const DAY_NAMES: [(&str, &str); 7] = [
+    ("sunday", "sun"),
+    ("monday", "mon"),
+    ("tuesday", "tue"),
+    ("wednesday", "wed"),
+    ("thursday", "thu"),
+    ("friday", "fri"),
+    ("saturday", "sat"),
+];
+
+const MONTH_NAMES: [(&str, &str); 12] = [
+    ("january", "jan"),
+    ("february", "feb"),
+    ("march", "mar"),
+    ("april", "apr"),
+    ("may", "may"),
+    ("june", "jun"),
+    ("july", "jul"),
+    ("august", "aug"),
+    ("september", "sep"),
+    ("october", "oct"),
+    ("november", "nov"),
+    ("december", "dec"),
+];
+
+fn possible_value((name, abbreviation): (&'static str, &'static str)) -> PossibleValue {
+    let value = PossibleValue::new(name);
+    if abbreviation == name {
+        value
+    } else {
+        value.alias(abbreviation)
+    }
+}
+
+impl ValueEnum for Day {
+    fn value_variants<'a>() -> &'a [Self] {
+        &DAYS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(possible_value(DAY_NAMES[*self as usize]))
+    }
+}
+
+impl ValueEnum for Month {
+    fn value_variants<'a>() -> &'a [Self] {
+        &MONTHS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(possible_value(MONTH_NAMES[*self as usize]))
+    }
+}
+
+/// a clap value parser for [`AbsoluteTime`]s, accepting an RFC 3339
+/// timestamp or a `yyyy-mm-dd` date meaning midnight UTC
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AbsoluteTimeParser;
+
+impl AbsoluteTimeParser {
+    fn parse(value: &str) -> Result<AbsoluteTime, ParseError> {
+        let date = if value.len() == "yyyy-mm-dd".len() {
+            DateTime::parse_rfc3339(&format!("{}T00:00:00Z", value))?
+        } else {
+            DateTime::parse_rfc3339(value)?
+        };
+
+        Ok(AbsoluteTime::from(&date))
+    }
+}
+
+impl TypedValueParser for AbsoluteTimeParser {
+    type Value = AbsoluteTime;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+
+        AbsoluteTimeParser::parse(value).map_err(|e| {
+            let arg = arg.map_or_else(|| "...".to_string(), |arg| arg.to_string());
+            let message = format!("invalid value '{}' for '{}': {}\n", value, arg, e);
+            Error::raw(ErrorKind::ValueValidation, message).with_cmd(cmd)
+        })
+    }
+}
+
+impl ValueParserFactory for AbsoluteTime {
+    type Parser = AbsoluteTimeParser;
+
+    fn value_parser() -> Self::Parser {
+        AbsoluteTimeParser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AbsoluteTime, Day, Month};
+    use ::clap::error::ErrorKind;
+    use ::clap::{value_parser, Arg, Command, ValueEnum};
+
+    fn command() -> Command {
+        Command::new("test")
+            .arg(
+                Arg::new("since")
+                    .long("since")
+                    .value_parser(value_parser!(AbsoluteTime)),
+            )
+            .arg(
+                Arg::new("month")
+                    .long("month")
+                    .value_parser(value_parser!(Month)),
+            )
+    }
+
+    #[test]
+    fn test_absolute_time_arg() {
+        let matches = command()
+            .try_get_matches_from(["test", "--since", "1996-09-09T17:23:44+02:00"])
+            .unwrap();
+        let since = matches.get_one::<AbsoluteTime>("since").unwrap();
+        assert_eq!(since.seconds(), 842282624);
+
+        let matches = command()
+            .try_get_matches_from(["test", "--since", "1970-01-02"])
+            .unwrap();
+        let since = matches.get_one::<AbsoluteTime>("since").unwrap();
+        assert_eq!(since.seconds(), 86_400);
+
+        let err = command()
+            .try_get_matches_from(["test", "--since", "1970-02-30"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_value_enums() {
+        let matches = command()
+            .try_get_matches_from(["test", "--month", "sep"])
+            .unwrap();
+        assert_eq!(matches.get_one::<Month>("month"), Some(&Month::September));
+
+        assert_eq!(Day::from_str("Tue", true), Ok(Day::Tuesday));
+        assert!(Day::from_str("Tue", false).is_err());
+        assert_eq!(Month::from_str("may", false), Ok(Month::May));
+        assert!(command()
+            .try_get_matches_from(["test", "--month", "sept"])
+            .is_err());
+    }
+}
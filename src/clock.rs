@@ -0,0 +1,378 @@
+//! the clocks behind [`DateTime::now`](crate::DateTime::now)
+//!
+//! on most targets the current time comes from `std::time::SystemTime`.
+//! some targets need a feature to pick a backend instead:
+//!
+//! - `wasi`: reads the realtime clock with WASI's `clock_time_get`
+//! - `js`: on `wasm32-unknown-unknown`, where `SystemTime::now` panics,
+//!   reads JavaScript's `Date.now()`, which is only accurate to the
+//!   millisecond
+//! - `sgx`: SGX enclaves have no clock of their own, so the time must be
+//!   supplied by the application (usually from the untrusted host) with
+//!   [`set_time_source`]
+//!
+//! building for a WASI, browser or SGX target without the matching feature
+//! is a compile error
+//!
+//! [`DateTime::now_coarse`](crate::DateTime::now_coarse) reads Linux's
+//! `CLOCK_REALTIME_COARSE` instead, and is the same as `now` elsewhere
+//!
+//! code that needs to be tested at a known time can take a [`Clock`]
+//! instead, and call [`DateTime::now_with`](crate::DateTime::now_with).
+//! [`SystemClock`] reads the real clock as `now` does, while
+//! [`FixedClock`] and [`MockClock`] return a time chosen by the test. the
+//! trait is also available without the `std` feature, for clocks such as
+//! hardware RTCs
+//! ```
+//! # use datetime::clock::{Clock, FixedClock};
+//! # use datetime::{AbsoluteTime, DateTime};
+//! fn greeting(clock: &impl Clock) -> &'static str {
+//!     if DateTime::now_with(clock).hour() < 12 {
+//!         "good morning"
+//!     } else {
+//!         "good afternoon"
+//!     }
+//! }
+//!
+//! let clock = FixedClock::new(AbsoluteTime::new(842282624, 0).unwrap());
+//! assert_eq!(greeting(&clock), "good afternoon");
+//! ```
+use crate::{AbsoluteTime, DateTime, FixedOffset};
+#[cfg(feature = "std")]
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+/// a source of the current time
+pub trait Clock {
+    /// returns the current time
+    fn now(&self) -> AbsoluteTime;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> AbsoluteTime {
+        (**self).now()
+    }
+}
+
+/// the system's clock, as read by [`DateTime::now`]
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> AbsoluteTime {
+        let (secs, nanos) = now();
+        AbsoluteTime::new(secs, nanos).expect("the system clock is out of range")
+    }
+}
+
+/// a clock which is stopped at a given time
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FixedClock(AbsoluteTime);
+
+impl FixedClock {
+    /// returns a clock which always reads `time`
+    pub fn new(time: AbsoluteTime) -> Self {
+        FixedClock(time)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> AbsoluteTime {
+        self.0
+    }
+}
+
+/// a clock which only moves when told to, for tests that need time to
+/// pass; it can be shared between threads
+/// ```
+/// # use datetime::clock::{Clock, MockClock};
+/// # use datetime::AbsoluteTime;
+/// # use std::time::Duration;
+/// let clock = MockClock::new(AbsoluteTime::new(842282624, 0).unwrap());
+/// clock.advance(Duration::from_secs(60));
+///
+/// assert_eq!(clock.now().seconds(), 842282684);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct MockClock {
+    time: Mutex<AbsoluteTime>,
+}
+
+#[cfg(feature = "std")]
+impl MockClock {
+    /// returns a clock reading `time`
+    pub fn new(time: AbsoluteTime) -> Self {
+        MockClock {
+            time: Mutex::new(time),
+        }
+    }
+
+    /// sets the clock to `time`, which may be earlier than its current time
+    pub fn set(&self, time: AbsoluteTime) {
+        *self.time.lock().unwrap_or_else(|e| e.into_inner()) = time;
+    }
+
+    /// moves the clock forward by `duration`
+    ///
+    /// # Panics
+    ///
+    /// panics if the clock would pass the latest time a DateTime can hold
+    pub fn advance(&self, duration: Duration) {
+        let mut time = self.time.lock().unwrap_or_else(|e| e.into_inner());
+        let later = time
+            .to_datetime(FixedOffset::utc())
+            .checked_add_duration(duration)
+            .expect("MockClock advanced out of range");
+
+        *time = AbsoluteTime::from(&later);
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for MockClock {
+    fn now(&self) -> AbsoluteTime {
+        *self.time.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl DateTime {
+    /// returns a DateTime for the current time of the given clock, in UTC
+    /// ```
+    /// # use datetime::clock::FixedClock;
+    /// # use datetime::{AbsoluteTime, DateTime};
+    /// let clock = FixedClock::new(AbsoluteTime::new(842282624, 0).unwrap());
+    ///
+    /// assert_eq!(
+    ///     DateTime::now_with(&clock).as_time_stamp(),
+    ///     "Mon Sep 9, 1996  15:23:44 (UTC)"
+    /// );
+    /// ```
+    pub fn now_with<C: Clock + ?Sized>(clock: &C) -> DateTime {
+        clock.now().to_datetime(FixedOffset::utc())
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "wasi", not(feature = "wasi")))]
+compile_error!("DateTime::now() on WASI targets needs the `wasi` feature of the datetime crate");
+
+#[cfg(all(
+    feature = "std",
+    target_arch = "wasm32",
+    target_os = "unknown",
+    not(feature = "js")
+))]
+compile_error!(
+    "DateTime::now() on wasm32-unknown-unknown needs the `js` feature of the datetime crate"
+);
+
+#[cfg(all(feature = "std", target_env = "sgx", not(feature = "sgx")))]
+compile_error!("DateTime::now() in SGX enclaves needs the `sgx` feature of the datetime crate");
+
+// the current time as (seconds, nanoseconds) relative to the Unix epoch,
+// with the seconds rounded towards the past
+#[cfg(all(
+    feature = "std",
+    not(any(
+        target_os = "wasi",
+        target_env = "sgx",
+        all(target_arch = "wasm32", target_os = "unknown")
+    ))
+))]
+pub(crate) fn now() -> (i64, u32) {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => split(duration),
+        Err(e) => split_before_epoch(e.duration()),
+    }
+}
+
+#[cfg(all(target_os = "wasi", feature = "wasi"))]
+pub(crate) fn now() -> (i64, u32) {
+    // the realtime clock is in nanoseconds since the epoch; a precision of
+    // 1 asks for the finest resolution available
+    let nanos = unsafe { wasi::clock_time_get(wasi::CLOCKID_REALTIME, 1) }
+        .expect("the WASI realtime clock is unavailable");
+
+    split(Duration::from_nanos(nanos))
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "js"))]
+pub(crate) fn now() -> (i64, u32) {
+    // whole milliseconds since the epoch, as a float; before the epoch
+    // they're negative, so round towards the past
+    let millis = js_sys::Date::now().floor() as i64;
+    let secs = millis.div_euclid(1000);
+
+    (secs, millis.rem_euclid(1000) as u32 * 1_000_000)
+}
+
+#[cfg(all(target_env = "sgx", feature = "sgx"))]
+static TIME_SOURCE: std::sync::OnceLock<fn() -> Duration> = std::sync::OnceLock::new();
+
+/// sets the function DateTime::now calls for the time since the Unix epoch
+/// inside an SGX enclave; only the first call has any effect, and returns
+/// true
+///
+/// the host's time can't be trusted by the enclave, so anything relying on
+/// it for security needs its own checks
+#[cfg(all(target_env = "sgx", feature = "sgx"))]
+pub fn set_time_source(source: fn() -> Duration) -> bool {
+    TIME_SOURCE.set(source).is_ok()
+}
+
+#[cfg(all(target_env = "sgx", feature = "sgx"))]
+pub(crate) fn now() -> (i64, u32) {
+    let source = TIME_SOURCE
+        .get()
+        .expect("no time source set; call datetime::clock::set_time_source first");
+
+    split(source())
+}
+
+// like now, but may be a few milliseconds stale in exchange for being much
+// cheaper to read; on Linux this is CLOCK_REALTIME_COARSE, which is read
+// from memory shared with the kernel without a system call
+#[cfg(all(
+    feature = "std",
+    target_os = "linux",
+    not(any(target_os = "wasi", target_env = "sgx"))
+))]
+pub(crate) fn now_coarse() -> (i64, u32) {
+    use std::os::raw::{c_int, c_long};
+
+    // time_t and the nanoseconds field are both C longs on Linux
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    extern "C" {
+        fn clock_gettime(clock: c_int, time: *mut Timespec) -> c_int;
+    }
+
+    const CLOCK_REALTIME_COARSE: c_int = 5;
+
+    let mut time = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // the coarse clocks need Linux 2.6.32; fall back to the precise clock
+    // if this kernel doesn't have them
+    if unsafe { clock_gettime(CLOCK_REALTIME_COARSE, &mut time) } != 0 {
+        return now();
+    }
+
+    // c_long is only 32 bits on 32-bit targets
+    #[allow(clippy::unnecessary_cast)]
+    let secs = time.tv_sec as i64;
+
+    (secs, time.tv_nsec as u32)
+}
+
+#[cfg(all(
+    feature = "std",
+    not(all(target_os = "linux", not(any(target_os = "wasi", target_env = "sgx"))))
+))]
+pub(crate) fn now_coarse() -> (i64, u32) {
+    now()
+}
+
+// splits a duration after the epoch into seconds and nanoseconds; the
+// seconds saturate rather than wrap, so that a duration too long for any
+// DateTime stays out of range
+#[cfg(feature = "std")]
+pub(crate) fn split(duration: Duration) -> (i64, u32) {
+    let secs = i64::try_from(duration.as_secs()).unwrap_or(i64::MAX);
+    (secs, duration.subsec_nanos())
+}
+
+// like split, for a duration before the epoch; the seconds are rounded
+// down to the start of the second, as for times after the epoch
+#[cfg(feature = "std")]
+pub(crate) fn split_before_epoch(duration: Duration) -> (i64, u32) {
+    let secs = -i64::try_from(duration.as_secs()).unwrap_or(i64::MAX);
+
+    match duration.subsec_nanos() {
+        0 => (secs, 0),
+        nanos => (secs - 1, 1_000_000_000 - nanos),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use super::{now, now_coarse, split_before_epoch, MockClock, SystemClock};
+    use super::{Clock, FixedClock};
+    use crate::{AbsoluteTime, DateTime};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[test]
+    fn test_fixed_clock() {
+        let time = AbsoluteTime::new(-1, 500).unwrap();
+        let clock = FixedClock::new(time);
+
+        assert_eq!(clock.now(), time);
+        let date = DateTime::now_with(&clock);
+        assert_eq!((date.secs, date.nanos), (-1, 500));
+
+        // clocks can also be used as trait objects
+        let clock: &dyn Clock = &clock;
+        assert_eq!(DateTime::now_with(clock).hour(), 23);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mock_clock() {
+        let clock = MockClock::new(AbsoluteTime::new(0, 0).unwrap());
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now(), AbsoluteTime::new(1, 500_000_000).unwrap());
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(DateTime::now_with(&clock).second(), 2);
+
+        clock.set(AbsoluteTime::new(-86_400, 0).unwrap());
+        assert_eq!(DateTime::now_with(&clock).date(), 31);
+
+        // the system clock is well past 2020-01-01
+        assert!(SystemClock.now().seconds() > 1_577_836_800);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_split_before_epoch() {
+        assert_eq!(split_before_epoch(Duration::from_secs(2)), (-2, 0));
+        assert_eq!(
+            split_before_epoch(Duration::from_millis(1500)),
+            (-2, 500_000_000)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_now() {
+        // 2020-01-01
+        let (secs, nanos) = now();
+        assert!(secs > 1_577_836_800);
+        assert!(nanos < 1_000_000_000);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_now_coarse() {
+        let (coarse, nanos) = now_coarse();
+        let (precise, _) = now();
+
+        assert!(nanos < 1_000_000_000);
+        // the coarse clock lags by at most a tick, well under a second
+        assert!(precise - coarse <= 1);
+    }
+}
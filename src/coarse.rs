@@ -0,0 +1,133 @@
+//! cached time stamps for the current second
+//!
+//! servers that stamp every log line with the time spend much of that time
+//! formatting the same second over and over. [`CoarseNow`] and
+//! [`LocalCoarseNow`] keep the formatted stamp for the current second and
+//! only format again once the second changes
+//! ```
+//! # use datetime::{CoarseNow, FormatSpec};
+//! let stamps = CoarseNow::new(FormatSpec::parse("%F %T").unwrap());
+//!
+//! let stamp = stamps.get();
+//! assert_eq!(stamp.len(), "1996-09-09 15:23:44".len());
+//! ```
+use crate::{clock, DateTime, FormatSpec};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+
+/// a thread-safe cache of the current second formatted with a FormatSpec
+///
+/// the time comes from [`DateTime::now_coarse`], and stamps are in UTC
+#[derive(Debug)]
+pub struct CoarseNow {
+    spec: FormatSpec,
+    // the second last formatted, and its stamp
+    current: RwLock<(i64, Arc<str>)>,
+}
+
+impl CoarseNow {
+    /// returns a cache formatting stamps with the given spec
+    pub fn new(spec: FormatSpec) -> Self {
+        CoarseNow {
+            spec,
+            current: RwLock::new((i64::MIN, Arc::from(""))),
+        }
+    }
+
+    /// returns the stamp for the current second
+    pub fn get(&self) -> Arc<str> {
+        self.get_at(clock::now_coarse().0)
+    }
+
+    fn get_at(&self, secs: i64) -> Arc<str> {
+        {
+            let current = self.current.read().unwrap_or_else(|e| e.into_inner());
+            if current.0 == secs {
+                return current.1.clone();
+            }
+        }
+
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        // another thread may have got here first
+        if current.0 != secs {
+            let stamp = DateTime::from_signed_secs(secs).format_with(&self.spec);
+            *current = (secs, Arc::from(stamp));
+        }
+
+        current.1.clone()
+    }
+}
+
+/// like [`CoarseNow`], but for use from a single thread (for example in a
+/// `thread_local!`), avoiding the cost of locking
+/// ```
+/// # use datetime::{FormatSpec, LocalCoarseNow};
+/// thread_local! {
+///     static STAMPS: LocalCoarseNow = LocalCoarseNow::new(FormatSpec::parse("%T").unwrap());
+/// }
+///
+/// let stamp = STAMPS.with(|stamps| stamps.get());
+/// assert_eq!(stamp.len(), "15:23:44".len());
+/// ```
+#[derive(Debug)]
+pub struct LocalCoarseNow {
+    spec: FormatSpec,
+    current: RefCell<(i64, Rc<str>)>,
+}
+
+impl LocalCoarseNow {
+    /// returns a cache formatting stamps with the given spec
+    pub fn new(spec: FormatSpec) -> Self {
+        LocalCoarseNow {
+            spec,
+            current: RefCell::new((i64::MIN, Rc::from(""))),
+        }
+    }
+
+    /// returns the stamp for the current second
+    pub fn get(&self) -> Rc<str> {
+        self.get_at(clock::now_coarse().0)
+    }
+
+    fn get_at(&self, secs: i64) -> Rc<str> {
+        let mut current = self.current.borrow_mut();
+        if current.0 != secs {
+            let stamp = DateTime::from_signed_secs(secs).format_with(&self.spec);
+            *current = (secs, Rc::from(stamp));
+        }
+
+        current.1.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CoarseNow, LocalCoarseNow};
+    use crate::FormatSpec;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_coarse_now() {
+        let stamps = CoarseNow::new(FormatSpec::parse("%T").unwrap());
+
+        let first = stamps.get_at(842282624);
+        assert_eq!(&*first, "15:23:44");
+        assert!(Arc::ptr_eq(&first, &stamps.get_at(842282624)));
+
+        assert_eq!(&*stamps.get_at(842282625), "15:23:45");
+    }
+
+    #[test]
+    fn test_local_coarse_now() {
+        let stamps = LocalCoarseNow::new(FormatSpec::parse("%T").unwrap());
+
+        let first = stamps.get_at(-1);
+        assert_eq!(&*first, "23:59:59");
+        assert!(Rc::ptr_eq(&first, &stamps.get_at(-1)));
+
+        assert_eq!(&*stamps.get_at(0), "00:00:00");
+        assert_eq!(stamps.get().len(), 8);
+    }
+}
@@ -0,0 +1,7 @@
+//! APIs shaped like those of other date and time crates, to make moving
+//! code over to this one a matter of changing imports first and names
+//! later
+//!
+//! - [`chrono_like`]: the common surface of `chrono`'s `DateTime`
+
+pub mod chrono_like;
@@ -0,0 +1,264 @@
+//! chrono's method names for DateTimes and offsets
+//!
+//! importing [`ChronoLike`] and [`OffsetLike`] gives a DateTime and a
+//! FixedOffset the methods chrono code calls most, mapped onto this
+//! crate's own, and [`Utc`] stands in for chrono's zone of the same name
+//! ```
+//! use datetime::compat::chrono_like::{ChronoLike, OffsetLike, Utc};
+//! use datetime::{DateTime, Day, FixedOffset};
+//!
+//! let date = DateTime::from_timestamp(842282624, 0).unwrap();
+//! assert_eq!(date.timestamp(), 842282624);
+//! assert_eq!(date.weekday(), Day::Monday);
+//!
+//! let local = date.with_timezone(&FixedOffset::east_opt(7200).unwrap());
+//! assert_eq!(local.format("%Y-%m-%d %H:%M"), "1996-09-09 17:23");
+//! assert_eq!(local.with_timezone(&Utc).to_rfc3339(), "1996-09-09T15:23:44Z");
+//! ```
+//!
+//! `format`, `to_rfc3339`, `year`, `hour`, `minute`, `second` and
+//! `nanosecond` need nothing from here, since a DateTime already has them.
+//! two chrono names mean something else here, and can't be changed by a
+//! trait: `day()` is the day of the week, where chrono's is the day of
+//! the month, which is `date()`; and `month()` returns a [`Month`], whose
+//! number is `month().number()`
+//!
+//! [`Month`]: crate::Month
+use crate::{Date, DateTime, Day, FixedOffset, Time};
+use core::convert::TryFrom;
+
+/// chrono's `Utc`, which is the zero [`FixedOffset`] here
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Utc;
+
+impl Utc {
+    /// returns the current time in UTC, as chrono's `Utc::now` does
+    #[cfg(feature = "std")]
+    pub fn now() -> DateTime {
+        DateTime::now()
+    }
+}
+
+impl From<Utc> for FixedOffset {
+    fn from(_: Utc) -> Self {
+        FixedOffset::utc()
+    }
+}
+
+/// chrono's names for the methods of a DateTime; see the
+/// [module docs](self)
+pub trait ChronoLike: Sized {
+    /// returns the DateTime `secs` seconds and `nsecs` nanoseconds after
+    /// the Unix epoch, or None if it is out of range or `nsecs` is a
+    /// second or more
+    fn from_timestamp(secs: i64, nsecs: u32) -> Option<Self>;
+
+    /// returns the DateTime `millis` milliseconds after the Unix epoch, or
+    /// None if it is out of range
+    fn from_timestamp_millis(millis: i64) -> Option<Self>;
+
+    /// returns the whole seconds since the Unix epoch
+    fn timestamp(&self) -> i64;
+
+    /// returns the whole milliseconds since the Unix epoch
+    fn timestamp_millis(&self) -> i64;
+
+    /// returns the whole microseconds since the Unix epoch
+    fn timestamp_micros(&self) -> i64;
+
+    /// returns the nanoseconds since the Unix epoch, or None if they don't
+    /// fit in an i64, which they don't outside 1677 to 2262
+    fn timestamp_nanos_opt(&self) -> Option<i64>;
+
+    /// returns the milliseconds since the last whole second
+    fn timestamp_subsec_millis(&self) -> u32;
+
+    /// returns the microseconds since the last whole second
+    fn timestamp_subsec_micros(&self) -> u32;
+
+    /// returns the nanoseconds since the last whole second
+    fn timestamp_subsec_nanos(&self) -> u32;
+
+    /// returns the same instant in another offset, such as [`Utc`]
+    fn with_timezone<Tz: Copy + Into<FixedOffset>>(&self, tz: &Tz) -> Self;
+
+    /// returns the day of the week
+    fn weekday(&self) -> Day;
+
+    /// returns the day of the year, from 1
+    fn ordinal(&self) -> u32;
+
+    /// returns the day of the year, from 0
+    fn ordinal0(&self) -> u32;
+
+    /// returns the number of the month, from 0 for January
+    fn month0(&self) -> u32;
+
+    /// returns the day of the month, from 0
+    fn day0(&self) -> u32;
+
+    /// returns the date, without the time or offset
+    fn date_naive(&self) -> Date;
+
+    /// returns the time of day, without the date or offset
+    fn time(&self) -> Time;
+}
+
+impl ChronoLike for DateTime {
+    fn from_timestamp(secs: i64, nsecs: u32) -> Option<Self> {
+        if nsecs >= 1_000_000_000 {
+            return None;
+        }
+        let date = DateTime::try_from_signed_secs(secs).ok()?;
+        date.checked_add_duration(core::time::Duration::from_nanos(nsecs as u64))
+    }
+
+    fn from_timestamp_millis(millis: i64) -> Option<Self> {
+        DateTime::try_from_millis(millis).ok()
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.secs
+    }
+
+    fn timestamp_millis(&self) -> i64 {
+        // every DateTime is within 300 million years of the epoch
+        self.as_millis() as i64
+    }
+
+    fn timestamp_micros(&self) -> i64 {
+        self.as_micros() as i64
+    }
+
+    fn timestamp_nanos_opt(&self) -> Option<i64> {
+        i64::try_from(self.as_nanos()).ok()
+    }
+
+    fn timestamp_subsec_millis(&self) -> u32 {
+        self.nanos / 1_000_000
+    }
+
+    fn timestamp_subsec_micros(&self) -> u32 {
+        self.nanos / 1000
+    }
+
+    fn timestamp_subsec_nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    fn with_timezone<Tz: Copy + Into<FixedOffset>>(&self, tz: &Tz) -> Self {
+        self.with_offset((*tz).into())
+    }
+
+    fn weekday(&self) -> Day {
+        self.day()
+    }
+
+    fn ordinal(&self) -> u32 {
+        self.day_of_year() as u32
+    }
+
+    fn ordinal0(&self) -> u32 {
+        self.day_of_year() as u32 - 1
+    }
+
+    fn month0(&self) -> u32 {
+        self.month() as u32
+    }
+
+    fn day0(&self) -> u32 {
+        self.date() as u32 - 1
+    }
+
+    fn date_naive(&self) -> Date {
+        self.date_part()
+    }
+
+    fn time(&self) -> Time {
+        self.time_part()
+    }
+}
+
+/// chrono's names for the methods of a FixedOffset
+pub trait OffsetLike: Sized {
+    /// returns the offset `secs` seconds east of UTC, or None if it is a
+    /// day or more
+    fn east_opt(secs: i32) -> Option<Self>;
+
+    /// returns the offset `secs` seconds west of UTC, or None if it is a
+    /// day or more
+    fn west_opt(secs: i32) -> Option<Self>;
+
+    /// returns the seconds to add to UTC to get local time
+    fn local_minus_utc(&self) -> i32;
+
+    /// returns the seconds to add to local time to get UTC
+    fn utc_minus_local(&self) -> i32;
+}
+
+impl OffsetLike for FixedOffset {
+    fn east_opt(secs: i32) -> Option<Self> {
+        FixedOffset::east(secs)
+    }
+
+    fn west_opt(secs: i32) -> Option<Self> {
+        FixedOffset::west(secs)
+    }
+
+    fn local_minus_utc(&self) -> i32 {
+        self.seconds()
+    }
+
+    fn utc_minus_local(&self) -> i32 {
+        -self.seconds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChronoLike, OffsetLike, Utc};
+    use crate::{DateTime, Day, FixedOffset};
+
+    #[test]
+    fn test_timestamps() {
+        let date = DateTime::from_timestamp(-2, 123_456_789).unwrap();
+        assert_eq!(date.timestamp(), -2);
+        assert_eq!(date.timestamp_millis(), -1877);
+        assert_eq!(date.timestamp_micros(), -1_876_544);
+        assert_eq!(date.timestamp_nanos_opt(), Some(-1_876_543_211));
+        assert_eq!(date.timestamp_subsec_millis(), 123);
+        assert_eq!(date.timestamp_subsec_micros(), 123_456);
+        assert_eq!(date.timestamp_subsec_nanos(), 123_456_789);
+
+        assert!(DateTime::from_timestamp(0, 1_000_000_000).is_none());
+        assert!(DateTime::from_timestamp(i64::MAX, 0).is_none());
+        assert_eq!(
+            DateTime::from_timestamp_millis(842282624123)
+                .unwrap()
+                .timestamp_subsec_millis(),
+            123
+        );
+        assert_eq!(DateTime::MAX.timestamp_nanos_opt(), None);
+    }
+
+    #[test]
+    fn test_fields() {
+        let offset = FixedOffset::west_opt(3600).unwrap();
+        assert_eq!(offset.local_minus_utc(), -3600);
+        assert_eq!(offset.utc_minus_local(), 3600);
+
+        // 00:30 on 1 January locally is still 31 December in UTC
+        let date = DateTime::parse_rfc3339("1997-01-01T01:30:00Z").unwrap();
+        let local = date.with_timezone(&offset);
+        assert_eq!((local.ordinal(), local.ordinal0()), (1, 0));
+        assert_eq!((local.month0(), local.day0()), (0, 0));
+        assert_eq!(local.weekday(), Day::Wednesday);
+        assert_eq!(local.time().hour(), 0);
+        assert_eq!(local.date_naive().year(), 1997);
+
+        let utc = local.with_timezone(&Utc);
+        assert_eq!(utc, date);
+        assert_eq!(utc.ordinal(), 1);
+        assert_eq!(utc.with_timezone(&offset).day0(), 0);
+    }
+}
@@ -0,0 +1,234 @@
+//! low-level calendar arithmetic
+//!
+//! these are the functions DateTime itself is built on, for code that
+//! needs to convert between calendar dates and day counts without
+//! constructing full DateTimes. days are counted from 1970-01-01 with the
+//! proleptic Gregorian calendar, and months are numbered 1-12. all of them
+//! are `const fn`s, so they can also be used in constants
+//! ```
+//! # use datetime::convert::{civil_from_days, days_from_civil};
+//! let days = days_from_civil(1996, 9, 9);
+//! assert_eq!(days, 9748);
+//! assert_eq!(civil_from_days(days + 30), (1996, 10, 9));
+//! ```
+use crate::{Day, InvalidDate, DAYS};
+
+/// returns true if the given year is a leap year
+/// ```
+/// # use datetime::convert::is_leap_year;
+/// assert!(is_leap_year(2000));
+/// assert!(!is_leap_year(1900));
+/// ```
+pub const fn is_leap_year(year: usize) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+// the number of days in a common and a leap year before the start of each
+// month, with the length of the whole year last
+const CUMULATIVE_DAYS: [[u16; 13]; 2] = [
+    [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334, 365],
+    [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335, 366],
+];
+
+// the row of CUMULATIVE_DAYS for a common or leap year, in any calendar
+// with the Gregorian months
+pub(crate) const fn month_table(leap: bool) -> &'static [u16; 13] {
+    &CUMULATIVE_DAYS[leap as usize]
+}
+
+// the row of CUMULATIVE_DAYS for the given year
+pub(crate) const fn cumulative_days(year: usize) -> &'static [u16; 13] {
+    month_table(is_leap_year(year))
+}
+
+/// returns the number of days in the given month of the given year
+///
+/// # Panics
+///
+/// panics if `month` is not 1-12
+/// ```
+/// # use datetime::convert::days_in_month;
+/// assert_eq!(days_in_month(2024, 2), 29);
+/// assert_eq!(days_in_month(2023, 4), 30);
+/// ```
+pub const fn days_in_month(year: usize, month: usize) -> usize {
+    assert!(month >= 1 && month <= 12, "month must be 1-12");
+    let table = cumulative_days(year);
+    (table[month] - table[month - 1]) as usize
+}
+
+/// returns the number of days between 1970-01-01 and the given date,
+/// which is negative for dates before it
+///
+/// `month` and `day` are 1-based and are not checked, so out-of-range
+/// values give a day count but not a meaningful one
+/// ```
+/// # use datetime::convert::days_from_civil;
+/// assert_eq!(days_from_civil(1970, 1, 1), 0);
+/// assert_eq!(days_from_civil(1969, 12, 31), -1);
+/// ```
+pub const fn days_from_civil(year: i64, month: usize, day: usize) -> i64 {
+    // this and civil_from_days are Howard Hinnant's constant-time
+    // algorithms, which work in 400-year eras starting on March 1st so that
+    // leap days fall at the end
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+/// returns the (year, month, day) of the date the given number of days
+/// after 1970-01-01; the inverse of [`days_from_civil`]
+/// ```
+/// # use datetime::convert::civil_from_days;
+/// assert_eq!(civil_from_days(-1), (1969, 12, 31));
+/// assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+/// ```
+pub const fn civil_from_days(days: i64) -> (i64, usize, usize) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month as usize, day as usize)
+}
+
+/// returns the day of the week of the date the given number of days after
+/// 1970-01-01
+/// ```
+/// # use datetime::{convert::weekday_from_days, Day};
+/// assert_eq!(weekday_from_days(0), Day::Thursday);
+/// assert_eq!(weekday_from_days(-1), Day::Wednesday);
+/// ```
+pub const fn weekday_from_days(days: i64) -> Day {
+    match (days + 4).rem_euclid(7) {
+        0 => Day::Sunday,
+        1 => Day::Monday,
+        2 => Day::Tuesday,
+        3 => Day::Wednesday,
+        4 => Day::Thursday,
+        5 => Day::Friday,
+        6 => Day::Saturday,
+        _ => unreachable!(),
+    }
+}
+
+/// checks that the fields make a date, where `month` is 1-12, returning
+/// the reason they don't
+/// ```
+/// # use datetime::{is_valid_date, InvalidDate};
+/// assert_eq!(is_valid_date(2024, 2, 29), Ok(()));
+/// assert_eq!(is_valid_date(2023, 2, 29), Err(InvalidDate::LeapDay));
+/// assert_eq!(is_valid_date(2023, 4, 31), Err(InvalidDate::Date));
+/// assert_eq!(is_valid_date(0, 1, 1), Err(InvalidDate::Year));
+/// ```
+pub fn is_valid_date(year: usize, month: usize, day: usize) -> Result<(), InvalidDate> {
+    if year < 1 {
+        Err(InvalidDate::Year)
+    } else if !(1..=12).contains(&month) {
+        Err(InvalidDate::Month)
+    } else if month == 2 && day == 29 && !is_leap_year(year) {
+        Err(InvalidDate::LeapDay)
+    } else if day < 1 || day > days_in_month(year, month) {
+        Err(InvalidDate::Date)
+    } else {
+        Ok(())
+    }
+}
+
+/// checks that the fields make a time of day, returning the first that is
+/// out of range; leap seconds aren't allowed
+/// ```
+/// # use datetime::{is_valid_time, InvalidDate};
+/// assert_eq!(is_valid_time(23, 59, 59), Ok(()));
+/// assert_eq!(is_valid_time(23, 59, 60), Err(InvalidDate::Second));
+/// ```
+pub fn is_valid_time(hour: usize, minute: usize, second: usize) -> Result<(), InvalidDate> {
+    if hour > 23 {
+        Err(InvalidDate::Hour)
+    } else if minute > 59 {
+        Err(InvalidDate::Minute)
+    } else if second > 59 {
+        Err(InvalidDate::Second)
+    } else {
+        Ok(())
+    }
+}
+
+/// returns the day of the week of a date, without going through a count
+/// of days; `day` is not checked
+///
+/// # Panics
+///
+/// panics if `month` isn't 1-12
+/// ```
+/// # use datetime::{weekday, Day};
+/// assert_eq!(weekday(1996, 9, 9), Day::Monday);
+/// assert_eq!(weekday(2000, 2, 29), Day::Tuesday);
+/// ```
+pub const fn weekday(year: usize, month: usize, day: usize) -> Day {
+    assert!(month >= 1 && month <= 12, "month must be 1-12");
+    // Tomohiko Sakamoto's method, counting January and February as part of
+    // the year before; the calendar repeats every 400 years, which keeps
+    // the year small and never below 0
+    const OFFSETS: [usize; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = year % 400 + 400 - if month < 3 { 1 } else { 0 };
+    let days = year + year / 4 - year / 100 + year / 400 + OFFSETS[month - 1] + day % 7;
+
+    DAYS[days % 7]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::weekday_from_days;
+    use super::{civil_from_days, cumulative_days, days_from_civil, days_in_month, weekday};
+
+    #[test]
+    fn test_civil_round_trip() {
+        for days in (-719_162..3_000_000).step_by(97) {
+            let (year, month, date) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, date), days);
+        }
+    }
+
+    #[test]
+    fn test_calendar_tables() {
+        // the calendar helpers can be evaluated at compile time
+        const LEAP_DAY: i64 = days_from_civil(2000, 2, 29);
+        const CIVIL: (i64, usize, usize) = civil_from_days(LEAP_DAY);
+        assert_eq!(LEAP_DAY, 11_016);
+        assert_eq!(CIVIL, (2000, 2, 29));
+
+        for &(year, leap) in &[(1900, false), (2000, true), (2023, false), (2024, true)] {
+            let table = cumulative_days(year);
+            let lengths: usize = (1..=12).map(|month| days_in_month(year, month)).sum();
+            assert_eq!(lengths, table[12] as usize);
+            assert_eq!(days_in_month(year, 2), if leap { 29 } else { 28 });
+        }
+    }
+
+    #[test]
+    fn test_weekday() {
+        for days in (-719_162..3_000_000).step_by(89) {
+            let (year, month, date) = civil_from_days(days);
+            assert_eq!(weekday(year as usize, month, date), weekday_from_days(days));
+        }
+        assert_eq!(weekday(usize::MAX, 1, 1), weekday(usize::MAX % 400, 1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "month must be 1-12")]
+    fn test_days_in_month_panics() {
+        days_in_month(2000, 13);
+    }
+}
@@ -0,0 +1,578 @@
+//! cron expressions, and the times they fire
+//!
+//! a [`Cron`] is parsed from the five fields of a crontab line: minute,
+//! hour, day of the month, month and day of the week. each field is `*`,
+//! a number, a range such as `9-17`, any of those with a step such as
+//! `*/15`, or a comma-separated list of them; months and days of the week
+//! can also be given by their three-letter names, and Sunday is either 0
+//! or 7. the shorthands `@yearly`, `@annually`, `@monthly`, `@weekly`,
+//! `@daily`, `@midnight` and `@hourly` are accepted too
+//!
+//! as in cron, when both the day of the month and the day of the week are
+//! restricted, a day matching either of them will do
+//!
+//! the six- and seven-field forms used by Quartz and AWS EventBridge are
+//! accepted as well, with a field for the second before the minute and,
+//! in seven fields, one for the year from 1970 to 2199 at the end. in
+//! these forms the days of the week count from 1 for Sunday to 7 for
+//! Saturday, as they do there. AWS writes its expressions without the
+//! seconds, so put `0` in front of one to parse it
+//!
+//! either day field can be `?`, which is the same as `*`. the day of the
+//! month can also be `L` for the last day, `L-3` for three days before
+//! it, `15W` for the weekday nearest the 15th without leaving the month,
+//! or `LW` for the last weekday; the day of the week can be `5L` for the
+//! last Friday of the month, or `5#3` for the third
+//! ```
+//! # use datetime::cron::Cron;
+//! # use datetime::DateTime;
+//! // every quarter of an hour during the working week
+//! let cron: Cron = "*/15 9-17 * * MON-FRI".parse().unwrap();
+//!
+//! // Friday 1996-09-13 at 17:50
+//! let friday = DateTime::parse_rfc3339("1996-09-13T17:50:00Z").unwrap();
+//! let times: Vec<_> = cron.occurrences_after(&friday).take(2).map(|date| date.to_rfc3339()).collect();
+//! assert_eq!(times, ["1996-09-16T09:00:00Z", "1996-09-16T09:15:00Z"]);
+//! ```
+//!
+//! ```
+//! # use datetime::cron::Cron;
+//! # use datetime::DateTime;
+//! // at 10:15:30 on the last Friday of every month in 1997
+//! let cron: Cron = "30 15 10 ? * 6L 1997".parse().unwrap();
+//!
+//! let date = DateTime::parse_rfc3339("1996-09-13T17:50:00Z").unwrap();
+//! assert_eq!(cron.next_after(&date).unwrap().to_rfc3339(), "1997-01-31T10:15:30Z");
+//! ```
+//!
+//! times are matched in the offset of the DateTime they are found after
+use crate::convert::{civil_from_days, days_from_civil, days_in_month, weekday_from_days};
+use crate::{DateTime, ParseError, MAX_SECS};
+use core::iter::FusedIterator;
+use core::str::FromStr;
+
+/// a parsed cron expression; see the [module docs](self)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cron {
+    // a bit for each allowed value of each field
+    seconds: u64,
+    minutes: u64,
+    hours: u32,
+    dates: u32,
+    months: u16,
+    // indexed by `Day as usize`
+    days: u8,
+    // whether the day of the month and day of the week fields were `*`,
+    // which decides how they combine
+    any_date: bool,
+    any_day: bool,
+    // days of the month counted back from the last, with 0 the last day
+    from_last: u32,
+    // days of the month whose nearest weekday matches, and whether the
+    // last weekday of the month does
+    nearest_weekdays: u32,
+    last_weekday: bool,
+    // days of the week that match in the last week of the month, and in
+    // each of its numbered weeks
+    last_days: u8,
+    nth_days: [u8; 5],
+    // a bit for each allowed year from 1970, if the year was given
+    years: Option<[u64; 4]>,
+}
+
+// the days in 400 years, after which the calendar repeats
+const DAYS_IN_CYCLE: i64 = 146_097;
+
+const FIRST_YEAR: u32 = 1970;
+const LAST_YEAR: u32 = 2199;
+
+impl Cron {
+    /// returns the first time after `date` that the expression fires, in
+    /// `date`'s offset, or None if it never fires (such as on 30 February)
+    /// or the time is out of range
+    /// ```
+    /// # use datetime::cron::Cron;
+    /// # use datetime::DateTime;
+    /// let leap_day: Cron = "0 0 29 2 *".parse().unwrap();
+    /// let date = DateTime::parse_rfc3339("1997-01-01T00:00:00Z").unwrap();
+    ///
+    /// assert_eq!(leap_day.next_after(&date).unwrap().to_rfc3339(), "2000-02-29T00:00:00Z");
+    /// assert!("0 0 30 2 *".parse::<Cron>().unwrap().next_after(&date).is_none());
+    /// ```
+    pub fn next_after(&self, date: &DateTime) -> Option<DateTime> {
+        let offset = date.offset.seconds() as i64;
+        // whole seconds, so the first one after `date` is the next
+        let next = date.secs.checked_add(offset)?.checked_add(1)?;
+        let (mut first_day, mut first_second) =
+            (next.div_euclid(86_400), next.rem_euclid(86_400) as u32);
+
+        // skip ahead to the first year allowed, which may be centuries away
+        if let Some(years) = &self.years {
+            let (i, bits) = years.iter().enumerate().find(|(_, &bits)| bits != 0)?;
+            let year = FIRST_YEAR as i64 + (i * 64) as i64 + bits.trailing_zeros() as i64;
+            let day = days_from_civil(year, 1, 1);
+            if day > first_day {
+                first_day = day;
+                first_second = 0;
+            }
+        }
+
+        for day in first_day..first_day + DAYS_IN_CYCLE {
+            if day > MAX_SECS / 86_400 + 1 {
+                return None;
+            }
+            if !self.matches_day(day) {
+                continue;
+            }
+
+            let from = if day == first_day { first_second } else { 0 };
+            if let Some(second) = self.first_second(from) {
+                let secs = (day * 86_400 - offset).checked_add(second as i64)?;
+                return if secs <= MAX_SECS {
+                    Some(DateTime::from_parts(secs, 0, date.offset))
+                } else {
+                    None
+                };
+            }
+        }
+
+        None
+    }
+
+    /// returns an iterator over the times the expression fires after
+    /// `date`, in `date`'s offset
+    pub fn occurrences_after(&self, date: &DateTime) -> Occurrences<'_> {
+        Occurrences {
+            cron: self,
+            last: Some(*date),
+        }
+    }
+
+    // whether a local day, in days since the epoch, matches the date fields
+    fn matches_day(&self, day: i64) -> bool {
+        let (year, month, date) = civil_from_days(day);
+        if let Some(years) = &self.years {
+            let year = year - FIRST_YEAR as i64;
+            if !(0..=(LAST_YEAR - FIRST_YEAR) as i64).contains(&year)
+                || years[year as usize / 64] & 1 << (year % 64) == 0
+            {
+                return false;
+            }
+        }
+        if self.months & 1 << month == 0 {
+            return false;
+        }
+
+        // leap years repeat every 400 years, which keeps the year positive
+        let last = days_in_month(year.rem_euclid(400) as usize, month);
+        let day = weekday_from_days(day) as usize;
+        let weekday = self.days & 1 << day != 0
+            || date + 7 > last && self.last_days & 1 << day != 0
+            || self.nth_days[(date - 1) / 7] & 1 << day != 0;
+        let date = self.dates & 1 << date != 0
+            || self.from_last & 1 << (last - date) != 0
+            || self.matches_nearest_weekday(date, day, last);
+        if self.any_date || self.any_day {
+            date && weekday
+        } else {
+            date || weekday
+        }
+    }
+
+    // whether `date`, on `weekday` in a month of `last` days, is the
+    // weekday nearest one of the `W` days or the last weekday
+    fn matches_nearest_weekday(&self, date: usize, weekday: usize, last: usize) -> bool {
+        let mut targets = self.nearest_weekdays & !(!0 << 1 << last);
+        if self.last_weekday {
+            targets |= 1 << last;
+        }
+
+        (1..=last).filter(|&n| targets & 1 << n != 0).any(|n| {
+            let nearest = match (weekday + 35 + n - date) % 7 {
+                // a Saturday moves back, unless that leaves the month
+                6 if n == 1 => 3,
+                6 => n - 1,
+                // and a Sunday forward
+                0 if n == last => n - 2,
+                0 => n + 1,
+                _ => n,
+            };
+            nearest == date
+        })
+    }
+
+    // the first matching second of the day at or after `from`
+    fn first_second(&self, from: u32) -> Option<u32> {
+        let (hour, minute, second) = (from / 3600, from / 60 % 60, from % 60);
+        (hour..24)
+            .filter(|&h| self.hours & 1 << h != 0)
+            .find_map(|h| {
+                let start = if h == hour { minute } else { 0 };
+                (start..60)
+                    .filter(|&m| self.minutes & 1 << m != 0)
+                    .find_map(|m| {
+                        let start = if h == hour && m == minute { second } else { 0 };
+                        match self.seconds >> start << start {
+                            0 => None,
+                            seconds => Some(h * 3600 + m * 60 + seconds.trailing_zeros()),
+                        }
+                    })
+            })
+    }
+}
+
+/// an iterator over the times a [`Cron`] fires; see
+/// [`Cron::occurrences_after`]
+#[derive(Debug, Clone)]
+pub struct Occurrences<'a> {
+    cron: &'a Cron,
+    last: Option<DateTime>,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        self.last = self.cron.next_after(&self.last?);
+        self.last
+    }
+}
+
+impl FusedIterator for Occurrences<'_> {}
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl FromStr for Cron {
+    type Err = ParseError;
+
+    /// parses a cron expression of five, six or seven fields, or one of
+    /// the `@` shorthands
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = match input.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => input,
+        };
+
+        // the fields and their byte offsets
+        let mut fields = [("", 0); 7];
+        let mut count = 0;
+        let mut start = None;
+        for (i, c) in input.char_indices().chain(Some((input.len(), ' '))) {
+            match (c.is_ascii_whitespace(), start) {
+                (false, None) => start = Some(i),
+                (true, Some(from)) => {
+                    if count == 7 {
+                        return Err(ParseError::TooLong);
+                    }
+                    fields[count] = (&input[from..i], from);
+                    count += 1;
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if count < 5 {
+            return Err(ParseError::TooShort);
+        }
+
+        // Quartz puts the seconds first, and numbers the days from 1
+        let quartz = count > 5;
+        let (seconds, fields) = match quartz {
+            true => (bits(fields[0], 0, 59, &[])?.0, &fields[1..count]),
+            false => (1, &fields[..count]),
+        };
+        let (min_day, max_day) = if quartz { (1, 7) } else { (0, 7) };
+        let day_index = |n: u32| if quartz { n - 1 } else { n % 7 };
+        // `?` is any value in the day fields
+        let any = |(text, pos)| if text == "?" { ("*", pos) } else { (text, pos) };
+
+        let (minutes, _) = bits(fields[0], 0, 59, &[])?;
+        let (hours, _) = bits(fields[1], 0, 23, &[])?;
+        let (months, _) = bits(fields[3], 1, 12, &MONTH_NAMES)?;
+
+        let (mut dates, mut from_last, mut nearest_weekdays) = (0, 0, 0);
+        let mut last_weekday = false;
+        let any_date = field(
+            any(fields[2]),
+            1,
+            31,
+            &[],
+            &mut |n| dates |= 1 << n,
+            &mut |item, pos| {
+                if item == "LW" {
+                    last_weekday = true;
+                } else if let Some(back) = item.strip_prefix('L') {
+                    let back = match back.strip_prefix('-') {
+                        Some(back) => value(back, pos + 2, 0, 30, &[])?,
+                        None if back.is_empty() => 0,
+                        None => return Err(ParseError::Invalid(pos + 1)),
+                    };
+                    from_last |= 1 << back;
+                } else if let Some(date) = item.strip_suffix('W') {
+                    nearest_weekdays |= 1 << value(date, pos, 1, 31, &[])?;
+                } else {
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        let (mut days, mut last_days, mut nth_days) = (0, 0, [0; 5]);
+        let any_day = field(
+            any(fields[4]),
+            min_day,
+            max_day,
+            &DAY_NAMES,
+            &mut |n| days |= 1 << day_index(n),
+            &mut |item, pos| {
+                if let Some(day) = item.strip_suffix('L') {
+                    last_days |= 1 << day_index(value(day, pos, min_day, max_day, &DAY_NAMES)?);
+                } else if let Some(i) = item.find('#') {
+                    let day = value(&item[..i], pos, min_day, max_day, &DAY_NAMES)?;
+                    let nth = value(&item[i + 1..], pos + i + 1, 1, 5, &[])?;
+                    nth_days[nth as usize - 1] |= 1 << day_index(day);
+                } else {
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        let years = match fields.get(5) {
+            Some(&text) => {
+                let mut years = [0; 4];
+                let set = &mut |n: u32| {
+                    let n = n - FIRST_YEAR;
+                    years[n as usize / 64] |= 1 << (n % 64);
+                };
+                field(text, FIRST_YEAR, LAST_YEAR, &[], set, &mut plain)?;
+                Some(years)
+            }
+            None => None,
+        };
+
+        Ok(Cron {
+            seconds,
+            minutes,
+            hours: hours as u32,
+            dates,
+            months: months as u16,
+            days,
+            any_date,
+            any_day,
+            from_last,
+            nearest_weekdays,
+            last_weekday,
+            last_days,
+            nth_days,
+            years,
+        })
+    }
+}
+
+// a field's allowed values as bits, and whether it started with `*`
+fn bits(
+    text: (&str, usize),
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<(u64, bool), ParseError> {
+    let mut bits = 0;
+    let any = field(text, min, max, names, &mut |n| bits |= 1 << n, &mut plain)?;
+    Ok((bits, any))
+}
+
+// a field with no items other than values, ranges and steps
+fn plain(_: &str, _: usize) -> Result<bool, ParseError> {
+    Ok(false)
+}
+
+// calls `set` with each of a field's allowed values, after offering each
+// item to `special`, which returns true if it took it; returns whether the
+// field started with `*`
+fn field(
+    (text, pos): (&str, usize),
+    min: u32,
+    max: u32,
+    names: &[&str],
+    set: &mut dyn FnMut(u32),
+    special: &mut dyn FnMut(&str, usize) -> Result<bool, ParseError>,
+) -> Result<bool, ParseError> {
+    let mut item_pos = pos;
+    for item in text.split(',') {
+        if special(item, item_pos)? {
+            item_pos += item.len() + 1;
+            continue;
+        }
+
+        let (range, step) = match item.find('/') {
+            Some(i) => {
+                let step = value(&item[i + 1..], item_pos + i + 1, 1, max, &[])?;
+                (&item[..i], Some(step))
+            }
+            None => (item, None),
+        };
+
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some(i) = range.find('-') {
+            let from = value(&range[..i], item_pos, min, max, names)?;
+            let to = value(&range[i + 1..], item_pos + i + 1, min, max, names)?;
+            if from > to {
+                return Err(ParseError::OutOfRange);
+            }
+            (from, to)
+        } else {
+            let from = value(range, item_pos, min, max, names)?;
+            // a single value with a step runs to the end of the field
+            (from, if step.is_some() { max } else { from })
+        };
+
+        for n in (from..=to).step_by(step.unwrap_or(1) as usize) {
+            set(n);
+        }
+        item_pos += item.len() + 1;
+    }
+
+    Ok(text.starts_with('*'))
+}
+
+// a number or name in a field, from `min` to `max`
+fn value(text: &str, pos: usize, min: u32, max: u32, names: &[&str]) -> Result<u32, ParseError> {
+    if let Some(i) = names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(text))
+    {
+        // month names count from 1, and day names from 0 or 1 as the
+        // numbers do
+        return Ok(i as u32 + min);
+    }
+
+    match text.bytes().position(|b| !b.is_ascii_digit()) {
+        Some(i) => Err(ParseError::Invalid(pos + i)),
+        None if text.is_empty() => Err(ParseError::TooShort),
+        None => match text.parse() {
+            Ok(n) if (min..=max).contains(&n) => Ok(n),
+            _ => Err(ParseError::OutOfRange),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cron;
+    use crate::{DateTime, FixedOffset, ParseError};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    fn next(cron: &str, after: &str) -> Option<String> {
+        let cron: Cron = cron.parse().unwrap();
+        let date = DateTime::parse_rfc3339(after).unwrap();
+        cron.next_after(&date).map(|date| date.to_rfc3339())
+    }
+
+    #[test]
+    fn test_next_after() {
+        let at = "1996-09-09T15:23:44Z";
+        assert_eq!(next("* * * * *", at).unwrap(), "1996-09-09T15:24:00Z");
+        assert_eq!(next("23 15 * * *", at).unwrap(), "1996-09-10T15:23:00Z");
+        assert_eq!(next("0 0 1 1 *", at).unwrap(), "1997-01-01T00:00:00Z");
+        assert_eq!(next("@hourly", at).unwrap(), "1996-09-09T16:00:00Z");
+        assert_eq!(next("@weekly", at).unwrap(), "1996-09-15T00:00:00Z");
+        assert_eq!(next("30 8 * dec sun", at).unwrap(), "1996-12-01T08:30:00Z");
+        assert_eq!(next("0 12 * * 7", at).unwrap(), "1996-09-15T12:00:00Z");
+        assert_eq!(next("5/20 * * * *", at).unwrap(), "1996-09-09T15:25:00Z");
+
+        // the 13th or any Friday, whichever comes first
+        assert_eq!(next("0 0 13 * FRI", at).unwrap(), "1996-09-13T00:00:00Z");
+        assert_eq!(
+            next("0 0 13 * FRI", "1996-09-13T00:00:00Z").unwrap(),
+            "1996-09-20T00:00:00Z"
+        );
+        assert_eq!(next("0 0 31 4 *", at), None);
+
+        // in the DateTime's offset
+        let offset = FixedOffset::east(5 * 3600 + 45 * 60).unwrap();
+        let date = DateTime::parse_rfc3339(at).unwrap().with_offset(offset);
+        let cron: Cron = "0 9 * * *".parse().unwrap();
+        let times: Vec<_> = cron
+            .occurrences_after(&date)
+            .take(2)
+            .map(|date| date.to_rfc3339())
+            .collect();
+        assert_eq!(
+            times,
+            ["1996-09-10T09:00:00+05:45", "1996-09-11T09:00:00+05:45"]
+        );
+
+        let last = DateTime::from_signed_secs(crate::MAX_SECS);
+        assert!(cron.next_after(&last).is_none());
+        assert!("59 23 * * *"
+            .parse::<Cron>()
+            .unwrap()
+            .next_after(&last.with_offset(offset))
+            .is_none());
+    }
+
+    #[test]
+    fn test_quartz() {
+        let at = "1996-09-09T15:23:44Z";
+        assert_eq!(next("* * * * * ?", at).unwrap(), "1996-09-09T15:23:45Z");
+        assert_eq!(next("50 23 15 * * ?", at).unwrap(), "1996-09-09T15:23:50Z");
+        assert_eq!(next("0 0 12 ? * 1", at).unwrap(), "1996-09-15T12:00:00Z");
+        assert_eq!(next("0 0 12 ? * SAT", at).unwrap(), "1996-09-14T12:00:00Z");
+        assert_eq!(
+            next("0 0 0 1 1 ? 2000/5", at).unwrap(),
+            "2000-01-01T00:00:00Z"
+        );
+        assert_eq!(next("0 0 0 1 1 ? 1990", at), None);
+
+        // the last days and weekdays of the month
+        assert_eq!(next("0 0 0 L * ?", at).unwrap(), "1996-09-30T00:00:00Z");
+        assert_eq!(next("0 0 0 L-2 2 ?", at).unwrap(), "1997-02-26T00:00:00Z");
+        assert_eq!(next("0 0 0 LW 11 ?", at).unwrap(), "1996-11-29T00:00:00Z");
+        assert_eq!(next("0 0 0 ? * 2L", at).unwrap(), "1996-09-30T00:00:00Z");
+        assert_eq!(next("0 0 0 ? * 6#2", at).unwrap(), "1996-09-13T00:00:00Z");
+        assert_eq!(next("0 0 0 ? * MON#5", at).unwrap(), "1996-09-30T00:00:00Z");
+
+        // the nearest weekday stays in the month
+        assert_eq!(next("0 0 0 14W * ?", at).unwrap(), "1996-09-13T00:00:00Z");
+        assert_eq!(next("0 0 0 15W * ?", at).unwrap(), "1996-09-16T00:00:00Z");
+        assert_eq!(next("0 0 0 1W 3 ?", at).unwrap(), "1997-03-03T00:00:00Z");
+        assert_eq!(next("0 0 0 30W 11 ?", at).unwrap(), "1996-11-29T00:00:00Z");
+        assert_eq!(next("0 0 0 31W 11 ?", "1996-12-01T00:00:00Z"), None);
+
+        // the five-field form takes the same tokens, numbering days from 0
+        assert_eq!(next("0 0 ? * 5L", at).unwrap(), "1996-09-27T00:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input: &str| input.parse::<Cron>();
+
+        assert!(parse("  0  9\t* *  1-5 ").is_ok());
+        assert_eq!(parse("0 9 * *"), Err(ParseError::TooShort));
+        assert_eq!(parse("0 0 9 * * * * *"), Err(ParseError::TooLong));
+        assert_eq!(parse("0 9 * * 0 0"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 0 9 * * * 1969"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 ? * * *"), Err(ParseError::Invalid(2)));
+        assert_eq!(parse("0 9 LX * *"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("0 9 L-31 * *"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 9 * * FRI#6"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 9 * * L"), Err(ParseError::TooShort));
+        assert_eq!(parse("60 9 * * *"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 9 0 * *"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 17-9 * * *"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("*/0 * * * *"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0 9 * FOO *"), Err(ParseError::Invalid(6)));
+        assert_eq!(parse("0 9,x * * *"), Err(ParseError::Invalid(4)));
+        assert_eq!(parse("0 9, * * *"), Err(ParseError::TooShort));
+    }
+}
@@ -0,0 +1,159 @@
+//! the phase of periodic phenomena
+//!
+//! anything that repeats with a steady period, such as the Moon's phases,
+//! a tide, a circadian rhythm or a sampled signal, can be described by a
+//! time when a cycle started (the epoch) and the period. the phase at any
+//! time is then how far through its cycle it is, from 0 up to (but not
+//! including) 1
+//! ```
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! // a new moon, and the mean length of a lunar month
+//! let new_moon = DateTime::parse_rfc3339("2000-01-06T18:14:00Z").unwrap();
+//! let synodic_month = Duration::from_secs_f64(29.530_588_853 * 86_400.0);
+//!
+//! let date = DateTime::parse_rfc3339("2024-04-23T23:49:00Z").unwrap();
+//! let phase = date.phase_in_cycle(&new_moon, synodic_month);
+//! // close to a full moon
+//! assert!((phase - 0.5).abs() < 0.02);
+//! ```
+use crate::{DateTime, MAX_SECS, MIN_SECS};
+use core::convert::TryFrom;
+use core::time::Duration;
+
+// nanoseconds from `epoch` to `date`
+fn nanos_since(date: &DateTime, epoch: &DateTime) -> i128 {
+    (date.secs as i128 - epoch.secs as i128) * 1_000_000_000 + date.nanos as i128
+        - epoch.nanos as i128
+}
+
+impl DateTime {
+    /// returns the phase, from 0 up to 1, of a cycle with the given period
+    /// that started at `epoch`; times before the epoch are in earlier
+    /// cycles
+    ///
+    /// # Panics
+    ///
+    /// panics if the period is zero
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::Duration;
+    /// let epoch = DateTime::from_secs(0);
+    /// let day = Duration::from_secs(86_400);
+    ///
+    /// assert_eq!(DateTime::from_secs(6 * 3600).phase_in_cycle(&epoch, day), 0.25);
+    /// assert_eq!(DateTime::from_signed_secs(-6 * 3600).phase_in_cycle(&epoch, day), 0.75);
+    /// ```
+    pub fn phase_in_cycle(&self, epoch: &DateTime, period: Duration) -> f64 {
+        let period = period.as_nanos();
+        assert!(period > 0, "a cycle's period must not be zero");
+
+        // the span between any two DateTimes and the longest Duration both
+        // fit in an i128 of nanoseconds
+        let into_cycle = nanos_since(self, epoch).rem_euclid(period as i128);
+
+        let phase = into_cycle as f64 / period as f64;
+        // rounding can bring a phase just short of 1 up to it
+        if phase < 1.0 {
+            phase
+        } else {
+            0.0
+        }
+    }
+
+    /// returns the first time after this one at which a cycle with the
+    /// given period that started at `epoch` reaches the given phase (from
+    /// 0 up to 1), to within a nanosecond, or None if that is out of
+    /// range or the phase is not in `0.0..1.0`
+    ///
+    /// # Panics
+    ///
+    /// panics if the period is zero
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::Duration;
+    /// let epoch = DateTime::from_secs(0);
+    /// let day = Duration::from_secs(86_400);
+    ///
+    /// let noon = DateTime::from_secs(3600).next_phase(&epoch, day, 0.5).unwrap();
+    /// assert_eq!(noon, DateTime::from_secs(43_200));
+    /// ```
+    pub fn next_phase(&self, epoch: &DateTime, period: Duration, phase: f64) -> Option<DateTime> {
+        let period_nanos = period.as_nanos();
+        assert!(period_nanos > 0, "a cycle's period must not be zero");
+        if !(0.0..1.0).contains(&phase) {
+            return None;
+        }
+
+        let period_nanos = period_nanos as i128;
+        let offset = (phase * period_nanos as f64) as i128;
+
+        let elapsed = nanos_since(self, epoch);
+        let cycle = elapsed.div_euclid(period_nanos);
+        let mut target = cycle.checked_mul(period_nanos)?.checked_add(offset)?;
+        if target <= elapsed {
+            target = target.checked_add(period_nanos)?;
+        }
+
+        // back to seconds and nanoseconds since the Unix epoch
+        let nanos =
+            (epoch.secs as i128 * 1_000_000_000 + epoch.nanos as i128).checked_add(target)?;
+        let secs = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+        if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+            return None;
+        }
+        let nanos = nanos.rem_euclid(1_000_000_000) as u32;
+
+        Some(DateTime::from_parts(secs, nanos, self.offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, MAX_SECS, MIN_SECS};
+    use core::time::Duration;
+
+    #[test]
+    fn test_phase_in_cycle() {
+        let epoch = DateTime::from_millis(1_500);
+        let period = Duration::from_millis(400);
+
+        let phase = |millis| DateTime::from_millis(millis).phase_in_cycle(&epoch, period);
+        assert_eq!(phase(1_500), 0.0);
+        assert_eq!(phase(1_600), 0.25);
+        assert_eq!(phase(1_900), 0.0);
+        assert_eq!(phase(1_400), 0.75);
+        assert_eq!(phase(-1_000_300), 0.5);
+
+        // the widest possible span, and the longest period
+        let first = DateTime::from_signed_secs(MIN_SECS);
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        let phase = last.phase_in_cycle(&first, Duration::from_secs(7 * 86_400));
+        assert!((0.0..1.0).contains(&phase));
+        let phase = first.phase_in_cycle(&last, Duration::MAX);
+        assert!((0.0..1.0).contains(&phase));
+    }
+
+    #[test]
+    fn test_next_phase() {
+        let epoch = DateTime::from_secs(1_000);
+        let period = Duration::from_secs(100);
+
+        let next = |secs, phase| {
+            DateTime::from_signed_secs(secs)
+                .next_phase(&epoch, period, phase)
+                .map(|date| date.secs)
+        };
+        assert_eq!(next(1_000, 0.0), Some(1_100));
+        assert_eq!(next(1_010, 0.5), Some(1_050));
+        assert_eq!(next(1_050, 0.5), Some(1_150));
+        assert_eq!(next(960, 0.25), Some(1_025));
+        assert_eq!(next(-1_000, 0.1), Some(-990));
+        assert_eq!(next(0, 1.0), None);
+        assert_eq!(next(0, -0.5), None);
+        assert_eq!(next(0, f64::NAN), None);
+
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert!(last.next_phase(&epoch, period, 0.0).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+//! dates without a time, and times without a date
+//!
+//! a [`Date`] is a day in the calendar, such as a birthday, and a [`Time`]
+//! is a time of day, such as when a shop opens; neither is an instant
+//! until it is combined with the other and an offset. [`Date::and_time`]
+//! puts the two together into a [`CivilTime`], and
+//! [`DateTime::date_part`] and [`DateTime::time_part`] take a DateTime
+//! apart again
+//! ```
+//! # use datetime::{Date, DateTime, FixedOffset, Time};
+//! let birthday: Date = "1996-09-09".parse().unwrap();
+//! let opening = Time::new(9, 30, 0).unwrap();
+//!
+//! let civil = birthday.and_time(opening);
+//! assert_eq!(civil.to_string(), "1996-09-09T09:30:00");
+//!
+//! let date = civil.to_absolute(FixedOffset::utc()).to_datetime(FixedOffset::utc());
+//! assert_eq!(date.date_part(), birthday);
+//! assert_eq!(date.time_part(), opening);
+//! ```
+use crate::convert::{is_valid_date, is_valid_time, weekday};
+use crate::parse::{Cursor, ParseError};
+use crate::{CivilTime, DateTime, Day, InvalidDate, Month, MONTHS};
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+const NANOS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+
+/// a day in the proleptic Gregorian calendar, with no time or offset
+///
+/// Dates are ordered from earliest to latest
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: usize,
+    // 1-12, kept as a number so that Dates can be ordered
+    month: usize,
+    date: usize,
+}
+
+impl Date {
+    /// returns the date with the given fields, where `month` is 1-12, or
+    /// the first field that is out of range; the year can't be 0
+    /// ```
+    /// # use datetime::{Date, InvalidDate};
+    /// assert!(Date::new(2000, 2, 29).is_ok());
+    /// assert_eq!(Date::new(1900, 2, 29), Err(InvalidDate::LeapDay));
+    /// ```
+    pub fn new(year: usize, month: usize, date: usize) -> Result<Self, InvalidDate> {
+        is_valid_date(year, month, date)?;
+        Ok(Date { year, month, date })
+    }
+
+    /// returns the year
+    pub fn year(&self) -> usize {
+        self.year
+    }
+
+    /// returns the month
+    pub fn month(&self) -> Month {
+        MONTHS[self.month - 1]
+    }
+
+    /// returns the day of the month
+    pub fn date(&self) -> usize {
+        self.date
+    }
+
+    /// returns the day of the week
+    pub fn day(&self) -> Day {
+        weekday(self.year, self.month, self.date)
+    }
+
+    /// returns the wall-clock time at `time` on this date
+    pub fn and_time(&self, time: Time) -> CivilTime {
+        CivilTime::from_fields(
+            (self.year, self.month, self.date),
+            (time.hour, time.minute, time.second),
+            time.nanos,
+        )
+    }
+}
+
+impl fmt::Display for Date {
+    /// formats the date as `yyyy-mm-dd`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.date)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseError;
+
+    /// parses a date written `yyyy-mm-dd`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let year = cursor.digits(4)?;
+        cursor.expect(b'-')?;
+        let month = cursor.digits(2)?;
+        cursor.expect(b'-')?;
+        let date = cursor.digits(2)?;
+        cursor.finish()?;
+
+        Date::new(year, month, date).map_err(|_| ParseError::OutOfRange)
+    }
+}
+
+/// a time of day, to the nanosecond, with no date or offset
+///
+/// Times are ordered from midnight to the last nanosecond of the day
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: usize,
+    minute: usize,
+    second: usize,
+    nanos: u32,
+}
+
+impl Time {
+    /// the start of the day
+    pub const MIDNIGHT: Time = Time {
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanos: 0,
+    };
+
+    /// twelve o'clock in the day
+    pub const NOON: Time = Time {
+        hour: 12,
+        minute: 0,
+        second: 0,
+        nanos: 0,
+    };
+
+    /// returns the time with the given fields, or the first field that is
+    /// out of range
+    /// ```
+    /// # use datetime::{InvalidDate, Time};
+    /// assert_eq!(Time::new(23, 59, 59).unwrap().to_string(), "23:59:59");
+    /// assert_eq!(Time::new(24, 0, 0), Err(InvalidDate::Hour));
+    /// ```
+    pub fn new(hour: usize, minute: usize, second: usize) -> Result<Self, InvalidDate> {
+        is_valid_time(hour, minute, second)?;
+        Ok(Time {
+            hour,
+            minute,
+            second,
+            nanos: 0,
+        })
+    }
+
+    /// returns the same time with the given fraction of a second, or None
+    /// if `nanos` is not less than a second
+    pub fn with_nanosecond(&self, nanos: u32) -> Option<Self> {
+        if nanos >= 1_000_000_000 {
+            return None;
+        }
+
+        Some(Time { nanos, ..*self })
+    }
+
+    /// returns the hour
+    pub fn hour(&self) -> usize {
+        self.hour
+    }
+
+    /// returns the minute
+    pub fn minute(&self) -> usize {
+        self.minute
+    }
+
+    /// returns the second
+    pub fn second(&self) -> usize {
+        self.second
+    }
+
+    /// returns the fraction of the second in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+
+    /// returns the whole seconds since midnight
+    pub fn seconds_from_midnight(&self) -> u32 {
+        (self.hour * 3600 + self.minute * 60 + self.second) as u32
+    }
+
+    /// returns how far through the day the time is, from 0 at midnight
+    /// up to but not including 1
+    /// ```
+    /// # use datetime::Time;
+    /// assert_eq!(Time::new(18, 0, 0).unwrap().fraction_of_day(), 0.75);
+    /// ```
+    pub fn fraction_of_day(&self) -> f64 {
+        self.nanos_from_midnight() as f64 / NANOS_PER_DAY as f64
+    }
+
+    /// formats the time in French Revolutionary decimal time, where the
+    /// day has 10 hours of 100 minutes of 100 seconds, as `h:mm:ss`;
+    /// the decimal second is truncated
+    /// ```
+    /// # use datetime::Time;
+    /// assert_eq!(Time::NOON.to_decimal_time(), "5:00:00");
+    /// assert_eq!(Time::new(18, 0, 0).unwrap().to_decimal_time(), "7:50:00");
+    /// ```
+    pub fn to_decimal_time(&self) -> String {
+        let seconds = self.nanos_from_midnight() * 100_000 / NANOS_PER_DAY;
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 10_000,
+            seconds / 100 % 100,
+            seconds % 100
+        )
+    }
+
+    fn nanos_from_midnight(&self) -> u64 {
+        self.seconds_from_midnight() as u64 * 1_000_000_000 + self.nanos as u64
+    }
+}
+
+impl fmt::Display for Time {
+    /// formats the time as `hh:mm:ss`, with a fraction if it is not a
+    /// whole second
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanos != 0 {
+            write!(f, ".{:09}", self.nanos)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Time {
+    type Err = ParseError;
+
+    /// parses a time written `hh:mm`, `hh:mm:ss` or `hh:mm:ss.fff`, with
+    /// any number of fractional digits
+    /// ```
+    /// # use datetime::Time;
+    /// let time: Time = "09:30:00.25".parse().unwrap();
+    /// assert_eq!(time.nanosecond(), 250_000_000);
+    /// assert_eq!("09:30".parse::<Time>(), Time::new(9, 30, 0).map_err(|_| unreachable!()));
+    /// ```
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let hour = cursor.digits(2)?;
+        cursor.expect(b':')?;
+        let minute = cursor.digits(2)?;
+        let (second, nanos) = if cursor.peek() == Some(b':') {
+            cursor.next()?;
+            let second = cursor.digits(2)?;
+            let nanos = if cursor.peek() == Some(b'.') {
+                cursor.next()?;
+                cursor.fraction()?
+            } else {
+                0
+            };
+            (second, nanos)
+        } else {
+            (0, 0)
+        };
+        cursor.finish()?;
+
+        let time = Time::new(hour, minute, second).map_err(|_| ParseError::OutOfRange)?;
+        Ok(Time { nanos, ..time })
+    }
+}
+
+impl DateTime {
+    /// returns the DateTime's date, as reported in its offset
+    pub fn date_part(&self) -> Date {
+        Date {
+            year: self.year(),
+            month: self.month() as usize + 1,
+            date: self.date(),
+        }
+    }
+
+    /// returns the DateTime's time of day, as reported in its offset
+    pub fn time_part(&self) -> Time {
+        Time {
+            hour: self.hour(),
+            minute: self.minute(),
+            second: self.second(),
+            nanos: self.nanos,
+        }
+    }
+
+    /// returns how far through the day the DateTime is in its offset; see
+    /// [`Time::fraction_of_day`]
+    pub fn fraction_of_day(&self) -> f64 {
+        self.time_part().fraction_of_day()
+    }
+
+    /// formats the DateTime's time of day in its offset as decimal time;
+    /// see [`Time::to_decimal_time`]
+    pub fn to_decimal_time(&self) -> String {
+        self.time_part().to_decimal_time()
+    }
+
+    /// formats the DateTime in Swatch Internet Time, as `@` and the
+    /// number of .beats (thousandths of a day) since midnight in Biel,
+    /// which is always UTC+1; the DateTime's own offset doesn't matter
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+    ///
+    /// assert_eq!(date.to_swatch_beats(), "@683");
+    /// ```
+    pub fn to_swatch_beats(&self) -> String {
+        let secs = (self.secs + 3600).rem_euclid(86_400) as u64;
+        let nanos = secs * 1_000_000_000 + self.nanos as u64;
+        format!("@{:03}", nanos * 1000 / NANOS_PER_DAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date, Time};
+    use crate::{DateTime, Day, FixedOffset, InvalidDate, Month, ParseError};
+
+    #[test]
+    fn test_date() {
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(date.month(), Month::February);
+        assert_eq!(date.day(), Day::Thursday);
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert_eq!("2024-02-29".parse(), Ok(date));
+
+        assert!(date < Date::new(2024, 3, 1).unwrap());
+        assert!(date > Date::new(2023, 12, 31).unwrap());
+        assert_eq!(Date::new(0, 1, 1), Err(InvalidDate::Year));
+        assert_eq!(Date::new(2024, 0, 1), Err(InvalidDate::Month));
+
+        assert_eq!("2023-02-29".parse::<Date>(), Err(ParseError::OutOfRange));
+        assert_eq!("2024-2-29".parse::<Date>(), Err(ParseError::Invalid(6)));
+        assert_eq!("2024-02-29T".parse::<Date>(), Err(ParseError::TooLong));
+    }
+
+    #[test]
+    fn test_time() {
+        let time = Time::new(9, 30, 5).unwrap().with_nanosecond(500).unwrap();
+        assert_eq!(time.to_string(), "09:30:05.000000500");
+        assert_eq!(time.seconds_from_midnight(), 34205);
+        assert!(Time::MIDNIGHT < time && time < Time::NOON);
+        assert!(time.with_nanosecond(1_000_000_000).is_none());
+
+        assert_eq!("09:30:05.0000005".parse(), Ok(time));
+        assert_eq!("23:59".parse(), Ok(Time::new(23, 59, 0).unwrap()));
+        assert_eq!("24:00".parse::<Time>(), Err(ParseError::OutOfRange));
+        assert_eq!("9:30".parse::<Time>(), Err(ParseError::Invalid(1)));
+        assert_eq!("09:30:".parse::<Time>(), Err(ParseError::TooShort));
+        assert_eq!(Time::new(0, 60, 0), Err(InvalidDate::Minute));
+    }
+
+    #[test]
+    fn test_day_fractions() {
+        let time = Time::new(23, 59, 59)
+            .unwrap()
+            .with_nanosecond(999_999_999)
+            .unwrap();
+        assert!(time.fraction_of_day() < 1.0);
+        assert_eq!(Time::MIDNIGHT.fraction_of_day(), 0.0);
+        assert_eq!(time.to_decimal_time(), "9:99:99");
+        assert_eq!(Time::new(2, 24, 0).unwrap().to_decimal_time(), "1:00:00");
+
+        // .beats ignore the offset, and @000 is 23:00 UTC
+        let date = DateTime::from_secs(82800).with_offset(FixedOffset::west(5 * 3600).unwrap());
+        assert_eq!(date.to_swatch_beats(), "@000");
+        assert_eq!(date.fraction_of_day(), 0.75);
+        assert_eq!(date.to_decimal_time(), "7:50:00");
+        assert_eq!(DateTime::from_signed_secs(-1).to_swatch_beats(), "@041");
+    }
+
+    #[test]
+    fn test_parts() {
+        let offset = FixedOffset::west(5 * 3600).unwrap();
+        let date = DateTime::from_millis(842282624250).with_offset(offset);
+
+        let (day, time) = (date.date_part(), date.time_part());
+        assert_eq!(day.to_string(), "1996-09-09");
+        assert_eq!(time.to_string(), "10:23:44.250000000");
+        assert_eq!(
+            day.and_time(time).to_absolute(offset).to_datetime(offset),
+            date
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! growing degree days and growing seasons
+//!
+//! a crop or pest develops roughly in proportion to the heat it gets above
+//! a base temperature below which it doesn't grow at all. growing degree
+//! days (GDD) measure that heat from each day's minimum and maximum
+//! temperature, and add up over a season to predict when a crop will
+//! flower or ripen. [`DegreeDays`] works them out for one base temperature
+//! (and optional upper cutoff), and [`growing_season`] finds the stretch of
+//! a year's days without frost
+//!
+//! readings are `(date, min, max)` tuples of daily temperatures, in any
+//! unit as long as it is the same for the base temperature. each reading
+//! counts for the UTC calendar day its date falls on
+//! ```
+//! # use datetime::degree_days::DegreeDays;
+//! # use datetime::{DateTime, Interval};
+//! let day = |date: &str| DateTime::parse_rfc3339(date).unwrap();
+//! let readings = [
+//!     (day("2024-05-01T00:00:00Z"), 8.0, 20.0),
+//!     (day("2024-05-02T00:00:00Z"), 12.0, 26.0),
+//!     (day("2024-05-03T00:00:00Z"), 2.0, 9.0),
+//! ];
+//!
+//! // corn, with a base of 10°C and an upper cutoff of 30°C
+//! let corn = DegreeDays::new(10.0).with_upper_cutoff(30.0);
+//! let may = Interval::new(&day("2024-05-01T00:00:00Z"), &day("2024-06-01T00:00:00Z"));
+//!
+//! assert_eq!(corn.daily(8.0, 20.0), 5.0);
+//! assert_eq!(corn.accumulate(readings.iter().copied(), &may), 14.0);
+//! ```
+use crate::range::Step;
+use crate::{DateTime, Interval};
+use alloc::vec::Vec;
+
+// the UTC day number of a DateTime
+fn day_number(date: &DateTime) -> i64 {
+    date.secs.div_euclid(86_400)
+}
+
+/// a way of working out growing degree days, from a base temperature and
+/// an optional upper cutoff
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DegreeDays {
+    base: f64,
+    upper: Option<f64>,
+}
+
+impl DegreeDays {
+    /// returns a calculation with the given base temperature, with the
+    /// simple average method: the day's mean temperature less the base, or
+    /// zero if the mean is below the base
+    pub fn new(base: f64) -> Self {
+        DegreeDays { base, upper: None }
+    }
+
+    /// returns the calculation with an upper cutoff, above which a crop
+    /// grows no faster, as the modified method used for corn and many
+    /// other crops: temperatures are clamped between the base and the
+    /// cutoff before averaging
+    pub fn with_upper_cutoff(self, upper: f64) -> Self {
+        DegreeDays {
+            upper: Some(upper),
+            ..self
+        }
+    }
+
+    /// returns the base temperature
+    pub fn base(&self) -> f64 {
+        self.base
+    }
+
+    /// returns the upper cutoff, if any
+    pub fn upper_cutoff(&self) -> Option<f64> {
+        self.upper
+    }
+
+    /// returns the growing degree days of a day with the given minimum and
+    /// maximum temperatures, which is never negative
+    /// ```
+    /// # use datetime::degree_days::DegreeDays;
+    /// let simple = DegreeDays::new(10.0);
+    /// assert_eq!(simple.daily(4.0, 20.0), 2.0);
+    ///
+    /// let modified = simple.with_upper_cutoff(30.0);
+    /// assert_eq!(modified.daily(4.0, 34.0), 10.0);
+    /// ```
+    pub fn daily(&self, min: f64, max: f64) -> f64 {
+        let (min, max) = match self.upper {
+            Some(upper) => (min.max(self.base).min(upper), max.max(self.base).min(upper)),
+            None => (min, max),
+        };
+
+        ((min + max) / 2.0 - self.base).max(0.0)
+    }
+
+    /// returns the growing degree days of the readings whose dates fall in
+    /// the interval, added up
+    ///
+    /// days missing from the readings add nothing, and a day read more
+    /// than once counts each time
+    pub fn accumulate<I>(&self, readings: I, interval: &Interval) -> f64
+    where
+        I: IntoIterator<Item = (DateTime, f64, f64)>,
+    {
+        readings
+            .into_iter()
+            .filter(|(date, _, _)| interval.contains(date))
+            .map(|(_, min, max)| self.daily(min, max))
+            .sum()
+    }
+
+    /// returns the running total of growing degree days at the end of each
+    /// UTC day starting in the interval, with the start of the day
+    ///
+    /// a day missing from the readings adds nothing, so its total is the
+    /// same as the day before's, and a day read more than once counts each
+    /// time
+    /// ```
+    /// # use datetime::degree_days::DegreeDays;
+    /// # use datetime::{DateTime, Interval};
+    /// let may_1 = DateTime::parse_rfc3339("2024-05-01T00:00:00Z").unwrap();
+    /// let may_3 = DateTime::parse_rfc3339("2024-05-03T00:00:00Z").unwrap();
+    /// let may_4 = DateTime::parse_rfc3339("2024-05-04T00:00:00Z").unwrap();
+    /// let readings = [(may_1, 10.0, 20.0), (may_3, 12.0, 24.0)];
+    ///
+    /// let totals = DegreeDays::new(10.0).running_totals(readings, &Interval::new(&may_1, &may_4));
+    /// let totals: Vec<f64> = totals.iter().map(|&(_, total)| total).collect();
+    /// assert_eq!(totals, [5.0, 5.0, 13.0]);
+    /// ```
+    pub fn running_totals<I>(&self, readings: I, interval: &Interval) -> Vec<(DateTime, f64)>
+    where
+        I: IntoIterator<Item = (DateTime, f64, f64)>,
+    {
+        let mut daily: Vec<(i64, f64)> = readings
+            .into_iter()
+            .filter(|(date, _, _)| interval.contains(date))
+            .map(|(date, min, max)| (day_number(&date), self.daily(min, max)))
+            .collect();
+        daily.sort_by_key(|&(day, _)| day);
+
+        // start from midnight on the interval's first day
+        let first = DateTime::from_signed_secs(day_number(&interval.start()) * 86_400);
+        let mut daily = daily.into_iter().peekable();
+        let mut total = 0.0;
+
+        DateTime::range(&first, &interval.end(), Step::Days(1))
+            .map(|day| {
+                while let Some((_, gdd)) = daily.next_if(|&(n, _)| n == day_number(&day)) {
+                    total += gdd;
+                }
+                (day, total)
+            })
+            .collect()
+    }
+
+    /// returns the start of the UTC day on which the running total of
+    /// growing degree days in the interval first reaches the target, as
+    /// when a crop is expected to reach a growth stage, or None if it never
+    /// does
+    pub fn date_reaching<I>(
+        &self,
+        readings: I,
+        interval: &Interval,
+        target: f64,
+    ) -> Option<DateTime>
+    where
+        I: IntoIterator<Item = (DateTime, f64, f64)>,
+    {
+        self.running_totals(readings, interval)
+            .into_iter()
+            .find(|&(_, total)| total >= target)
+            .map(|(day, _)| day)
+    }
+}
+
+/// returns the growing season in the readings: the longest run of
+/// consecutive UTC days whose minimum temperature stays above `frost`,
+/// from the start of its first day to the start of the day after its last,
+/// or None if every day had a frost
+///
+/// a day missing from the readings ends a run, and of two equally long
+/// runs the earlier is returned
+/// ```
+/// # use datetime::degree_days::growing_season;
+/// # use datetime::DateTime;
+/// let day = |n: i64| DateTime::from_signed_secs(n * 86_400);
+/// let minimums = [-2.0, 1.0, 3.0, 4.0, -1.0, 5.0];
+/// let readings = minimums.iter().enumerate().map(|(n, &min)| (day(n as i64), min, 15.0));
+///
+/// let season = growing_season(readings, 0.0).unwrap();
+/// assert_eq!((season.start(), season.end()), (day(1), day(4)));
+/// ```
+pub fn growing_season<I>(readings: I, frost: f64) -> Option<Interval>
+where
+    I: IntoIterator<Item = (DateTime, f64, f64)>,
+{
+    let mut days: Vec<(i64, f64)> = readings
+        .into_iter()
+        .map(|(date, min, _)| (day_number(&date), min))
+        .collect();
+    days.sort_by_key(|&(day, _)| day);
+
+    // (first day, day after the last) of the best run and the current one
+    let mut best: Option<(i64, i64)> = None;
+    let mut run: Option<(i64, i64)> = None;
+    for (day, min) in days {
+        run = match run {
+            _ if min <= frost => None,
+            // a second reading of the same day
+            Some((start, end)) if day < end => Some((start, end)),
+            Some((start, end)) if day == end => Some((start, end + 1)),
+            _ => Some((day, day + 1)),
+        };
+
+        if let Some((start, end)) = run {
+            if best.is_none_or(|(s, e)| end - start > e - s) {
+                best = Some((start, end));
+            }
+        }
+    }
+
+    best.map(|(start, end)| Interval::from_signed_secs(start * 86_400, end * 86_400))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{growing_season, DegreeDays};
+    use crate::{DateTime, Interval};
+
+    fn day(n: i64) -> DateTime {
+        DateTime::from_signed_secs(n * 86_400)
+    }
+
+    #[test]
+    fn test_daily_and_accumulate() {
+        let simple = DegreeDays::new(10.0);
+        assert_eq!(simple.daily(2.0, 12.0), 0.0);
+        assert_eq!(simple.daily(10.0, 30.0), 10.0);
+
+        let modified = simple.with_upper_cutoff(30.0);
+        assert_eq!(modified.daily(2.0, 12.0), 1.0);
+        assert_eq!(modified.daily(25.0, 40.0), 17.5);
+        assert_eq!(modified.upper_cutoff(), Some(30.0));
+
+        // readings part way through a day count for that day
+        let readings = [
+            (day(0), 10.0, 20.0),
+            (day(1).checked_add_secs(3600).unwrap(), 10.0, 30.0),
+            (day(2), 20.0, 20.0),
+        ];
+        let first_two = Interval::new(&day(0), &day(2));
+        assert_eq!(simple.accumulate(readings, &first_two), 15.0);
+        assert_eq!(
+            simple.date_reaching(readings, &first_two, 6.0),
+            Some(day(1))
+        );
+        assert_eq!(simple.date_reaching(readings, &first_two, 16.0), None);
+
+        let totals = simple.running_totals(readings, &Interval::new(&day(1), &day(4)));
+        assert_eq!(totals, [(day(1), 10.0), (day(2), 20.0), (day(3), 20.0)]);
+    }
+
+    #[test]
+    fn test_growing_season() {
+        let readings = |minimums: &[f64]| {
+            minimums
+                .iter()
+                .enumerate()
+                .map(|(n, &min)| (day(n as i64), min, min + 10.0))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(growing_season(readings(&[-1.0, 0.0]), 0.0).is_none());
+
+        let season = growing_season(readings(&[1.0, 1.0, -1.0, 2.0, 2.0]), 0.0).unwrap();
+        assert_eq!((season.start(), season.end()), (day(0), day(2)));
+
+        // a gap in the readings ends a run
+        let mut gappy = readings(&[1.0, 1.0, 1.0]);
+        gappy[2].0 = day(5);
+        gappy.push((day(1), 3.0, 8.0));
+        let season = growing_season(gappy, 0.0).unwrap();
+        assert_eq!((season.start(), season.end()), (day(0), day(2)));
+    }
+}
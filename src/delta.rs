@@ -0,0 +1,202 @@
+//! compact encoding of timestamp columns
+//!
+//! time-series data is usually sampled at a steady rate, so the gaps
+//! between its timestamps barely change. [`encode_deltas`] stores the
+//! first timestamp, the first gap and then only how much each gap differs
+//! from the one before, as in Facebook's Gorilla, with each number as a
+//! zigzag varint. a regularly sampled column then takes a single byte per
+//! timestamp
+//! ```
+//! # use datetime::delta::{decode_deltas, encode_deltas};
+//! # use datetime::DateTime;
+//! let dates: Vec<_> = (0..100).map(|i| DateTime::from_secs(842282624 + i * 10)).collect();
+//!
+//! let bytes = encode_deltas(&dates);
+//! assert!(bytes.len() < 120);
+//! assert_eq!(decode_deltas(&bytes).unwrap(), dates);
+//! ```
+//!
+//! # Format
+//!
+//! all numbers are LEB128 varints, and signed ones are zigzag encoded
+//! first. the encoding is the number of timestamps, then the first as
+//! nanoseconds since the Unix epoch, then the nanoseconds from the first
+//! to the second, and then for each later timestamp the difference
+//! between its gap from the one before and the previous gap. timestamps
+//! need not be sorted, though sorted ones compress best
+//!
+//! only the instants are stored, so decoded DateTimes are in UTC
+use crate::{DateTime, FixedOffset, ParseError, MAX_SECS, MIN_SECS};
+use alloc::vec::Vec;
+
+// nanoseconds since the Unix epoch
+fn to_nanos(date: &DateTime) -> i128 {
+    date.secs as i128 * 1_000_000_000 + date.nanos as i128
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_signed(out: &mut Vec<u8>, value: i128) {
+    write_varint(out, ((value << 1) ^ (value >> 127)) as u128);
+}
+
+// reads a varint starting at `pos`, moving `pos` past it
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u128, ParseError> {
+    let start = *pos;
+    let mut value = 0;
+    let mut shift = 0;
+
+    loop {
+        let b = *bytes.get(*pos).ok_or(ParseError::TooShort)?;
+        *pos += 1;
+
+        // the last byte of a u128 holds just two bits
+        if shift == 126 && b > 0b11 {
+            return Err(ParseError::Invalid(start));
+        }
+        value |= ((b & 0x7f) as u128) << shift;
+        if b & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+fn read_signed(bytes: &[u8], pos: &mut usize) -> Result<i128, ParseError> {
+    let value = read_varint(bytes, pos)?;
+    Ok((value >> 1) as i128 ^ -((value & 1) as i128))
+}
+
+/// encodes DateTimes as delta-of-delta varints (see the
+/// [module documentation](self) for the format)
+pub fn encode_deltas(dates: &[DateTime]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(dates.len() + 16);
+    write_varint(&mut out, dates.len() as u128);
+
+    let mut previous = 0;
+    let mut previous_delta = 0;
+    for (i, date) in dates.iter().enumerate() {
+        let nanos = to_nanos(date);
+        // differences between DateTimes fit in an i128 with plenty of room
+        let delta = nanos - previous;
+        match i {
+            0 => write_signed(&mut out, nanos),
+            1 => write_signed(&mut out, delta),
+            _ => write_signed(&mut out, delta - previous_delta),
+        }
+
+        previous = nanos;
+        previous_delta = delta;
+    }
+
+    out
+}
+
+/// decodes DateTimes written by [`encode_deltas`], in UTC
+///
+/// fails with [`ParseError::TooShort`] if the bytes end early,
+/// [`ParseError::TooLong`] if there are bytes left over,
+/// [`ParseError::Invalid`] with the offset of a varint too long for any
+/// timestamp, and [`ParseError::OutOfRange`] if a timestamp is outside the
+/// range of a DateTime
+/// ```
+/// # use datetime::delta::decode_deltas;
+/// # use datetime::ParseError;
+/// // two timestamps, but only one is there
+/// assert_eq!(decode_deltas(&[2, 0]), Err(ParseError::TooShort));
+/// assert_eq!(decode_deltas(&[0, 0]), Err(ParseError::TooLong));
+/// ```
+pub fn decode_deltas(bytes: &[u8]) -> Result<Vec<DateTime>, ParseError> {
+    let mut pos = 0;
+    let count = read_varint(bytes, &mut pos)?;
+
+    // every timestamp takes at least a byte, which bounds the allocation
+    let mut dates = Vec::with_capacity((count as usize).min(bytes.len()));
+    let mut previous = 0i128;
+    let mut previous_delta = 0i128;
+    let (min, max) = (
+        MIN_SECS as i128 * 1_000_000_000,
+        MAX_SECS as i128 * 1_000_000_000 + 999_999_999,
+    );
+
+    for i in 0..count {
+        let value = read_signed(bytes, &mut pos)?;
+        let delta = match i {
+            0 | 1 => value,
+            _ => previous_delta
+                .checked_add(value)
+                .ok_or(ParseError::OutOfRange)?,
+        };
+        let nanos = previous.checked_add(delta).ok_or(ParseError::OutOfRange)?;
+        if !(min..=max).contains(&nanos) {
+            return Err(ParseError::OutOfRange);
+        }
+
+        let secs = nanos.div_euclid(1_000_000_000) as i64;
+        let subsec = nanos.rem_euclid(1_000_000_000) as u32;
+        dates.push(DateTime::from_parts(secs, subsec, FixedOffset::utc()));
+
+        previous = nanos;
+        previous_delta = delta;
+    }
+
+    if pos < bytes.len() {
+        return Err(ParseError::TooLong);
+    }
+    Ok(dates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_deltas, encode_deltas, read_varint, write_varint};
+    use crate::{DateTime, FixedOffset, ParseError, MAX_SECS, MIN_SECS};
+
+    #[test]
+    fn test_round_trip() {
+        let offset = FixedOffset::east(3600).unwrap();
+        let dates = [
+            DateTime::from_nanos(842_282_624_123_456_789),
+            DateTime::from_signed_secs(MAX_SECS),
+            DateTime::from_signed_secs(MIN_SECS),
+            DateTime::from_nanos(-1).with_offset(offset),
+            DateTime::from_nanos(-1),
+        ];
+        assert_eq!(decode_deltas(&encode_deltas(&dates)).unwrap(), dates);
+        assert_eq!(decode_deltas(&encode_deltas(&[])).unwrap(), []);
+        assert_eq!(encode_deltas(&[]), [0]);
+
+        // a steady series costs a byte per timestamp after the first two
+        let steady: Vec<_> = (0..1000)
+            .map(|i| DateTime::from_millis(1_700_000_000_000 + i * 250))
+            .collect();
+        let bytes = encode_deltas(&steady);
+        assert!(bytes.len() < 1000 + 16);
+        assert_eq!(decode_deltas(&bytes).unwrap(), steady);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut bytes = encode_deltas(&[DateTime::from_secs(1), DateTime::from_secs(2)]);
+        bytes.pop();
+        assert_eq!(decode_deltas(&bytes), Err(ParseError::TooShort));
+        assert_eq!(decode_deltas(&[]), Err(ParseError::TooShort));
+
+        // 2^128 doesn't fit
+        let mut too_long = [0xff; 19];
+        too_long[18] = 0x04;
+        assert_eq!(read_varint(&too_long, &mut 0), Err(ParseError::Invalid(0)));
+        too_long[18] = 0x03;
+        assert_eq!(read_varint(&too_long, &mut 0), Ok(u128::MAX));
+
+        // one timestamp, 2^100 nanoseconds after the epoch
+        let mut out = vec![1];
+        write_varint(&mut out, 1 << 101);
+        assert_eq!(decode_deltas(&out), Err(ParseError::OutOfRange));
+    }
+}
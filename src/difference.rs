@@ -0,0 +1,224 @@
+//! differences between DateTimes in calendar units
+//!
+//! the seconds between two DateTimes don't say how many months or years
+//! apart they are, since months and years vary in length.
+//! [`DateTime::difference`] counts whole calendar years and months first,
+//! the way ages and membership lengths are counted, and then the days and
+//! time left over
+//! ```
+//! # use datetime::DateTime;
+//! let born = DateTime::parse_rfc3339("2000-02-29T08:00:00Z").unwrap();
+//! let today = DateTime::parse_rfc3339("2024-02-28T12:30:00Z").unwrap();
+//!
+//! let age = born.difference(&today);
+//! assert_eq!((age.years(), age.months(), age.days()), (23, 11, 30));
+//! assert_eq!((age.hours(), age.minutes()), (4, 30));
+//! assert_eq!(born.years_between(&today), 23);
+//! ```
+use crate::DateTime;
+
+/// a signed difference between two DateTimes, broken down into calendar
+/// years, months and days and a time of day, as returned by
+/// [`DateTime::difference`]
+///
+/// every field has the same sign, and the months, hours, minutes, seconds
+/// and nanoseconds are less than a year, day, hour, minute and second.
+/// differences are ordered by length, from the most negative
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct CalendarDifference {
+    years: i64,
+    months: i64,
+    days: i64,
+    hours: i64,
+    minutes: i64,
+    seconds: i64,
+    nanoseconds: i64,
+}
+
+impl CalendarDifference {
+    /// returns the whole years
+    pub fn years(&self) -> i64 {
+        self.years
+    }
+
+    /// returns the whole months after the years, from -11 to 11
+    pub fn months(&self) -> i64 {
+        self.months
+    }
+
+    /// returns the whole days after the months
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    /// returns the whole hours after the days, from -23 to 23
+    pub fn hours(&self) -> i64 {
+        self.hours
+    }
+
+    /// returns the whole minutes after the hours, from -59 to 59
+    pub fn minutes(&self) -> i64 {
+        self.minutes
+    }
+
+    /// returns the whole seconds after the minutes, from -59 to 59
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// returns the nanoseconds after the seconds
+    pub fn nanoseconds(&self) -> i64 {
+        self.nanoseconds
+    }
+
+    /// returns true if the difference runs backwards in time
+    pub fn is_negative(&self) -> bool {
+        *self < CalendarDifference::default()
+    }
+
+    // the same difference, running the other way
+    fn negate(self) -> CalendarDifference {
+        CalendarDifference {
+            years: -self.years,
+            months: -self.months,
+            days: -self.days,
+            hours: -self.hours,
+            minutes: -self.minutes,
+            seconds: -self.seconds,
+            nanoseconds: -self.nanoseconds,
+        }
+    }
+}
+
+impl DateTime {
+    /// returns the calendar difference from this DateTime to `other`, which
+    /// is negative if `other` is earlier, with both read in this
+    /// DateTime's offset
+    ///
+    /// a month is counted once the day of the month and time of day are
+    /// reached again, so from January 31 to February 28 is 28 days, and to
+    /// March 1 is a month and a day (February 28 being as close as February
+    /// gets to the 31st)
+    /// ```
+    /// # use datetime::DateTime;
+    /// let start = DateTime::parse_rfc3339("2023-01-31T00:00:00Z").unwrap();
+    /// let end = DateTime::parse_rfc3339("2023-03-01T00:00:00Z").unwrap();
+    ///
+    /// let difference = start.difference(&end);
+    /// assert_eq!((difference.months(), difference.days()), (1, 1));
+    ///
+    /// let difference = end.difference(&start);
+    /// assert_eq!((difference.months(), difference.days()), (-1, -1));
+    /// ```
+    pub fn difference(&self, other: &DateTime) -> CalendarDifference {
+        let end = other.with_offset(self.offset);
+        if end < *self {
+            return end.difference(self).negate();
+        }
+
+        let month_number = |date: &DateTime| date.year() as i64 * 12 + date.month() as i64;
+        let within_month = |date: &DateTime| {
+            (
+                date.date(),
+                date.hour(),
+                date.minute(),
+                date.second(),
+                date.nanosecond(),
+            )
+        };
+        let mut months = month_number(&end) - month_number(self);
+        if within_month(&end) < within_month(self) {
+            months -= 1;
+        }
+
+        // never later than the end, so always in range
+        let anchor = self
+            .checked_add_months(months)
+            .expect("a DateTime between two others is in range");
+        let mut secs = end.secs - anchor.secs;
+        let mut nanos = end.nanos as i64 - anchor.nanos as i64;
+        if nanos < 0 {
+            secs -= 1;
+            nanos += 1_000_000_000;
+        }
+
+        CalendarDifference {
+            years: months / 12,
+            months: months % 12,
+            days: secs / 86_400,
+            hours: secs / 3600 % 24,
+            minutes: secs / 60 % 60,
+            seconds: secs % 60,
+            nanoseconds: nanos,
+        }
+    }
+
+    /// returns the whole calendar years from this DateTime to `other`, as
+    /// in a person's age, negative if `other` is earlier (see
+    /// [`DateTime::difference`])
+    pub fn years_between(&self, other: &DateTime) -> i64 {
+        self.difference(other).years
+    }
+
+    /// returns the whole calendar months from this DateTime to `other`,
+    /// negative if `other` is earlier (see [`DateTime::difference`])
+    /// ```
+    /// # use datetime::DateTime;
+    /// let joined = DateTime::parse_rfc3339("2022-06-15T00:00:00Z").unwrap();
+    /// let now = DateTime::parse_rfc3339("2024-01-14T23:59:59Z").unwrap();
+    /// assert_eq!(joined.months_between(&now), 18);
+    /// ```
+    pub fn months_between(&self, other: &DateTime) -> i64 {
+        let difference = self.difference(other);
+        difference.years * 12 + difference.months
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, FixedOffset, MAX_SECS, MIN_SECS};
+
+    fn date(input: &str) -> DateTime {
+        DateTime::parse_rfc3339(input).unwrap()
+    }
+
+    #[test]
+    fn test_difference() {
+        let start = date("2023-01-31T10:00:00Z");
+        let fields = |end: &str| {
+            let d = start.difference(&date(end));
+            (d.years(), d.months(), d.days(), d.hours(), d.minutes())
+        };
+
+        assert_eq!(fields("2023-02-28T10:00:00Z"), (0, 0, 28, 0, 0));
+        assert_eq!(fields("2023-03-31T09:59:00Z"), (0, 1, 30, 23, 59));
+        assert_eq!(fields("2023-03-31T10:00:00Z"), (0, 2, 0, 0, 0));
+        assert_eq!(fields("2025-01-31T10:00:00Z"), (2, 0, 0, 0, 0));
+        assert_eq!(fields("2022-12-31T10:30:00Z"), (0, 0, -30, -23, -30));
+        assert_eq!(fields("2023-01-31T10:00:00Z"), (0, 0, 0, 0, 0));
+
+        let d = start.difference(&date("2023-01-31T09:59:59.75Z"));
+        assert_eq!((d.seconds(), d.nanoseconds()), (0, -250_000_000));
+        assert!(d.is_negative());
+        assert!(!start.difference(&start).is_negative());
+
+        // the widest range works without overflowing
+        let first = DateTime::from_signed_secs(MIN_SECS);
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert_eq!(first.years_between(&last), -last.years_between(&first));
+    }
+
+    #[test]
+    fn test_offsets() {
+        // 2023-01-30 23:30 in UTC is already the 31st at +01:00, and the
+        // end is already March
+        let offset = FixedOffset::east(3600).unwrap();
+        let start = date("2023-01-30T23:30:00Z");
+        let end = date("2023-02-28T23:30:00Z");
+
+        assert_eq!(start.months_between(&end), 0);
+        assert_eq!(start.difference(&end).days(), 29);
+        let difference = start.with_offset(offset).difference(&end);
+        assert_eq!((difference.months(), difference.days()), (1, 1));
+    }
+}
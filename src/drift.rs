@@ -0,0 +1,158 @@
+//! noticing when the system clock jumps
+//!
+//! the wall clock can be stepped at any time, by NTP correcting a large
+//! error or by someone setting the time by hand, while `Instant` only ever
+//! moves forward at a steady rate. a [`DriftMonitor`] compares the two each
+//! time it is sampled, and reports a [`ClockJump`] when the wall clock has
+//! moved by more than a threshold further than the monotonic clock
+//! ```no_run
+//! # use datetime::drift::DriftMonitor;
+//! # use std::time::Duration;
+//! let mut monitor = DriftMonitor::new(Duration::from_secs(1));
+//!
+//! loop {
+//!     std::thread::sleep(Duration::from_secs(10));
+//!     if let Some(jump) = monitor.sample() {
+//!         eprintln!("clock jumped by {:?} at {}", jump.jump(), jump.observed());
+//!     }
+//! }
+//! ```
+use crate::{DateTime, Span};
+use std::time::{Duration, Instant};
+
+/// a step change in the wall clock, as reported by [`DriftMonitor`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ClockJump {
+    expected: DateTime,
+    observed: DateTime,
+}
+
+impl ClockJump {
+    /// returns the time the wall clock would have read had it kept pace
+    /// with the monotonic clock
+    pub fn expected(&self) -> DateTime {
+        self.expected
+    }
+
+    /// returns the time the wall clock actually read
+    pub fn observed(&self) -> DateTime {
+        self.observed
+    }
+
+    /// returns how far the wall clock moved beyond the monotonic clock,
+    /// which is negative if it went back
+    pub fn jump(&self) -> Span {
+        &self.observed - &self.expected
+    }
+
+    /// returns true if the wall clock was set back
+    pub fn is_backward(&self) -> bool {
+        self.observed < self.expected
+    }
+}
+
+/// compares the wall clock with the monotonic clock between samples
+///
+/// each sample is compared only with the one before, so the slow slewing
+/// NTP uses for small corrections is never mistaken for a jump as long as
+/// samples are taken often enough
+#[derive(Debug, Clone)]
+pub struct DriftMonitor {
+    threshold: Duration,
+    instant: Instant,
+    wall: DateTime,
+}
+
+impl DriftMonitor {
+    /// returns a monitor reporting jumps of more than `threshold`, starting
+    /// from the current time
+    ///
+    /// # Panics
+    ///
+    /// panics as [`DateTime::now`] does
+    pub fn new(threshold: Duration) -> Self {
+        DriftMonitor {
+            threshold,
+            instant: Instant::now(),
+            wall: DateTime::now(),
+        }
+    }
+
+    /// returns the threshold a jump must exceed to be reported
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// reads both clocks, returning a ClockJump if the wall clock has
+    /// jumped since the last sample
+    ///
+    /// # Panics
+    ///
+    /// panics as [`DateTime::now`] does
+    pub fn sample(&mut self) -> Option<ClockJump> {
+        self.sample_at(Instant::now(), DateTime::now())
+    }
+
+    // compares a pair of readings with the last, and makes them the new
+    // baseline
+    fn sample_at(&mut self, instant: Instant, wall: DateTime) -> Option<ClockJump> {
+        let elapsed = instant.saturating_duration_since(self.instant);
+        // only out of range at the very end of time, where no jump can be
+        // measured anyway
+        let expected = self.wall.checked_add_duration(elapsed).unwrap_or(wall);
+        self.instant = instant;
+        self.wall = wall;
+
+        let jump = ClockJump {
+            expected,
+            observed: wall,
+        };
+        if jump.jump().unsigned_abs() > self.threshold {
+            Some(jump)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DriftMonitor;
+    use crate::{DateTime, Span};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_jumps() {
+        let mut monitor = DriftMonitor::new(Duration::from_millis(500));
+        let (start, wall) = (monitor.instant, monitor.wall);
+        let later = |secs: u64| start + Duration::from_secs(secs);
+
+        // the clocks agree, apart from a little slewing
+        let slewed = wall + Span::from_millis(10_200);
+        assert_eq!(monitor.sample_at(later(10), slewed), None);
+
+        // set forward by a minute
+        let stepped = wall + Span::from_secs(80);
+        let jump = monitor.sample_at(later(20), stepped).unwrap();
+        assert_eq!(jump.jump(), Span::from_millis(59_800));
+        assert!(!jump.is_backward());
+        assert_eq!(jump.observed(), stepped);
+
+        // and then back again
+        let jump = monitor
+            .sample_at(later(30), wall + Span::from_secs(30))
+            .unwrap();
+        assert_eq!(jump.jump(), Span::from_secs(-60));
+        assert!(jump.is_backward());
+        assert_eq!(jump.expected(), wall + Span::from_secs(90));
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut monitor = DriftMonitor::new(Duration::from_secs(60));
+        assert_eq!(monitor.threshold(), Duration::from_secs(60));
+        assert_eq!(monitor.sample(), None);
+        assert!(monitor.instant <= Instant::now());
+        assert!(monitor.wall <= DateTime::now());
+    }
+}
@@ -0,0 +1,603 @@
+//! the Extended Date/Time Format (EDTF), levels 0 and 1
+//!
+//! [EDTF](https://www.loc.gov/standards/datetime/) is the Library of
+//! Congress's profile of ISO 8601 for the vague and partial dates found in
+//! archives and collections. on top of ISO 8601's dates, times and
+//! intervals (level 0), level 1 adds:
+//!
+//! - qualifiers: `1984?` (uncertain), `2004-06~` (approximate) and
+//!   `2004-06-11%` (both)
+//! - unspecified digits: `201X`, `20XX`, `2004-XX` and `1985-04-XX`
+//! - seasons: `2001-21` to `2001-24` for spring to winter
+//! - intervals with an open (`..`) or unknown (empty) end: `1985-04-12/..`
+//!   and `/1985-04-12`
+//! - negative years, `-1985`, and years of more than four digits written
+//!   with a `Y`, `Y170000002`
+//! ```
+//! # use datetime::edtf::{Bound, Edtf, Qualifier};
+//! let date: Edtf = "1996-09?".parse().unwrap();
+//! match date {
+//!     Edtf::Date(date) => assert_eq!(date.qualifier(), Some(Qualifier::Uncertain)),
+//!     _ => unreachable!(),
+//! }
+//!
+//! let interval: Edtf = "1996~/..".parse().unwrap();
+//! assert!(matches!(interval, Edtf::Interval(Bound::Date(_), Bound::Open)));
+//! assert_eq!(interval.to_string(), "1996~/..");
+//! ```
+use crate::convert::days_from_civil;
+use crate::parse::{from_fields, zone, Cursor};
+pub use crate::season::Season;
+use crate::{DateTime, FixedOffset, Interval, ParseError, MAX_SECS, MIN_SECS};
+use core::fmt;
+use core::str::FromStr;
+
+/// how sure a date is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Qualifier {
+    /// `?`, the date may not be right
+    Uncertain,
+    /// `~`, the date is roughly right
+    Approximate,
+    /// `%`, the date is both uncertain and approximate
+    UncertainApproximate,
+}
+
+impl Qualifier {
+    fn symbol(self) -> char {
+        match self {
+            Qualifier::Uncertain => '?',
+            Qualifier::Approximate => '~',
+            Qualifier::UncertainApproximate => '%',
+        }
+    }
+}
+
+/// how much of an [`EdtfDate`] is given after its year; a month or day of
+/// None is unspecified, written `XX`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Precision {
+    /// just the year
+    Year,
+    /// the year and a season, written as a month from 21 (spring) to 24
+    /// (winter)
+    ///
+    /// EDTF doesn't say which months the seasons cover; this crate takes
+    /// them to be the meteorological seasons of the northern hemisphere,
+    /// with the winter of a year starting in its December
+    Season(Season),
+    /// the year and month
+    Month(Option<usize>),
+    /// the year, month and day
+    Day(Option<usize>, Option<usize>),
+}
+
+/// an EDTF date, which may be qualified and have unspecified digits
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EdtfDate {
+    // with any unspecified digits as zeros
+    year: i64,
+    unspecified_digits: u32,
+    precision: Precision,
+    qualifier: Option<Qualifier>,
+}
+
+impl EdtfDate {
+    /// returns the year, with any unspecified digits as zeros
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    /// returns how many of the year's last digits are unspecified, as in
+    /// `201X`
+    pub fn unspecified_year_digits(&self) -> u32 {
+        self.unspecified_digits
+    }
+
+    /// returns how much of the date is given after the year
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// returns the date's qualifier, if any
+    pub fn qualifier(&self) -> Option<Qualifier> {
+        self.qualifier
+    }
+
+    /// returns true if the date is marked uncertain (`?` or `%`)
+    pub fn is_uncertain(&self) -> bool {
+        matches!(
+            self.qualifier,
+            Some(Qualifier::Uncertain | Qualifier::UncertainApproximate)
+        )
+    }
+
+    /// returns true if the date is marked approximate (`~` or `%`)
+    pub fn is_approximate(&self) -> bool {
+        matches!(
+            self.qualifier,
+            Some(Qualifier::Approximate | Qualifier::UncertainApproximate)
+        )
+    }
+
+    /// returns the span of time the date could refer to, from midnight UTC
+    /// on its first day to midnight after its last, or None if that is
+    /// outside the range of a DateTime
+    ///
+    /// qualifiers are ignored, but unspecified digits widen the span, so
+    /// `201X` covers 2010 to 2019
+    /// ```
+    /// # use datetime::edtf::Edtf;
+    /// let date = match "2001-24".parse().unwrap() {
+    ///     Edtf::Date(date) => date,
+    ///     _ => unreachable!(),
+    /// };
+    /// let interval = date.interval().unwrap();
+    ///
+    /// assert_eq!(interval.start().to_rfc3339(), "2001-12-01T00:00:00Z");
+    /// assert_eq!(interval.end().to_rfc3339(), "2002-03-01T00:00:00Z");
+    /// ```
+    pub fn interval(&self) -> Option<Interval> {
+        // days_from_civil overflows long before the year does, so check the
+        // year against the range of a DateTime first
+        let years = DateTime::MIN.year() as i64..=DateTime::MAX.year() as i64;
+        if !years.contains(&self.year) {
+            return None;
+        }
+
+        let year = self.year;
+        let ((start_year, start_month), (end_year, end_month)) = match self.precision {
+            Precision::Year | Precision::Month(None) | Precision::Day(None, _) => {
+                let years = 10i64.pow(self.unspecified_digits);
+                ((year, 1), (year + years, 1))
+            }
+            Precision::Season(season) => {
+                let start = 3 + 3 * season as usize;
+                if start == 12 {
+                    ((year, 12), (year + 1, 3))
+                } else {
+                    ((year, start), (year, start + 3))
+                }
+            }
+            Precision::Month(Some(month)) | Precision::Day(Some(month), None) => {
+                let next = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                ((year, month), next)
+            }
+            Precision::Day(Some(month), Some(day)) => {
+                let start = days_from_civil(year, month, day) * 86_400;
+                return span(start, start + 86_400);
+            }
+        };
+
+        span(
+            days_from_civil(start_year, start_month, 1) * 86_400,
+            days_from_civil(end_year, end_month, 1) * 86_400,
+        )
+    }
+
+    // reads a date, leaving the cursor after it
+    fn read(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        let start = cursor.pos;
+        if cursor.peek() == Some(b'Y') {
+            // a year of more than four digits
+            cursor.next()?;
+            let negative = cursor.peek() == Some(b'-');
+            if negative {
+                cursor.next()?;
+            }
+            let (year, _) = cursor.digits_between(5, 18)?;
+            if cursor.peek() == Some(b'-') {
+                return Err(ParseError::Invalid(cursor.pos));
+            }
+
+            let year = year as i64;
+            return Ok(EdtfDate {
+                year: if negative { -year } else { year },
+                unspecified_digits: 0,
+                precision: Precision::Year,
+                qualifier: qualifier(cursor)?,
+            });
+        }
+
+        let negative = cursor.peek() == Some(b'-');
+        if negative {
+            cursor.next()?;
+        }
+
+        // the year, whose last two digits may be X
+        let mut year = 0;
+        let mut unspecified_digits = 0;
+        for i in 0..4 {
+            let b = cursor.next()?;
+            match b {
+                b'0'..=b'9' if unspecified_digits == 0 => year = year * 10 + (b - b'0') as i64,
+                b'X' if i >= 2 && !negative => {
+                    year *= 10;
+                    unspecified_digits += 1;
+                }
+                _ => return Err(ParseError::Invalid(cursor.pos - 1)),
+            }
+        }
+        if negative && year == 0 {
+            return Err(ParseError::Invalid(start));
+        }
+        let year = if negative { -year } else { year };
+
+        let mut precision = Precision::Year;
+        if cursor.peek() == Some(b'-') && unspecified_digits == 0 {
+            cursor.next()?;
+            precision = match two_digits(cursor)? {
+                Some(month @ 1..=12) => Precision::Month(Some(month)),
+                Some(21) => Precision::Season(Season::Spring),
+                Some(22) => Precision::Season(Season::Summer),
+                Some(23) => Precision::Season(Season::Autumn),
+                Some(24) => Precision::Season(Season::Winter),
+                Some(_) => return Err(ParseError::OutOfRange),
+                None => Precision::Month(None),
+            };
+
+            if let (Precision::Month(month), Some(b'-')) = (precision, cursor.peek()) {
+                cursor.next()?;
+                let pos = cursor.pos;
+                let day = two_digits(cursor)?;
+                precision = match (month, day) {
+                    (None, Some(_)) => return Err(ParseError::Invalid(pos)),
+                    (Some(month), Some(day)) if !valid_day(year, month, day) => {
+                        return Err(ParseError::OutOfRange)
+                    }
+                    (month, day) => Precision::Day(month, day),
+                };
+            }
+        }
+
+        Ok(EdtfDate {
+            year,
+            unspecified_digits,
+            precision,
+            qualifier: qualifier(cursor)?,
+        })
+    }
+}
+
+// the interval between two times in seconds, if both are in range
+fn span(start: i64, end: i64) -> Option<Interval> {
+    if start < MIN_SECS || end > MAX_SECS {
+        return None;
+    }
+
+    Some(Interval::from_signed_secs(start, end))
+}
+
+// whether a day exists in a month, allowing year 0 and negative years
+fn valid_day(year: i64, month: usize, day: usize) -> bool {
+    let next = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    let days = days_from_civil(next.0, next.1, 1) - days_from_civil(year, month, 1);
+    (1..=days).contains(&(day as i64))
+}
+
+// reads two digits, or `XX` as None
+fn two_digits(cursor: &mut Cursor) -> Result<Option<usize>, ParseError> {
+    if cursor.peek() == Some(b'X') {
+        cursor.next()?;
+        cursor.expect(b'X')?;
+        Ok(None)
+    } else {
+        cursor.digits(2).map(Some)
+    }
+}
+
+// reads an optional qualifier
+fn qualifier(cursor: &mut Cursor) -> Result<Option<Qualifier>, ParseError> {
+    let qualifier = match cursor.peek() {
+        Some(b'?') => Qualifier::Uncertain,
+        Some(b'~') => Qualifier::Approximate,
+        Some(b'%') => Qualifier::UncertainApproximate,
+        _ => return Ok(None),
+    };
+
+    cursor.next()?;
+    Ok(Some(qualifier))
+}
+
+impl fmt::Display for EdtfDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.year.abs() > 9999 {
+            write!(f, "Y{}", self.year)?;
+        } else {
+            if self.year < 0 {
+                f.write_str("-")?;
+            }
+
+            let digits = alloc::format!("{:04}", self.year.abs());
+            let known = 4 - self.unspecified_digits as usize;
+            f.write_str(&digits[..known])?;
+            for _ in known..4 {
+                f.write_str("X")?;
+            }
+        }
+
+        let two = |f: &mut fmt::Formatter, value: Option<usize>| match value {
+            Some(value) => write!(f, "-{:02}", value),
+            None => f.write_str("-XX"),
+        };
+        match self.precision {
+            Precision::Year => {}
+            Precision::Season(season) => write!(f, "-{}", 21 + season as usize)?,
+            Precision::Month(month) => two(f, month)?,
+            Precision::Day(month, day) => {
+                two(f, month)?;
+                two(f, day)?;
+            }
+        }
+
+        if let Some(qualifier) = self.qualifier {
+            write!(f, "{}", qualifier.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+/// one end of an EDTF interval
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Bound {
+    /// a date
+    Date(EdtfDate),
+    /// `..`, the interval has no end in this direction
+    Open,
+    /// an empty end, which isn't known
+    Unknown,
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bound::Date(date) => write!(f, "{}", date),
+            Bound::Open => f.write_str(".."),
+            Bound::Unknown => Ok(()),
+        }
+    }
+}
+
+/// an EDTF value: a date, a date and time, or an interval between dates
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Edtf {
+    /// a date, such as `1996-09-09`, `1996-09?` or `201X`
+    Date(EdtfDate),
+    /// a date and time, in UTC, with its offset if it had one; without an
+    /// offset the DateTime reads the local time as if it were UTC
+    DateTime(DateTime, Option<FixedOffset>),
+    /// an interval between two dates, either of which may be open or
+    /// unknown, but not both
+    Interval(Bound, Bound),
+}
+
+impl fmt::Display for Edtf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edtf::Date(date) => write!(f, "{}", date),
+            Edtf::DateTime(date, offset) => {
+                let local = date.with_offset(offset.unwrap_or_default());
+                f.write_str(&local.format("%Y-%m-%dT%H:%M:%S"))?;
+                match offset {
+                    Some(offset) if offset.is_utc() => f.write_str("Z"),
+                    Some(offset) => write!(f, "{}", offset),
+                    None => Ok(()),
+                }
+            }
+            Edtf::Interval(start, end) => write!(f, "{}/{}", start, end),
+        }
+    }
+}
+
+impl FromStr for Edtf {
+    type Err = ParseError;
+
+    /// parses an EDTF value of level 0 or 1
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+
+        let start = match cursor.peek() {
+            Some(b'/') => Bound::Unknown,
+            Some(b'.') => {
+                cursor.next()?;
+                cursor.expect(b'.')?;
+                Bound::Open
+            }
+            _ => Bound::Date(EdtfDate::read(&mut cursor)?),
+        };
+
+        let result = match (start, cursor.peek()) {
+            (Bound::Date(date), None) => Edtf::Date(date),
+            (Bound::Date(date), Some(b'T')) => date_time(&mut cursor, date)?,
+            (start, _) => {
+                let pos = cursor.pos;
+                cursor.expect(b'/')?;
+                let end = match cursor.peek() {
+                    None => Bound::Unknown,
+                    Some(b'.') => {
+                        cursor.next()?;
+                        cursor.expect(b'.')?;
+                        Bound::Open
+                    }
+                    _ => Bound::Date(EdtfDate::read(&mut cursor)?),
+                };
+
+                if !matches!((start, end), (Bound::Date(_), _) | (_, Bound::Date(_))) {
+                    return Err(ParseError::Invalid(pos));
+                }
+                Edtf::Interval(start, end)
+            }
+        };
+        cursor.finish()?;
+
+        Ok(result)
+    }
+}
+
+// reads the time after a date, which must be a complete, unqualified date
+// in years 1 to 9999
+fn date_time(cursor: &mut Cursor, date: EdtfDate) -> Result<Edtf, ParseError> {
+    let (month, day) = match date.precision {
+        Precision::Day(Some(month), Some(day))
+            if date.qualifier.is_none() && (1..=9999).contains(&date.year) =>
+        {
+            (month, day)
+        }
+        _ => return Err(ParseError::Invalid(cursor.pos)),
+    };
+
+    cursor.expect(b'T')?;
+    let hour = cursor.digits(2)?;
+    cursor.expect(b':')?;
+    let minute = cursor.digits(2)?;
+    cursor.expect(b':')?;
+    let second = cursor.digits(2)?;
+    let offset = match cursor.peek() {
+        Some(_) => Some(zone(cursor)?),
+        None => None,
+    };
+
+    let secs = offset.map_or(0, |offset| offset.seconds() as i64);
+    let year = date.year as usize;
+    let utc = from_fields(year, month, day, hour, minute, second, 0, secs)?;
+    Ok(Edtf::DateTime(utc, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bound, Edtf, Precision, Qualifier, Season};
+    use crate::ParseError;
+
+    fn date(input: &str) -> super::EdtfDate {
+        match input.parse() {
+            Ok(Edtf::Date(date)) => date,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in &[
+            "1985-04-12",
+            "1985-04",
+            "1985",
+            "0000",
+            "1985-04-12T23:20:30",
+            "1985-04-12T23:20:30Z",
+            "1985-04-12T23:20:30-04:00",
+            "1964/2008",
+            "2004-02-01/2005-02-08",
+            "Y170000002",
+            "Y-170000002",
+            "-1985",
+            "2001-21",
+            "1984?",
+            "2004-06~",
+            "2004-06-11%",
+            "201X",
+            "20XX",
+            "2004-XX",
+            "1985-04-XX",
+            "1985-XX-XX",
+            "1985-04-12/..",
+            "../1985-04-12",
+            "/1985-04-12",
+            "1985-04-12/",
+            "2004-06~/2004-06-11%",
+        ] {
+            let edtf: Edtf = input.parse().unwrap();
+            assert_eq!(edtf.to_string(), *input);
+        }
+    }
+
+    #[test]
+    fn test_components() {
+        let uncertain = date("2004-06-11%");
+        assert!(uncertain.is_uncertain() && uncertain.is_approximate());
+        assert_eq!(uncertain.precision(), Precision::Day(Some(6), Some(11)));
+        assert_eq!(date("1984~").qualifier(), Some(Qualifier::Approximate));
+        assert_eq!(
+            date("2001-23").precision(),
+            Precision::Season(Season::Autumn)
+        );
+        assert_eq!(date("Y-170000002").year(), -170_000_002);
+
+        let decade = date("201X");
+        assert_eq!((decade.year(), decade.unspecified_year_digits()), (2010, 1));
+
+        match "1985-04-12T23:20:30+02:00".parse() {
+            Ok(Edtf::DateTime(utc, offset)) => {
+                assert_eq!(utc.hour(), 21);
+                assert_eq!(offset.unwrap().seconds(), 7200);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        let parse = |input: &str| input.parse::<Edtf>();
+        assert_eq!(parse("1985-13"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("1985-02-29"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("1985-XX-12"), Err(ParseError::Invalid(8)));
+        assert_eq!(parse("2X10"), Err(ParseError::Invalid(1)));
+        assert_eq!(parse("201X-04"), Err(ParseError::Invalid(4)));
+        assert_eq!(parse("Y1985"), Err(ParseError::TooShort));
+        assert_eq!(parse("Y1985/2000"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("1985?T12:00:00"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("../.."), Err(ParseError::Invalid(2)));
+        assert_eq!(parse("/"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("1985//"), Err(ParseError::Invalid(5)));
+        assert!(matches!(
+            parse("../1985"),
+            Ok(Edtf::Interval(Bound::Open, Bound::Date(_)))
+        ));
+    }
+
+    #[test]
+    fn test_interval() {
+        let span = |input: &str| {
+            let interval = date(input).interval().unwrap();
+            (interval.start().to_rfc3339(), interval.end().to_rfc3339())
+        };
+
+        assert_eq!(
+            span("201X"),
+            ("2010-01-01T00:00:00Z".into(), "2020-01-01T00:00:00Z".into())
+        );
+        assert_eq!(
+            span("1996-02?"),
+            ("1996-02-01T00:00:00Z".into(), "1996-03-01T00:00:00Z".into())
+        );
+        assert_eq!(
+            span("1996-12-31"),
+            ("1996-12-31T00:00:00Z".into(), "1997-01-01T00:00:00Z".into())
+        );
+        assert_eq!(
+            span("1996-21"),
+            ("1996-03-01T00:00:00Z".into(), "1996-06-01T00:00:00Z".into())
+        );
+        assert_eq!(
+            span("1996-23"),
+            ("1996-09-01T00:00:00Z".into(), "1996-12-01T00:00:00Z".into())
+        );
+        assert_eq!(
+            span("1997-24"),
+            ("1997-12-01T00:00:00Z".into(), "1998-03-01T00:00:00Z".into())
+        );
+        assert_eq!(
+            span("1985-XX-XX"),
+            ("1985-01-01T00:00:00Z".into(), "1986-01-01T00:00:00Z".into())
+        );
+        assert!(date("-1985").interval().is_none());
+        assert!(date("Y170000000000000000").interval().is_none());
+        assert!(date("Y-100000000000000000").interval().is_none());
+        assert!(date("Y4294967296").interval().is_none());
+        assert!(date("Y4294967294").interval().is_some());
+    }
+}
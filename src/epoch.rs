@@ -0,0 +1,297 @@
+//! Unix timestamps of unknown unit
+//!
+//! data from other systems often holds Unix timestamps without saying
+//! whether they count seconds, milliseconds, microseconds or nanoseconds.
+//! the units are a thousand times apart, so for dates in a plausible
+//! window of years only one unit usually makes sense, and
+//! [`guess_epoch_unit`] picks it
+//! ```
+//! # use datetime::epoch::{guess_epoch_unit, EpochUnit};
+//! # use datetime::DateTime;
+//! assert_eq!(guess_epoch_unit(842282624), Some(EpochUnit::Seconds));
+//! assert_eq!(guess_epoch_unit(842282624123), Some(EpochUnit::Millis));
+//! assert_eq!(guess_epoch_unit(842282624123456789), Some(EpochUnit::Nanos));
+//!
+//! let date = DateTime::from_epoch_any(842282624123456).unwrap();
+//! assert_eq!(date.to_rfc3339(), "1996-09-09T15:23:44.123456Z");
+//! ```
+//!
+//! other systems count from other epochs: NTP from 1900, and Windows'
+//! FILETIME in 100-nanosecond ticks from 1601. DateTimes convert to and
+//! from both here; GPS time, which also differs from UTC by its leap
+//! seconds, is in the `leap` module
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_filetime(124_867_562_240_000_000);
+//! assert_eq!(date.to_rfc3339(), "1996-09-09T15:23:44Z");
+//! assert_eq!(date.to_ntp_secs(), 3_051_271_424);
+//! ```
+use crate::{DateTime, FixedOffset, MAX_SECS, MIN_SECS};
+use core::convert::TryFrom;
+
+// the seconds from the NTP epoch (1900-01-01) to the Unix epoch
+pub(crate) const NTP_EPOCH: i64 = 2_208_988_800;
+
+// the seconds from the FILETIME epoch (1601-01-01) to the Unix epoch
+const FILETIME_EPOCH: i64 = 11_644_473_600;
+
+// FILETIME ticks in a second
+const TICKS_PER_SEC: u64 = 10_000_000;
+
+/// the unit a Unix timestamp counts
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EpochUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+// every unit, from the coarsest
+const UNITS: [EpochUnit; 4] = [
+    EpochUnit::Seconds,
+    EpochUnit::Millis,
+    EpochUnit::Micros,
+    EpochUnit::Nanos,
+];
+
+impl EpochUnit {
+    /// returns how many of the unit there are in a second
+    pub const fn per_second(self) -> i64 {
+        match self {
+            EpochUnit::Seconds => 1,
+            EpochUnit::Millis => 1_000,
+            EpochUnit::Micros => 1_000_000,
+            EpochUnit::Nanos => 1_000_000_000,
+        }
+    }
+
+    /// returns the DateTime of a Unix timestamp in this unit, or None if
+    /// that is out of range
+    /// ```
+    /// # use datetime::epoch::EpochUnit;
+    /// let date = EpochUnit::Micros.to_datetime(-1).unwrap();
+    /// assert_eq!(date.to_rfc3339(), "1969-12-31T23:59:59.999999Z");
+    /// ```
+    pub fn to_datetime(self, value: i64) -> Option<DateTime> {
+        let per_second = self.per_second();
+        let secs = value.div_euclid(per_second);
+        let nanos = value.rem_euclid(per_second) * (1_000_000_000 / per_second);
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(secs, nanos as u32, FixedOffset::utc()))
+        } else {
+            None
+        }
+    }
+}
+
+/// returns the coarsest unit in which the Unix timestamp falls in the
+/// years 1970 to 2100, or None if it doesn't in any
+///
+/// small values are ambiguous: 1,000,000,000 is both a date in 2001 in
+/// seconds and one in January 1970 in milliseconds, and is taken to be
+/// seconds
+pub fn guess_epoch_unit(value: i64) -> Option<EpochUnit> {
+    guess_epoch_unit_within(value, 1970, 2100)
+}
+
+/// returns the coarsest unit in which the Unix timestamp falls between the
+/// given years, inclusive, or None if it doesn't in any
+/// ```
+/// # use datetime::epoch::{guess_epoch_unit, guess_epoch_unit_within, EpochUnit};
+/// // 1938-04-24 in seconds, or December 1969 in any smaller unit
+/// let value = -1_000_000_000;
+///
+/// assert_eq!(guess_epoch_unit(value), None);
+/// assert_eq!(guess_epoch_unit_within(value, 1900, 1969), Some(EpochUnit::Seconds));
+/// ```
+pub fn guess_epoch_unit_within(
+    value: i64,
+    first_year: usize,
+    last_year: usize,
+) -> Option<EpochUnit> {
+    UNITS.iter().copied().find(|unit| {
+        unit.to_datetime(value)
+            .is_some_and(|date| (first_year..=last_year).contains(&date.year()))
+    })
+}
+
+impl DateTime {
+    /// returns the DateTime of a Unix timestamp in whichever unit puts it
+    /// in the years 1970 to 2100 (see [`guess_epoch_unit`]), or None if no
+    /// unit does
+    pub fn from_epoch_any(value: i64) -> Option<DateTime> {
+        let unit = guess_epoch_unit(value)?;
+        trace_event!(value, unit = ?unit, "guessed the unit of a timestamp");
+        unit.to_datetime(value)
+    }
+
+    /// returns the DateTime the given number of seconds after the NTP
+    /// epoch, 1900-01-01, or None if that is out of range
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_ntp_secs(2_208_988_800).unwrap();
+    /// assert_eq!(date, DateTime::from_secs(0));
+    /// ```
+    pub fn from_ntp_secs(secs: i64) -> Option<DateTime> {
+        let secs = secs.checked_sub(NTP_EPOCH)?;
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(secs, 0, FixedOffset::utc()))
+        } else {
+            None
+        }
+    }
+
+    /// returns the whole seconds since the NTP epoch, 1900-01-01, rounded
+    /// down
+    ///
+    /// # Panics
+    ///
+    /// panics if the DateTime is too far in the future for an i64
+    pub fn to_ntp_secs(&self) -> i64 {
+        self.secs
+            .checked_add(NTP_EPOCH)
+            .expect("DateTime too late for NTP seconds")
+    }
+
+    /// returns the DateTime of a 64-bit NTP timestamp, which holds seconds
+    /// since 1900-01-01 in its upper 32 bits and a binary fraction of a
+    /// second in its lower 32, rounded down to the nanosecond
+    ///
+    /// the timestamp is read in NTP's first era, which ends in 2036
+    pub fn from_ntp_timestamp(timestamp: u64) -> DateTime {
+        let secs = (timestamp >> 32) as i64 - NTP_EPOCH;
+        let nanos = ((timestamp & 0xFFFF_FFFF) * 1_000_000_000) >> 32;
+        DateTime::from_parts(secs, nanos as u32, FixedOffset::utc())
+    }
+
+    /// returns the DateTime as a 64-bit NTP timestamp, or None if it falls
+    /// outside NTP's first era, 1900 to 2036
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_millis(842282624500);
+    /// let timestamp = date.to_ntp_timestamp().unwrap();
+    ///
+    /// assert_eq!(timestamp, 3_051_271_424 << 32 | 1 << 31);
+    /// assert_eq!(DateTime::from_ntp_timestamp(timestamp), date);
+    /// ```
+    pub fn to_ntp_timestamp(&self) -> Option<u64> {
+        let secs = u32::try_from(self.secs.checked_add(NTP_EPOCH)?).ok()?;
+        // rounded up, so that reading the timestamp back gives the same
+        // nanosecond
+        let fraction = ((self.nanos as u64) << 32).div_ceil(1_000_000_000);
+        Some((secs as u64) << 32 | fraction)
+    }
+
+    /// returns the DateTime of a Windows FILETIME, a count of
+    /// 100-nanosecond ticks since 1601-01-01
+    pub fn from_filetime(ticks: u64) -> DateTime {
+        let secs = (ticks / TICKS_PER_SEC) as i64 - FILETIME_EPOCH;
+        let nanos = (ticks % TICKS_PER_SEC) as u32 * 100;
+        DateTime::from_parts(secs, nanos, FixedOffset::utc())
+    }
+
+    /// returns the DateTime as a Windows FILETIME, rounded down to the
+    /// tick, or None if it is before 1601 or too late for a u64
+    pub fn to_filetime(&self) -> Option<u64> {
+        let secs = u64::try_from(self.secs.checked_add(FILETIME_EPOCH)?).ok()?;
+        secs.checked_mul(TICKS_PER_SEC)?
+            .checked_add(self.nanos as u64 / 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{guess_epoch_unit, guess_epoch_unit_within, EpochUnit};
+    use crate::{DateTime, MAX_SECS};
+
+    #[test]
+    fn test_guess_epoch_unit() {
+        // 2024-01-01 in each unit
+        let secs = 1_704_067_200;
+        assert_eq!(guess_epoch_unit(secs), Some(EpochUnit::Seconds));
+        assert_eq!(guess_epoch_unit(secs * 1000), Some(EpochUnit::Millis));
+        assert_eq!(guess_epoch_unit(secs * 1_000_000), Some(EpochUnit::Micros));
+        assert_eq!(
+            guess_epoch_unit(secs * 1_000_000_000),
+            Some(EpochUnit::Nanos)
+        );
+
+        // 2101 in seconds is too late, so it's read as milliseconds
+        assert_eq!(guess_epoch_unit(4_133_980_800), Some(EpochUnit::Millis));
+        assert_eq!(guess_epoch_unit(0), Some(EpochUnit::Seconds));
+        assert_eq!(guess_epoch_unit(-1), None);
+        assert_eq!(guess_epoch_unit(i64::MAX), None);
+        assert_eq!(
+            guess_epoch_unit_within(-14182940, 1960, 1970),
+            Some(EpochUnit::Seconds)
+        );
+        assert_eq!(
+            guess_epoch_unit_within(i64::MIN, 1, 9999),
+            Some(EpochUnit::Nanos)
+        );
+    }
+
+    #[test]
+    fn test_to_datetime() {
+        assert_eq!(
+            EpochUnit::Millis.to_datetime(-1),
+            Some(DateTime::from_millis(-1))
+        );
+        assert_eq!(
+            EpochUnit::Nanos.to_datetime(i64::MIN),
+            Some(DateTime::from_nanos(i64::MIN))
+        );
+        assert!(EpochUnit::Seconds.to_datetime(i64::MIN).is_none());
+        assert!(EpochUnit::Seconds.to_datetime(i64::MAX).is_none());
+        assert_eq!(DateTime::from_epoch_any(-5), None);
+    }
+
+    #[test]
+    fn test_ntp() {
+        assert_eq!(
+            DateTime::from_ntp_secs(0).unwrap().to_rfc3339(),
+            "1900-01-01T00:00:00Z"
+        );
+        assert_eq!(DateTime::from_millis(-1).to_ntp_secs(), 2_208_988_799);
+        assert!(DateTime::from_ntp_secs(i64::MIN).is_none());
+
+        // the last nanosecond of the era
+        let last = DateTime::from_ntp_timestamp(u64::MAX);
+        assert_eq!(last.to_rfc3339(), "2036-02-07T06:28:15.999999999Z");
+        let timestamp = last.to_ntp_timestamp().unwrap();
+        assert_eq!(DateTime::from_ntp_timestamp(timestamp), last);
+        assert_eq!(last.checked_add_secs(1).unwrap().to_ntp_timestamp(), None);
+        assert_eq!(
+            DateTime::from_ntp_secs(-1).unwrap().to_ntp_timestamp(),
+            None
+        );
+
+        for nanos in [0, 1, 999_999_999, 123_456_789].iter() {
+            let date = DateTime::from_nanos(1_700_000_000_000_000_000 + nanos);
+            let timestamp = date.to_ntp_timestamp().unwrap();
+            assert_eq!(DateTime::from_ntp_timestamp(timestamp), date);
+        }
+    }
+
+    #[test]
+    fn test_filetime() {
+        let epoch = DateTime::from_filetime(0);
+        assert_eq!(epoch.to_rfc3339(), "1601-01-01T00:00:00Z");
+        assert_eq!(epoch.to_filetime(), Some(0));
+        assert_eq!(
+            DateTime::from_filetime(116_444_736_000_000_001),
+            DateTime::from_nanos(100)
+        );
+        assert_eq!(
+            DateTime::from_nanos(-1).to_filetime(),
+            Some(116_444_735_999_999_999)
+        );
+
+        assert_eq!(epoch.checked_sub_secs(1).unwrap().to_filetime(), None);
+        assert_eq!(DateTime::from_signed_secs(MAX_SECS).to_filetime(), None);
+        let last = DateTime::from_filetime(u64::MAX);
+        assert_eq!(last.to_filetime(), Some(u64::MAX));
+    }
+}
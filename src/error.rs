@@ -0,0 +1,210 @@
+//! the errors the crate returns
+//!
+//! each fallible operation returns the most specific error it can: a
+//! [`ParseError`] from parsing, a [`RangeError`] when an instant can't be
+//! held, an [`InvalidDate`] when fields don't make a date, and so on.
+//! [`Error`] can hold any of them, for code that handles several kinds
+//! together and wants to use `?`
+//! ```
+//! # use datetime::{CivilTime, DateTime, Error};
+//! # use std::error::Error as _;
+//! fn midnight_after(input: &str) -> Result<CivilTime, Error> {
+//!     let date = DateTime::parse_rfc3339(input)?;
+//!     let next = date.checked_add_secs(86_400).ok_or(datetime::RangeError)?;
+//!     Ok(CivilTime::new(next.year(), next.month().number(), next.date(), 0, 0, 0)?)
+//! }
+//!
+//! assert_eq!(midnight_after("1996-09-09T15:23:44Z").unwrap().to_string(), "1996-09-10T00:00:00");
+//!
+//! let e = midnight_after("1996-09-31T15:23:44Z").unwrap_err();
+//! assert_eq!(e.to_string(), "could not parse timestamp");
+//! assert_eq!(e.source().unwrap().to_string(), "field out of range");
+//! ```
+use crate::format::{FormatError, ReformatError};
+use crate::parse::ParseError;
+#[cfg(feature = "tz")]
+use crate::tz::TzError;
+use crate::ParseNameError;
+use core::error;
+use core::fmt;
+
+/// an error returned when converting a value from another date and time
+/// library that a DateTime can't represent, or a DateTime that the other
+/// library can't represent, or when an instant would fall outside the
+/// range of a DateTime
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RangeError;
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "date and time out of range")
+    }
+}
+
+impl error::Error for RangeError {}
+
+/// the former name of [`RangeError`]
+#[deprecated(note = "renamed to RangeError")]
+pub type OutOfRangeError = RangeError;
+
+/// an error returned when fields don't make a valid date or time, naming
+/// the first field that is out of range
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InvalidDate {
+    /// the year is 0, or too late
+    Year,
+    /// the month isn't 1-12
+    Month,
+    /// the day isn't in the month
+    Date,
+    /// the date is 29 February in a year that isn't a leap year
+    LeapDay,
+    /// the hour isn't 0-23
+    Hour,
+    /// the minute isn't 0-59
+    Minute,
+    /// the second isn't 0-59
+    Second,
+}
+
+impl fmt::Display for InvalidDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let field = match self {
+            InvalidDate::LeapDay => return write!(f, "29 February in a common year"),
+            InvalidDate::Year => "year",
+            InvalidDate::Month => "month",
+            InvalidDate::Date => "day of the month",
+            InvalidDate::Hour => "hour",
+            InvalidDate::Minute => "minute",
+            InvalidDate::Second => "second",
+        };
+        write!(f, "{} out of range", field)
+    }
+}
+
+impl error::Error for InvalidDate {}
+
+/// any error returned by the crate
+///
+/// its Display output only says what was being done, and
+/// [`source`](core::error::Error::source) gives the error underneath, so
+/// error reporters that walk the chain don't print the same text twice
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// a timestamp could not be parsed
+    Parse(ParseError),
+    /// a format string could not be parsed
+    Format(FormatError),
+    /// a timestamp could not be converted between formats
+    Reformat(ReformatError),
+    /// a day or month name could not be parsed
+    ParseName(ParseNameError),
+    /// an instant was out of range
+    Range(RangeError),
+    /// fields didn't make a valid date or time
+    InvalidDate(InvalidDate),
+    /// a time zone could not be loaded
+    #[cfg(feature = "tz")]
+    Tz(TzError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse(_) => write!(f, "could not parse timestamp"),
+            Error::Format(_) => write!(f, "could not parse format string"),
+            Error::Reformat(_) => write!(f, "could not reformat timestamp"),
+            Error::ParseName(_) => write!(f, "could not parse name"),
+            Error::Range(_) => write!(f, "could not hold instant"),
+            Error::InvalidDate(_) => write!(f, "invalid date"),
+            #[cfg(feature = "tz")]
+            Error::Tz(_) => write!(f, "could not load time zone"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Parse(e) => Some(e),
+            Error::Format(e) => Some(e),
+            Error::Reformat(e) => Some(e),
+            Error::ParseName(e) => Some(e),
+            Error::Range(e) => Some(e),
+            Error::InvalidDate(e) => Some(e),
+            #[cfg(feature = "tz")]
+            Error::Tz(e) => Some(e),
+        }
+    }
+}
+
+macro_rules! from_error {
+    ($($variant:ident($error:ty)),* $(,)?) => {
+        $(
+            impl From<$error> for Error {
+                fn from(e: $error) -> Self {
+                    Error::$variant(e)
+                }
+            }
+        )*
+    };
+}
+
+from_error!(
+    Parse(ParseError),
+    Format(FormatError),
+    Reformat(ReformatError),
+    ParseName(ParseNameError),
+    Range(RangeError),
+    InvalidDate(InvalidDate),
+);
+
+#[cfg(feature = "tz")]
+from_error!(Tz(TzError));
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, InvalidDate, RangeError};
+    use crate::{CivilTime, ParseError};
+    use core::error::Error as _;
+
+    #[test]
+    fn test_display_and_source() {
+        let e = Error::from(ParseError::TooShort);
+        assert_eq!(e.to_string(), "could not parse timestamp");
+        assert_eq!(e.source().unwrap().to_string(), "premature end of input");
+
+        let e = Error::from(RangeError);
+        assert_eq!(e.to_string(), "could not hold instant");
+        assert_eq!(
+            e.source().unwrap().to_string(),
+            "date and time out of range"
+        );
+
+        let e = Error::from(CivilTime::new(2023, 2, 30, 0, 0, 0).unwrap_err());
+        assert_eq!(e.to_string(), "invalid date");
+        assert_eq!(
+            e.source().unwrap().to_string(),
+            "day of the month out of range"
+        );
+        let e = Error::from(CivilTime::new(2023, 2, 29, 0, 0, 0).unwrap_err());
+        assert_eq!(
+            e.source().unwrap().to_string(),
+            "29 February in a common year"
+        );
+    }
+
+    #[test]
+    fn test_invalid_date_fields() {
+        let field = |y, mo, d, h, mi, s| CivilTime::new(y, mo, d, h, mi, s).unwrap_err();
+
+        assert_eq!(field(0, 1, 1, 0, 0, 0), InvalidDate::Year);
+        assert_eq!(field(2000, 13, 1, 0, 0, 0), InvalidDate::Month);
+        assert_eq!(field(2000, 4, 31, 0, 0, 0), InvalidDate::Date);
+        assert_eq!(field(2000, 4, 30, 24, 0, 0), InvalidDate::Hour);
+        assert_eq!(field(2000, 4, 30, 23, 60, 0), InvalidDate::Minute);
+        assert_eq!(field(2000, 4, 30, 23, 59, 60), InvalidDate::Second);
+    }
+}
@@ -0,0 +1,196 @@
+//! the timestamps in photo metadata
+//!
+//! EXIF records when a photo was taken as `YYYY:MM:DD HH:MM:SS` in the
+//! camera's local time, and (since EXIF 2.31) its offset from UTC in a
+//! separate `OffsetTime` tag such as `+02:00`. XMP uses ISO 8601 dates,
+//! which may leave out the time, or just the zone. either way the offset
+//! may be unknown, so these parsers return it as an Option alongside the
+//! DateTime, which is in UTC when the offset is known and otherwise reads
+//! the local wall-clock time as if it were UTC
+//! ```
+//! # use datetime::DateTime;
+//! let (date, offset) = DateTime::parse_exif("1996:09:09 17:23:44", Some("+02:00")).unwrap();
+//! assert_eq!(date.to_rfc3339(), "1996-09-09T15:23:44Z");
+//! assert_eq!(offset.unwrap().to_string(), "+02:00");
+//!
+//! let (date, offset) = DateTime::parse_exif("1996:09:09 17:23:44", None).unwrap();
+//! assert_eq!(date.hour(), 17);
+//! assert!(offset.is_none());
+//! ```
+use crate::parse::{from_fields, zone, Cursor};
+use crate::{DateTime, FixedOffset, ParseError};
+
+impl DateTime {
+    /// parses an EXIF `DateTime`, `DateTimeOriginal` or
+    /// `DateTimeDigitized` tag, with the matching `OffsetTime` tag if the
+    /// photo has one
+    ///
+    /// the seconds may be followed by a fraction, as some tools merge the
+    /// `SubSecTime` tags in. unknown fields, which EXIF writes as spaces or
+    /// zeros, are out of range
+    pub fn parse_exif(
+        datetime: &str,
+        offset: Option<&str>,
+    ) -> Result<(DateTime, Option<FixedOffset>), ParseError> {
+        let offset = offset.map(parse_offset).transpose()?;
+
+        let mut cursor = Cursor::new(datetime);
+        let year = cursor.digits(4)?;
+        cursor.expect(b':')?;
+        let month = cursor.digits(2)?;
+        cursor.expect(b':')?;
+        let date = cursor.digits(2)?;
+        cursor.expect(b' ')?;
+        let hour = cursor.digits(2)?;
+        cursor.expect(b':')?;
+        let minute = cursor.digits(2)?;
+        cursor.expect(b':')?;
+        let second = cursor.digits(2)?;
+
+        let mut nanos = 0;
+        if cursor.peek() == Some(b'.') {
+            cursor.next()?;
+            nanos = cursor.fraction()?;
+        }
+        cursor.finish()?;
+
+        let secs = offset.map_or(0, |offset| offset.seconds() as i64);
+        let date = from_fields(year, month, date, hour, minute, second, nanos, secs)?;
+        Ok((date, offset))
+    }
+
+    /// parses an XMP date such as `xmp:CreateDate` or
+    /// `photoshop:DateCreated`
+    ///
+    /// XMP allows any of `YYYY`, `YYYY-MM`, `YYYY-MM-DD`,
+    /// `YYYY-MM-DDThh:mm`, `YYYY-MM-DDThh:mm:ss` and
+    /// `YYYY-MM-DDThh:mm:ss.s`, where the forms with a time may end in a
+    /// zone (`Z` or `+hh:mm`). missing fields are the first of their kind,
+    /// so `1996` is midnight on the 1st of January
+    /// ```
+    /// # use datetime::DateTime;
+    /// let (date, offset) = DateTime::parse_xmp_date("1996-09-09T17:23+02:00").unwrap();
+    /// assert_eq!(date.to_rfc3339(), "1996-09-09T15:23:00Z");
+    /// assert_eq!(offset.unwrap().seconds(), 7200);
+    ///
+    /// let (date, offset) = DateTime::parse_xmp_date("1996-09").unwrap();
+    /// assert_eq!(date.to_rfc3339(), "1996-09-01T00:00:00Z");
+    /// assert!(offset.is_none());
+    /// ```
+    pub fn parse_xmp_date(input: &str) -> Result<(DateTime, Option<FixedOffset>), ParseError> {
+        let mut cursor = Cursor::new(input);
+        let year = cursor.digits(4)?;
+        let (mut month, mut date) = (1, 1);
+        let (mut hour, mut minute, mut second, mut nanos) = (0, 0, 0, 0);
+        let mut offset = None;
+
+        if cursor.peek().is_some() {
+            cursor.expect(b'-')?;
+            month = cursor.digits(2)?;
+        }
+        if cursor.peek().is_some() {
+            cursor.expect(b'-')?;
+            date = cursor.digits(2)?;
+        }
+        if cursor.peek().is_some() {
+            cursor.expect(b'T')?;
+            hour = cursor.digits(2)?;
+            cursor.expect(b':')?;
+            minute = cursor.digits(2)?;
+
+            if cursor.peek() == Some(b':') {
+                cursor.next()?;
+                second = cursor.digits(2)?;
+                if cursor.peek() == Some(b'.') {
+                    cursor.next()?;
+                    nanos = cursor.fraction()?;
+                }
+            }
+            if cursor.peek().is_some() {
+                offset = Some(zone(&mut cursor)?);
+            }
+        }
+        cursor.finish()?;
+
+        let secs = offset.map_or(0, |offset: FixedOffset| offset.seconds() as i64);
+        let date = from_fields(year, month, date, hour, minute, second, nanos, secs)?;
+        Ok((date, offset))
+    }
+}
+
+// parses an EXIF OffsetTime tag, `+hh:mm` or `-hh:mm`
+fn parse_offset(input: &str) -> Result<FixedOffset, ParseError> {
+    let mut cursor = Cursor::new(input);
+    if cursor.peek() == Some(b'Z') {
+        return Err(ParseError::Invalid(0));
+    }
+
+    let offset = zone(&mut cursor)?;
+    cursor.finish()?;
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, ParseError};
+
+    #[test]
+    fn test_parse_exif() {
+        let (date, offset) =
+            DateTime::parse_exif("2019:02:28 23:59:59.25", Some("-05:30")).unwrap();
+        assert_eq!(date.to_rfc3339(), "2019-03-01T05:29:59.250Z");
+        assert_eq!(offset.unwrap().seconds(), -19_800);
+
+        assert_eq!(
+            DateTime::parse_exif("2019-02-28 23:59:59", None),
+            Err(ParseError::Invalid(4))
+        );
+        assert_eq!(
+            DateTime::parse_exif("0000:00:00 00:00:00", None),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_exif("    :  :     :  :  ", None),
+            Err(ParseError::Invalid(0))
+        );
+        assert_eq!(
+            DateTime::parse_exif("2019:02:28 23:59:59", Some("+0530")),
+            Err(ParseError::Invalid(3))
+        );
+        assert_eq!(
+            DateTime::parse_exif("2019:02:28 23:59:59", Some("Z")),
+            Err(ParseError::Invalid(0))
+        );
+    }
+
+    #[test]
+    fn test_parse_xmp_date() {
+        let parse = |input| {
+            DateTime::parse_xmp_date(input)
+                .map(|(date, offset)| (date.to_rfc3339(), offset.map(|o| o.seconds())))
+        };
+
+        assert_eq!(parse("1996"), Ok(("1996-01-01T00:00:00Z".into(), None)));
+        assert_eq!(
+            parse("1996-09-09"),
+            Ok(("1996-09-09T00:00:00Z".into(), None))
+        );
+        assert_eq!(
+            parse("1996-09-09T15:23:44.5"),
+            Ok(("1996-09-09T15:23:44.500Z".into(), None))
+        );
+        assert_eq!(
+            parse("1996-09-09T15:23:44Z"),
+            Ok(("1996-09-09T15:23:44Z".into(), Some(0)))
+        );
+        assert_eq!(
+            parse("1996-09-09T12:23-03:00"),
+            Ok(("1996-09-09T15:23:00Z".into(), Some(-10_800)))
+        );
+
+        assert_eq!(parse("1996-9"), Err(ParseError::TooShort));
+        assert_eq!(parse("1996-09-09T15"), Err(ParseError::TooShort));
+        assert_eq!(parse("1996-09-09 15:23"), Err(ParseError::Invalid(10)));
+        assert_eq!(parse("1996-13"), Err(ParseError::OutOfRange));
+    }
+}
@@ -0,0 +1,107 @@
+//! expiry times for caches such as Redis
+//!
+//! Redis's `EXPIREAT` takes an expiry in Unix seconds and `PEXPIREAT` in
+//! Unix milliseconds, while `EXPIRE`, `PEXPIRE` and `SET ... PX` take a
+//! time to live. `PEXPIRETIME` reports an expiry in milliseconds, or a
+//! negative number if the key has none
+//! ```
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let now = DateTime::from_millis(842282624500);
+//! let expiry = DateTime::from_secs(842282684);
+//!
+//! assert_eq!(expiry.to_unix_expiry(), 842282684);
+//! assert_eq!(now.ttl_until(&expiry), Some(Duration::from_millis(59_500)));
+//!
+//! // PEXPIRETIME returns -1 for keys with no expiry
+//! assert!(DateTime::from_pexpiretime(-1).is_none());
+//! ```
+use crate::{AbsoluteTime, DateTime, FixedOffset};
+use core::time::Duration;
+
+impl DateTime {
+    /// returns the DateTime in Unix seconds, for `EXPIREAT`; any fraction
+    /// of a second is dropped, so the key expires no later than the
+    /// DateTime
+    pub fn to_unix_expiry(&self) -> i64 {
+        self.secs
+    }
+
+    /// returns the DateTime in Unix milliseconds, for `PEXPIREAT`; any
+    /// finer fraction is dropped, and DateTimes too far from the epoch to
+    /// fit saturate
+    /// ```
+    /// # use datetime::DateTime;
+    /// assert_eq!(DateTime::from_nanos(1_999_999).to_unix_pexpiry(), 1);
+    /// ```
+    pub fn to_unix_pexpiry(&self) -> i64 {
+        self.secs
+            .saturating_mul(1000)
+            .saturating_add((self.nanos / 1_000_000) as i64)
+    }
+
+    /// returns the expiry reported by `PEXPIRETIME` (Unix milliseconds),
+    /// or None if it is negative, meaning the key has no expiry (-1) or
+    /// doesn't exist (-2)
+    pub fn from_pexpiretime(millis: i64) -> Option<DateTime> {
+        if millis < 0 {
+            return None;
+        }
+
+        let secs = millis / 1000;
+        let nanos = (millis % 1000) as u32 * 1_000_000;
+        AbsoluteTime::new(secs, nanos).map(|time| time.to_datetime(FixedOffset::utc()))
+    }
+
+    /// returns the time to live from this DateTime until `expiry`, for
+    /// `EXPIRE` or `PEXPIRE`, or None if `expiry` is not after it
+    pub fn ttl_until(&self, expiry: &DateTime) -> Option<Duration> {
+        if expiry <= self {
+            return None;
+        }
+
+        let (secs, nanos) = if expiry.nanos >= self.nanos {
+            (expiry.secs - self.secs, expiry.nanos - self.nanos)
+        } else {
+            (
+                expiry.secs - self.secs - 1,
+                expiry.nanos + 1_000_000_000 - self.nanos,
+            )
+        };
+
+        Some(Duration::new(secs as u64, nanos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DateTime;
+    use std::time::Duration;
+
+    #[test]
+    fn test_expiry_round_trip() {
+        let date = DateTime::from_millis(842282624123);
+
+        assert_eq!(date.to_unix_expiry(), 842282624);
+        assert_eq!(date.to_unix_pexpiry(), 842282624123);
+        assert_eq!(
+            DateTime::from_pexpiretime(date.to_unix_pexpiry()),
+            Some(date)
+        );
+        assert!(DateTime::from_pexpiretime(-2).is_none());
+
+        // before the epoch, the fraction still counts forwards
+        assert_eq!(DateTime::from_millis(-1500).to_unix_pexpiry(), -1500);
+        assert_eq!(DateTime::MAX.to_unix_pexpiry(), i64::MAX);
+    }
+
+    #[test]
+    fn test_ttl_until() {
+        let now = DateTime::from_millis(-1);
+        let expiry = DateTime::from_millis(1000);
+
+        assert_eq!(now.ttl_until(&expiry), Some(Duration::from_millis(1001)));
+        assert_eq!(expiry.ttl_until(&now), None);
+        assert_eq!(now.ttl_until(&DateTime::from_millis(-1)), None);
+    }
+}
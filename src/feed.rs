@@ -0,0 +1,247 @@
+//! dates in RSS and Atom feeds
+//!
+//! RSS's `pubDate` is meant to be an RFC 822 date and Atom's `updated` an
+//! RFC 3339 timestamp, but feeds in the wild get both wrong in many ways.
+//! [`DateTime::parse_feed_date`] accepts either, along with the variations
+//! that turn up most often
+//! ```
+//! # use datetime::DateTime;
+//! let dates = [
+//!     "Mon, 09 Sep 1996 15:23:44 GMT",
+//!     "Monday, 9 September 1996 15:23:44",
+//!     "Mon, 09 Sep 1996 17:23:44 +02:00",
+//!     "1996-09-09T15:23:44Z",
+//!     "1996-09-09 15:23:44",
+//! ];
+//!
+//! for date in &dates {
+//!     assert_eq!(DateTime::parse_feed_date(date).unwrap().to_rss_date(), dates[0].replace("GMT", "+0000"));
+//! }
+//! ```
+use crate::parse::{from_fields, obsolete_zone, Cursor};
+use crate::{DateTime, Month, ParseError};
+use alloc::format;
+use alloc::string::String;
+use core::str::{self, FromStr};
+
+impl DateTime {
+    /// parses a date from an RSS or Atom feed, converting it to UTC
+    ///
+    /// on top of [`DateTime::parse_rfc2822`] and
+    /// [`DateTime::parse_rfc3339`], this accepts:
+    ///
+    /// - full day and month names, and `Sept`
+    /// - a day of the week which doesn't match the date, which is ignored
+    /// - single-digit hours, dashes between the date's fields, and
+    ///   fractional seconds
+    /// - offsets written `+02:00`, and the zone names `Z` and `UTC`
+    /// - no zone at all, which is taken to be UTC
+    /// - RFC 3339 timestamps with a space or a lowercase `t` before the
+    ///   time, and plain dates, which are taken to be midnight UTC
+    pub fn parse_feed_date(input: &str) -> Result<DateTime, ParseError> {
+        let trimmed = input.trim();
+        let bytes = trimmed.as_bytes();
+
+        let result =
+            if bytes.len() >= 10 && bytes[4] == b'-' && bytes[..4].iter().all(u8::is_ascii_digit) {
+                parse_iso(trimmed)
+            } else {
+                DateTime::parse_rfc2822(trimmed).or_else(|_e| {
+                    trace_event!(input = trimmed, error = %_e, "not RFC 2822, trying looser forms");
+                    parse_rss(trimmed)
+                })
+            };
+
+        // report positions in the input as given
+        let leading = input.len() - input.trim_start().len();
+        result.map_err(|e| match e {
+            ParseError::Invalid(pos) => ParseError::Invalid(pos + leading),
+            e => e,
+        })
+    }
+
+    /// returns the DateTime as an RSS date, which is the same as
+    /// [`DateTime::to_rfc2822`]
+    pub fn to_rss_date(&self) -> String {
+        self.to_rfc2822()
+    }
+
+    /// returns the DateTime as an Atom date, which is the same as
+    /// [`DateTime::to_rfc3339`]
+    pub fn to_atom_date(&self) -> String {
+        self.to_rfc3339()
+    }
+}
+
+// parses an RFC 3339 timestamp, filling in what's missing
+fn parse_iso(input: &str) -> Result<DateTime, ParseError> {
+    if input.len() == 10 {
+        return DateTime::parse_rfc3339(&format!("{}T00:00:00Z", input));
+    }
+
+    let mut fixed = String::from(input);
+    if matches!(input.as_bytes()[10], b' ' | b't') {
+        fixed.replace_range(10..11, "T");
+    }
+    if fixed.ends_with(|c: char| c.is_ascii_digit()) && fixed.len() <= 29 {
+        // no zone, unless it ends in an offset such as +02:00
+        let tail = &fixed.as_bytes()[fixed.len() - 6..];
+        if !matches!(tail[0], b'+' | b'-') || tail[3] != b':' {
+            fixed.push('Z');
+        }
+    }
+
+    DateTime::parse_rfc3339(&fixed)
+}
+
+// parses an RFC 822 date with the leniencies parse_feed_date allows
+fn parse_rss(input: &str) -> Result<DateTime, ParseError> {
+    let mut cursor = Cursor::new(input);
+
+    // a day of the week, which is often wrong, so is ignored
+    if cursor.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+        cursor.letters()?;
+        if cursor.peek() == Some(b',') {
+            cursor.next()?;
+        }
+        cursor.skip_spaces();
+    }
+
+    let (date, _) = cursor.digits_between(1, 2)?;
+    separator(&mut cursor)?;
+
+    let start = cursor.pos;
+    let name = str::from_utf8(cursor.letters()?).expect("letters are ASCII");
+    let month = match Month::from_str(name) {
+        Ok(month) => month as usize + 1,
+        Err(_) if name.eq_ignore_ascii_case("sept") => 9,
+        Err(_) => return Err(ParseError::Invalid(start)),
+    };
+    separator(&mut cursor)?;
+
+    let year = match cursor.digits_between(2, 4)? {
+        (year, 2) if year < 50 => year + 2000,
+        (year, 2) | (year, 3) => year + 1900,
+        (year, _) => year,
+    };
+    if cursor.skip_spaces() == 0 {
+        return Err(cursor
+            .peek()
+            .map_or(ParseError::TooShort, |_| ParseError::Invalid(cursor.pos)));
+    }
+
+    let (hour, _) = cursor.digits_between(1, 2)?;
+    cursor.expect(b':')?;
+    let minute = cursor.digits(2)?;
+    let (mut second, mut nanos) = (0, 0);
+    if cursor.peek() == Some(b':') {
+        cursor.next()?;
+        second = cursor.digits(2)?;
+        if cursor.peek() == Some(b'.') {
+            cursor.next()?;
+            nanos = cursor.fraction()?;
+        }
+    }
+    cursor.skip_spaces();
+
+    let start = cursor.pos;
+    let offset = match cursor.peek() {
+        None => 0,
+        Some(sign @ (b'+' | b'-')) => {
+            cursor.next()?;
+            let hours = cursor.digits(2)?;
+            if cursor.peek() == Some(b':') {
+                cursor.next()?;
+            }
+            let minutes = cursor.digits(2)?;
+            if minutes > 59 {
+                return Err(ParseError::OutOfRange);
+            }
+
+            let offset = (hours * 3600 + minutes * 60) as i64;
+            if sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        Some(_) => match cursor.letters()? {
+            b"Z" | b"z" => 0,
+            name if name.eq_ignore_ascii_case(b"UTC") => 0,
+            name => obsolete_zone(name).ok_or(ParseError::Invalid(start))?,
+        },
+    };
+
+    cursor.skip_spaces();
+    cursor.finish()?;
+
+    from_fields(year, month, date, hour, minute, second, nanos, offset)
+}
+
+// skips the spaces or dash between the fields of a date, failing if there
+// is neither
+fn separator(cursor: &mut Cursor) -> Result<(), ParseError> {
+    let mut found = cursor.skip_spaces() > 0;
+    if cursor.peek() == Some(b'-') {
+        cursor.next()?;
+        found = true;
+    }
+    cursor.skip_spaces();
+
+    if found {
+        Ok(())
+    } else {
+        Err(cursor
+            .peek()
+            .map_or(ParseError::TooShort, |_| ParseError::Invalid(cursor.pos)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, ParseError};
+
+    #[test]
+    fn test_rss_variants() {
+        let parse = |input: &str| DateTime::parse_feed_date(input).map(|date| date.to_rfc3339());
+
+        assert_eq!(
+            parse("Tue, 09 Sep 1996 15:23:44 GMT"),
+            Ok("1996-09-09T15:23:44Z".into())
+        );
+        assert_eq!(parse("  9 Sept 96 3:23 PM"), Err(ParseError::Invalid(17)));
+        assert_eq!(
+            parse("9-Sept-96 3:23:44.5 -0400"),
+            Ok("1996-09-09T07:23:44.500Z".into())
+        );
+        assert_eq!(
+            parse("Mon, 09 Sep 1996 15:23:44 UTC\n"),
+            Ok("1996-09-09T15:23:44Z".into())
+        );
+        assert_eq!(
+            parse("Mon, 09 Sep 1996 15:23:44 CET"),
+            Err(ParseError::Invalid(26))
+        );
+        assert_eq!(parse("Mon, 31 Sep 1996 15:23"), Err(ParseError::OutOfRange));
+    }
+
+    #[test]
+    fn test_atom_variants() {
+        let parse = |input: &str| DateTime::parse_feed_date(input).map(|date| date.to_rfc3339());
+
+        assert_eq!(parse("1996-09-09"), Ok("1996-09-09T00:00:00Z".into()));
+        assert_eq!(
+            parse("1996-09-09t17:23:44.25+02:00"),
+            Ok("1996-09-09T15:23:44.250Z".into())
+        );
+        assert_eq!(
+            parse("1996-09-09 15:23:44.123456"),
+            Ok("1996-09-09T15:23:44.123456Z".into())
+        );
+        assert!(parse("1996-09-09X15:23:44Z").is_err());
+
+        let date = DateTime::from_millis(842282624500);
+        assert_eq!(date.to_atom_date(), "1996-09-09T15:23:44.500Z");
+        assert_eq!(date.to_rss_date(), "Mon, 09 Sep 1996 15:23:44 +0000");
+    }
+}
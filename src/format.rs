@@ -0,0 +1,1801 @@
+//! strftime-style formatting of DateTimes
+//!
+//! a format string is made up of literal text and specifiers introduced by
+//! `%`. the supported specifiers are:
+//!
+//! | spec | meaning                                   | example     |
+//! |------|-------------------------------------------|-------------|
+//! | `%Y` | year, at least four digits                | `1996`      |
+//! | `%C` | century (year / 100), two digits          | `19`        |
+//! | `%y` | year within the century, two digits       | `96`        |
+//! | `%G` | ISO 8601 week-based year, four digits     | `1996`      |
+//! | `%g` | ISO 8601 week-based year, two digits      | `96`        |
+//! | `%m` | month number, two digits                  | `09`        |
+//! | `%B` | full month name                           | `September` |
+//! | `%b` | abbreviated month name (also `%h`)        | `Sep`       |
+//! | `%d` | day of the month, two digits              | `09`        |
+//! | `%e` | day of the month, space padded            | ` 9`        |
+//! | `%j` | day of the year, three digits             | `253`       |
+//! | `%A` | full weekday name                         | `Monday`    |
+//! | `%a` | abbreviated weekday name                  | `Mon`       |
+//! | `%u` | weekday number, Monday = 1                | `1`         |
+//! | `%w` | weekday number, Sunday = 0                | `1`         |
+//! | `%U` | week of the year, weeks starting Sunday   | `36`        |
+//! | `%W` | week of the year, weeks starting Monday   | `37`        |
+//! | `%V` | ISO 8601 week number                      | `37`        |
+//! | `%H` | hour (24-hour clock), two digits          | `15`        |
+//! | `%k` | hour (24-hour clock), space padded        | `15`        |
+//! | `%I` | hour (12-hour clock), two digits          | `03`        |
+//! | `%l` | hour (12-hour clock), space padded        | ` 3`        |
+//! | `%p` | `AM` or `PM`                              | `PM`        |
+//! | `%M` | minute, two digits                        | `23`        |
+//! | `%S` | second, two digits                        | `44`        |
+//! | `%s` | seconds since the Unix epoch              | `842282624` |
+//! | `%f` | fraction of the second, nine digits       | `026490000` |
+//! | `%.f`| `.` and the fraction to 3, 6 or 9 digits  | `.026490`   |
+//! | `%Z` | time zone name, e.g. `UTC+05:30`          | `UTC`       |
+//! | `%z` | offset from UTC as `+hhmm`                | `+0000`     |
+//! | `%n` | a newline                                 |             |
+//! | `%t` | a tab                                     |             |
+//! | `%%` | a literal `%`                             |             |
+//!
+//! as well as the composites `%F` (`%Y-%m-%d`), `%T` (`%H:%M:%S`),
+//! `%D` (`%m/%d/%y`), `%R` (`%H:%M`), `%r` (`%I:%M:%S %p`),
+//! `%c` (`%a %b %e %H:%M:%S %Y`), `%x` (`%m/%d/%y`) and `%X` (`%H:%M:%S`)
+//!
+//! # Flags and widths
+//!
+//! as in GNU `date`, a specifier may be modified by flags and a minimum
+//! width placed between the `%` and the specifier character:
+//!
+//! | flag | meaning                                                   |
+//! |------|-----------------------------------------------------------|
+//! | `-`  | don't pad numbers                                         |
+//! | `_`  | pad numbers with spaces                                   |
+//! | `0`  | pad numbers (and text) with zeros                         |
+//! | `^`  | upper-case text                                           |
+//! | `#`  | swap the case of text (`%#p` gives `pm`, `%#a` gives `MON`) |
+//! | `+`  | pad with zeros, and add `+` to numbers longer than the width |
+//!
+//! the width follows any flags, e.g. `%10A` or `%_3d`, and can be at most
+//! 1024
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_secs(842282624);
+//!
+//! assert_eq!(date.format("%-d/%-m %_H:%M"), "9/9 15:23");
+//! assert_eq!(date.format("%03j|%5d|%^a|%#p"), "253|00009|MON|pm");
+//! ```
+//!
+//! # Fractional seconds
+//!
+//! a width given to `%f` sets the number of digits of the fraction to
+//! print, up to nine; extra precision is truncated rather than rounded.
+//! `%.3f`, `%.6f` and `%.9f` do the same but add a leading `.`, while
+//! `%.f` uses as many digits as needed and prints nothing at all for a
+//! whole second
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_nanos(842282624026490000);
+//!
+//! assert_eq!(date.format("%T%.3f"), "15:23:44.026");
+//! assert_eq!(date.format("%T.%6f"), "15:23:44.026490");
+//! assert_eq!(date.format("%T%.f"), "15:23:44.026490");
+//! ```
+//!
+//! # Literal text
+//!
+//! any text outside of a specifier is copied to the output as is, so
+//! patterns like `Week %V of %G` or `at %H o'clock` need no escaping.
+//! `%%` prints a single `%`, and a quoted section `%'...'` prints the text
+//! between the quotes without interpreting any specifiers in it; within a
+//! quoted section, `''` prints a single `'`
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_secs(842282624);
+//!
+//! assert_eq!(date.format("at %H o'clock"), "at 15 o'clock");
+//! assert_eq!(date.format("%'%Y' is %Y"), "%Y is 1996");
+//! assert_eq!(date.format("%'it''s' %Y"), "it's 1996");
+//! ```
+//!
+//! # Parsing
+//!
+//! [`DateTime::parse_from_str`] reads a DateTime back with the same
+//! specifiers. numbers may be shorter than their usual width (a width
+//! given in the format is the most digits read), names and `AM`/`PM` are
+//! read in English in any case, with month and weekday names read in full
+//! or abbreviated, and whitespace in the format matches any amount of
+//! whitespace, including none. `%z` also reads `Z`, `+hh` and `+hh:mm`.
+//! a two-digit year is taken to be from 1969 to 2068
+//!
+//! fields missing from the format are taken from the Unix epoch, so a
+//! format of just `%H:%M` gives a time on 1970-01-01. the date comes from
+//! `%s` if given, then the month and day, then `%j`, then the ISO 8601
+//! week fields; a weekday is checked against the date, and the week
+//! numbers `%U` and `%W` are read but not used
+//! ```
+//! # use datetime::{DateTime, ParseError};
+//! let date = DateTime::parse_from_str("Monday, September 9 1996 3pm", "%A, %B %e %Y %I%p")
+//!     .unwrap();
+//! assert_eq!(date.to_rfc3339(), "1996-09-09T15:00:00Z");
+//!
+//! assert_eq!(
+//!     DateTime::parse_from_str("1996-09-31", "%F"),
+//!     Err(ParseError::OutOfRange)
+//! );
+//! assert_eq!(
+//!     DateTime::parse_from_str("1996/09/09", "%F"),
+//!     Err(ParseError::Invalid(4))
+//! );
+//! ```
+use crate::parse::{from_fields, obsolete_zone, Cursor, ParseError};
+use crate::sink::FmtSink;
+use crate::{
+    CalendarFields, DateTime, Day, FixedOffset, Locale, TimestampSink, DAYS, MAX_SECS, MIN_SECS,
+    MONTHS,
+};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{self, Write};
+
+/// an error returned when a format string could not be parsed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FormatError {
+    /// the format string ended in the middle of a specifier
+    Incomplete,
+    /// an unknown specifier character was found at the given byte offset
+    UnknownSpecifier(char, usize),
+    /// a quoted section starting at the given byte offset was never closed
+    UnterminatedQuote(usize),
+    /// the specifier at the given byte offset has a width over 1024
+    WidthTooLarge(usize),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Incomplete => write!(f, "incomplete format specifier"),
+            FormatError::UnknownSpecifier(c, pos) => {
+                write!(f, "unknown format specifier '%{}' at position {}", c, pos)
+            }
+            FormatError::UnterminatedQuote(pos) => {
+                write!(f, "unterminated quoted section at position {}", pos)
+            }
+            FormatError::WidthTooLarge(pos) => {
+                write!(f, "format specifier width too large at position {}", pos)
+            }
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+/// an error returned by [`DateTime::reformat`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReformatError {
+    /// the input didn't match the input format
+    Parse(ParseError),
+    /// the output format can't hold everything read from the input, such
+    /// as its fractional seconds or offset, so reading the output back
+    /// wouldn't give the same DateTime
+    Lossy,
+}
+
+impl fmt::Display for ReformatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReformatError::Parse(e) => write!(f, "invalid input: {}", e),
+            ReformatError::Lossy => write!(f, "the output format would lose information"),
+        }
+    }
+}
+
+impl Error for ReformatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReformatError::Parse(e) => Some(e),
+            ReformatError::Lossy => None,
+        }
+    }
+}
+
+impl From<ParseError> for ReformatError {
+    fn from(e: ParseError) -> Self {
+        ReformatError::Parse(e)
+    }
+}
+
+// a single field of a DateTime that a specifier can print
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Field {
+    Year,
+    Century,
+    YearOfCentury,
+    IsoYear,
+    IsoYearOfCentury,
+    Month,
+    MonthName,
+    MonthAbbreviation,
+    Date,
+    DateSpacePadded,
+    DayOfYear,
+    WeekdayName,
+    WeekdayAbbreviation,
+    WeekdayFromMonday,
+    WeekdayFromSunday,
+    WeekFromSunday,
+    WeekFromMonday,
+    IsoWeek,
+    Hour,
+    HourSpacePadded,
+    Hour12,
+    Hour12SpacePadded,
+    AmPm,
+    Minute,
+    Second,
+    Timestamp,
+    Fraction,
+    DottedFraction,
+    ZoneName,
+    ZoneOffset,
+}
+
+// the widest a field can be padded to, which is far more than any real
+// format needs
+const MAX_WIDTH: usize = 1024;
+
+// how a field is padded to its width
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Pad {
+    None,
+    Space,
+    Zero,
+}
+
+// the flags and width given between the `%` and the specifier character;
+// anything unset falls back to the field's defaults
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Modifiers {
+    pad: Option<Pad>,
+    width: Option<usize>,
+    upper_case: bool,
+    swap_case: bool,
+    sign: bool,
+}
+
+// one piece of a parsed format string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Item {
+    Literal(String),
+    Field(Field, Modifiers),
+}
+
+/// a parsed format string, which can be reused to format many DateTimes
+/// without parsing the string each time
+/// ```
+/// # use datetime::{DateTime, FormatSpec};
+/// let spec = FormatSpec::parse("%Y-%m-%d").unwrap();
+/// let date = DateTime::from_secs(842282624);
+///
+/// assert_eq!(date.format_with(&spec), "1996-09-09");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSpec {
+    items: Vec<Item>,
+}
+
+impl FormatSpec {
+    /// parses a strftime-style format string, failing on unknown or
+    /// incomplete specifiers
+    /// ```
+    /// # use datetime::{FormatError, FormatSpec};
+    /// assert!(FormatSpec::parse("%H:%M").is_ok());
+    /// assert_eq!(
+    ///     FormatSpec::parse("%H:%Q").unwrap_err(),
+    ///     FormatError::UnknownSpecifier('Q', 3)
+    /// );
+    /// ```
+    pub fn parse(fmt: &str) -> Result<FormatSpec, FormatError> {
+        FormatSpec::parse_items(fmt, true)
+    }
+
+    /// returns a builder for assembling a FormatSpec field by field, as a
+    /// typed alternative to format strings which can't fail at runtime
+    /// ```
+    /// # use datetime::{DateTime, FormatSpec};
+    /// let spec = FormatSpec::builder()
+    ///     .year4()
+    ///     .literal("-")
+    ///     .month2()
+    ///     .literal("-")
+    ///     .date2()
+    ///     .build();
+    ///
+    /// assert_eq!(spec, FormatSpec::parse("%Y-%m-%d").unwrap());
+    /// assert_eq!(DateTime::from_secs(842282624).format_with(&spec), "1996-09-09");
+    /// ```
+    pub fn builder() -> FormatSpecBuilder {
+        FormatSpecBuilder {
+            spec: FormatSpec { items: Vec::new() },
+        }
+    }
+
+    // parses a format string; when not strict, unknown and incomplete
+    // specifiers are kept as literal text
+    fn parse_items(fmt: &str, strict: bool) -> Result<FormatSpec, FormatError> {
+        let mut spec = FormatSpec { items: Vec::new() };
+        let mut chars = fmt.char_indices();
+
+        while let Some((pos, c)) = chars.next() {
+            if c != '%' {
+                spec.push_literal(c);
+                continue;
+            }
+
+            // everything from the `%` on, in case it has to be kept as is
+            let raw = &fmt[pos..];
+            let mut modifiers = Modifiers::default();
+            let mut width: Option<usize> = None;
+            let mut too_wide = false;
+            let mut dotted = false;
+
+            let c = loop {
+                let c = match chars.next() {
+                    Some((_, c)) => c,
+                    None if strict => return Err(FormatError::Incomplete),
+                    None => {
+                        raw.chars().for_each(|c| spec.push_literal(c));
+                        return Ok(spec);
+                    }
+                };
+
+                match c {
+                    '-' if width.is_none() => modifiers.pad = Some(Pad::None),
+                    '_' if width.is_none() => modifiers.pad = Some(Pad::Space),
+                    '0' if width.is_none() => modifiers.pad = Some(Pad::Zero),
+                    '^' if width.is_none() => modifiers.upper_case = true,
+                    '#' if width.is_none() => modifiers.swap_case = true,
+                    '+' if width.is_none() => {
+                        modifiers.pad = Some(Pad::Zero);
+                        modifiers.sign = true;
+                    }
+                    '.' if width.is_none() && !dotted => dotted = true,
+                    '0'..='9' => {
+                        let digit = c as usize - '0' as usize;
+                        let wider = width.unwrap_or(0).checked_mul(10);
+                        match wider.and_then(|width| width.checked_add(digit)) {
+                            Some(wider) if wider <= MAX_WIDTH => width = Some(wider),
+                            _ => {
+                                // keep reading digits, to find the specifier
+                                width = Some(MAX_WIDTH);
+                                too_wide = true;
+                            }
+                        }
+                    }
+                    // the E and O modifiers select alternative
+                    // representations in some locales, and are ignored
+                    'E' | 'O' => {}
+                    c => break c,
+                }
+            };
+            modifiers.width = width;
+            if too_wide {
+                if strict {
+                    return Err(FormatError::WidthTooLarge(pos));
+                }
+                let len = raw.len() - chars.as_str().len();
+                raw[..len].chars().for_each(|c| spec.push_literal(c));
+                continue;
+            }
+
+            match c {
+                '%' => spec.push_literal('%'),
+                '\'' => {
+                    let mut closed = false;
+                    while let Some((_, c)) = chars.next() {
+                        if c != '\'' {
+                            spec.push_literal(c);
+                        } else if chars.as_str().starts_with('\'') {
+                            chars.next();
+                            spec.push_literal('\'');
+                        } else {
+                            closed = true;
+                            break;
+                        }
+                    }
+
+                    if !closed && strict {
+                        return Err(FormatError::UnterminatedQuote(pos));
+                    }
+                }
+                'n' => spec.push_literal('\n'),
+                't' => spec.push_literal('\t'),
+                'F' => spec.push_composite("%Y-%m-%d"),
+                'T' | 'X' => spec.push_composite("%H:%M:%S"),
+                'D' | 'x' => spec.push_composite("%m/%d/%y"),
+                'R' => spec.push_composite("%H:%M"),
+                'r' => spec.push_composite("%I:%M:%S %p"),
+                'c' => spec.push_composite("%a %b %e %H:%M:%S %Y"),
+                // only `%f` may be written with a leading `.`
+                _ => match (field_for(c), dotted) {
+                    (Some(Field::Fraction), true) => spec
+                        .items
+                        .push(Item::Field(Field::DottedFraction, modifiers)),
+                    (Some(field), false) => spec.items.push(Item::Field(field, modifiers)),
+                    _ if strict => return Err(FormatError::UnknownSpecifier(c, pos)),
+                    _ => {
+                        let len = raw.len() - chars.as_str().len();
+                        raw[..len].chars().for_each(|c| spec.push_literal(c));
+                    }
+                },
+            }
+        }
+
+        Ok(spec)
+    }
+
+    fn push_literal(&mut self, c: char) {
+        if let Some(Item::Literal(s)) = self.items.last_mut() {
+            s.push(c);
+        } else {
+            self.items.push(Item::Literal(c.to_string()));
+        }
+    }
+
+    fn push_composite(&mut self, fmt: &str) {
+        let composite = FormatSpec::parse_items(fmt, true).expect("invalid composite");
+        for item in composite.items {
+            match item {
+                Item::Literal(s) => s.chars().for_each(|c| self.push_literal(c)),
+                item => self.items.push(item),
+            }
+        }
+    }
+
+    // writes the DateTime formatted according to this spec into `w`, using
+    // `zone` as the time zone name and the names of `locale`
+    pub(crate) fn write<S: TimestampSink + ?Sized>(
+        &self,
+        date: &DateTime,
+        zone: &str,
+        locale: Locale,
+        w: &mut S,
+    ) -> fmt::Result {
+        for item in &self.items {
+            match item {
+                Item::Literal(s) => w.write_str(s)?,
+                Item::Field(field, modifiers) => {
+                    write_field(*field, *modifiers, date, zone, locale, w)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! builder_fields {
+    ($($(#[$doc:meta])* $name:ident => $field:ident,)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(mut self) -> Self {
+                self.spec.items.push(Item::Field(Field::$field, Modifiers::default()));
+                self
+            }
+        )*
+    };
+}
+
+/// a builder for FormatSpecs, created with [`FormatSpec::builder`]
+///
+/// each method appends one field (or some literal text) to the spec; the
+/// equivalent format specifier is given in each method's description
+#[derive(Debug, Clone)]
+pub struct FormatSpecBuilder {
+    spec: FormatSpec,
+}
+
+impl FormatSpecBuilder {
+    /// appends literal text
+    pub fn literal(mut self, text: &str) -> Self {
+        text.chars().for_each(|c| self.spec.push_literal(c));
+        self
+    }
+
+    builder_fields! {
+        /// appends the year (`%Y`)
+        year4 => Year,
+        /// appends the century, two digits (`%C`)
+        century2 => Century,
+        /// appends the year within the century, two digits (`%y`)
+        year2 => YearOfCentury,
+        /// appends the ISO 8601 week-based year (`%G`)
+        iso_year4 => IsoYear,
+        /// appends the ISO 8601 week-based year, two digits (`%g`)
+        iso_year2 => IsoYearOfCentury,
+        /// appends the month number, two digits (`%m`)
+        month2 => Month,
+        /// appends the full month name (`%B`)
+        month_name => MonthName,
+        /// appends the abbreviated month name (`%b`)
+        month_abbreviation => MonthAbbreviation,
+        /// appends the day of the month, two digits (`%d`)
+        date2 => Date,
+        /// appends the day of the month, space padded (`%e`)
+        date_space_padded => DateSpacePadded,
+        /// appends the day of the year, three digits (`%j`)
+        day_of_year3 => DayOfYear,
+        /// appends the full weekday name (`%A`)
+        weekday_name => WeekdayName,
+        /// appends the abbreviated weekday name (`%a`)
+        weekday_abbreviation => WeekdayAbbreviation,
+        /// appends the weekday number, Monday = 1 (`%u`)
+        weekday_from_monday => WeekdayFromMonday,
+        /// appends the weekday number, Sunday = 0 (`%w`)
+        weekday_from_sunday => WeekdayFromSunday,
+        /// appends the week of the year, weeks starting Sunday (`%U`)
+        week_from_sunday2 => WeekFromSunday,
+        /// appends the week of the year, weeks starting Monday (`%W`)
+        week_from_monday2 => WeekFromMonday,
+        /// appends the ISO 8601 week number, two digits (`%V`)
+        iso_week2 => IsoWeek,
+        /// appends the hour on the 24-hour clock, two digits (`%H`)
+        hour2 => Hour,
+        /// appends the hour on the 24-hour clock, space padded (`%k`)
+        hour_space_padded => HourSpacePadded,
+        /// appends the hour on the 12-hour clock, two digits (`%I`)
+        hour12_2 => Hour12,
+        /// appends the hour on the 12-hour clock, space padded (`%l`)
+        hour12_space_padded => Hour12SpacePadded,
+        /// appends `AM` or `PM` (`%p`)
+        am_pm => AmPm,
+        /// appends the minute, two digits (`%M`)
+        minute2 => Minute,
+        /// appends the second, two digits (`%S`)
+        second2 => Second,
+        /// appends the seconds since the Unix epoch (`%s`)
+        timestamp => Timestamp,
+        /// appends the time zone name (`%Z`)
+        zone_name => ZoneName,
+        /// appends the offset from UTC as `+hhmm` (`%z`)
+        zone_offset => ZoneOffset,
+    }
+
+    /// appends the fraction of the second to the given number of digits,
+    /// at most nine, without a leading `.` (`%3f`, `%6f` and so on)
+    /// ```
+    /// # use datetime::{DateTime, FormatSpec};
+    /// let spec = FormatSpec::builder().second2().literal(".").fraction(3).build();
+    ///
+    /// assert_eq!(spec, FormatSpec::parse("%S.%3f").unwrap());
+    /// assert_eq!(DateTime::from_millis(842282624123).format_with(&spec), "44.123");
+    /// ```
+    pub fn fraction(mut self, digits: usize) -> Self {
+        let modifiers = Modifiers {
+            width: Some(digits),
+            ..Modifiers::default()
+        };
+        self.spec
+            .items
+            .push(Item::Field(Field::Fraction, modifiers));
+        self
+    }
+
+    /// returns the finished FormatSpec
+    pub fn build(self) -> FormatSpec {
+        self.spec
+    }
+}
+
+// maps a specifier character to the field it prints
+fn field_for(c: char) -> Option<Field> {
+    let field = match c {
+        'Y' => Field::Year,
+        'C' => Field::Century,
+        'y' => Field::YearOfCentury,
+        'G' => Field::IsoYear,
+        'g' => Field::IsoYearOfCentury,
+        'm' => Field::Month,
+        'B' => Field::MonthName,
+        'b' | 'h' => Field::MonthAbbreviation,
+        'd' => Field::Date,
+        'e' => Field::DateSpacePadded,
+        'j' => Field::DayOfYear,
+        'A' => Field::WeekdayName,
+        'a' => Field::WeekdayAbbreviation,
+        'u' => Field::WeekdayFromMonday,
+        'w' => Field::WeekdayFromSunday,
+        'U' => Field::WeekFromSunday,
+        'W' => Field::WeekFromMonday,
+        'V' => Field::IsoWeek,
+        'H' => Field::Hour,
+        'k' => Field::HourSpacePadded,
+        'I' => Field::Hour12,
+        'l' => Field::Hour12SpacePadded,
+        'p' => Field::AmPm,
+        'M' => Field::Minute,
+        'S' => Field::Second,
+        's' => Field::Timestamp,
+        'Z' => Field::ZoneName,
+        'z' => Field::ZoneOffset,
+        'f' => Field::Fraction,
+        _ => return None,
+    };
+
+    Some(field)
+}
+
+// writes a number padded according to the modifiers, falling back to the
+// field's default width and padding
+fn write_number<S: TimestampSink + ?Sized>(
+    w: &mut S,
+    value: i64,
+    width: usize,
+    pad: Pad,
+    modifiers: Modifiers,
+) -> fmt::Result {
+    let width = modifiers.width.unwrap_or(width);
+    let pad = modifiers.pad.unwrap_or(pad);
+    let digits = value.unsigned_abs().to_string();
+
+    let sign = if value < 0 {
+        "-"
+    } else if modifiers.sign && digits.len() > width {
+        "+"
+    } else {
+        ""
+    };
+
+    let fill = width.saturating_sub(sign.len() + digits.len());
+    match pad {
+        Pad::None => w.write_str(sign)?,
+        Pad::Space => {
+            w.write_str(&" ".repeat(fill))?;
+            w.write_str(sign)?;
+        }
+        Pad::Zero => {
+            w.write_str(sign)?;
+            w.write_str(&"0".repeat(fill))?;
+        }
+    }
+    w.write_str(&digits)
+}
+
+// writes text padded according to the modifiers; `^` upper-cases the text
+// and `#` swaps its case (which for mixed-case names means upper-casing)
+fn write_text<S: TimestampSink + ?Sized>(
+    w: &mut S,
+    text: &str,
+    modifiers: Modifiers,
+) -> fmt::Result {
+    let text = if modifiers.upper_case {
+        text.to_uppercase()
+    } else if modifiers.swap_case {
+        if text.chars().any(|c| c.is_lowercase()) {
+            text.to_uppercase()
+        } else {
+            text.to_lowercase()
+        }
+    } else {
+        text.to_string()
+    };
+
+    let fill = modifiers
+        .width
+        .unwrap_or(0)
+        .saturating_sub(text.chars().count());
+    let pad = if modifiers.pad == Some(Pad::Zero) {
+        "0"
+    } else {
+        " "
+    };
+
+    w.write_str(&pad.repeat(fill))?;
+    w.write_str(&text)
+}
+
+// writes the leading `digits` digits of a nanosecond fraction, truncating
+// the rest
+fn write_fraction<S: TimestampSink + ?Sized>(w: &mut S, nanos: u32, digits: usize) -> fmt::Result {
+    w.write_u32_padded(nanos / 10u32.pow(9 - digits as u32), digits)
+}
+
+fn write_field<S: TimestampSink + ?Sized>(
+    field: Field,
+    modifiers: Modifiers,
+    date: &DateTime,
+    zone: &str,
+    locale: Locale,
+    w: &mut S,
+) -> fmt::Result {
+    let hour12 = match date.hour() % 12 {
+        0 => 12,
+        h => h,
+    };
+
+    let (value, width, pad) = match field {
+        Field::Year => (date.year() as i64, 4, Pad::Zero),
+        Field::Century => (date.year() as i64 / 100, 2, Pad::Zero),
+        Field::YearOfCentury => (date.year() as i64 % 100, 2, Pad::Zero),
+        Field::IsoYear => (date.iso_week().0 as i64, 4, Pad::Zero),
+        Field::IsoYearOfCentury => (date.iso_week().0 as i64 % 100, 2, Pad::Zero),
+        Field::Month => (date.month() as i64 + 1, 2, Pad::Zero),
+        Field::Date => (date.date() as i64, 2, Pad::Zero),
+        Field::DateSpacePadded => (date.date() as i64, 2, Pad::Space),
+        Field::DayOfYear => (date.day_of_year() as i64, 3, Pad::Zero),
+        Field::WeekdayFromMonday => ((date.day() as i64 + 6) % 7 + 1, 1, Pad::Zero),
+        Field::WeekdayFromSunday => (date.day() as i64, 1, Pad::Zero),
+        Field::WeekFromSunday => {
+            let week = (date.day_of_year() + 6 - date.day() as usize) / 7;
+            (week as i64, 2, Pad::Zero)
+        }
+        Field::WeekFromMonday => {
+            let week = (date.day_of_year() + 6 - (date.day() as usize + 6) % 7) / 7;
+            (week as i64, 2, Pad::Zero)
+        }
+        Field::IsoWeek => (date.iso_week().1 as i64, 2, Pad::Zero),
+        Field::Hour => (date.hour() as i64, 2, Pad::Zero),
+        Field::HourSpacePadded => (date.hour() as i64, 2, Pad::Space),
+        Field::Hour12 => (hour12 as i64, 2, Pad::Zero),
+        Field::Hour12SpacePadded => (hour12 as i64, 2, Pad::Space),
+        Field::Minute => (date.minute() as i64, 2, Pad::Zero),
+        Field::Second => (date.second() as i64, 2, Pad::Zero),
+        Field::Timestamp => (date.secs, 1, Pad::Zero),
+        Field::MonthName => return write_text(w, locale.month_name(date.month()), modifiers),
+        Field::MonthAbbreviation => {
+            return write_text(w, locale.month_abbreviation(date.month()), modifiers)
+        }
+        Field::WeekdayName => return write_text(w, locale.day_name(date.day()), modifiers),
+        Field::WeekdayAbbreviation => {
+            return write_text(w, locale.day_abbreviation(date.day()), modifiers)
+        }
+        Field::AmPm => {
+            let am_pm = if date.hour() < 12 { "AM" } else { "PM" };
+            return write_text(w, am_pm, modifiers);
+        }
+        Field::Fraction => {
+            let digits = modifiers.width.unwrap_or(9).min(9);
+            return write_fraction(w, date.nanos, digits);
+        }
+        Field::DottedFraction => {
+            let digits = match modifiers.width {
+                Some(digits) => digits.min(9),
+                None if date.nanos == 0 => return Ok(()),
+                None if date.nanos.is_multiple_of(1_000_000) => 3,
+                None if date.nanos.is_multiple_of(1000) => 6,
+                None => 9,
+            };
+            w.write_str(".")?;
+            return write_fraction(w, date.nanos, digits);
+        }
+        Field::ZoneName => return write_text(w, zone, modifiers),
+        Field::ZoneOffset => {
+            let mut offset = String::new();
+            date.offset().write(&mut offset, false)?;
+            return write_text(w, &offset, modifiers);
+        }
+    };
+
+    write_number(w, value, width, pad, modifiers)
+}
+
+// the fields read from an input by FormatSpec::read, before they are
+// combined into a DateTime
+#[derive(Debug, Default)]
+struct ReadFields {
+    year: Option<usize>,
+    century: Option<usize>,
+    year_of_century: Option<usize>,
+    iso_year: Option<usize>,
+    iso_week: Option<usize>,
+    month: Option<usize>,
+    date: Option<usize>,
+    day_of_year: Option<usize>,
+    weekday: Option<Day>,
+    hour: Option<usize>,
+    hour12: Option<usize>,
+    pm: Option<bool>,
+    minute: Option<usize>,
+    second: Option<usize>,
+    nanos: u32,
+    timestamp: Option<i64>,
+    offset: Option<FixedOffset>,
+}
+
+// the year of a two-digit year, taken to be from 1969 to 2068 as POSIX says
+fn full_year(year: usize) -> usize {
+    if year < 69 {
+        2000 + year
+    } else {
+        1900 + year
+    }
+}
+
+// reads one of `count` names, or their abbreviations, in any case,
+// returning the index of the one found
+fn read_name(
+    input: &str,
+    cursor: &mut Cursor,
+    count: usize,
+    name: impl Fn(usize) -> (&'static str, &'static str),
+) -> Result<usize, ParseError> {
+    let rest = &input.as_bytes()[cursor.pos..];
+    let matches = |name: &str| {
+        let name = name.as_bytes();
+        rest.len() >= name.len() && rest[..name.len()].eq_ignore_ascii_case(name)
+    };
+
+    // full names first, as every abbreviation is a prefix of one
+    let found = (0..count)
+        .map(|i| name(i).0)
+        .chain((0..count).map(|i| name(i).1))
+        .enumerate()
+        .find(|(_, name)| matches(name));
+
+    match found {
+        Some((i, name)) => {
+            cursor.pos += name.len();
+            Ok(i % count)
+        }
+        None if rest.is_empty() => Err(ParseError::TooShort),
+        None => Err(ParseError::Invalid(cursor.pos)),
+    }
+}
+
+// reads an offset written `Z`, `+hh`, `+hhmm` or `+hh:mm`
+fn read_offset(cursor: &mut Cursor) -> Result<FixedOffset, ParseError> {
+    let sign = cursor.expect_any(b"Z+-")?;
+    if sign == b'Z' {
+        return Ok(FixedOffset::utc());
+    }
+
+    let hours = cursor.digits(2)?;
+    if cursor.peek() == Some(b':') {
+        cursor.next()?;
+    }
+    let minutes = match cursor.peek() {
+        Some(b) if b.is_ascii_digit() => cursor.digits(2)?,
+        _ => 0,
+    };
+    if hours > 23 || minutes > 59 {
+        return Err(ParseError::OutOfRange);
+    }
+
+    let secs = (hours * 3600 + minutes * 60) as i32;
+    let secs = if sign == b'-' { -secs } else { secs };
+    FixedOffset::east(secs).ok_or(ParseError::OutOfRange)
+}
+
+fn read_field(
+    field: Field,
+    modifiers: Modifiers,
+    input: &str,
+    cursor: &mut Cursor,
+    fields: &mut ReadFields,
+) -> Result<(), ParseError> {
+    // numbers may be shorter than their usual width, and space-padded ones
+    // may start with spaces
+    let number = |cursor: &mut Cursor, width: usize, pad: Pad| {
+        if modifiers.pad.unwrap_or(pad) == Pad::Space {
+            cursor.skip_spaces();
+        }
+        cursor
+            .digits_between(1, modifiers.width.unwrap_or(width))
+            .map(|(value, _)| value)
+    };
+
+    match field {
+        Field::Year => fields.year = Some(number(cursor, 4, Pad::Zero)?),
+        Field::Century => fields.century = Some(number(cursor, 2, Pad::Zero)?),
+        Field::YearOfCentury => fields.year_of_century = Some(number(cursor, 2, Pad::Zero)?),
+        Field::IsoYear => fields.iso_year = Some(number(cursor, 4, Pad::Zero)?),
+        Field::IsoYearOfCentury => fields.iso_year = Some(full_year(number(cursor, 2, Pad::Zero)?)),
+        Field::Month => fields.month = Some(number(cursor, 2, Pad::Zero)?),
+        Field::Date => fields.date = Some(number(cursor, 2, Pad::Zero)?),
+        Field::DateSpacePadded => fields.date = Some(number(cursor, 2, Pad::Space)?),
+        Field::DayOfYear => fields.day_of_year = Some(number(cursor, 3, Pad::Zero)?),
+        Field::WeekdayFromMonday | Field::WeekdayFromSunday => {
+            let pos = cursor.pos;
+            let day = number(cursor, 1, Pad::Zero)?;
+            let day = match (field, day) {
+                (Field::WeekdayFromMonday, 1..=7) => day % 7,
+                (Field::WeekdayFromSunday, 0..=6) => day,
+                _ => return Err(ParseError::Invalid(pos)),
+            };
+            fields.weekday = Some(DAYS[day]);
+        }
+        // these are checked, but don't pick out a date
+        Field::WeekFromSunday | Field::WeekFromMonday => {
+            if number(cursor, 2, Pad::Zero)? > 53 {
+                return Err(ParseError::OutOfRange);
+            }
+        }
+        Field::IsoWeek => fields.iso_week = Some(number(cursor, 2, Pad::Zero)?),
+        Field::Hour => fields.hour = Some(number(cursor, 2, Pad::Zero)?),
+        Field::HourSpacePadded => fields.hour = Some(number(cursor, 2, Pad::Space)?),
+        Field::Hour12 => fields.hour12 = Some(number(cursor, 2, Pad::Zero)?),
+        Field::Hour12SpacePadded => fields.hour12 = Some(number(cursor, 2, Pad::Space)?),
+        Field::Minute => fields.minute = Some(number(cursor, 2, Pad::Zero)?),
+        Field::Second => fields.second = Some(number(cursor, 2, Pad::Zero)?),
+        Field::MonthName | Field::MonthAbbreviation => {
+            let month = read_name(input, cursor, 12, |i| {
+                (MONTHS[i].name(), MONTHS[i].abbreviation())
+            })?;
+            fields.month = Some(month + 1);
+        }
+        Field::WeekdayName | Field::WeekdayAbbreviation => {
+            let day = read_name(input, cursor, 7, |i| {
+                (DAYS[i].name(), DAYS[i].abbreviation())
+            })?;
+            fields.weekday = Some(DAYS[day]);
+        }
+        Field::AmPm => {
+            let am_pm = read_name(input, cursor, 2, |i| (["AM", "PM"][i], ["AM", "PM"][i]))?;
+            fields.pm = Some(am_pm == 1);
+        }
+        Field::Timestamp => {
+            let negative = cursor.peek() == Some(b'-');
+            if negative {
+                cursor.next()?;
+            }
+            let (secs, _) = cursor.digits_between(1, 18)?;
+            let secs = secs as i64;
+            fields.timestamp = Some(if negative { -secs } else { secs });
+        }
+        Field::Fraction => fields.nanos = cursor.fraction()?,
+        Field::DottedFraction => {
+            if cursor.peek() == Some(b'.') {
+                cursor.next()?;
+                fields.nanos = cursor.fraction()?;
+            }
+        }
+        Field::ZoneOffset => fields.offset = Some(read_offset(cursor)?),
+        Field::ZoneName => {
+            // `UTC` or `UTC+hh:mm`, as written by `%Z`, or one of the zone
+            // names RFC 2822 knows
+            let pos = cursor.pos;
+            let name = cursor.letters()?;
+            let offset = if name.eq_ignore_ascii_case(b"UTC") {
+                match cursor.peek() {
+                    Some(b'+' | b'-') => read_offset(cursor)?.seconds() as i64,
+                    _ => 0,
+                }
+            } else {
+                obsolete_zone(name).ok_or(ParseError::Invalid(pos))?
+            };
+            fields.offset = FixedOffset::east(offset as i32);
+        }
+    }
+
+    Ok(())
+}
+
+impl ReadFields {
+    // combines the fields into a DateTime, taking anything missing from
+    // the Unix epoch
+    fn resolve(self) -> Result<DateTime, ParseError> {
+        let offset = self.offset.unwrap_or_default();
+        if let Some(secs) = self.timestamp {
+            if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+                return Err(ParseError::OutOfRange);
+            }
+            return Ok(DateTime::from_parts(secs, self.nanos, offset));
+        }
+
+        let year = match (self.year, self.century, self.year_of_century) {
+            (Some(year), _, _) => year,
+            (None, Some(century), year) => century
+                .checked_mul(100)
+                .and_then(|century| century.checked_add(year.unwrap_or(0)))
+                .ok_or(ParseError::OutOfRange)?,
+            (None, None, Some(year)) => full_year(year),
+            (None, None, None) => self.iso_year.unwrap_or(1970),
+        };
+        // later years would overflow on the way to a DateTime
+        let max = DateTime::MAX.year();
+        if year > max || self.iso_year.is_some_and(|year| year > max) {
+            return Err(ParseError::OutOfRange);
+        }
+
+        let (month, date) = match (self.month, self.date, self.day_of_year, self.iso_week) {
+            (None, None, Some(day_of_year), _) => {
+                let start =
+                    DateTime::from_ordinal(year, day_of_year).ok_or(ParseError::OutOfRange)?;
+                (start.month() as usize + 1, start.date())
+            }
+            (None, None, None, Some(week)) => {
+                let weekday = self.weekday.unwrap_or(Day::Monday);
+                let year = self.iso_year.unwrap_or(year);
+                let start =
+                    DateTime::from_iso_week(year, week, weekday).ok_or(ParseError::OutOfRange)?;
+                let year = start.year();
+                return self.with_date(year, start.month() as usize + 1, start.date(), offset);
+            }
+            (month, date, _, _) => (month.unwrap_or(1), date.unwrap_or(1)),
+        };
+
+        self.with_date(year, month, date, offset)
+    }
+
+    // combines a date with the time fields
+    fn with_date(
+        &self,
+        year: usize,
+        month: usize,
+        date: usize,
+        offset: FixedOffset,
+    ) -> Result<DateTime, ParseError> {
+        let hour = match (self.hour12, self.pm) {
+            (Some(hour), _) if !(1..=12).contains(&hour) => return Err(ParseError::OutOfRange),
+            (Some(hour), pm) => hour % 12 + if pm == Some(true) { 12 } else { 0 },
+            (None, _) => self.hour.unwrap_or(0),
+        };
+        let (minute, second) = (self.minute.unwrap_or(0), self.second.unwrap_or(0));
+
+        let secs = offset.seconds() as i64;
+        let date = from_fields(year, month, date, hour, minute, second, self.nanos, secs)?
+            .with_offset(offset);
+        match self.weekday {
+            Some(weekday) if weekday != date.day() => Err(ParseError::OutOfRange),
+            _ => Ok(date),
+        }
+    }
+}
+
+impl FormatSpec {
+    // reads a DateTime from input laid out by this spec
+    fn read(&self, input: &str) -> Result<DateTime, ParseError> {
+        let mut cursor = Cursor::new(input);
+        let mut fields = ReadFields::default();
+
+        for item in &self.items {
+            match item {
+                Item::Literal(s) => {
+                    for c in s.chars() {
+                        let rest = &input[cursor.pos..];
+                        if c.is_whitespace() {
+                            // whitespace matches any amount, including none
+                            cursor.pos += rest.len() - rest.trim_start().len();
+                        } else if rest.starts_with(c) {
+                            cursor.pos += c.len_utf8();
+                        } else if rest.is_empty() {
+                            return Err(ParseError::TooShort);
+                        } else {
+                            return Err(ParseError::Invalid(cursor.pos));
+                        }
+                    }
+                }
+                Item::Field(field, modifiers) => {
+                    read_field(*field, *modifiers, input, &mut cursor, &mut fields)?
+                }
+            }
+        }
+        cursor.finish()?;
+
+        fields.resolve()
+    }
+}
+
+impl DateTime {
+    /// returns a String with the DateTime formatted according to a
+    /// strftime-style format string (see the [`format`](crate::format)
+    /// module for the supported specifiers)
+    ///
+    /// unknown specifiers are copied to the output unchanged; use
+    /// [`FormatSpec::parse`] to reject them instead
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.format("%Y-%m-%d %H:%M:%S"), "1996-09-09 15:23:44");
+    /// assert_eq!(date.format("%A, %B %e (day %j)"), "Monday, September  9 (day 253)");
+    /// ```
+    pub fn format(&self, fmt: &str) -> String {
+        self.format_in_zone(fmt, &self.zone_name())
+    }
+
+    // like format, but with `zone` as the time zone name
+    pub(crate) fn format_in_zone(&self, fmt: &str, zone: &str) -> String {
+        let spec = FormatSpec::parse_items(fmt, false).expect("lenient parsing cannot fail");
+        let mut s = String::new();
+        spec.write(self, zone, Locale::En, &mut s)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// returns a String with the DateTime formatted according to a
+    /// previously parsed FormatSpec
+    pub fn format_with(&self, spec: &FormatSpec) -> String {
+        let mut s = String::new();
+        spec.write(self, &self.zone_name(), Locale::En, &mut s)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// parses a DateTime laid out by a strftime-style format string, the
+    /// inverse of [`DateTime::format`] (see the [`format`](crate::format)
+    /// module for the supported specifiers and how they are read)
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_from_str("10/Oct/2000:13:55:36 -0700", "%d/%b/%Y:%H:%M:%S %z")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(date.to_rfc3339(), "2000-10-10T13:55:36-07:00");
+    /// ```
+    pub fn parse_from_str(input: &str, fmt: &str) -> Result<DateTime, ParseError> {
+        let spec = FormatSpec::parse_items(fmt, false).expect("lenient parsing cannot fail");
+        spec.read(input)
+    }
+
+    /// parses a DateTime laid out by a previously parsed FormatSpec
+    /// ```
+    /// # use datetime::{DateTime, FormatSpec};
+    /// let spec = FormatSpec::parse("%Y-%m-%d %H:%M").unwrap();
+    /// let date = DateTime::parse_with("1996-09-09 15:23", &spec).unwrap();
+    ///
+    /// assert_eq!(date.format_with(&spec), "1996-09-09 15:23");
+    /// ```
+    pub fn parse_with(input: &str, spec: &FormatSpec) -> Result<DateTime, ParseError> {
+        spec.read(input)
+    }
+
+    /// reads a timestamp laid out by one format and writes it in another,
+    /// failing rather than losing anything read from the input
+    ///
+    /// the output is checked by reading it back with `out_spec`, which has
+    /// to give the same instant in the same offset. fields the input
+    /// format doesn't have are taken from the Unix epoch as
+    /// [`DateTime::parse_from_str`] does, so adding them is allowed
+    /// ```
+    /// # use datetime::{DateTime, FormatSpec, ReformatError};
+    /// let rfc3339 = FormatSpec::parse("%Y-%m-%dT%H:%M:%S%.f%z").unwrap();
+    /// let log = FormatSpec::parse("%d/%b/%Y:%H:%M:%S %z").unwrap();
+    ///
+    /// let output = DateTime::reformat("2000-10-10T13:55:36-07:00", &rfc3339, &log);
+    /// assert_eq!(output.unwrap(), "10/Oct/2000:13:55:36 -0700");
+    ///
+    /// // the log format has no fractional seconds
+    /// let output = DateTime::reformat("2000-10-10T13:55:36.5-07:00", &rfc3339, &log);
+    /// assert_eq!(output, Err(ReformatError::Lossy));
+    /// ```
+    pub fn reformat(
+        input: &str,
+        in_spec: &FormatSpec,
+        out_spec: &FormatSpec,
+    ) -> Result<String, ReformatError> {
+        let date = in_spec.read(input)?;
+        let output = date.format_with(out_spec);
+
+        match out_spec.read(&output) {
+            Ok(back) if back == date && back.offset == date.offset => Ok(output),
+            _ => Err(ReformatError::Lossy),
+        }
+    }
+
+    /// like [`DateTime::format`], but with the names of days and months
+    /// (`%a`, `%A`, `%b`, `%h` and `%B`) in the given locale
+    /// ```
+    /// # use datetime::{DateTime, Locale};
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.format_localized("%A %d. %B %Y", Locale::De), "Montag 09. September 1996");
+    /// assert_eq!(date.format_localized("%^a", Locale::Es), "LUN");
+    /// ```
+    pub fn format_localized(&self, fmt: &str, locale: Locale) -> String {
+        let spec = FormatSpec::parse_items(fmt, false).expect("lenient parsing cannot fail");
+        let mut s = String::new();
+        spec.write(self, &self.zone_name(), locale, &mut s)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// returns the DateTime as an RFC 3339 timestamp in its own offset,
+    /// with as many fractional digits as needed; this is the inverse of
+    /// [`DateTime::parse_rfc3339`]
+    /// ```
+    /// # use datetime::{DateTime, FixedOffset};
+    /// let date = DateTime::from_millis(842282624500);
+    /// assert_eq!(date.to_rfc3339(), "1996-09-09T15:23:44.500Z");
+    ///
+    /// let date = date.with_offset(FixedOffset::east(7200).unwrap());
+    /// assert_eq!(date.to_rfc3339(), "1996-09-09T17:23:44.500+02:00");
+    /// ```
+    pub fn to_rfc3339(&self) -> String {
+        let mut s = String::new();
+        self.write_rfc3339(&mut s)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// writes the timestamp returned by [`DateTime::to_rfc3339`] into `w`,
+    /// without allocating
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::fmt::Write;
+    /// let mut line = String::from("at ");
+    /// DateTime::from_secs(842282624).write_rfc3339(&mut line).unwrap();
+    ///
+    /// assert_eq!(line, "at 1996-09-09T15:23:44Z");
+    /// ```
+    pub fn write_rfc3339<W: Write>(&self, w: &mut W) -> fmt::Result {
+        self.write_rfc3339_to(&mut FmtSink(w))
+    }
+
+    /// writes the timestamp returned by [`DateTime::to_rfc3339`] into a
+    /// [`TimestampSink`]
+    pub fn write_rfc3339_to<S: TimestampSink + ?Sized>(&self, w: &mut S) -> fmt::Result {
+        let digits = match self.nanos {
+            0 => 0,
+            nanos if nanos.is_multiple_of(1_000_000) => 3,
+            nanos if nanos.is_multiple_of(1000) => 6,
+            _ => 9,
+        };
+        self.write_rfc3339_with_fraction_to(w, digits)
+    }
+
+    /// returns the DateTime as an RFC 3339 timestamp like
+    /// [`DateTime::to_rfc3339`], but with exactly the given number of
+    /// fractional digits (at most 9), and none at all for 0
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_millis(842282624500);
+    ///
+    /// assert_eq!(date.to_rfc3339_with_fraction(0), "1996-09-09T15:23:44Z");
+    /// assert_eq!(date.to_rfc3339_with_fraction(6), "1996-09-09T15:23:44.500000Z");
+    /// ```
+    pub fn to_rfc3339_with_fraction(&self, digits: usize) -> String {
+        let mut s = String::new();
+        self.write_rfc3339_with_fraction(&mut s, digits)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// writes the timestamp returned by
+    /// [`DateTime::to_rfc3339_with_fraction`] into `w`, without allocating
+    pub fn write_rfc3339_with_fraction<W: Write>(&self, w: &mut W, digits: usize) -> fmt::Result {
+        self.write_rfc3339_with_fraction_to(&mut FmtSink(w), digits)
+    }
+
+    /// writes the timestamp returned by
+    /// [`DateTime::to_rfc3339_with_fraction`] into a [`TimestampSink`]
+    pub fn write_rfc3339_with_fraction_to<S: TimestampSink + ?Sized>(
+        &self,
+        w: &mut S,
+        digits: usize,
+    ) -> fmt::Result {
+        let fields = self.fields();
+        w.write_u32_padded(fields.year as u32, 4)?;
+        w.write_str("-")?;
+        w.write_u32_padded(fields.month as u32 + 1, 2)?;
+        w.write_str("-")?;
+        w.write_u32_padded(fields.date as u32, 2)?;
+        w.write_str("T")?;
+        write_clock(w, &fields)?;
+
+        let digits = digits.min(9);
+        if digits > 0 {
+            w.write_str(".")?;
+            write_fraction(w, self.nanos, digits)?;
+        }
+
+        if self.offset.is_utc() {
+            w.write_str("Z")
+        } else {
+            self.offset.write(w, true)
+        }
+    }
+
+    /// formats the DateTime as an RFC 2822 timestamp, as used in the
+    /// `Date:` header of emails, in the DateTime's own offset; this is the
+    /// inverse of [`DateTime::parse_rfc2822`]
+    ///
+    /// RFC 2822 offsets are whole minutes, so any seconds in the offset are
+    /// dropped from it (but not from the time)
+    /// ```
+    /// # use datetime::{DateTime, FixedOffset};
+    /// let date = DateTime::from_secs(842282624);
+    /// assert_eq!(date.to_rfc2822(), "Mon, 09 Sep 1996 15:23:44 +0000");
+    ///
+    /// let date = date.with_offset(FixedOffset::west(16200).unwrap());
+    /// assert_eq!(date.to_rfc2822(), "Mon, 09 Sep 1996 10:53:44 -0430");
+    /// ```
+    pub fn to_rfc2822(&self) -> String {
+        let mut s = String::new();
+        self.write_rfc2822(&mut s)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// writes the timestamp returned by [`DateTime::to_rfc2822`] into `w`,
+    /// without allocating
+    pub fn write_rfc2822<W: Write>(&self, w: &mut W) -> fmt::Result {
+        self.write_rfc2822_to(&mut FmtSink(w))
+    }
+
+    /// writes the timestamp returned by [`DateTime::to_rfc2822`] into a
+    /// [`TimestampSink`]
+    pub fn write_rfc2822_to<S: TimestampSink + ?Sized>(&self, w: &mut S) -> fmt::Result {
+        self.write_imf_date(w)?;
+
+        let minutes = self.offset.seconds().unsigned_abs() / 60;
+        w.write_str(if self.offset.seconds() < 0 {
+            " -"
+        } else {
+            " +"
+        })?;
+        w.write_u32_padded(minutes / 60, 2)?;
+        w.write_u32_padded(minutes % 60, 2)
+    }
+
+    /// formats the DateTime as an HTTP date in the preferred IMF-fixdate
+    /// form, as used in `Date` and `Last-Modified` headers; HTTP dates are
+    /// always in UTC, whatever the DateTime's offset
+    /// ```
+    /// # use datetime::{DateTime, FixedOffset};
+    /// let date = DateTime::from_secs(784111777).with_offset(FixedOffset::east(3600).unwrap());
+    /// assert_eq!(date.to_http_date(), "Sun, 06 Nov 1994 08:49:37 GMT");
+    /// ```
+    pub fn to_http_date(&self) -> String {
+        let mut s = String::new();
+        self.write_http_date(&mut s)
+            .expect("writing to a String cannot fail");
+
+        s
+    }
+
+    /// writes the date returned by [`DateTime::to_http_date`] into `w`,
+    /// without allocating
+    pub fn write_http_date<W: Write>(&self, w: &mut W) -> fmt::Result {
+        self.write_http_date_to(&mut FmtSink(w))
+    }
+
+    /// writes the date returned by [`DateTime::to_http_date`] into a
+    /// [`TimestampSink`]
+    pub fn write_http_date_to<S: TimestampSink + ?Sized>(&self, w: &mut S) -> fmt::Result {
+        self.with_offset(FixedOffset::utc()).write_imf_date(w)?;
+        w.write_str(" GMT")
+    }
+
+    /// writes the DateTime formatted according to a FormatSpec, as
+    /// [`DateTime::format_with`] returns it, into a [`TimestampSink`]
+    pub fn write_formatted_to<S: TimestampSink + ?Sized>(
+        &self,
+        spec: &FormatSpec,
+        w: &mut S,
+    ) -> fmt::Result {
+        spec.write(self, &self.zone_name(), Locale::En, w)
+    }
+
+    // writes the date and time shared by RFC 2822 and HTTP dates, like
+    // `Mon, 09 Sep 1996 15:23:44`
+    fn write_imf_date<S: TimestampSink + ?Sized>(&self, w: &mut S) -> fmt::Result {
+        let fields = self.fields();
+        w.write_str(Locale::En.day_abbreviation(fields.day))?;
+        w.write_str(", ")?;
+        w.write_u32_padded(fields.date as u32, 2)?;
+        w.write_str(" ")?;
+        w.write_str(Locale::En.month_abbreviation(fields.month))?;
+        w.write_str(" ")?;
+        w.write_u32_padded(fields.year as u32, 4)?;
+        w.write_str(" ")?;
+        write_clock(w, &fields)
+    }
+}
+
+// writes the time of day as `hh:mm:ss`
+fn write_clock<S: TimestampSink + ?Sized>(w: &mut S, fields: &CalendarFields) -> fmt::Result {
+    w.write_u32_padded(fields.hour as u32, 2)?;
+    w.write_str(":")?;
+    w.write_u32_padded(fields.minute as u32, 2)?;
+    w.write_str(":")?;
+    w.write_u32_padded(fields.second as u32, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatError, FormatSpec, ReformatError};
+    use crate::{DateTime, FixedOffset, Locale, ParseError};
+    use alloc::string::String;
+    use core::cell::Cell;
+    use core::fmt::Write as _;
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    #[test]
+    fn test_format_fields() {
+        let date = DateTime::from_secs(842282624);
+
+        assert_eq!(date.format("%C %y %m %d %e %j"), "19 96 09 09  9 253");
+        assert_eq!(
+            date.format("%a %A %b %h %B"),
+            "Mon Monday Sep Sep September"
+        );
+        assert_eq!(date.format("%u %w %U %W %V %G"), "1 1 36 37 37 1996");
+        assert_eq!(date.format("%I %l %p %k"), "03  3 PM 15");
+        assert_eq!(date.format("%s %Z %z"), "842282624 UTC +0000");
+        assert_eq!(
+            date.format("%F|%T|%D|%R|%r"),
+            "1996-09-09|15:23:44|09/09/96|15:23|03:23:44 PM"
+        );
+        assert_eq!(date.format("%c"), "Mon Sep  9 15:23:44 1996");
+        assert_eq!(date.format("100%% %n%t"), "100% \n\t");
+
+        let date = date.to_offset(-9, 30).unwrap();
+        assert_eq!(date.format("%H:%M %Z %z"), "05:53 UTC-09:30 -0930");
+    }
+
+    #[test]
+    fn test_iso_week_year_boundaries() {
+        // 2008-12-29 is in week 1 of 2009
+        let date = DateTime::from_secs(1230508800);
+        assert_eq!(date.format("%G-W%V-%u"), "2009-W01-1");
+
+        // 2010-01-03 is in week 53 of 2009
+        let date = DateTime::from_secs(1262476800);
+        assert_eq!(date.format("%G-W%V-%u"), "2009-W53-7");
+
+        // 2000-01-01 (midnight) is in week 52 of 1999
+        let date = DateTime::from_secs(946684800);
+        assert_eq!(date.format("%G-W%V-%u %H:%M"), "1999-W52-6 00:00");
+    }
+
+    #[test]
+    fn test_builder() {
+        let spec = FormatSpec::builder()
+            .weekday_abbreviation()
+            .literal(", ")
+            .date2()
+            .literal(" ")
+            .month_abbreviation()
+            .literal(" ")
+            .year4()
+            .literal(" ")
+            .hour12_2()
+            .literal(":")
+            .minute2()
+            .literal(" ")
+            .am_pm()
+            .build();
+
+        assert_eq!(spec, FormatSpec::parse("%a, %d %b %Y %I:%M %p").unwrap());
+
+        let date = DateTime::from_secs(842282624);
+        assert_eq!(date.format_with(&spec), "Mon, 09 Sep 1996 03:23 PM");
+    }
+
+    #[test]
+    fn test_unknown_specifiers() {
+        let date = DateTime::from_secs(0);
+
+        assert_eq!(date.format("%Q %Y %"), "%Q 1970 %");
+        assert!(FormatSpec::parse("%Y %").is_err());
+    }
+
+    #[test]
+    fn test_flags_and_widths() {
+        let date = DateTime::from_secs(842282624);
+
+        assert_eq!(date.format("%-d %-j %-I %-e"), "9 253 3 9");
+        assert_eq!(date.format("%_d|%_H|%_5j|%0e"), " 9|15|  253|09");
+        assert_eq!(date.format("%6Y|%-Y|%+6Y|%+2Y"), "001996|1996|001996|+1996");
+        assert_eq!(
+            date.format("%^B|%#B|%10A|%-10A"),
+            "SEPTEMBER|SEPTEMBER|    Monday|    Monday"
+        );
+        assert_eq!(date.format("%^p %#p %#Z %Ey %Od"), "PM pm utc 96 09");
+        assert_eq!(date.format("%_10s|%-s"), " 842282624|842282624");
+
+        let date = DateTime::from_signed_secs(-62135596800);
+        assert_eq!(
+            date.format("%Y %-Y %_5Y %010s"),
+            "0001 1     1 -62135596800"
+        );
+
+        assert_eq!(date.format("%-Q %_"), "%-Q %_");
+        assert_eq!(date.format("%1024Y").len(), 1024);
+        assert_eq!(
+            date.format("%1025Y|%1000000000000d"),
+            "%1025Y|%1000000000000d"
+        );
+        assert_eq!(
+            FormatSpec::parse("x%99999999999999999999999Y").unwrap_err(),
+            FormatError::WidthTooLarge(1)
+        );
+        assert_eq!(
+            FormatSpec::parse("%_3Q").unwrap_err(),
+            FormatError::UnknownSpecifier('Q', 0)
+        );
+    }
+
+    #[test]
+    fn test_fractional_seconds() {
+        let date = DateTime::from_nanos(842282624123456789);
+        assert_eq!(
+            date.format("%f|%3f|%6f|%1f|%12f"),
+            "123456789|123|123456|1|123456789"
+        );
+        assert_eq!(
+            date.format("%.f|%.3f|%.6f|%.9f"),
+            ".123456789|.123|.123456|.123456789"
+        );
+
+        let date = DateTime::from_millis(842282624050);
+        assert_eq!(date.format("%f %.f %.6f"), "050000000 .050 .050000");
+
+        let date = DateTime::from_secs(842282624);
+        assert_eq!(date.format("%S%.f|%S%.3f|%f"), "44|44.000|000000000");
+
+        let date = DateTime::from_millis(-1);
+        assert_eq!(date.format("%T%.3f"), "23:59:59.999");
+
+        assert_eq!(date.format("%.Y %.%"), "%.Y %");
+        assert_eq!(
+            FormatSpec::parse("%.3Y").unwrap_err(),
+            FormatError::UnknownSpecifier('Y', 0)
+        );
+    }
+
+    #[test]
+    fn test_literal_text() {
+        let date = DateTime::from_secs(842282624);
+
+        assert_eq!(date.format("Week %V of %G"), "Week 37 of 1996");
+        assert_eq!(date.format("%'%H:%M'=%H:%M"), "%H:%M=15:23");
+        assert_eq!(date.format("%'it''s'%'' %%"), "it's %");
+        assert_eq!(date.format("%'%Y"), "%Y");
+
+        assert_eq!(
+            FormatSpec::parse("%Y %'%m").unwrap_err(),
+            FormatError::UnterminatedQuote(3)
+        );
+    }
+
+    #[test]
+    fn test_rfc3339_fraction_digits() {
+        let date = DateTime::from_nanos(-1).with_offset(FixedOffset::west(3600).unwrap());
+
+        assert_eq!(date.to_rfc3339(), "1969-12-31T22:59:59.999999999-01:00");
+        assert_eq!(
+            date.to_rfc3339_with_fraction(3),
+            "1969-12-31T22:59:59.999-01:00"
+        );
+        assert_eq!(
+            date.to_rfc3339_with_fraction(12),
+            date.to_rfc3339_with_fraction(9)
+        );
+
+        let date = DateTime::from_secs(0);
+        assert_eq!(date.to_rfc3339(), "1970-01-01T00:00:00Z");
+        assert_eq!(date.to_rfc3339_with_fraction(1), "1970-01-01T00:00:00.0Z");
+    }
+
+    #[test]
+    fn test_format_localized() {
+        // 2000-03-01 was a Wednesday
+        let date = DateTime::from_secs(951868800);
+
+        assert_eq!(
+            date.format_localized("%a %A %b %B", Locale::De),
+            "Mi Mittwoch Mär März"
+        );
+        assert_eq!(
+            date.format_localized("%^B|%10B|", Locale::De),
+            "MÄRZ|      März|"
+        );
+        assert_eq!(
+            date.format_localized("%A %e %B", Locale::Fr),
+            "mercredi  1 mars"
+        );
+        assert_eq!(
+            date.format_localized("%a %b", Locale::En),
+            date.format("%a %b")
+        );
+    }
+    #[test]
+    fn test_parse_from_str() {
+        let parse = |input: &str, fmt: &str| DateTime::parse_from_str(input, fmt);
+        let rfc3339 = |input: &str, fmt: &str| parse(input, fmt).unwrap().to_rfc3339();
+
+        assert_eq!(
+            rfc3339("10/Oct/2000:13:55:36 +0000", "%d/%b/%Y:%H:%M:%S %z"),
+            "2000-10-10T13:55:36Z"
+        );
+        assert_eq!(
+            rfc3339("2000-10-10T13:55:36.5+05:30", "%FT%T%.f%z"),
+            "2000-10-10T13:55:36.500+05:30"
+        );
+        assert_eq!(
+            rfc3339("842282624.026", "%s%.f"),
+            "1996-09-09T15:23:44.026Z"
+        );
+        assert_eq!(rfc3339("12:05 am", "%I:%M %p"), "1970-01-01T00:05:00Z");
+        assert_eq!(rfc3339("96 253", "%y %j"), "1996-09-09T00:00:00Z");
+        assert_eq!(rfc3339("2009-W53-7", "%G-W%V-%u"), "2010-01-03T00:00:00Z");
+        assert_eq!(rfc3339(" 9 SEP 1996", "%e %b %Y"), "1996-09-09T00:00:00Z");
+    }
+
+    #[test]
+    fn test_reformat() {
+        let spec = |fmt| FormatSpec::parse(fmt).unwrap();
+        let reformat = |input, from, to| DateTime::reformat(input, &spec(from), &spec(to));
+
+        assert_eq!(
+            reformat("1996-09-09 15:23:44.125", "%F %T%.f", "%s%.3f"),
+            Ok(String::from("842282624.125"))
+        );
+        assert_eq!(
+            reformat("842282624.125", "%s%.f", "%F %T%.f"),
+            Ok(String::from("1996-09-09 15:23:44.125"))
+        );
+        // the time is taken from the epoch, and written out
+        assert_eq!(
+            reformat("1996-09-09", "%F", "%F %T"),
+            Ok(String::from("1996-09-09 00:00:00"))
+        );
+
+        assert_eq!(
+            reformat("1996-09-09 15:23:44.125", "%F %T%.f", "%F %T"),
+            Err(ReformatError::Lossy)
+        );
+        // the same instant, but without its offset
+        assert_eq!(
+            reformat("1996-09-09 17:23:44 +0200", "%F %T %z", "%s"),
+            Err(ReformatError::Lossy)
+        );
+        assert_eq!(
+            reformat("1996-09-09 15:23:44 +0000", "%F %T %z", "%s"),
+            Ok(String::from("842282624"))
+        );
+        assert_eq!(
+            reformat("1996-09-09", "%F %T", "%F"),
+            Err(ReformatError::Parse(ParseError::TooShort))
+        );
+    }
+
+    #[test]
+    fn test_parse_from_str_errors() {
+        let parse = |input: &str, fmt: &str| DateTime::parse_from_str(input, fmt);
+
+        assert_eq!(parse("1996-09", "%Y-%m-%d"), Err(ParseError::TooShort));
+        assert_eq!(parse("1996-09-09x", "%Y-%m-%d"), Err(ParseError::TooLong));
+        assert_eq!(parse("1996-Sep", "%Y-%m"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("Sept 9", "%b %e"), Err(ParseError::Invalid(3)));
+        assert_eq!(parse("13:00 PM", "%I:%M %p"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("25:00", "%H:%M"), Err(ParseError::OutOfRange));
+        assert_eq!(
+            parse("Tue 1996-09-09", "%a %F"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(parse("8 1996", "%u %Y"), Err(ParseError::Invalid(0)));
+        let est = parse("1996-09-09 EST", "%F %Z").unwrap();
+        assert_eq!(est.offset().seconds(), -5 * 3600);
+        assert_eq!(
+            parse("1996-09-09 XYZ", "%F %Z"),
+            Err(ParseError::Invalid(11))
+        );
+
+        // wide fields can't overflow, or give years past the last DateTime
+        assert_eq!(
+            parse("999999999999999999999999", "%30Y"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            parse("99999999999999999", "%20C"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(parse("4294967296", "%10Y"), Err(ParseError::OutOfRange));
+        assert_eq!(
+            parse("4294967295-12-31", "%10Y-%m-%d"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            parse("4294967295-12-30", "%10Y-%m-%d").unwrap(),
+            DateTime::MAX.start_of_day()
+        );
+        assert_eq!(
+            parse("4294967295-W52-7", "%10G-W%V-%u"),
+            Err(ParseError::OutOfRange)
+        );
+        assert!(parse("4294967294-W52-7", "%10G-W%V-%u").is_ok());
+    }
+
+    // counts the allocations made on each thread, so that tests running
+    // alongside don't disturb the count
+    struct CountingAllocator;
+
+    std::thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    // a fixed buffer on the stack, which fails once full
+    struct Buffer {
+        bytes: [u8; 48],
+        len: usize,
+    }
+
+    impl core::fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            let space = self.bytes.get_mut(self.len..end).ok_or(core::fmt::Error)?;
+            space.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    // returns what `write` wrote into a Buffer, and how many allocations
+    // it made doing so
+    fn written(write: &dyn Fn(&mut Buffer) -> core::fmt::Result) -> (String, usize) {
+        let mut buffer = Buffer {
+            bytes: [0; 48],
+            len: 0,
+        };
+        let before = ALLOCATIONS.with(Cell::get);
+        write(&mut buffer).unwrap();
+        let allocations = ALLOCATIONS.with(Cell::get) - before;
+
+        let text = core::str::from_utf8(&buffer.bytes[..buffer.len]).unwrap();
+        (String::from(text), allocations)
+    }
+
+    #[test]
+    fn test_write_without_allocating() {
+        let date =
+            DateTime::from_nanos(842282624123456000).with_offset(FixedOffset::west(16200).unwrap());
+
+        // the counter does see allocations
+        assert_eq!(
+            written(&|w| w.write_str(&String::from("x"))),
+            ("x".into(), 1)
+        );
+
+        assert_eq!(
+            written(&|w| date.write_rfc3339(w)),
+            ("1996-09-09T10:53:44.123456-04:30".into(), 0)
+        );
+        assert_eq!(written(&|w| date.write_rfc2822(w)), (date.to_rfc2822(), 0));
+        assert_eq!(
+            written(&|w| date.write_http_date(w)),
+            ("Mon, 09 Sep 1996 15:23:44 GMT".into(), 0)
+        );
+        assert_eq!(
+            written(&|w| date.write_time_stamp(w)),
+            ("Mon Sep 9, 1996  10:53:44 (UTC-04:30)".into(), 0)
+        );
+        assert_eq!(
+            written(&|w| write!(w, "{:.3}", date)),
+            ("Mon Sep 9, 1996  10:53:44.123 (UTC-04:30)".into(), 0)
+        );
+
+        // too long for the buffer
+        let mut buffer = Buffer {
+            bytes: [0; 48],
+            len: 0,
+        };
+        assert!(date.write_rfc3339_with_fraction(&mut buffer, 9).is_ok());
+        assert!(date.write_rfc3339(&mut buffer).is_err());
+    }
+}
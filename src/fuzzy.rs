@@ -0,0 +1,391 @@
+//! natural-language times relative to a reference, as typed at a command
+//! line
+//!
+//! [`DateTime::parse_relative`] understands short English phrases:
+//! - `now`
+//! - an amount of time from the reference: `in 45 minutes`, `2 days ago`,
+//!   `in an hour`
+//! - a day, a time of day, or both, in either order: `tomorrow`,
+//!   `next monday 09:00`, `yesterday at noon`, `5pm tomorrow`, `at 17:30`
+//!
+//! days are `today`, `yesterday`, `tomorrow`, or a weekday, which on its
+//! own or after `next` is the first one after today, after `last` the last
+//! one before today, and after `this` the first one from today on. times
+//! are `hh:mm[:ss]` on a 24-hour clock, an hour with `am` or `pm` such as
+//! `5pm` or `5:30 pm`, `noon` or `midnight`. a day without a time means its
+//! start, and a time without a day means that time today, even if it has
+//! passed. days and times are in the reference's offset
+//! ```
+//! # use datetime::{DateTime, FixedOffset};
+//! // a Monday afternoon
+//! let base = DateTime::from_secs(842282624).with_offset(FixedOffset::east(7200).unwrap());
+//! let parse = |input| DateTime::parse_relative(input, &base).unwrap().to_rfc3339();
+//!
+//! assert_eq!(parse("tomorrow at 5pm"), "1996-09-10T17:00:00+02:00");
+//! assert_eq!(parse("in 2 hours"), "1996-09-09T19:23:44+02:00");
+//! assert_eq!(parse("next monday 09:00"), "1996-09-16T09:00:00+02:00");
+//! assert_eq!(parse("yesterday"), "1996-09-08T00:00:00+02:00");
+//! ```
+use crate::parse::ParseError;
+use crate::{DateTime, Day, MAX_SECS, MIN_SECS};
+use alloc::vec::Vec;
+
+// the units an amount of time can be given in, with their length in
+// seconds, or None for the calendar units
+const UNITS: [(&str, &str, Option<i64>); 9] = [
+    ("second", "sec", Some(1)),
+    ("minute", "min", Some(60)),
+    ("hour", "hr", Some(3600)),
+    ("day", "day", Some(86_400)),
+    ("week", "wk", Some(7 * 86_400)),
+    ("fortnight", "fortnight", Some(14 * 86_400)),
+    ("month", "mo", None),
+    ("year", "yr", None),
+    ("decade", "decade", None),
+];
+
+// the words of the input, with their byte offsets
+fn words(input: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (i, c) in input.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push((s, &input[s..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, &input[s..]));
+    }
+
+    words
+}
+
+// whether `word` is `name`, or `name` with an `s`, in any case
+fn is_unit(word: &str, name: &str) -> bool {
+    let word = match word.len().checked_sub(1) {
+        Some(last) if word.len() > name.len() && word[last..].eq_ignore_ascii_case("s") => {
+            &word[..last]
+        }
+        _ => word,
+    };
+    word.eq_ignore_ascii_case(name)
+}
+
+// adds an amount of a unit, given as two words, to `base`
+fn add_amount(
+    base: &DateTime,
+    (pos, amount): (usize, &str),
+    (unit_pos, unit): (usize, &str),
+    sign: i64,
+) -> Result<DateTime, ParseError> {
+    let amount: i64 = if amount.eq_ignore_ascii_case("a") || amount.eq_ignore_ascii_case("an") {
+        1
+    } else if amount.bytes().all(|b| b.is_ascii_digit()) {
+        amount.parse().map_err(|_| ParseError::OutOfRange)?
+    } else {
+        return Err(ParseError::Invalid(pos));
+    };
+
+    let &(name, _, length) = UNITS
+        .iter()
+        .find(|(name, short, _)| is_unit(unit, name) || is_unit(unit, short))
+        .ok_or(ParseError::Invalid(unit_pos))?;
+    let amount = amount * sign;
+
+    let date = match (name, length) {
+        (_, Some(length)) => {
+            base.checked_add_secs(amount.checked_mul(length).ok_or(ParseError::OutOfRange)?)
+        }
+        ("month", _) => base.checked_add_months(amount),
+        ("year", _) => amount
+            .checked_mul(12)
+            .and_then(|months| base.checked_add_months(months)),
+        _ => amount
+            .checked_mul(120)
+            .and_then(|months| base.checked_add_months(months)),
+    };
+    date.ok_or(ParseError::OutOfRange)
+}
+
+// reads a time of day from the start of `words`, returning the
+// seconds since midnight and the number of words read, or None if they
+// don't start with a time. a bare hour is only read if `after_at`
+fn time_of_day(
+    words: &[(usize, &str)],
+    after_at: bool,
+) -> Result<Option<(i64, usize)>, ParseError> {
+    let (pos, word) = match words.first() {
+        Some(&word) => word,
+        None => return Err(ParseError::TooShort),
+    };
+    if word.eq_ignore_ascii_case("noon") {
+        return Ok(Some((12 * 3600, 1)));
+    }
+    if word.eq_ignore_ascii_case("midnight") {
+        return Ok(Some((0, 1)));
+    }
+
+    // the digits and colons, then an optional am or pm, in the same word
+    // or the next
+    let split = word
+        .find(|c: char| !c.is_ascii_digit() && c != ':')
+        .unwrap_or(word.len());
+    let (clock, suffix) = word.split_at(split);
+    if clock.is_empty() {
+        return Ok(None);
+    }
+    let (meridiem, read) = match (suffix, words.get(1)) {
+        ("", Some(&(_, next)))
+            if next.eq_ignore_ascii_case("am") || next.eq_ignore_ascii_case("pm") =>
+        {
+            (Some(next), 2)
+        }
+        ("", _) => (None, 1),
+        (suffix, _) if suffix.eq_ignore_ascii_case("am") || suffix.eq_ignore_ascii_case("pm") => {
+            (Some(suffix), 1)
+        }
+        _ => return Err(ParseError::Invalid(pos + split)),
+    };
+
+    // an hour of one or two digits, then fields of two
+    let mut fields = Vec::new();
+    for (n, field) in clock.split(':').enumerate() {
+        if field.is_empty() || field.len() > 2 || (n > 0 && field.len() != 2) {
+            return Err(ParseError::Invalid(pos));
+        }
+        fields.push(field.parse::<i64>().map_err(|_| ParseError::Invalid(pos))?);
+    }
+    if fields.len() == 1 && meridiem.is_none() && !after_at {
+        return Err(ParseError::Invalid(pos));
+    }
+    let (hour, minute, second) = match fields[..] {
+        [hour] => (hour, 0, 0),
+        [hour, minute] => (hour, minute, 0),
+        [hour, minute, second] => (hour, minute, second),
+        _ => return Err(ParseError::Invalid(pos)),
+    };
+
+    let hour = match meridiem {
+        None if hour < 24 => hour,
+        Some(meridiem) if (1..=12).contains(&hour) => {
+            hour % 12
+                + if meridiem.eq_ignore_ascii_case("pm") {
+                    12
+                } else {
+                    0
+                }
+        }
+        _ => return Err(ParseError::OutOfRange),
+    };
+    if minute > 59 || second > 59 {
+        return Err(ParseError::OutOfRange);
+    }
+
+    Ok(Some((hour * 3600 + minute * 60 + second, read)))
+}
+
+// the number of days from a day of the week to the given weekday, going
+// the way `direction` says: "next", "last" or "this"
+fn days_to(today: Day, target: Day, direction: &str) -> i64 {
+    let ahead = (target as i64 - today as i64).rem_euclid(7);
+    match direction {
+        "this" => ahead,
+        "last" => match (today as i64 - target as i64).rem_euclid(7) {
+            0 => -7,
+            behind => -behind,
+        },
+        _ if ahead == 0 => 7,
+        _ => ahead,
+    }
+}
+
+impl DateTime {
+    /// reads a short English description of a time relative to `base`,
+    /// such as `tomorrow at 5pm` or `in 45 minutes`; see the
+    /// [module docs](crate::fuzzy) for what is understood
+    ///
+    /// words are matched in any case, and the result is in `base`'s offset.
+    /// fails with [`ParseError::Invalid`] at the byte offset of the first
+    /// word not understood, or [`ParseError::OutOfRange`] if a field is out
+    /// of range or the result would be
+    /// ```
+    /// # use datetime::{DateTime, ParseError};
+    /// let base = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+    ///
+    /// let date = DateTime::parse_relative("3 days ago", &base).unwrap();
+    /// assert_eq!(date.to_rfc3339(), "1996-09-06T15:23:44Z");
+    /// assert_eq!(DateTime::parse_relative("next blursday", &base), Err(ParseError::Invalid(5)));
+    /// ```
+    pub fn parse_relative(input: &str, base: &DateTime) -> Result<DateTime, ParseError> {
+        let words = words(input);
+        let is =
+            |i: usize, word: &str| words.get(i).is_some_and(|w| w.1.eq_ignore_ascii_case(word));
+
+        match words.len() {
+            0 => return Err(ParseError::TooShort),
+            1 if is(0, "now") => return Ok(*base),
+            _ if is(0, "in") => {
+                return match words[1..] {
+                    [amount, unit] => add_amount(base, amount, unit, 1),
+                    [] | [_] => Err(ParseError::TooShort),
+                    _ => Err(ParseError::Invalid(words[words.len().min(4) - 1].0)),
+                };
+            }
+            3 if is(2, "ago") => return add_amount(base, words[0], words[1], -1),
+            _ => {}
+        }
+
+        let local = base
+            .secs
+            .checked_add(base.offset.seconds() as i64)
+            .ok_or(ParseError::OutOfRange)?;
+        let mut days = None;
+        let mut time = None;
+
+        let mut i = 0;
+        while let Some(&(pos, word)) = words.get(i) {
+            let lower = word.to_ascii_lowercase();
+            let offset = match lower.as_str() {
+                _ if days.is_some() && time.is_some() => return Err(ParseError::Invalid(pos)),
+                "today" => Some(0),
+                "tomorrow" => Some(1),
+                "yesterday" => Some(-1),
+                "next" | "last" | "this" => {
+                    let &(day_pos, day) = words.get(i + 1).ok_or(ParseError::TooShort)?;
+                    let target: Day = day.parse().map_err(|_| ParseError::Invalid(day_pos))?;
+                    i += 1;
+                    Some(days_to(base.day(), target, &lower))
+                }
+                _ => match word.parse::<Day>() {
+                    Ok(target) => Some(days_to(base.day(), target, "next")),
+                    Err(_) => None,
+                },
+            };
+
+            if let Some(offset) = offset {
+                if days.is_some() {
+                    return Err(ParseError::Invalid(pos));
+                }
+                days = Some(offset);
+                i += 1;
+                continue;
+            }
+
+            let after_at = lower == "at";
+            if after_at {
+                i += 1;
+            }
+            match time_of_day(&words[i..], after_at)? {
+                Some((secs, read)) if time.is_none() => {
+                    time = Some(secs);
+                    i += read;
+                }
+                _ => return Err(ParseError::Invalid(words[i].0)),
+            }
+        }
+
+        let secs = (local.div_euclid(86_400) + days.unwrap_or(0))
+            .checked_mul(86_400)
+            .and_then(|secs| secs.checked_add(time.unwrap_or(0) - base.offset.seconds() as i64))
+            .filter(|secs| (MIN_SECS..=MAX_SECS).contains(secs))
+            .ok_or(ParseError::OutOfRange)?;
+        Ok(DateTime::from_parts(secs, 0, base.offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, FixedOffset, ParseError};
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        // a Monday
+        let base = DateTime::parse_rfc3339("1996-09-09T20:23:44.5Z")
+            .unwrap()
+            .with_offset(FixedOffset::west(5 * 3600).unwrap());
+        DateTime::parse_relative(input, &base).map(|date| date.to_rfc3339())
+    }
+
+    #[test]
+    fn test_amounts() {
+        assert_eq!(parse("now").unwrap(), "1996-09-09T15:23:44.500-05:00");
+        assert_eq!(
+            parse("in 45 minutes").unwrap(),
+            "1996-09-09T16:08:44.500-05:00"
+        );
+        assert_eq!(
+            parse("in an hour").unwrap(),
+            "1996-09-09T16:23:44.500-05:00"
+        );
+        assert_eq!(parse("In 1 Sec").unwrap(), "1996-09-09T15:23:45.500-05:00");
+        assert_eq!(
+            parse("2 weeks ago").unwrap(),
+            "1996-08-26T15:23:44.500-05:00"
+        );
+        assert_eq!(
+            parse("1 month ago").unwrap(),
+            "1996-08-09T15:23:44.500-05:00"
+        );
+        assert_eq!(
+            parse("in 4 years").unwrap(),
+            "2000-09-09T15:23:44.500-05:00"
+        );
+
+        assert_eq!(parse(""), Err(ParseError::TooShort));
+        assert_eq!(parse("in 2"), Err(ParseError::TooShort));
+        assert_eq!(parse("in"), Err(ParseError::TooShort));
+        assert_eq!(parse("in two hours"), Err(ParseError::Invalid(3)));
+        assert_eq!(parse("in 2 parsecs"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("in 2 hours later"), Err(ParseError::Invalid(11)));
+        assert_eq!(
+            parse("in 99999999999999999 years"),
+            Err(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_days_and_times() {
+        assert_eq!(parse("today").unwrap(), "1996-09-09T00:00:00-05:00");
+        assert_eq!(
+            parse("tomorrow at 5pm").unwrap(),
+            "1996-09-10T17:00:00-05:00"
+        );
+        assert_eq!(
+            parse("5:30 PM yesterday").unwrap(),
+            "1996-09-08T17:30:00-05:00"
+        );
+        assert_eq!(parse("at 9").unwrap(), "1996-09-09T09:00:00-05:00");
+        assert_eq!(parse("12am").unwrap(), "1996-09-09T00:00:00-05:00");
+        assert_eq!(parse("noon").unwrap(), "1996-09-09T12:00:00-05:00");
+        assert_eq!(parse("23:59:59").unwrap(), "1996-09-09T23:59:59-05:00");
+
+        assert_eq!(parse("monday").unwrap(), "1996-09-16T00:00:00-05:00");
+        assert_eq!(
+            parse("next mon 09:00").unwrap(),
+            "1996-09-16T09:00:00-05:00"
+        );
+        assert_eq!(parse("this monday").unwrap(), "1996-09-09T00:00:00-05:00");
+        assert_eq!(parse("last monday").unwrap(), "1996-09-02T00:00:00-05:00");
+        assert_eq!(
+            parse("friday midnight").unwrap(),
+            "1996-09-13T00:00:00-05:00"
+        );
+        assert_eq!(parse("last sunday").unwrap(), "1996-09-08T00:00:00-05:00");
+
+        assert_eq!(parse("9"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("13pm"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("24:00"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("9:5"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("5xm"), Err(ParseError::Invalid(1)));
+        assert_eq!(parse("today tomorrow"), Err(ParseError::Invalid(6)));
+        assert_eq!(parse("noon 5pm"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("today at"), Err(ParseError::TooShort));
+        assert_eq!(parse("next"), Err(ParseError::TooShort));
+        assert_eq!(parse("soon"), Err(ParseError::Invalid(0)));
+    }
+}
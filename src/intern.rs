@@ -0,0 +1,132 @@
+//! interning of repeated timestamps
+//!
+//! batch imports often hold the same timestamp in many rows. a
+//! [`TimestampPool`] formats or decomposes each distinct timestamp once and
+//! hands out shared copies, so a million rows from the same second hold a
+//! million pointers to one string rather than a million strings
+//! ```
+//! # use datetime::{DateTime, FormatSpec, TimestampPool};
+//! # use std::sync::Arc;
+//! let mut pool = TimestampPool::new(FormatSpec::parse("%F %T").unwrap());
+//!
+//! let a = pool.formatted(&DateTime::from_secs(842282624));
+//! let b = pool.formatted(&DateTime::from_secs(842282624));
+//!
+//! assert_eq!(&*a, "1996-09-09 15:23:44");
+//! assert!(Arc::ptr_eq(&a, &b));
+//! ```
+use crate::{CivilTime, DateTime, FormatSpec};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// a DateTime's instant and offset, which together determine its fields
+type Key = (i64, u32, i32);
+
+fn key(date: &DateTime) -> Key {
+    (date.secs, date.nanos, date.offset.seconds())
+}
+
+/// a pool of formatted and decomposed timestamps, shared between equal
+/// DateTimes
+///
+/// DateTimes for the same instant in different offsets are interned
+/// separately, since their fields differ
+#[derive(Debug)]
+pub struct TimestampPool {
+    spec: FormatSpec,
+    formatted: HashMap<Key, Arc<str>>,
+    civil: HashMap<Key, Arc<CivilTime>>,
+}
+
+impl TimestampPool {
+    /// returns an empty pool which formats timestamps with the given spec
+    pub fn new(spec: FormatSpec) -> Self {
+        TimestampPool {
+            spec,
+            formatted: HashMap::new(),
+            civil: HashMap::new(),
+        }
+    }
+
+    /// returns the DateTime formatted with the pool's spec, formatting it
+    /// only if no equal DateTime has been formatted before
+    pub fn formatted(&mut self, date: &DateTime) -> Arc<str> {
+        let spec = &self.spec;
+        self.formatted
+            .entry(key(date))
+            .or_insert_with(|| Arc::from(date.format_with(spec)))
+            .clone()
+    }
+
+    /// returns the DateTime's wall-clock fields, decomposing it only if no
+    /// equal DateTime has been decomposed before
+    /// ```
+    /// # use datetime::{DateTime, FormatSpec, TimestampPool};
+    /// let mut pool = TimestampPool::new(FormatSpec::parse("%T").unwrap());
+    /// let civil = pool.civil(&DateTime::from_secs(842282624));
+    ///
+    /// assert_eq!(civil.hour(), 15);
+    /// ```
+    pub fn civil(&mut self, date: &DateTime) -> Arc<CivilTime> {
+        self.civil
+            .entry(key(date))
+            .or_insert_with(|| Arc::new(CivilTime::from(date)))
+            .clone()
+    }
+
+    /// returns the number of formatted strings and decomposed timestamps
+    /// held
+    pub fn len(&self) -> usize {
+        self.formatted.len() + self.civil.len()
+    }
+
+    /// returns true if the pool holds nothing
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// drops every interned timestamp; copies already handed out remain
+    /// valid
+    pub fn clear(&mut self) {
+        self.formatted.clear();
+        self.civil.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimestampPool;
+    use crate::{DateTime, FixedOffset, FormatSpec};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_formatted() {
+        let mut pool = TimestampPool::new(FormatSpec::parse("%T%.3f").unwrap());
+
+        let a = pool.formatted(&DateTime::from_millis(842282624500));
+        let b = pool.formatted(&DateTime::from_millis(842282624500));
+        let c = pool.formatted(&DateTime::from_millis(842282624501));
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(&*c, "15:23:44.501");
+
+        let shifted =
+            DateTime::from_millis(842282624500).with_offset(FixedOffset::east(3600).unwrap());
+        assert_eq!(&*pool.formatted(&shifted), "16:23:44.500");
+        assert_eq!(pool.len(), 3);
+    }
+
+    #[test]
+    fn test_civil() {
+        let mut pool = TimestampPool::new(FormatSpec::parse("%T").unwrap());
+        assert!(pool.is_empty());
+
+        let a = pool.civil(&DateTime::from_secs(0));
+        let b = pool.civil(&DateTime::from_secs(0));
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(a.year(), 1970);
+
+        pool.clear();
+        assert!(pool.is_empty());
+        assert_eq!(a.to_string(), "1970-01-01T00:00:00");
+    }
+}
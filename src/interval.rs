@@ -0,0 +1,377 @@
+//! half-open intervals of time, sets of non-overlapping intervals, and
+//! free/busy calculations built on top of them
+use crate::{DateTime, MAX_SECS, MIN_SECS};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// a half-open interval of time `[start, end)`, measured in seconds since
+/// the Unix epoch
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Interval {
+    start: i64,
+    end: i64,
+}
+
+impl Interval {
+    /// returns the interval between two DateTimes
+    ///
+    /// if `end` comes before `start`, the interval is empty
+    /// ```
+    /// # use datetime::{DateTime, Interval};
+    /// let start = DateTime::from_secs(3600);
+    /// let end = DateTime::from_secs(7200);
+    /// let interval = Interval::new(&start, &end);
+    ///
+    /// assert_eq!(interval.duration().as_secs(), 3600);
+    /// ```
+    pub fn new(start: &DateTime, end: &DateTime) -> Self {
+        Interval::from_signed_secs(start.secs, end.secs)
+    }
+
+    /// returns the interval between two times given in seconds since
+    /// the Unix epoch
+    ///
+    /// # Panics
+    ///
+    /// panics if either time is after [`DateTime::MAX`]
+    /// ```
+    /// # use datetime::Interval;
+    /// let interval = Interval::from_secs(3600, 7200);
+    ///
+    /// assert_eq!(interval.duration().as_secs(), 3600);
+    /// ```
+    pub fn from_secs(start: usize, end: usize) -> Self {
+        // a usize always fits in a u64
+        assert!(
+            start as u64 <= MAX_SECS as u64 && end as u64 <= MAX_SECS as u64,
+            "Interval too far in the future"
+        );
+        Interval::from_signed_secs(start as i64, end as i64)
+    }
+
+    /// returns the interval between two times given in seconds before (if
+    /// negative) or after the Unix epoch
+    ///
+    /// # Panics
+    ///
+    /// panics if either time is outside the range of a DateTime, so that
+    /// [`Interval::start`] and [`Interval::end`] always have one to return
+    pub fn from_signed_secs(start: i64, end: i64) -> Self {
+        let range = MIN_SECS..=MAX_SECS;
+        assert!(
+            range.contains(&start) && range.contains(&end),
+            "Interval outside the range of a DateTime"
+        );
+
+        Interval {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// returns the start of the interval
+    pub fn start(&self) -> DateTime {
+        DateTime::from_signed_secs(self.start)
+    }
+
+    /// returns the (exclusive) end of the interval
+    pub fn end(&self) -> DateTime {
+        DateTime::from_signed_secs(self.end)
+    }
+
+    /// returns the length of the interval
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs((self.end - self.start) as u64)
+    }
+
+    /// returns true if the interval contains no time at all
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// returns true if the given DateTime falls inside the interval
+    /// ```
+    /// # use datetime::{DateTime, Interval};
+    /// let interval = Interval::from_secs(3600, 7200);
+    ///
+    /// assert!(interval.contains(&DateTime::from_secs(3600)));
+    /// assert!(!interval.contains(&DateTime::from_secs(7200)));
+    /// ```
+    pub fn contains(&self, time: &DateTime) -> bool {
+        self.start <= time.secs && time.secs < self.end
+    }
+
+    /// returns true if the two intervals share any time
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// returns the time shared by both intervals, if any
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if self.overlaps(other) {
+            Some(Interval::from_signed_secs(
+                self.start.max(other.start),
+                self.end.min(other.end),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// a set of time stored as sorted, non-overlapping, non-adjacent intervals
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    /// returns an empty IntervalSet
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// adds an interval to the set, merging it with any intervals it
+    /// overlaps or touches
+    /// ```
+    /// # use datetime::{Interval, IntervalSet};
+    /// let mut set = IntervalSet::new();
+    /// set.insert(Interval::from_secs(0, 10));
+    /// set.insert(Interval::from_secs(10, 20));
+    /// set.insert(Interval::from_secs(30, 40));
+    ///
+    /// assert_eq!(
+    ///     set.iter().cloned().collect::<Vec<_>>(),
+    ///     vec![Interval::from_secs(0, 20), Interval::from_secs(30, 40)]
+    /// );
+    /// ```
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // first interval that ends at or after the new start
+        let lo = self
+            .intervals
+            .iter()
+            .position(|i| i.end >= interval.start)
+            .unwrap_or(self.intervals.len());
+
+        // first interval that starts strictly after the new end
+        let hi = self.intervals[lo..]
+            .iter()
+            .position(|i| i.start > interval.end)
+            .map_or(self.intervals.len(), |pos| lo + pos);
+
+        let mut merged = interval;
+        for i in &self.intervals[lo..hi] {
+            merged.start = merged.start.min(i.start);
+            merged.end = merged.end.max(i.end);
+        }
+
+        self.intervals.splice(lo..hi, Some(merged));
+    }
+
+    /// returns the union of two sets
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut set = self.clone();
+        for &interval in &other.intervals {
+            set.insert(interval);
+        }
+
+        set
+    }
+
+    /// returns the parts of `within` not covered by this set
+    /// ```
+    /// # use datetime::{Interval, IntervalSet};
+    /// let set: IntervalSet = vec![Interval::from_secs(10, 20)].into_iter().collect();
+    /// let gaps = set.complement(&Interval::from_secs(0, 30));
+    ///
+    /// assert_eq!(
+    ///     gaps.iter().cloned().collect::<Vec<_>>(),
+    ///     vec![Interval::from_secs(0, 10), Interval::from_secs(20, 30)]
+    /// );
+    /// ```
+    pub fn complement(&self, within: &Interval) -> IntervalSet {
+        let mut gaps = IntervalSet::new();
+        let mut cursor = within.start;
+
+        for interval in &self.intervals {
+            if interval.end <= cursor {
+                continue;
+            }
+            if interval.start >= within.end {
+                break;
+            }
+
+            gaps.insert(Interval::from_signed_secs(
+                cursor,
+                interval.start.min(within.end),
+            ));
+            cursor = interval.end;
+        }
+
+        if cursor < within.end {
+            gaps.insert(Interval::from_signed_secs(cursor, within.end));
+        }
+
+        gaps
+    }
+
+    /// treats the set as busy time and returns every free interval inside
+    /// `within` that is at least `duration` long
+    /// ```
+    /// # use datetime::{Interval, IntervalSet};
+    /// # use std::time::Duration;
+    /// let busy: IntervalSet = vec![
+    ///     Interval::from_secs(0, 3600),
+    ///     Interval::from_secs(5400, 7200),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// let free = busy.find_free(Duration::from_secs(3600), &Interval::from_secs(0, 14400));
+    ///
+    /// assert_eq!(free, vec![Interval::from_secs(7200, 14400)]);
+    /// ```
+    pub fn find_free(&self, duration: Duration, within: &Interval) -> Vec<Interval> {
+        self.complement(within)
+            .intervals
+            .into_iter()
+            .filter(|i| i.duration() >= duration)
+            .collect()
+    }
+
+    /// returns true if the set contains no time at all
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// returns an iterator over the intervals in the set, in order
+    pub fn iter(&self) -> core::slice::Iter<'_, Interval> {
+        self.intervals.iter()
+    }
+}
+
+impl core::iter::FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+
+        set
+    }
+}
+
+/// free/busy calculations for calendar backends
+pub struct FreeBusy;
+
+impl FreeBusy {
+    /// merges the busy time from several sources (e.g. one per calendar)
+    /// into a single IntervalSet
+    /// ```
+    /// # use datetime::{FreeBusy, Interval, IntervalSet};
+    /// let alice: IntervalSet = vec![Interval::from_secs(0, 10)].into_iter().collect();
+    /// let bob: IntervalSet = vec![Interval::from_secs(5, 20)].into_iter().collect();
+    ///
+    /// let busy = FreeBusy::merge(vec![alice, bob]);
+    ///
+    /// assert_eq!(busy.iter().cloned().collect::<Vec<_>>(), vec![Interval::from_secs(0, 20)]);
+    /// ```
+    pub fn merge<I>(busy_sets: I) -> IntervalSet
+    where
+        I: IntoIterator<Item = IntervalSet>,
+    {
+        busy_sets
+            .into_iter()
+            .fold(IntervalSet::new(), |acc, set| acc.union(&set))
+    }
+
+    /// merges the busy time from several sources and returns every free
+    /// interval inside `within` that is at least `duration` long
+    pub fn find_free<I>(busy_sets: I, duration: Duration, within: &Interval) -> Vec<Interval>
+    where
+        I: IntoIterator<Item = IntervalSet>,
+    {
+        FreeBusy::merge(busy_sets).find_free(duration, within)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FreeBusy, Interval, IntervalSet};
+    use crate::DateTime;
+    use std::time::Duration;
+
+    fn set(intervals: &[(usize, usize)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::from_secs(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_merges_overlaps() {
+        let set = set(&[(30, 40), (0, 10), (5, 15), (20, 25), (14, 21)]);
+
+        assert_eq!(
+            set.iter().cloned().collect::<Vec<_>>(),
+            vec![Interval::from_secs(0, 25), Interval::from_secs(30, 40)]
+        );
+    }
+
+    #[test]
+    fn test_merge_and_find_free() {
+        let a = set(&[(0, 60), (300, 400)]);
+        let b = set(&[(50, 120), (600, 700)]);
+        let within = Interval::from_secs(0, 1000);
+
+        let free = FreeBusy::find_free(vec![a, b], Duration::from_secs(150), &within);
+
+        assert_eq!(
+            free,
+            vec![
+                Interval::from_secs(120, 300),
+                Interval::from_secs(400, 600),
+                Interval::from_secs(700, 1000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complement_clipped() {
+        let busy = set(&[(0, 50), (80, 200)]);
+        let gaps = busy.complement(&Interval::from_secs(40, 100));
+
+        assert_eq!(
+            gaps.iter().cloned().collect::<Vec<_>>(),
+            vec![Interval::from_secs(50, 80)]
+        );
+    }
+
+    #[test]
+    fn test_full_range() {
+        let all = Interval::new(&DateTime::MIN, &DateTime::MAX);
+        assert_eq!(all.start(), DateTime::MIN.with_nanosecond(0).unwrap());
+        assert_eq!(all.end(), DateTime::MAX.with_nanosecond(0).unwrap());
+        assert_eq!(
+            all.duration().as_secs(),
+            (DateTime::MAX.as_secs() - DateTime::MIN.as_secs()) as u64
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Interval outside the range of a DateTime")]
+    fn test_from_signed_secs_panics() {
+        Interval::from_signed_secs(i64::MIN, 0);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "Interval too far in the future")]
+    fn test_from_secs_panics() {
+        Interval::from_secs(0, usize::MAX);
+    }
+}
@@ -0,0 +1,129 @@
+//! Kafka record timestamps
+//!
+//! Kafka stores a record's timestamp in Unix milliseconds, with -1 for
+//! records that have none. depending on the topic's
+//! `message.timestamp.type`, it is either the time the producer created
+//! the record or the time the broker appended it to the log.
+//! [`KafkaTimestamp`] keeps track of which, so stream-processing code can
+//! say what kind of time it is working with
+//! ```
+//! # use datetime::kafka::KafkaTimestamp;
+//! # use datetime::DateTime;
+//! let stamp = KafkaTimestamp::create_time(842282624123).unwrap();
+//!
+//! assert!(stamp.is_create_time());
+//! assert_eq!(stamp.to_datetime().millisecond(), 123);
+//! assert!(DateTime::from_kafka_timestamp(-1).is_none());
+//! ```
+use crate::{AbsoluteTime, DateTime, FixedOffset};
+
+impl DateTime {
+    /// returns the DateTime for a Kafka timestamp in Unix milliseconds, or
+    /// None if it is negative (Kafka uses -1 for "no timestamp")
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_kafka_timestamp(842282624123).unwrap();
+    ///
+    /// assert_eq!(date.to_kafka_timestamp(), 842282624123);
+    /// ```
+    pub fn from_kafka_timestamp(millis: i64) -> Option<DateTime> {
+        from_millis(millis).map(|time| time.to_datetime(FixedOffset::utc()))
+    }
+
+    /// returns the DateTime as a Kafka timestamp in Unix milliseconds; any
+    /// finer fraction is dropped, and DateTimes too far from the epoch to
+    /// fit saturate
+    pub fn to_kafka_timestamp(&self) -> i64 {
+        self.to_unix_pexpiry()
+    }
+}
+
+// the instant `millis` after the epoch, or None if it is negative
+fn from_millis(millis: i64) -> Option<AbsoluteTime> {
+    if millis < 0 {
+        return None;
+    }
+
+    AbsoluteTime::new(millis / 1000, (millis % 1000) as u32 * 1_000_000)
+}
+
+/// a Kafka record timestamp, along with what it measures
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KafkaTimestamp {
+    /// the time the producer created the record
+    CreateTime(AbsoluteTime),
+    /// the time the broker appended the record to its log
+    LogAppendTime(AbsoluteTime),
+}
+
+impl KafkaTimestamp {
+    /// returns a create-time timestamp from Unix milliseconds, or None if
+    /// it is negative
+    pub fn create_time(millis: i64) -> Option<Self> {
+        from_millis(millis).map(KafkaTimestamp::CreateTime)
+    }
+
+    /// returns a log-append-time timestamp from Unix milliseconds, or None
+    /// if it is negative
+    pub fn log_append_time(millis: i64) -> Option<Self> {
+        from_millis(millis).map(KafkaTimestamp::LogAppendTime)
+    }
+
+    /// returns true if this is the time the record was created
+    pub fn is_create_time(&self) -> bool {
+        matches!(self, KafkaTimestamp::CreateTime(_))
+    }
+
+    /// returns true if this is the time the record was appended to the log
+    pub fn is_log_append_time(&self) -> bool {
+        matches!(self, KafkaTimestamp::LogAppendTime(_))
+    }
+
+    /// returns the instant, whatever it measures
+    pub fn time(&self) -> AbsoluteTime {
+        match *self {
+            KafkaTimestamp::CreateTime(time) | KafkaTimestamp::LogAppendTime(time) => time,
+        }
+    }
+
+    /// returns the instant as a DateTime in UTC
+    pub fn to_datetime(&self) -> DateTime {
+        self.time().to_datetime(FixedOffset::utc())
+    }
+
+    /// returns the timestamp in Unix milliseconds, as Kafka stores it
+    pub fn to_millis(&self) -> i64 {
+        self.to_datetime().to_kafka_timestamp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KafkaTimestamp;
+    use crate::DateTime;
+
+    #[test]
+    fn test_kafka_timestamp() {
+        let date = DateTime::from_kafka_timestamp(1500).unwrap();
+        assert_eq!((date.secs, date.nanos), (1, 500_000_000));
+        assert_eq!(date.to_kafka_timestamp(), 1500);
+
+        assert!(DateTime::from_kafka_timestamp(-1).is_none());
+        assert_eq!(DateTime::from_nanos(1_999_999).to_kafka_timestamp(), 1);
+    }
+
+    #[test]
+    fn test_timestamp_types() {
+        let created = KafkaTimestamp::create_time(0).unwrap();
+        let appended = KafkaTimestamp::log_append_time(0).unwrap();
+
+        assert!(created.is_create_time() && !created.is_log_append_time());
+        assert!(appended.is_log_append_time());
+        // the same instant, but not the same kind of timestamp
+        assert_eq!(created.time(), appended.time());
+        assert_ne!(created, appended);
+
+        assert_eq!(appended.to_millis(), 0);
+        assert!(KafkaTimestamp::log_append_time(-1).is_none());
+    }
+}
@@ -0,0 +1,580 @@
+//! leap seconds, and conversions between UTC, TAI and GPS time
+//!
+//! a DateTime counts seconds as Unix time does, as if every day were 86,400
+//! seconds long, so it has no room for the leap seconds occasionally
+//! inserted at the end of a UTC day. International Atomic Time (TAI) has no
+//! leap seconds, and has run ahead of UTC by one more second after each.
+//! [`LeapSeconds`] is a table of those differences, which can convert
+//! DateTimes between the two [`TimeScale`]s and write out a leap second
+//! as `23:59:60`
+//! ```
+//! # use datetime::leap::{LeapSeconds, TimeScale};
+//! # use datetime::DateTime;
+//! let table = LeapSeconds::builtin();
+//! let utc = DateTime::parse_rfc3339("2017-01-01T00:00:00Z").unwrap();
+//!
+//! assert_eq!(table.tai_offset(&utc), 37);
+//! let tai = table.convert(&utc, TimeScale::Utc, TimeScale::Tai).unwrap();
+//! assert_eq!(tai.to_rfc3339(), "2017-01-01T00:00:37Z");
+//!
+//! // the second before that was the leap second
+//! let leap = tai.checked_sub_secs(1).unwrap();
+//! assert_eq!(table.format_utc(&leap), "2016-12-31T23:59:60Z");
+//! ```
+//!
+//! GPS time has no leap seconds either, and runs 19 seconds behind TAI, so
+//! it agreed with UTC when it started, at midnight on 1980-01-06
+//! ```
+//! # use datetime::leap::LeapSeconds;
+//! # use datetime::DateTime;
+//! let table = LeapSeconds::builtin();
+//! let utc = DateTime::parse_rfc3339("2017-01-01T00:00:00Z").unwrap();
+//!
+//! assert_eq!(table.to_gps_secs(&utc), 1_167_264_018);
+//! assert_eq!(table.from_gps_secs(1_167_264_018), Some(utc));
+//! ```
+//!
+//! clocks synchronised to some time servers never show a leap second, but
+//! spread it over the time around it; [`LeapSeconds::to_smeared`] and
+//! [`LeapSeconds::from_smeared`] convert between TAI and such a clock for
+//! each [`Smear`]
+//!
+//! the builtin table ends with the leap second at the end of 2016, the
+//! latest announced when it was written. newer tables can be read from the
+//! `leap-seconds.list` file published by the IERS and shipped with many
+//! systems' time zone data
+use crate::epoch::NTP_EPOCH;
+use crate::parse::ParseError;
+use crate::{DateTime, FixedOffset, MAX_SECS, MIN_SECS};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+// when each difference between TAI and UTC took effect, in Unix time
+const BUILTIN: [(i64, i32); 28] = [
+    (63_072_000, 10),
+    (78_796_800, 11),
+    (94_694_400, 12),
+    (126_230_400, 13),
+    (157_766_400, 14),
+    (189_302_400, 15),
+    (220_924_800, 16),
+    (252_460_800, 17),
+    (283_996_800, 18),
+    (315_532_800, 19),
+    (362_793_600, 20),
+    (394_329_600, 21),
+    (425_865_600, 22),
+    (489_024_000, 23),
+    (567_993_600, 24),
+    (631_152_000, 25),
+    (662_688_000, 26),
+    (709_948_800, 27),
+    (741_484_800, 28),
+    (773_020_800, 29),
+    (820_454_400, 30),
+    (867_715_200, 31),
+    (915_148_800, 32),
+    (1_136_073_600, 33),
+    (1_230_768_000, 34),
+    (1_341_100_800, 35),
+    (1_435_708_800, 36),
+    (1_483_228_800, 37),
+];
+
+/// a scale that DateTimes may be counted in
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TimeScale {
+    /// Coordinated Universal Time, as Unix time counts it
+    Utc,
+    /// International Atomic Time, counted as if its days were UTC's
+    Tai,
+    /// GPS time, 19 seconds behind TAI, counted as if its days were UTC's
+    Gps,
+}
+
+impl TimeScale {
+    // the seconds the scale runs behind TAI, which is fixed for all but UTC
+    fn behind_tai(self) -> i64 {
+        match self {
+            TimeScale::Gps => 19,
+            _ => 0,
+        }
+    }
+}
+
+/// a way of spreading a leap second over the time around it, so that a
+/// clock never shows `23:59:60` but runs slightly slow (or fast, for a
+/// removed second) instead
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Smear {
+    /// a linear smear over the 24 hours from noon to noon UTC around the
+    /// leap second, as Google's and Amazon's time servers do
+    Linear24Hour,
+    /// UTC-SLS, a linear smear over the last 1000 seconds of the day
+    /// before the leap second
+    UtcSls,
+}
+
+impl Smear {
+    // the seconds the smear starts before the leap second, and ends after
+    fn window(self) -> (i64, i64) {
+        match self {
+            Smear::Linear24Hour => (43_200, 43_200),
+            Smear::UtcSls => (1000, 0),
+        }
+    }
+}
+
+// a smear around one leap second, in nanoseconds: when it starts and ends
+// on the smeared clock, and when it starts and ends in TAI
+struct SmearWindow {
+    start: i128,
+    end: i128,
+    tai_start: i128,
+    tai_end: i128,
+}
+
+impl SmearWindow {
+    fn to_smeared(&self, tai: i128) -> Option<i128> {
+        if !(self.tai_start..self.tai_end).contains(&tai) {
+            return None;
+        }
+
+        let scaled = (tai - self.tai_start) * (self.end - self.start);
+        Some(self.start + scaled.div_euclid(self.tai_end - self.tai_start))
+    }
+
+    fn to_tai(&self, smeared: i128) -> Option<i128> {
+        if !(self.start..self.end).contains(&smeared) {
+            return None;
+        }
+
+        let scaled = (smeared - self.start) * (self.tai_end - self.tai_start);
+        Some(self.tai_start + scaled.div_euclid(self.end - self.start))
+    }
+}
+
+// the Unix time of the GPS epoch, 1980-01-06
+const GPS_EPOCH: i64 = 315_964_800;
+
+/// a table of the differences between TAI and UTC
+///
+/// dates before the first entry are taken to have its difference. the
+/// rubber seconds UTC used before 1972 are not modelled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeapSeconds {
+    // (Unix time the difference took effect, TAI - UTC in seconds), sorted
+    entries: Vec<(i64, i32)>,
+}
+
+impl LeapSeconds {
+    /// returns the table built into the crate
+    pub fn builtin() -> Self {
+        LeapSeconds {
+            entries: BUILTIN.to_vec(),
+        }
+    }
+
+    /// returns a table of the given differences, each the Unix time a
+    /// difference between TAI and UTC took effect and that difference in
+    /// seconds, or None if there are none or they aren't in order
+    pub fn from_entries(entries: Vec<(i64, i32)>) -> Option<Self> {
+        let in_order = entries.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        if entries.is_empty() || !in_order {
+            return None;
+        }
+
+        Some(LeapSeconds { entries })
+    }
+
+    /// returns the UTC DateTime the latest difference took effect
+    pub fn last_updated(&self) -> DateTime {
+        let (at, _) = self.entries[self.entries.len() - 1];
+        DateTime::from_signed_secs(at)
+    }
+
+    // the index of the entry in effect at the given Unix time
+    fn entry_at(&self, secs: i64) -> usize {
+        self.entries
+            .partition_point(|&(at, _)| at <= secs)
+            .saturating_sub(1)
+    }
+
+    /// returns TAI - UTC in seconds at the given UTC DateTime
+    pub fn tai_offset(&self, utc: &DateTime) -> i32 {
+        self.entries[self.entry_at(utc.secs)].1
+    }
+
+    // the entry in effect at a TAI time, and whether the time falls in the
+    // leap second just before that entry
+    fn entry_at_tai(&self, secs: i64) -> (usize, bool) {
+        let index = self
+            .entries
+            .partition_point(|&(at, offset)| at + offset as i64 <= secs)
+            .saturating_sub(1);
+
+        let leap = self.entries.get(index + 1).is_some_and(|&(at, offset)| {
+            let previous = self.entries[index].1;
+            offset > previous && secs >= at + previous as i64
+        });
+        (index, leap)
+    }
+
+    /// returns true if the given TAI DateTime falls in a leap second
+    pub fn is_leap_second(&self, tai: &DateTime) -> bool {
+        self.entry_at_tai(tai.secs).1
+    }
+
+    /// converts a DateTime from one time scale to another, keeping its
+    /// offset, or returns None if the result is out of range
+    ///
+    /// a TAI time during a leap second becomes the UTC second before it, as
+    /// Unix time repeats that second
+    pub fn convert(&self, date: &DateTime, from: TimeScale, to: TimeScale) -> Option<DateTime> {
+        let secs = match (from, to) {
+            _ if from == to => date.secs,
+            (TimeScale::Utc, _) => {
+                let offset = self.tai_offset(date) as i64 - to.behind_tai();
+                date.secs.checked_add(offset)?
+            }
+            (_, TimeScale::Utc) => {
+                let tai = date.secs.checked_add(from.behind_tai())?;
+                let (index, leap) = self.entry_at_tai(tai);
+                if leap {
+                    self.entries[index + 1].0 - 1
+                } else {
+                    tai - self.entries[index].1 as i64
+                }
+            }
+            _ => date.secs.checked_add(from.behind_tai() - to.behind_tai())?,
+        };
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(secs, date.nanos, date.offset))
+        } else {
+            None
+        }
+    }
+
+    /// returns the UTC DateTime the given number of GPS seconds after the
+    /// GPS epoch, 1980-01-06, or None if that is out of range
+    ///
+    /// a GPS time during a leap second becomes the UTC second before it
+    pub fn from_gps_secs(&self, secs: i64) -> Option<DateTime> {
+        let gps = secs.checked_add(GPS_EPOCH)?;
+        if !(MIN_SECS..=MAX_SECS).contains(&gps) {
+            return None;
+        }
+
+        let gps = DateTime::from_parts(gps, 0, FixedOffset::utc());
+        self.convert(&gps, TimeScale::Gps, TimeScale::Utc)
+    }
+
+    /// returns the whole GPS seconds since the GPS epoch, 1980-01-06, at the
+    /// given UTC DateTime, rounded down
+    ///
+    /// # Panics
+    ///
+    /// panics if the DateTime is too close to the limits of a DateTime for
+    /// GPS time to be counted
+    pub fn to_gps_secs(&self, utc: &DateTime) -> i64 {
+        let gps = self
+            .convert(utc, TimeScale::Utc, TimeScale::Gps)
+            .expect("UTC time too close to the limits of a DateTime");
+        gps.secs - GPS_EPOCH
+    }
+
+    /// returns the UTC time of the given TAI DateTime as an RFC 3339
+    /// timestamp, with a leap second written as `23:59:60`
+    ///
+    /// # Panics
+    ///
+    /// panics if the UTC time is out of range, which can only happen within
+    /// a minute of the limits of a DateTime
+    pub fn format_utc(&self, tai: &DateTime) -> String {
+        let utc = self
+            .convert(tai, TimeScale::Tai, TimeScale::Utc)
+            .expect("TAI time too close to the limits of a DateTime")
+            .with_offset(FixedOffset::utc());
+        let mut s = utc.to_rfc3339();
+
+        if self.is_leap_second(tai) {
+            let second = s.find('T').expect("RFC 3339 has a T") + 7;
+            s.replace_range(second..second + 2, "60");
+        }
+        s
+    }
+}
+
+impl LeapSeconds {
+    /// returns the time a clock using the given smear shows at the given
+    /// TAI DateTime, keeping its offset, or None if that is out of range
+    ///
+    /// away from leap seconds this is the same as UTC
+    /// ```
+    /// # use datetime::leap::{LeapSeconds, Smear, TimeScale};
+    /// # use datetime::DateTime;
+    /// let table = LeapSeconds::builtin();
+    /// let utc = DateTime::parse_rfc3339("2016-12-31T12:00:00Z").unwrap();
+    /// let tai = table.convert(&utc, TimeScale::Utc, TimeScale::Tai).unwrap();
+    ///
+    /// // half a day later, the smeared clock is half a second behind
+    /// let later = tai.checked_add_secs(43_200).unwrap();
+    /// let smeared = table.to_smeared(&later, Smear::Linear24Hour).unwrap();
+    /// assert_eq!(smeared.to_rfc3339(), "2016-12-31T23:59:59.500005786Z");
+    /// ```
+    pub fn to_smeared(&self, tai: &DateTime, smear: Smear) -> Option<DateTime> {
+        let nanos = tai.secs as i128 * 1_000_000_000 + tai.nanos as i128;
+        let (index, _) = self.entry_at_tai(tai.secs);
+
+        // the leap second just before or after the time is the only one
+        // whose smear can cover it
+        let smeared = [index, index + 1]
+            .iter()
+            .filter_map(|&i| self.smear_window(i, smear)?.to_smeared(nanos))
+            .next()
+            .unwrap_or(nanos - self.entries[index].1 as i128 * 1_000_000_000);
+        from_nanos(smeared, tai.offset)
+    }
+
+    /// returns the TAI DateTime at which a clock using the given smear
+    /// shows the given time, keeping its offset, or None if that is out of
+    /// range; the inverse of [`LeapSeconds::to_smeared`], to within a
+    /// nanosecond
+    pub fn from_smeared(&self, smeared: &DateTime, smear: Smear) -> Option<DateTime> {
+        let nanos = smeared.secs as i128 * 1_000_000_000 + smeared.nanos as i128;
+        let index = self.entry_at(smeared.secs);
+
+        let tai = [index, index + 1]
+            .iter()
+            .filter_map(|&i| self.smear_window(i, smear)?.to_tai(nanos))
+            .next()
+            .unwrap_or(nanos + self.entries[index].1 as i128 * 1_000_000_000);
+        from_nanos(tai, smeared.offset)
+    }
+
+    // the smear around the change to the given entry, or None if it is
+    // the first entry or past the last
+    fn smear_window(&self, index: usize, smear: Smear) -> Option<SmearWindow> {
+        let &(at, offset) = self.entries.get(index)?;
+        let previous = self.entries[index.checked_sub(1)?].1;
+        let (before, after) = smear.window();
+        let (start, end) = (at - before, at + after);
+
+        Some(SmearWindow {
+            start: start as i128 * 1_000_000_000,
+            end: end as i128 * 1_000_000_000,
+            tai_start: (start + previous as i64) as i128 * 1_000_000_000,
+            tai_end: (end + offset as i64) as i128 * 1_000_000_000,
+        })
+    }
+}
+
+// the DateTime the given nanoseconds after the epoch, or None if that is
+// out of range
+fn from_nanos(nanos: i128, offset: FixedOffset) -> Option<DateTime> {
+    let secs = nanos.div_euclid(1_000_000_000);
+    if !(MIN_SECS as i128..=MAX_SECS as i128).contains(&secs) {
+        return None;
+    }
+
+    let nanos = nanos.rem_euclid(1_000_000_000) as u32;
+    Some(DateTime::from_parts(secs as i64, nanos, offset))
+}
+
+impl Default for LeapSeconds {
+    /// returns the builtin table
+    fn default() -> Self {
+        LeapSeconds::builtin()
+    }
+}
+
+impl FromStr for LeapSeconds {
+    type Err = ParseError;
+
+    /// reads a `leap-seconds.list` file, whose lines give the NTP time
+    /// (seconds since 1900) each difference took effect and the difference,
+    /// and whose comments start with `#`
+    /// ```
+    /// # use datetime::leap::LeapSeconds;
+    /// let list = "\
+    /// #\t$\t 3676924800
+    /// 2272060800\t10\t# 1 Jan 1972
+    /// 2287785600\t11\t# 1 Jul 1972
+    /// ";
+    /// let table: LeapSeconds = list.parse().unwrap();
+    ///
+    /// assert_eq!(table.last_updated().to_rfc3339(), "1972-07-01T00:00:00Z");
+    /// ```
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::new();
+        let mut pos = 0;
+
+        for line in input.split_inclusive('\n') {
+            let start = pos;
+            pos += line.len();
+
+            let content = line.split('#').next().unwrap_or("");
+            let mut fields = content.split_whitespace();
+            let (at, offset) = match (fields.next(), fields.next()) {
+                (None, _) => continue,
+                (Some(at), Some(offset)) => (at, offset),
+                (Some(_), None) => return Err(ParseError::Invalid(start)),
+            };
+            if fields.next().is_some() {
+                return Err(ParseError::Invalid(start));
+            }
+
+            let at: i64 = at.parse().map_err(|_| ParseError::Invalid(start))?;
+            let offset: i32 = offset.parse().map_err(|_| ParseError::Invalid(start))?;
+            entries.push((at - NTP_EPOCH, offset));
+        }
+
+        if entries.is_empty() {
+            return Err(ParseError::TooShort);
+        }
+        LeapSeconds::from_entries(entries).ok_or(ParseError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LeapSeconds, Smear, TimeScale, BUILTIN};
+    use crate::convert::days_from_civil;
+    use crate::{DateTime, Month, ParseError};
+    use core::time::Duration;
+
+    #[test]
+    fn test_builtin_table() {
+        // every entry is the first of January or July, one second apart
+        for (i, &(at, offset)) in BUILTIN.iter().enumerate() {
+            let date = DateTime::from_signed_secs(at);
+            assert_eq!((date.date(), date.hour(), date.minute()), (1, 0, 0));
+            assert!(matches!(date.month(), Month::January | Month::July));
+            assert_eq!(offset, 10 + i as i32);
+        }
+        assert_eq!(BUILTIN[0].0, days_from_civil(1972, 1, 1) * 86_400);
+
+        let table = LeapSeconds::builtin();
+        assert_eq!(table.tai_offset(&DateTime::from_secs(0)), 10);
+        assert_eq!(table.tai_offset(&DateTime::from_secs(1_483_228_799)), 36);
+        assert_eq!(table.last_updated().year(), 2017);
+    }
+
+    #[test]
+    fn test_tai_round_trip() {
+        let table = LeapSeconds::builtin();
+        let (tai, utc) = (TimeScale::Tai, TimeScale::Utc);
+
+        // 2016-12-31T23:59:59 UTC is 36 seconds behind TAI, and midnight 37
+        let before = DateTime::from_secs(1_483_228_799);
+        let midnight = DateTime::from_millis(1_483_228_800_250);
+        let leap = DateTime::from_secs(1_483_228_799 + 37);
+
+        assert_eq!(
+            table.convert(&before, utc, tai).unwrap().secs,
+            1_483_228_799 + 36
+        );
+        assert!(table.is_leap_second(&leap));
+        assert!(!table.is_leap_second(&leap.checked_sub_secs(1).unwrap()));
+        assert!(!table.is_leap_second(&leap.checked_add_secs(1).unwrap()));
+        assert_eq!(table.convert(&leap, tai, utc).unwrap(), before);
+
+        let midnight_tai = table.convert(&midnight, utc, tai).unwrap();
+        assert_eq!(table.convert(&midnight_tai, tai, utc).unwrap(), midnight);
+        assert_eq!(table.format_utc(&midnight_tai), "2017-01-01T00:00:00.250Z");
+        assert_eq!(
+            table.format_utc(&leap.checked_sub_secs(1).unwrap()),
+            "2016-12-31T23:59:59Z"
+        );
+        assert_eq!(table.convert(&before, utc, utc).unwrap(), before);
+    }
+
+    #[test]
+    fn test_gps() {
+        let table = LeapSeconds::builtin();
+        let (utc, tai, gps) = (TimeScale::Utc, TimeScale::Tai, TimeScale::Gps);
+
+        let epoch = DateTime::parse_rfc3339("1980-01-06T00:00:00Z").unwrap();
+        assert_eq!(table.to_gps_secs(&epoch), 0);
+        assert_eq!(table.from_gps_secs(0), Some(epoch));
+
+        // GPS time is 19 seconds behind TAI, whatever UTC does
+        let date = DateTime::parse_rfc3339("2016-12-31T23:59:59.5Z").unwrap();
+        let as_tai = table.convert(&date, utc, tai).unwrap();
+        let as_gps = table.convert(&date, utc, gps).unwrap();
+        assert_eq!(table.convert(&as_tai, tai, gps), Some(as_gps));
+        assert_eq!(table.convert(&as_gps, gps, tai), Some(as_tai));
+        assert_eq!(table.convert(&as_gps, gps, utc), Some(date));
+        assert_eq!(as_gps.to_rfc3339(), "2017-01-01T00:00:16.500Z");
+
+        // the leap second is read as the second before it
+        let before = table.to_gps_secs(&date);
+        assert_eq!(table.from_gps_secs(before + 1), table.from_gps_secs(before));
+        assert_eq!(
+            table.from_gps_secs(before + 2).unwrap().to_rfc3339(),
+            "2017-01-01T00:00:00Z"
+        );
+        assert_eq!(table.from_gps_secs(i64::MAX), None);
+    }
+
+    #[test]
+    fn test_smear() {
+        let table = LeapSeconds::builtin();
+        let at = 1_483_228_800;
+
+        for &smear in &[Smear::Linear24Hour, Smear::UtcSls] {
+            let (before, after) = smear.window();
+            // the smear starts and ends in step with UTC
+            let start = DateTime::from_signed_secs(at - before);
+            let end = DateTime::from_signed_secs(at + after);
+            let tai_start = start.checked_add_secs(36).unwrap();
+            let tai_end = end.checked_add_secs(37).unwrap();
+            assert_eq!(table.to_smeared(&tai_start, smear), Some(start));
+            assert_eq!(table.to_smeared(&tai_end, smear), Some(end));
+            assert_eq!(table.from_smeared(&end, smear), Some(tai_end));
+
+            // and the smeared clock never goes backwards through the leap
+            let leap = DateTime::from_signed_secs(at + 36);
+            let mut previous = table.to_smeared(&tai_start, smear).unwrap();
+            for tai in &[leap, tai_end] {
+                let smeared = table.to_smeared(tai, smear).unwrap();
+                assert!(smeared > previous);
+                let back = table.from_smeared(&smeared, smear).unwrap();
+                let error = back.duration_since(tai).unwrap_or_else(|e| e);
+                assert!(error <= Duration::from_nanos(1));
+                previous = smeared;
+            }
+        }
+
+        let leap = DateTime::from_signed_secs(at + 36);
+        let smeared = table.to_smeared(&leap, Smear::UtcSls).unwrap();
+        assert_eq!(smeared.to_rfc3339(), "2016-12-31T23:59:59.000999Z");
+
+        // far from a leap second, a smeared clock reads UTC
+        let utc = DateTime::from_secs(1_500_000_000);
+        let tai = table.convert(&utc, TimeScale::Utc, TimeScale::Tai).unwrap();
+        assert_eq!(table.to_smeared(&tai, Smear::Linear24Hour), Some(utc));
+        assert_eq!(table.from_smeared(&utc, Smear::UtcSls), Some(tai));
+    }
+
+    #[test]
+    fn test_parse_leap_seconds_list() {
+        let table: LeapSeconds = "2272060800 10\n\n# a comment\n2287785600 11 # 1 Jul 1972\n"
+            .parse()
+            .unwrap();
+        assert_eq!(table.tai_offset(&DateTime::from_secs(78_796_800)), 11);
+
+        assert_eq!("".parse::<LeapSeconds>(), Err(ParseError::TooShort));
+        assert_eq!(
+            "2272060800 10\n2272060800\n".parse::<LeapSeconds>(),
+            Err(ParseError::Invalid(14))
+        );
+        assert_eq!(
+            "2287785600 11\n2272060800 10\n".parse::<LeapSeconds>(),
+            Err(ParseError::OutOfRange)
+        );
+        assert!(LeapSeconds::from_entries(vec![]).is_none());
+    }
+}
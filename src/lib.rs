@@ -1,10 +1,136 @@
 //! A simple Rust library for turning a SystemTime into a date and time
 //! (in UTC)
 //! and returning a simple time stamp suitable for printing.
-use std::ops::{Add, AddAssign};
-use std::time::SystemTime;
-use std::time::UNIX_EPOCH;
-use cache::Cache;
+//!
+//! everything but reading the system clock works without the standard
+//! library: build with `default-features = false` for `no_std` targets,
+//! which need an allocator for the String-returning APIs
+//!
+//! the crate follows semantic versioning, except for the experimental
+//! subsystems in the `unstable` module, which is only built with the
+//! `unstable` feature and may change in any release
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use crate::convert::{civil_from_days, cumulative_days, days_from_civil, weekday_from_days};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::str::FromStr;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// first, so that its macros can be used in every other module
+#[macro_use]
+mod trace;
+
+pub mod adjust;
+pub mod age;
+pub mod batch;
+#[cfg(feature = "bson")]
+pub mod bson;
+pub mod business;
+pub mod calendar;
+pub mod calendar_system;
+pub mod cert;
+#[cfg(feature = "chrono")]
+pub mod chrono;
+pub mod civil;
+#[cfg(feature = "clap")]
+pub mod clap;
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod coarse;
+pub mod compat;
+pub mod convert;
+pub mod cron;
+pub mod cycle;
+pub mod date;
+pub mod degree_days;
+pub mod delta;
+pub mod difference;
+#[cfg(feature = "std")]
+pub mod drift;
+pub mod edtf;
+pub mod epoch;
+pub mod error;
+pub mod exif;
+pub mod expiry;
+pub mod feed;
+pub mod format;
+#[cfg(feature = "fuzzy")]
+pub mod fuzzy;
+#[cfg(feature = "std")]
+pub mod intern;
+pub mod interval;
+pub mod kafka;
+#[cfg(feature = "leap")]
+pub mod leap;
+pub mod locale;
+pub mod media;
+pub mod metrics;
+pub mod offset;
+pub mod otel;
+pub mod packed;
+pub mod parse;
+pub mod partial;
+pub mod period;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod range;
+pub mod reconcile;
+pub mod relative;
+pub mod retry;
+pub mod schedule;
+#[cfg(feature = "schemars")]
+pub mod schemars;
+pub mod season;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod sink;
+pub mod span;
+#[cfg(any(feature = "sqlx-postgres", feature = "sqlx-sqlite"))]
+pub mod sqlx;
+pub mod stamp;
+pub mod systemd;
+#[cfg(feature = "time")]
+pub mod time;
+pub mod truncate;
+#[cfg(feature = "tz")]
+pub mod tz;
+#[cfg(feature = "unstable")]
+pub mod unstable;
+pub mod weekday;
+pub mod windows_zones;
+pub mod year;
+
+pub use crate::civil::{AbsoluteTime, CivilTime};
+#[cfg(feature = "std")]
+pub use crate::coarse::{CoarseNow, LocalCoarseNow};
+pub use crate::convert::{days_in_month, is_leap_year, is_valid_date, is_valid_time, weekday};
+pub use crate::date::{Date, Time};
+#[allow(deprecated)]
+pub use crate::error::OutOfRangeError;
+pub use crate::error::{Error, InvalidDate, RangeError};
+pub use crate::format::{FormatError, FormatSpec, FormatSpecBuilder, ReformatError};
+#[cfg(feature = "std")]
+pub use crate::intern::TimestampPool;
+pub use crate::interval::{FreeBusy, Interval, IntervalSet};
+pub use crate::locale::Locale;
+pub use crate::offset::FixedOffset;
+pub use crate::packed::{PackedDate, PackedTime};
+pub use crate::parse::{DetectedFormat, ParseError, ParseProfile};
+pub use crate::period::Period;
+pub use crate::sink::TimestampSink;
+pub use crate::span::Span;
+pub use crate::stamp::StampOptions;
+pub use crate::year::Year;
 
 /// an enum representing each day of the week
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -35,130 +161,365 @@ pub enum Month {
     December,
 }
 
-// cache for lazy computation of date and time
-#[derive(Debug)]
-struct DtCache {
-    year: usize,
-    month: Month,
-    day: Day,
-    date: usize,
-    hour: usize,
-    minute: usize,
-    second: usize,
-}
+impl Day {
+    /// returns the ISO 8601 number of the day, from 1 for Monday to 7 for
+    /// Sunday
+    /// ```
+    /// # use datetime::Day;
+    /// assert_eq!(Day::Monday.number(), 1);
+    /// assert_eq!(Day::Sunday.number(), 7);
+    /// ```
+    pub const fn number(self) -> usize {
+        (self as usize + 6) % 7 + 1
+    }
+
+    /// returns the day with the given ISO 8601 number, from 1 for Monday to
+    /// 7 for Sunday, or None if it is out of range
+    pub const fn from_number(number: usize) -> Option<Day> {
+        match number {
+            1..=7 => Some(DAYS[number % 7]),
+            _ => None,
+        }
+    }
 
-impl DtCache {
-    fn from_secs(secs: usize) -> Self {
-        let table = (1970..).map(|year| {
-            let days_per_year = if is_leap_year(year) { 366 } else { 365 };
+    /// returns the next day, wrapping from Saturday to Sunday
+    pub const fn succ(self) -> Day {
+        DAYS[(self as usize + 1) % 7]
+    }
+
+    /// returns the previous day, wrapping from Sunday to Saturday
+    pub const fn pred(self) -> Day {
+        DAYS[(self as usize + 6) % 7]
+    }
 
-            let sec_per_year = days_per_year * 24 * 60 * 60;
+    /// returns an iterator over every day, from Sunday to Saturday
+    /// ```
+    /// # use datetime::Day;
+    /// let weekend: Vec<_> = Day::iter().filter(|day| day.number() > 5).collect();
+    /// assert_eq!(weekend, [Day::Sunday, Day::Saturday]);
+    /// ```
+    pub fn iter() -> impl DoubleEndedIterator<Item = Day> + ExactSizeIterator {
+        DAYS.iter().copied()
+    }
 
-            (year, sec_per_year)
-        });
+    // the English name of the day
+    pub(crate) fn name(self) -> &'static str {
+        Locale::En.day_name(self)
+    }
 
-        let mut x = secs;
-        let mut date_year = 0;
+    // the three-letter English abbreviation of the day
+    pub(crate) fn abbreviation(self) -> &'static str {
+        Locale::En.day_abbreviation(self)
+    }
+}
 
-        for (year, sec) in table {
-            if x < sec {
-                date_year = year;
-                break;
-            }
+impl Month {
+    /// returns the number of the month, from 1 for January to 12 for
+    /// December
+    /// ```
+    /// # use datetime::Month;
+    /// assert_eq!(Month::September.number(), 9);
+    /// assert_eq!(Month::from_number(9), Some(Month::September));
+    /// assert_eq!(Month::from_number(13), None);
+    /// ```
+    pub const fn number(self) -> usize {
+        self as usize + 1
+    }
 
-            x -= sec;
+    /// returns the month with the given number, from 1 for January to 12
+    /// for December, or None if it is out of range
+    pub const fn from_number(number: usize) -> Option<Month> {
+        match number {
+            1..=12 => Some(MONTHS[number - 1]),
+            _ => None,
         }
+    }
 
-        let table = [
-            (Month::January, 31),
-            (
-                Month::February,
-                if is_leap_year(date_year) { 29 } else { 28 },
-            ),
-            (Month::March, 31),
-            (Month::April, 30),
-            (Month::May, 31),
-            (Month::June, 30),
-            (Month::July, 31),
-            (Month::August, 31),
-            (Month::September, 30),
-            (Month::October, 31),
-            (Month::November, 30),
-            (Month::December, 31),
-        ];
+    /// returns the next month, wrapping from December to January
+    /// ```
+    /// # use datetime::Month;
+    /// assert_eq!(Month::December.succ(), Month::January);
+    /// assert_eq!(Month::January.pred(), Month::December);
+    /// ```
+    pub const fn succ(self) -> Month {
+        MONTHS[(self as usize + 1) % 12]
+    }
 
-        let mut date_month = Month::January;
+    /// returns the previous month, wrapping from January to December
+    pub const fn pred(self) -> Month {
+        MONTHS[(self as usize + 11) % 12]
+    }
 
-        for &(month, days) in table.into_iter() {
-            let sec_per_month = days * 24 * 60 * 60;
+    /// returns an iterator over every month, from January to December
+    pub fn iter() -> impl DoubleEndedIterator<Item = Month> + ExactSizeIterator {
+        MONTHS.iter().copied()
+    }
 
-            if x < sec_per_month {
-                date_month = month;
-                break;
-            }
+    // the English name of the month
+    pub(crate) fn name(self) -> &'static str {
+        Locale::En.month_name(self)
+    }
 
-            x -= sec_per_month;
-        }
+    // the three-letter English abbreviation of the month
+    pub(crate) fn abbreviation(self) -> &'static str {
+        Locale::En.month_abbreviation(self)
+    }
 
-        let day = x / 24 / 60 / 60;
-        x -= day * 24 * 60 * 60;
+    /// returns the number of days in the month in the given year
+    /// ```
+    /// # use datetime::Month;
+    /// assert_eq!(Month::February.days(2024), 29);
+    /// assert_eq!(Month::February.days(1900), 28);
+    /// ```
+    pub const fn days(self, year: usize) -> usize {
+        days_in_month(year, self as usize + 1)
+    }
+}
+
+impl fmt::Display for Day {
+    /// writes the English name of the day, e.g. `Monday`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
 
-        let hour = x / 60 / 60;
-        x -= hour * 60 * 60;
+impl fmt::Display for Month {
+    /// writes the English name of the month, e.g. `September`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
 
-        let minute = x / 60;
-        x -= minute * 60;
+/// a wrapper for a [`Day`] or [`Month`] which displays its three-letter
+/// English abbreviation
+/// ```
+/// # use datetime::{Day, Month, ShortName};
+/// assert_eq!(ShortName(Day::Monday).to_string(), "Mon");
+/// assert_eq!(format!("{:>5}", ShortName(Month::September)), "  Sep");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShortName<T>(pub T);
 
-        let date_day = get_day(secs);
+/// a wrapper for a [`Day`] or [`Month`] which displays its full English
+/// name, the same as its own Display impl
+/// ```
+/// # use datetime::{Month, LongName};
+/// assert_eq!(LongName(Month::September).to_string(), "September");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LongName<T>(pub T);
 
-        DtCache {
-            year: date_year,
-            month: date_month,
-            day: date_day,
-            date: day + 1,
-            hour: hour,
-            minute: minute,
-            second: x,
-        }
+impl fmt::Display for ShortName<Day> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.0.abbreviation())
     }
 }
 
-/// A struct storing a date and time as measured in UTC
-pub struct DateTime {
-    secs: usize,
-    cache: Cache<DtCache>,
+impl fmt::Display for ShortName<Month> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.0.abbreviation())
+    }
+}
+
+impl fmt::Display for LongName<Day> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for LongName<Month> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// an error returned when a string is not the name of a [`Day`] or
+/// [`Month`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseNameError;
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not the name of a day or month")
+    }
+}
+
+impl core::error::Error for ParseNameError {}
+
+// finds the value whose English name, or its three-letter abbreviation,
+// is `s` in any case
+fn from_name<T: Copy>(values: &[T], name: fn(T) -> &'static str, s: &str) -> Option<T> {
+    values.iter().cloned().find(|&v| {
+        let name = name(v);
+        name.eq_ignore_ascii_case(s) || name[..3].eq_ignore_ascii_case(s)
+    })
+}
+
+impl FromStr for Day {
+    type Err = ParseNameError;
+
+    /// parses the English name of a day, or its three-letter abbreviation,
+    /// in any case
+    /// ```
+    /// # use datetime::Day;
+    /// assert_eq!("friday".parse(), Ok(Day::Friday));
+    /// assert_eq!("Tue".parse(), Ok(Day::Tuesday));
+    /// assert!("Fri.".parse::<Day>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        from_name(&DAYS, Day::name, s).ok_or(ParseNameError)
+    }
+}
+
+impl FromStr for Month {
+    type Err = ParseNameError;
+
+    /// parses the English name of a month, or its three-letter
+    /// abbreviation, in any case
+    /// ```
+    /// # use datetime::Month;
+    /// assert_eq!("sep".parse(), Ok(Month::September));
+    /// assert_eq!("SEPTEMBER".parse(), Ok(Month::September));
+    /// assert!("Sept".parse::<Month>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        from_name(&MONTHS, Month::name, s).ok_or(ParseNameError)
+    }
+}
+
+// every day, in the order of the Day enum
+const DAYS: [Day; 7] = [
+    Day::Sunday,
+    Day::Monday,
+    Day::Tuesday,
+    Day::Wednesday,
+    Day::Thursday,
+    Day::Friday,
+    Day::Saturday,
+];
+
+// every month, in calendar order
+const MONTHS: [Month; 12] = [
+    Month::January,
+    Month::February,
+    Month::March,
+    Month::April,
+    Month::May,
+    Month::June,
+    Month::July,
+    Month::August,
+    Month::September,
+    Month::October,
+    Month::November,
+    Month::December,
+];
+
+// the calendar fields of a DateTime
+#[derive(Debug, Copy, Clone)]
+struct CalendarFields {
+    year: usize,
+    month: Month,
+    day: Day,
+    date: usize,
+    day_of_year: usize,
+    hour: usize,
+    minute: usize,
+    second: usize,
 }
 
-fn is_leap_year(year: usize) -> bool {
-    if year % 400 == 0 {
-        true
-    } else if year % 100 == 0 {
-        false
-    } else if year % 4 == 0 {
-        true
-    } else {
-        false
+impl CalendarFields {
+    // computes the calendar fields of the given instant as seen from a zone
+    // `offset` ahead of UTC
+    const fn new(secs: i64, offset: FixedOffset) -> Self {
+        let local = secs + offset.seconds() as i64;
+        let days = local.div_euclid(86_400);
+        let secs = local.rem_euclid(86_400) as usize;
+
+        let (year, month, date) = civil_from_days(days);
+
+        CalendarFields {
+            year: year as usize,
+            month: MONTHS[month - 1],
+            day: weekday_from_days(days),
+            date,
+            day_of_year: cumulative_days(year as usize)[month - 1] as usize + date,
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+        }
     }
 }
 
-fn get_day(time: usize) -> Day {
-    let day = time / 24 / 60 / 60;
-    let day = day + 4;
-    let day = day % 7;
+/// A struct storing a date and time as measured in UTC, along with the
+/// offset from UTC its calendar fields are reported in
+///
+/// DateTimes are compared, ordered and hashed by the instant they
+/// represent, so the same instant seen in two different offsets is equal
+///
+/// only the instant and offset are stored, so DateTimes are small and
+/// `Copy`, and calendar fields are worked out when asked for
+///
+/// DateTimes run from the start of the year 1, [`DateTime::MIN`], to
+/// [`DateTime::MAX`] in the year 4294967295, on 32-bit and 64-bit targets
+/// alike. the `from_*` constructors panic outside that range, and the
+/// `try_from_*` ones return an error
+///
+/// the constructors from timestamps and the calendar field accessors are
+/// `const fn`s, so DateTimes can be built and read at compile time, for
+/// build metadata or static tables of dates
+/// ```
+/// # use datetime::{DateTime, Month};
+/// const RELEASE: DateTime = DateTime::from_secs(1_700_000_000);
+/// const RELEASE_YEAR: usize = RELEASE.year();
+///
+/// assert_eq!(RELEASE_YEAR, 2023);
+/// assert_eq!(RELEASE.month(), Month::November);
+/// ```
+#[derive(Copy, Clone)]
+pub struct DateTime {
+    secs: i64,
+    nanos: u32,
+    offset: FixedOffset,
+}
+
+// DateTimes can be shared between threads
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DateTime>();
+};
+
+// the first second of 0001-01-01, the earliest supported time
+const MIN_SECS: i64 = -62_135_596_800;
 
-    match day {
-        0 => Day::Sunday,
-        1 => Day::Monday,
-        2 => Day::Tuesday,
-        3 => Day::Wednesday,
-        4 => Day::Thursday,
-        5 => Day::Friday,
-        6 => Day::Saturday,
-        _ => unreachable!(),
+// the Julian Day Number of 1970-01-01
+const UNIX_EPOCH_JULIAN_DAY: i64 = 2_440_588;
+
+// the latest supported second, 4294967295-12-30T23:59:59Z, which is a
+// day before the end of the last year a 32-bit usize can hold, so that
+// the year is the same on every target in any offset from UTC
+const MAX_SECS: i64 = 135_536_014_634_198_399;
+
+// the number of ISO 8601 weeks in the given year (52 or 53)
+pub(crate) fn iso_weeks_in_year(year: usize) -> usize {
+    match weekday(year, 1, 1) {
+        Day::Thursday => 53,
+        Day::Wednesday if is_leap_year(year) => 53,
+        _ => 52,
     }
 }
 
 impl DateTime {
+    /// the earliest DateTime, the start of 0001-01-01 in UTC
+    pub const MIN: DateTime = DateTime::from_parts(MIN_SECS, 0, FixedOffset::utc());
+
+    /// the latest DateTime, the last nanosecond of 4294967295-12-30 in UTC
+    /// ```
+    /// # use datetime::DateTime;
+    /// assert_eq!(DateTime::MAX.year(), 4_294_967_295);
+    /// assert!(DateTime::MAX.checked_add_secs(86_400).is_none());
+    /// ```
+    pub const MAX: DateTime = DateTime::from_parts(MAX_SECS, 999_999_999, FixedOffset::utc());
+
     /// return a DateTime corresponding to the current system time
     /// ```
     /// # use datetime::DateTime;
@@ -167,17 +528,68 @@ impl DateTime {
     ///
     /// println!("The current time is {}", time_stamp);
     /// ```
+    ///
+    /// see the [`clock`] module for the clocks used on WASI and SGX targets
+    ///
+    /// # Panics
+    ///
+    /// panics if the system clock is set before the year 1, or too far in
+    /// the future for a DateTime; see [`DateTime::try_now`]
+    #[cfg(feature = "std")]
     pub fn now() -> Self {
-        let secs = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as usize;
+        DateTime::try_now().expect("the system clock is outside the range of a DateTime")
+    }
+
+    /// like [`DateTime::now`], but returns an error instead of panicking
+    /// if the system clock is set outside the range of a DateTime, for
+    /// library code that shouldn't panic because of a misconfigured clock
+    ///
+    /// times before the Unix epoch are fine
+    #[cfg(feature = "std")]
+    pub fn try_now() -> Result<Self, RangeError> {
+        let (secs, nanos) = clock::now();
+        if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+            return Err(RangeError);
+        }
+
+        Ok(DateTime::from_parts(secs, nanos, FixedOffset::utc()))
+    }
 
-        DateTime::from_secs(secs)
+    /// like [`DateTime::now`], but reads a cheaper clock with a resolution
+    /// of around a millisecond (`CLOCK_REALTIME_COARSE` on Linux), for code
+    /// stamping many events a second that can tolerate that
+    ///
+    /// on other platforms this is the same as `now`
+    #[cfg(feature = "std")]
+    pub fn now_coarse() -> Self {
+        let (secs, nanos) = clock::now_coarse();
+        DateTime::from_parts(secs, nanos, FixedOffset::utc())
+    }
+
+    /// returns the number of whole days since 1970-01-01 in UTC, which is
+    /// negative before it, without building a DateTime
+    ///
+    /// this reads the same clock as [`DateTime::now_coarse`], and is meant
+    /// for "once a day" guards and cache keys in hot paths
+    /// ```
+    /// # use datetime::DateTime;
+    /// let today = DateTime::now_utc_epoch_day();
+    /// let now = DateTime::now().as_secs().div_euclid(86_400);
+    /// // unless midnight passed in between
+    /// assert!(now == today || now == today + 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn now_utc_epoch_day() -> i64 {
+        clock::now_coarse().0.div_euclid(86_400)
     }
 
     /// returns a DateTime corresponding to a given length of time
     /// (in seconds)
+    ///
+    /// # Panics
+    ///
+    /// panics if the time is after [`DateTime::MAX`]; see
+    /// [`DateTime::try_from_secs`]
     /// ```
     /// # use datetime::{DateTime, Day, Month};
     /// let mut date = DateTime::from_secs(842282624);
@@ -190,192 +602,1124 @@ impl DateTime {
     /// assert_eq!(date.minute(), 23);
     /// assert_eq!(date.second(), 44);
     /// ```
-    pub fn from_secs(secs: usize) -> Self {
-        DateTime {
-            secs,
-            cache: Cache::new(Box::new(move || DtCache::from_secs(secs))),
-        }
+    ///
+    /// a 32-bit usize can't count past 2106, so seconds from other
+    /// systems are better given to [`DateTime::from_signed_secs`]
+    pub const fn from_secs(secs: usize) -> Self {
+        // a usize always fits in a u64
+        assert!(
+            secs as u64 <= MAX_SECS as u64,
+            "DateTime too far in the future"
+        );
+        DateTime::from_signed_secs(secs as i64)
     }
 
-    /// returns the DateTime's year
+    /// like [`DateTime::from_secs`], but returns an error instead of
+    /// panicking if the time is outside the range of a DateTime
     /// ```
     /// # use datetime::DateTime;
-    /// let mut date = DateTime::from_secs(842282624);
-    ///
-    /// assert_eq!(date.year(), 1996);
+    /// assert_eq!(DateTime::try_from_secs(842282624), Ok(DateTime::from_secs(842282624)));
     /// ```
-    pub fn year(&self) -> usize {
-        self.cache.get().year
+    pub const fn try_from_secs(secs: usize) -> Result<Self, RangeError> {
+        if secs as u64 > MAX_SECS as u64 {
+            return Err(RangeError);
+        }
+        DateTime::try_from_parts(secs as i64, 0)
     }
 
-    /// returns the DateTime's month
+    /// returns a DateTime corresponding to a given number of seconds
+    /// before (if negative) or after the Unix epoch
+    ///
+    /// # Panics
+    ///
+    /// panics if the time is outside the range of a DateTime; see
+    /// [`DateTime::try_from_signed_secs`]
     /// ```
-    /// # use datetime::DateTime;
-    /// # use datetime::Month;
-    /// let mut date = DateTime::from_secs(842282624);
+    /// # use datetime::{DateTime, Day, Month};
+    /// let date = DateTime::from_signed_secs(-14182940);
     ///
-    /// assert_eq!(date.month(), Month::September);
+    /// assert_eq!(date.year(), 1969);
+    /// assert_eq!(date.month(), Month::July);
+    /// assert_eq!(date.day(), Day::Sunday);
+    /// assert_eq!(date.date(), 20);
+    /// assert_eq!(date.hour(), 20);
+    /// assert_eq!(date.minute(), 17);
+    /// assert_eq!(date.second(), 40);
     /// ```
-    pub fn month(&self) -> Month {
-        self.cache.get().month
+    pub const fn from_signed_secs(secs: i64) -> Self {
+        DateTime::from_parts(secs, 0, FixedOffset::utc())
     }
 
-    /// returns the DateTime's day
+    /// like [`DateTime::from_signed_secs`], but returns an error instead
+    /// of panicking if the time is outside the range of a DateTime
     /// ```
-    /// # use datetime::DateTime;
-    /// # use datetime::Day;
-    /// let mut date = DateTime::from_secs(842282624);
-    ///
-    /// assert_eq!(date.day(), Day::Monday);
+    /// # use datetime::{DateTime, RangeError};
+    /// assert_eq!(DateTime::try_from_signed_secs(0), Ok(DateTime::from_secs(0)));
+    /// assert_eq!(DateTime::try_from_signed_secs(i64::MAX), Err(RangeError));
     /// ```
-    pub fn day(&self) -> Day {
-        self.cache.get().day
+    pub const fn try_from_signed_secs(secs: i64) -> Result<Self, RangeError> {
+        DateTime::try_from_parts(secs, 0)
     }
 
-    /// returns the DateTime's date
+    /// like [`DateTime::from_millis`], but returns an error instead of
+    /// panicking if the time is outside the range of a DateTime
+    pub const fn try_from_millis(millis: i64) -> Result<Self, RangeError> {
+        DateTime::try_from_parts(
+            millis.div_euclid(1000),
+            millis.rem_euclid(1000) as u32 * 1_000_000,
+        )
+    }
+
+    /// like [`DateTime::from_micros`], but returns an error instead of
+    /// panicking if the time is outside the range of a DateTime
+    pub const fn try_from_micros(micros: i64) -> Result<Self, RangeError> {
+        DateTime::try_from_parts(
+            micros.div_euclid(1_000_000),
+            micros.rem_euclid(1_000_000) as u32 * 1000,
+        )
+    }
+
+    // a DateTime in UTC, or an error if it is out of range
+    const fn try_from_parts(secs: i64, nanos: u32) -> Result<Self, RangeError> {
+        if secs < MIN_SECS || secs > MAX_SECS {
+            return Err(RangeError);
+        }
+
+        Ok(DateTime::from_parts(secs, nanos, FixedOffset::utc()))
+    }
+
+    /// returns a DateTime corresponding to a given number of milliseconds
+    /// before (if negative) or after the Unix epoch
+    ///
+    /// # Panics
+    ///
+    /// panics if the time is outside the range of a DateTime, from the
+    /// start of the year 1 to [`DateTime::MAX`]; since an i64 of
+    /// milliseconds can't reach the end, only times before the year 1 do.
+    /// see [`DateTime::try_from_millis`]
     /// ```
     /// # use datetime::DateTime;
-    /// let mut date = DateTime::from_secs(842282624);
+    /// let date = DateTime::from_millis(842282624123);
     ///
-    /// assert_eq!(date.date(), 9);
+    /// assert_eq!(date.second(), 44);
+    /// assert_eq!(date.millisecond(), 123);
     /// ```
-    pub fn date(&self) -> usize {
-        self.cache.get().date
+    pub const fn from_millis(millis: i64) -> Self {
+        let secs = millis.div_euclid(1000);
+        let nanos = millis.rem_euclid(1000) as u32 * 1_000_000;
+
+        DateTime::from_parts(secs, nanos, FixedOffset::utc())
     }
 
-    /// returns the DateTime's hour
+    /// returns a DateTime corresponding to a given number of nanoseconds
+    /// before (if negative) or after the Unix epoch
     /// ```
     /// # use datetime::DateTime;
-    /// let mut date = DateTime::from_secs(842282624);
+    /// let date = DateTime::from_nanos(-1);
     ///
-    /// assert_eq!(date.hour(), 15);
+    /// assert_eq!(date.year(), 1969);
+    /// assert_eq!(date.second(), 59);
+    /// assert_eq!(date.nanosecond(), 999_999_999);
     /// ```
-    pub fn hour(&self) -> usize {
-        self.cache.get().hour
+    pub const fn from_nanos(nanos: i64) -> Self {
+        let secs = nanos.div_euclid(1_000_000_000);
+        let nanos = nanos.rem_euclid(1_000_000_000) as u32;
+
+        DateTime::from_parts(secs, nanos, FixedOffset::utc())
     }
 
-    /// returns the DateTime's minute
+    /// returns a DateTime corresponding to a given number of microseconds
+    /// before (if negative) or after the Unix epoch
+    ///
+    /// # Panics
+    ///
+    /// panics if the time is outside the range of a DateTime, from the
+    /// start of the year 1 to [`DateTime::MAX`]; since an i64 of
+    /// microseconds can't reach the end, only times before the year 1 do.
+    /// see [`DateTime::try_from_micros`]
     /// ```
     /// # use datetime::DateTime;
-    /// let mut date = DateTime::from_secs(842282624);
+    /// let date = DateTime::from_micros(842282624123456);
     ///
-    /// assert_eq!(date.minute(), 23);
+    /// assert_eq!(date.nanosecond(), 123_456_000);
     /// ```
-    pub fn minute(&self) -> usize {
-        self.cache.get().minute
+    pub const fn from_micros(micros: i64) -> Self {
+        let secs = micros.div_euclid(1_000_000);
+        let nanos = micros.rem_euclid(1_000_000) as u32 * 1000;
+
+        DateTime::from_parts(secs, nanos, FixedOffset::utc())
     }
 
-    /// returns the DateTime's second
+    /// returns the whole seconds since the Unix epoch, rounded towards the
+    /// past, so that it is negative before the epoch
     /// ```
     /// # use datetime::DateTime;
-    /// let mut date = DateTime::from_secs(842282624);
-    ///
-    /// assert_eq!(date.second(), 44);
+    /// assert_eq!(DateTime::from_millis(842282624999).as_secs(), 842282624);
+    /// assert_eq!(DateTime::from_millis(-1).as_secs(), -1);
     /// ```
-    pub fn second(&self) -> usize {
-        self.cache.get().second
+    pub const fn as_secs(&self) -> i64 {
+        self.secs
     }
 
-    /// returns a String representing the time stamp of a DateTime
+    /// returns the whole milliseconds since the Unix epoch, rounded
+    /// towards the past; the inverse of [`DateTime::from_millis`]
+    ///
+    /// this is an i128, like `Duration::as_millis`, since the latest
+    /// DateTimes are too far from the epoch for an i64 of milliseconds
     /// ```
     /// # use datetime::DateTime;
-    /// let mut date = DateTime::from_secs(842282624);
-    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+    /// let date = DateTime::from_millis(842282624123);
+    /// assert_eq!(date.as_millis(), 842282624123);
     /// ```
-    pub fn as_time_stamp(&self) -> String {
-        let day = match self.day() {
-            Day::Sunday => "Sun",
-            Day::Monday => "Mon",
-            Day::Tuesday => "Tue",
-            Day::Wednesday => "Wed",
-            Day::Thursday => "Thu",
-            Day::Friday => "Fri",
-            Day::Saturday => "Sat",
-        };
+    pub const fn as_millis(&self) -> i128 {
+        self.secs as i128 * 1000 + (self.nanos / 1_000_000) as i128
+    }
 
-        let month = match self.month() {
-            Month::January => "Jan",
-            Month::February => "Feb",
-            Month::March => "Mar",
-            Month::April => "Apr",
-            Month::May => "May",
-            Month::June => "Jun",
-            Month::July => "Jul",
-            Month::August => "Aug",
-            Month::September => "Sep",
-            Month::October => "Oct",
-            Month::November => "Nov",
-            Month::December => "Dec",
-        };
+    /// returns the whole microseconds since the Unix epoch, rounded
+    /// towards the past; the inverse of [`DateTime::from_micros`]
+    pub const fn as_micros(&self) -> i128 {
+        self.secs as i128 * 1_000_000 + (self.nanos / 1000) as i128
+    }
 
-        format!(
-            "{} {} {}, {}  {}:{:02}:{:02} (UTC)",
-            day,
-            month,
-            self.date(),
-            self.year(),
-            self.hour(),
-            self.minute(),
-            self.second()
-        )
+    /// returns the nanoseconds since the Unix epoch; the inverse of
+    /// [`DateTime::from_nanos`]
+    pub const fn as_nanos(&self) -> i128 {
+        self.secs as i128 * 1_000_000_000 + self.nanos as i128
     }
-}
 
-impl From<SystemTime> for DateTime {
-    fn from(time: SystemTime) -> Self {
-        let secs = time.duration_since(UNIX_EPOCH).unwrap().as_secs() as usize;
+    // nanos must be less than a second
+    pub(crate) const fn from_parts(secs: i64, nanos: u32, offset: FixedOffset) -> Self {
+        assert!(secs >= MIN_SECS, "DateTime before the year 1");
+        assert!(secs <= MAX_SECS, "DateTime too far in the future");
 
-        Self::from_secs(secs)
+        DateTime {
+            secs,
+            nanos,
+            offset,
+        }
     }
-}
 
-impl Add<&DateTime> for DateTime {
-    type Output = DateTime;
+    // the calendar fields, computed on each use
+    const fn fields(&self) -> CalendarFields {
+        CalendarFields::new(self.secs, self.offset)
+    }
 
-    fn add(self, other: &DateTime) -> Self {
-        let secs = self.secs + other.secs;
+    /// returns a DateTime for the same instant whose calendar fields are
+    /// reported in the given offset from UTC
+    /// ```
+    /// # use datetime::{DateTime, Day, FixedOffset};
+    /// let offset = FixedOffset::from_hours_minutes(10, 0).unwrap();
+    /// let date = DateTime::from_secs(842282624).with_offset(offset);
+    ///
+    /// assert_eq!(date.day(), Day::Tuesday);
+    /// assert_eq!(date.date(), 10);
+    /// assert_eq!(date.hour(), 1);
+    /// assert_eq!(date.as_time_stamp(), "Tue Sep 10, 1996  1:23:44 (UTC+10:00)");
+    /// ```
+    pub const fn with_offset(&self, offset: FixedOffset) -> DateTime {
+        DateTime::from_parts(self.secs, self.nanos, offset)
+    }
 
-        DateTime::from_secs(secs)
+    /// returns a DateTime for the same instant whose calendar fields are
+    /// reported in the given offset from UTC (see
+    /// [`FixedOffset::from_hours_minutes`]), or None if the offset is
+    /// invalid
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624).to_offset(-4, 0).unwrap();
+    ///
+    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  11:23:44 (UTC-04:00)");
+    /// ```
+    pub fn to_offset(&self, hours: i32, minutes: i32) -> Option<DateTime> {
+        FixedOffset::from_hours_minutes(hours, minutes).map(|offset| self.with_offset(offset))
     }
-}
 
-impl AddAssign<&DateTime> for DateTime {
-    fn add_assign(&mut self, other: &DateTime) {
-        self.secs += other.secs;
+    /// returns the offset from UTC the DateTime's fields are reported in
+    pub const fn offset(&self) -> FixedOffset {
+        self.offset
+    }
 
-        let secs = self.secs;
-        self.cache = Cache::new(Box::new(move || DtCache::from_secs(secs)));
+    /// returns the DateTime the given number of seconds later, or None if
+    /// that is outside the range a DateTime can represent
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.checked_add_secs(3600).unwrap().hour(), 16);
+    /// assert!(date.checked_add_secs(i64::MAX).is_none());
+    /// ```
+    pub fn checked_add_secs(&self, secs: i64) -> Option<DateTime> {
+        self.checked_add_parts(secs, 0)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{DateTime, Day, Month};
+    /// returns the DateTime the given number of seconds earlier, or None if
+    /// that is outside the range a DateTime can represent
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.checked_sub_secs(3600).unwrap().hour(), 14);
+    /// assert!(date.checked_sub_secs(100_000_000_000).is_none());
+    /// ```
+    pub fn checked_sub_secs(&self, secs: i64) -> Option<DateTime> {
+        self.checked_add_parts(secs.checked_neg()?, 0)
+    }
 
-    #[test]
-    fn test_from_secs() {
-        let secs = 842282624;
+    /// returns the DateTime the given number of seconds later, clamped to
+    /// the range a DateTime can represent
+    pub fn saturating_add_secs(&self, secs: i64) -> DateTime {
+        self.checked_add_secs(secs)
+            .unwrap_or_else(|| self.clamped(secs >= 0))
+    }
 
-        let date = DateTime::from_secs(secs);
+    /// returns the DateTime the given number of seconds earlier, clamped to
+    /// the range a DateTime can represent
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624).saturating_sub_secs(i64::MAX);
+    ///
+    /// assert_eq!(date.as_time_stamp(), "Mon Jan 1, 1  0:00:00 (UTC)");
+    /// ```
+    pub fn saturating_sub_secs(&self, secs: i64) -> DateTime {
+        self.checked_sub_secs(secs)
+            .unwrap_or_else(|| self.clamped(secs < 0))
+    }
 
-        assert_eq!(date.year(), 1996);
-        assert_eq!(date.month(), Month::September);
-        assert_eq!(date.day(), Day::Monday);
-        assert_eq!(date.date(), 9);
-        assert_eq!(date.hour(), 15);
-        assert_eq!(date.minute(), 23);
-        assert_eq!(date.second(), 44);
+    /// like `self + other`, but returns None instead of panicking if the
+    /// result is outside the range a DateTime can represent
+    #[deprecated(note = "adding two DateTimes isn't meaningful; use `checked_add_span` instead")]
+    pub fn checked_add(&self, other: &DateTime) -> Option<DateTime> {
+        self.checked_add_parts(other.secs, other.nanos)
     }
 
-    #[test]
-    fn test_add() {
-        let date = DateTime::from_secs(123456789);
-        let date2 = DateTime::from_secs(234567890);
+    /// like `self + other`, but clamps the result to the range a DateTime
+    /// can represent instead of panicking
+    #[deprecated(note = "adding two DateTimes isn't meaningful; use `checked_add_span` instead")]
+    pub fn saturating_add(&self, other: &DateTime) -> DateTime {
+        #[allow(deprecated)]
+        self.checked_add(other)
+            .unwrap_or_else(|| self.clamped(other.secs >= 0))
+    }
 
-        let date = date + &date2;
+    /// returns the DateTime the given Duration later, or None if that is
+    /// outside the range a DateTime can represent
+    pub fn checked_add_duration(&self, duration: Duration) -> Option<DateTime> {
+        let secs = i64::try_from(duration.as_secs()).ok()?;
+        self.checked_add_parts(secs, duration.subsec_nanos())
+    }
 
-        assert_eq!(date.year(), 1981);
-        assert_eq!(date.month(), Month::May);
+    /// returns the DateTime the given Duration earlier, or None if that is
+    /// outside the range a DateTime can represent
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::Duration;
+    /// let date = DateTime::from_secs(0);
+    /// let earlier = date.checked_sub_duration(Duration::from_millis(1)).unwrap();
+    ///
+    /// assert_eq!(earlier.as_time_stamp_with_fraction(3), "Wed Dec 31, 1969  23:59:59.999 (UTC)");
+    /// assert!(date.checked_sub_duration(Duration::from_secs(u64::MAX)).is_none());
+    /// ```
+    pub fn checked_sub_duration(&self, duration: Duration) -> Option<DateTime> {
+        let secs = i64::try_from(duration.as_secs()).ok()?;
+        match duration.subsec_nanos() {
+            0 => self.checked_add_parts(secs.checked_neg()?, 0),
+            // borrow a second to subtract the nanoseconds
+            nanos => self.checked_add_parts((-secs).checked_sub(1)?, 1_000_000_000 - nanos),
+        }
+    }
+
+    // adds seconds and nanoseconds (less than a second), keeping the offset
+    fn checked_add_parts(&self, secs: i64, nanos: u32) -> Option<DateTime> {
+        let nanos = self.nanos + nanos;
+        let secs = self
+            .secs
+            .checked_add(secs)?
+            .checked_add((nanos / 1_000_000_000) as i64)?;
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(
+                secs,
+                nanos % 1_000_000_000,
+                self.offset,
+            ))
+        } else {
+            None
+        }
+    }
+
+    // the latest (or earliest) representable DateTime, in self's offset
+    fn clamped(&self, latest: bool) -> DateTime {
+        if latest {
+            DateTime::from_parts(MAX_SECS, 999_999_999, self.offset)
+        } else {
+            DateTime::from_parts(MIN_SECS, 0, self.offset)
+        }
+    }
+
+    /// returns the DateTime's year
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.year(), 1996);
+    /// ```
+    pub const fn year(&self) -> usize {
+        self.fields().year
+    }
+
+    /// returns the DateTime's month
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use datetime::Month;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.month(), Month::September);
+    /// ```
+    pub const fn month(&self) -> Month {
+        self.fields().month
+    }
+
+    /// returns the DateTime's day
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use datetime::Day;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.day(), Day::Monday);
+    /// ```
+    pub const fn day(&self) -> Day {
+        self.fields().day
+    }
+
+    /// returns the DateTime's date
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.date(), 9);
+    /// ```
+    pub const fn date(&self) -> usize {
+        self.fields().date
+    }
+
+    /// returns the DateTime's day of the year, from 1 to 366
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.day_of_year(), 253);
+    /// ```
+    pub const fn day_of_year(&self) -> usize {
+        self.fields().day_of_year
+    }
+
+    /// returns the number of days in the DateTime's month
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.days_in_month(), 30);
+    /// ```
+    pub const fn days_in_month(&self) -> usize {
+        self.month().days(self.year())
+    }
+
+    /// returns the number of days in the DateTime's year, 365 or 366
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.days_in_year(), 366);
+    /// ```
+    pub const fn days_in_year(&self) -> usize {
+        cumulative_days(self.year())[12] as usize
+    }
+
+    /// returns the quarter of the year the DateTime falls in, from 1 to 4
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.quarter(), 3);
+    /// ```
+    pub const fn quarter(&self) -> usize {
+        self.month() as usize / 3 + 1
+    }
+
+    /// returns the week of the month the DateTime falls in, from 1 to 6,
+    /// with weeks starting on Sunday as in a wall calendar; week 1 is the
+    /// one containing the 1st
+    /// ```
+    /// # use datetime::DateTime;
+    /// // Monday 1996-09-09; September 1996 started on a Sunday
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.week_of_month(), 2);
+    /// ```
+    pub const fn week_of_month(&self) -> usize {
+        let fields = self.fields();
+        // the weekday of the 1st, counting from Sunday as 0
+        let first = (fields.day as usize + 35 - (fields.date - 1)) % 7;
+        (fields.date - 1 + first) / 7 + 1
+    }
+
+    /// returns true if the DateTime falls on the last day of its month
+    /// ```
+    /// # use datetime::DateTime;
+    /// // 2000-02-29
+    /// let date = DateTime::from_secs(951782400);
+    ///
+    /// assert!(date.is_last_day_of_month());
+    /// ```
+    pub const fn is_last_day_of_month(&self) -> bool {
+        self.date() == self.days_in_month()
+    }
+
+    /// returns the start of the given day of the year (1-366), in UTC, or
+    /// None if the year is 0, past the last DateTime, or doesn't have that
+    /// many days
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_ordinal(1996, 253).unwrap();
+    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  0:00:00 (UTC)");
+    ///
+    /// assert!(DateTime::from_ordinal(1997, 366).is_none());
+    /// ```
+    pub const fn from_ordinal(year: usize, day_of_year: usize) -> Option<DateTime> {
+        let days_in_year = cumulative_days(year)[12] as usize;
+        if year < 1 || year > DateTime::MAX.year() || day_of_year < 1 || day_of_year > days_in_year
+        {
+            return None;
+        }
+
+        let days = days_from_civil(year as i64, 1, 1) + day_of_year as i64 - 1;
+        if days * 86_400 > MAX_SECS {
+            return None;
+        }
+        Some(DateTime::from_signed_secs(days * 86_400))
+    }
+
+    /// returns the DateTime's hour
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.hour(), 15);
+    /// ```
+    pub const fn hour(&self) -> usize {
+        self.fields().hour
+    }
+
+    /// returns the DateTime's minute
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.minute(), 23);
+    /// ```
+    pub const fn minute(&self) -> usize {
+        self.fields().minute
+    }
+
+    /// returns the DateTime's second
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.second(), 44);
+    /// ```
+    pub const fn second(&self) -> usize {
+        self.fields().second
+    }
+
+    /// returns the fraction of the DateTime's second in milliseconds
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_nanos(842282624123456789);
+    ///
+    /// assert_eq!(date.millisecond(), 123);
+    /// ```
+    pub const fn millisecond(&self) -> usize {
+        self.nanos as usize / 1_000_000
+    }
+
+    /// returns the fraction of the DateTime's second in microseconds
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_nanos(842282624123456789);
+    ///
+    /// assert_eq!(date.microsecond(), 123456);
+    /// ```
+    pub const fn microsecond(&self) -> usize {
+        self.nanos as usize / 1000
+    }
+
+    /// returns the fraction of the DateTime's second in nanoseconds
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_nanos(842282624123456789);
+    ///
+    /// assert_eq!(date.nanosecond(), 123456789);
+    /// ```
+    pub const fn nanosecond(&self) -> usize {
+        self.nanos as usize
+    }
+
+    /// returns the Julian Day Number of the DateTime's date in UTC; the
+    /// number of days since 1 January 4713 BC in the proleptic Julian
+    /// calendar, so that 1970-01-01 is day 2440588
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.to_julian_day(), 2450336);
+    /// ```
+    pub fn to_julian_day(&self) -> i64 {
+        self.secs.div_euclid(86_400) + UNIX_EPOCH_JULIAN_DAY
+    }
+
+    /// returns the start (midnight UTC) of the date with the given Julian
+    /// Day Number, or None if it is outside the range of a DateTime
+    pub fn from_julian_day(jdn: i64) -> Option<DateTime> {
+        let secs = jdn
+            .checked_sub(UNIX_EPOCH_JULIAN_DAY)?
+            .checked_mul(86_400)?;
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_signed_secs(secs))
+        } else {
+            None
+        }
+    }
+
+    /// returns the astronomical Julian Date of the DateTime: the days since
+    /// noon UTC on 1 January 4713 BC (Julian calendar), with the time of
+    /// day as a fraction. Julian Dates start at noon, so midnight is `.5`
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(946728000); // 2000-01-01 12:00
+    ///
+    /// assert_eq!(date.julian_date(), 2451545.0);
+    /// ```
+    pub fn julian_date(&self) -> f64 {
+        let days = self.secs.div_euclid(86_400);
+        let secs = self.secs.rem_euclid(86_400) as f64 + self.nanos as f64 / 1e9;
+
+        (days + UNIX_EPOCH_JULIAN_DAY) as f64 - 0.5 + secs / 86_400.0
+    }
+
+    /// returns the DateTime at the given astronomical Julian Date, or None
+    /// if it is not finite or outside the range of a DateTime
+    ///
+    /// a double only holds a Julian Date to within about 40 microseconds,
+    /// so the result is rounded to the nearest millisecond
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_julian_date(2451545.25).unwrap();
+    ///
+    /// assert_eq!(date.as_time_stamp(), "Sat Jan 1, 2000  18:00:00 (UTC)");
+    /// ```
+    pub fn from_julian_date(jd: f64) -> Option<DateTime> {
+        let days = jd - UNIX_EPOCH_JULIAN_DAY as f64 + 0.5;
+        let millis = days * 86_400_000.0;
+
+        // beyond this a double can't even hold whole milliseconds exactly
+        if !(-9.0e15..9.0e15).contains(&millis) {
+            return None;
+        }
+
+        // rounds half away from zero, as f64::round (which needs std) does
+        let whole = millis as i64;
+        let millis = match millis - whole as f64 {
+            fraction if fraction >= 0.5 => whole + 1,
+            fraction if fraction <= -0.5 => whole - 1,
+            _ => whole,
+        };
+        let secs = millis.div_euclid(1000);
+        if secs < MIN_SECS {
+            return None;
+        }
+
+        Some(DateTime::from_millis(millis))
+    }
+
+    /// returns the ISO 8601 week-based year and week number (1-53) of the
+    /// DateTime. weeks start on Monday, and week 1 is the week containing
+    /// the year's first Thursday, so the first few days of January can
+    /// belong to the previous year's last week and vice versa
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624);
+    /// assert_eq!(date.iso_week(), (1996, 37));
+    ///
+    /// // Sunday, January 3rd 2010
+    /// let date = DateTime::from_secs(1262476800);
+    /// assert_eq!(date.iso_week(), (2009, 53));
+    /// ```
+    pub fn iso_week(&self) -> (usize, usize) {
+        let year = self.year();
+        let weekday = (self.day() as usize + 6) % 7 + 1;
+        let week = (self.day_of_year() + 10 - weekday) / 7;
+
+        if week < 1 {
+            (year - 1, iso_weeks_in_year(year - 1))
+        } else if week > iso_weeks_in_year(year) {
+            (year + 1, 1)
+        } else {
+            (year, week)
+        }
+    }
+
+    /// returns the start of the given day of an ISO 8601 week, in UTC, or
+    /// None if the week doesn't exist in that week-based year or the date
+    /// is outside the range of a DateTime
+    /// ```
+    /// # use datetime::{DateTime, Day};
+    /// let date = DateTime::from_iso_week(2009, 1, Day::Monday).unwrap();
+    /// assert_eq!(date.as_time_stamp(), "Mon Dec 29, 2008  0:00:00 (UTC)");
+    ///
+    /// assert!(DateTime::from_iso_week(2010, 53, Day::Monday).is_none());
+    /// ```
+    pub fn from_iso_week(year: usize, week: usize, weekday: Day) -> Option<DateTime> {
+        if year < 1 || year > DateTime::MAX.year() || week < 1 || week > iso_weeks_in_year(year) {
+            return None;
+        }
+
+        // January 4th is always in week 1
+        let jan4 = days_from_civil(year as i64, 1, 4);
+        let monday = jan4 - ((weekday_from_days(jan4) as i64 + 6) % 7);
+        let days = monday + (week as i64 - 1) * 7 + (weekday as i64 + 6) % 7;
+
+        let secs = days.checked_mul(86_400)?;
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_signed_secs(secs))
+        } else {
+            None
+        }
+    }
+
+    /// returns a String representing the time stamp of a DateTime
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut date = DateTime::from_secs(842282624);
+    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+    /// ```
+    pub fn as_time_stamp(&self) -> String {
+        self.as_time_stamp_with(&StampOptions::new())
+    }
+
+    /// writes the time stamp returned by [`DateTime::as_time_stamp`] into
+    /// `w`, without allocating, e.g. into a buffer on the stack or a
+    /// [`fmt::Formatter`]
+    pub fn write_time_stamp<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        self.write_time_stamp_with(w, &StampOptions::new())
+    }
+
+    /// returns a String representing the time stamp of a DateTime, with
+    /// the given number of digits (at most 9) of fractional seconds
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_millis(842282624123);
+    /// assert_eq!(
+    ///     date.as_time_stamp_with_fraction(3),
+    ///     "Mon Sep 9, 1996  15:23:44.123 (UTC)"
+    /// );
+    /// ```
+    pub fn as_time_stamp_with_fraction(&self, digits: usize) -> String {
+        self.as_time_stamp_with(&StampOptions::new().fraction_digits(digits))
+    }
+
+    // the name of the DateTime's zone, e.g. `UTC` or `UTC+05:30`
+    pub(crate) fn zone_name(&self) -> String {
+        if self.offset.is_utc() {
+            "UTC".to_string()
+        } else {
+            format!("UTC{}", self.offset)
+        }
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// writes the same time stamp as [`DateTime::as_time_stamp`]; a
+    /// precision adds that many digits (at most 9) of fractional seconds,
+    /// like [`DateTime::as_time_stamp_with_fraction`]
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_millis(842282624123);
+    ///
+    /// assert_eq!(date.to_string(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+    /// assert_eq!(format!("{:.3}", date), "Mon Sep 9, 1996  15:23:44.123 (UTC)");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = StampOptions::new().fraction_digits(f.precision().unwrap_or(0));
+        self.write_stamp(f, None, &options)
+    }
+}
+
+impl fmt::Debug for DateTime {
+    /// shows the broken-down fields of the DateTime, along with the
+    /// instant it represents
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DateTime")
+            .field("year", &self.year())
+            .field("month", &self.month())
+            .field("date", &self.date())
+            .field("day", &self.day())
+            .field("hour", &self.hour())
+            .field("minute", &self.minute())
+            .field("second", &self.second())
+            .field("nanosecond", &self.nanos)
+            .field("offset", &self.offset)
+            .field("secs", &self.secs)
+            .finish()
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &DateTime) -> bool {
+        (self.secs, self.nanos) == (other.secs, other.nanos)
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &DateTime) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    /// orders DateTimes from earliest to latest
+    /// ```
+    /// # use datetime::DateTime;
+    /// let mut dates = vec![DateTime::from_secs(3), DateTime::from_signed_secs(-5)];
+    /// dates.sort();
+    ///
+    /// assert_eq!(dates[0].year(), 1969);
+    /// assert_eq!(dates.iter().max(), Some(&DateTime::from_secs(3)));
+    /// ```
+    fn cmp(&self, other: &DateTime) -> Ordering {
+        (self.secs, self.nanos).cmp(&(other.secs, other.nanos))
+    }
+}
+
+impl Hash for DateTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.secs.hash(state);
+        self.nanos.hash(state);
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<SystemTime> for DateTime {
+    type Error = RangeError;
+
+    /// converts a SystemTime, before or after the Unix epoch, to a
+    /// DateTime in UTC
+    ///
+    /// fails if the time is before the year 1 or too far in the future for
+    /// a DateTime
+    /// ```
+    /// # use datetime::{DateTime, RangeError};
+    /// # use std::convert::TryFrom;
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// let date = DateTime::try_from(UNIX_EPOCH + Duration::from_secs(842282624)).unwrap();
+    /// assert_eq!(date.year(), 1996);
+    ///
+    /// // the year 1 BC, which some platforms can represent
+    /// let ancient = UNIX_EPOCH.checked_sub(Duration::from_secs(62_135_596_801));
+    /// if let Some(ancient) = ancient {
+    ///     assert_eq!(DateTime::try_from(ancient), Err(RangeError));
+    /// }
+    /// ```
+    fn try_from(time: SystemTime) -> Result<Self, Self::Error> {
+        let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => clock::split(duration),
+            Err(e) => clock::split_before_epoch(e.duration()),
+        };
+        if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+            return Err(RangeError);
+        }
+
+        Ok(Self::from_parts(secs, nanos, FixedOffset::utc()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl DateTime {
+    /// converts the DateTime to a SystemTime, or returns None if the
+    /// platform's SystemTime can't represent it (Windows, for one, can't
+    /// go back before 1601)
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// let date = DateTime::from_millis(842282624250);
+    ///
+    /// assert_eq!(date.to_system_time(), Some(UNIX_EPOCH + Duration::from_millis(842282624250)));
+    /// ```
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let start = if self.secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(self.secs as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(self.secs.unsigned_abs()))
+        };
+
+        start?.checked_add(Duration::from_nanos(self.nanos.into()))
+    }
+
+    /// returns how long it is from `now` until this DateTime, or zero if
+    /// it has already passed, for driving timers from a computed deadline
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// let deadline = DateTime::from_secs(842282624);
+    /// let now = UNIX_EPOCH + Duration::from_secs(842282564);
+    ///
+    /// assert_eq!(deadline.duration_until_from(now), Duration::from_secs(60));
+    /// assert_eq!(deadline.duration_until_from(now + Duration::from_secs(61)), Duration::ZERO);
+    /// ```
+    pub fn duration_until_from(&self, now: SystemTime) -> Duration {
+        match now.duration_since(UNIX_EPOCH) {
+            Ok(duration) => self.duration_after(clock::split(duration)),
+            Err(e) => self.duration_after(clock::split_before_epoch(e.duration())),
+        }
+    }
+
+    /// blocks the current thread until the clock [`DateTime::now`] reads
+    /// has reached this DateTime, returning at once if it already has
+    ///
+    /// the clock is checked again after each sleep, so that the thread
+    /// doesn't wake early if the clock is set back while it is asleep
+    pub fn sleep_until(&self) {
+        loop {
+            let remaining = self.duration_after(clock::now());
+            if remaining == Duration::ZERO {
+                return;
+            }
+
+            std::thread::sleep(remaining);
+        }
+    }
+
+    // the time from the given seconds and nanoseconds since the epoch
+    // until this DateTime, or zero if it is earlier
+    fn duration_after(&self, (secs, nanos): (i64, u32)) -> Duration {
+        let remaining =
+            (self.secs as i128 - secs as i128) * 1_000_000_000 + self.nanos as i128 - nanos as i128;
+        if remaining <= 0 {
+            return Duration::ZERO;
+        }
+
+        Duration::new(
+            (remaining / 1_000_000_000) as u64,
+            (remaining % 1_000_000_000) as u32,
+        )
+    }
+}
+
+/// deprecated: adding two DateTimes isn't meaningful, so add a [`Span`]
+/// instead. this impl will be removed in a future release
+impl Add<&DateTime> for DateTime {
+    type Output = DateTime;
+
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent
+    fn add(self, other: &DateTime) -> Self {
+        #[allow(deprecated)]
+        self.checked_add(other)
+            .expect("overflow when adding DateTimes")
+    }
+}
+
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    /// returns the DateTime the given Duration later
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::Duration;
+    /// let date = DateTime::from_secs(842282624) + Duration::from_secs(3600);
+    ///
+    /// assert_eq!(date.hour(), 16);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent;
+    /// see [`DateTime::checked_add_duration`]
+    fn add(self, duration: Duration) -> Self {
+        self.checked_add_duration(duration)
+            .expect("overflow when adding a Duration to a DateTime")
+    }
+}
+
+impl AddAssign<Duration> for DateTime {
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent
+    fn add_assign(&mut self, duration: Duration) {
+        *self = self
+            .checked_add_duration(duration)
+            .expect("overflow when adding a Duration to a DateTime");
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    /// returns the DateTime the given Duration earlier
+    ///
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent;
+    /// see [`DateTime::checked_sub_duration`]
+    fn sub(self, duration: Duration) -> Self {
+        self.checked_sub_duration(duration)
+            .expect("overflow when subtracting a Duration from a DateTime")
+    }
+}
+
+impl SubAssign<Duration> for DateTime {
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = self
+            .checked_sub_duration(duration)
+            .expect("overflow when subtracting a Duration from a DateTime");
+    }
+}
+
+/// deprecated: adding two DateTimes isn't meaningful, so add a [`Span`]
+/// instead. this impl will be removed in a future release
+impl AddAssign<&DateTime> for DateTime {
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent
+    fn add_assign(&mut self, other: &DateTime) {
+        #[allow(deprecated)]
+        let sum = self.checked_add(other);
+        *self = sum.expect("overflow when adding DateTimes");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeError;
+    use super::{
+        DateTime, Day, FixedOffset, LongName, Month, ParseNameError, ShortName, MAX_SECS, MIN_SECS,
+    };
+    #[cfg(feature = "std")]
+    use core::convert::TryFrom;
+    use std::collections::HashSet;
+    use std::time::Duration;
+    #[cfg(feature = "std")]
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_from_secs() {
+        let secs = 842282624;
+
+        let date = DateTime::from_secs(secs);
+
+        assert_eq!(date.year(), 1996);
+        assert_eq!(date.month(), Month::September);
+        assert_eq!(date.day(), Day::Monday);
+        assert_eq!(date.date(), 9);
+        assert_eq!(date.hour(), 15);
+        assert_eq!(date.minute(), 23);
+        assert_eq!(date.second(), 44);
+    }
+
+    #[test]
+    fn test_epoch_units() {
+        let date = DateTime::from_nanos(-1);
+        assert_eq!(date.as_secs(), -1);
+        assert_eq!(date.as_millis(), -1);
+        assert_eq!(date.as_micros(), -1);
+        assert_eq!(date.as_nanos(), -1);
+
+        let date = DateTime::from_micros(-1_500_001);
+        assert_eq!((date.secs, date.nanos), (-2, 499_999_000));
+        assert_eq!(date.as_millis(), -1501);
+        assert_eq!(DateTime::from_micros(date.as_micros() as i64), date);
+
+        // far beyond what an i64 of nanoseconds can hold
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert_eq!(last.as_nanos(), MAX_SECS as i128 * 1_000_000_000);
+        assert_eq!(last.as_millis() / 1000, MAX_SECS as i128);
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(DateTime::MIN.as_time_stamp(), "Mon Jan 1, 1  0:00:00 (UTC)");
+        assert_eq!(
+            (DateTime::MAX.secs, DateTime::MAX.nanos),
+            (MAX_SECS, 999_999_999)
+        );
+
+        // the year fits a 32-bit usize even in the furthest offset ahead
+        let ahead = FixedOffset::east(86_399).unwrap();
+        let last = DateTime::MAX.with_offset(ahead);
+        assert_eq!(last.year(), u32::MAX as usize);
+        assert_eq!((last.month(), last.date()), (Month::December, 31));
+        assert!(DateTime::MIN.checked_sub_secs(1).is_none());
+        assert!(DateTime::MAX.checked_add_secs(1).is_none());
+
+        assert_eq!(DateTime::try_from_signed_secs(MIN_SECS), Ok(DateTime::MIN));
+        assert_eq!(
+            DateTime::try_from_signed_secs(MIN_SECS - 1),
+            Err(RangeError)
+        );
+        assert_eq!(
+            DateTime::try_from_signed_secs(MAX_SECS + 1),
+            Err(RangeError)
+        );
+        assert_eq!(DateTime::try_from_millis(-1), Ok(DateTime::from_millis(-1)));
+        assert_eq!(DateTime::try_from_millis(i64::MIN), Err(RangeError));
+        assert_eq!(DateTime::try_from_micros(-1), Ok(DateTime::from_micros(-1)));
+        assert_eq!(DateTime::try_from_micros(i64::MIN), Err(RangeError));
+        // a 32-bit usize of seconds can't reach the end
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(DateTime::try_from_secs(usize::MAX), Err(RangeError));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "DateTime too far in the future")]
+    fn test_from_secs_panics() {
+        DateTime::from_secs(usize::MAX);
+    }
+
+    #[test]
+    fn test_const_construction() {
+        const EPOCH: DateTime = DateTime::from_signed_secs(0);
+        const DATES: [DateTime; 2] = [
+            DateTime::from_millis(842_282_624_123),
+            DateTime::from_secs(842_282_624).with_offset(match FixedOffset::east(3600) {
+                Some(offset) => offset,
+                None => panic!(),
+            }),
+        ];
+        const HOUR: usize = DATES[1].hour();
+
+        assert_eq!(EPOCH.year(), 1970);
+        assert_eq!(DATES[0].millisecond(), 123);
+        assert_eq!(HOUR, 16);
+        assert_eq!(DATES[0].second(), DATES[1].second());
+    }
+
+    #[test]
+    fn test_add() {
+        let date = DateTime::from_secs(123456789);
+        let date2 = DateTime::from_secs(234567890);
+
+        let date = date + &date2;
+
+        assert_eq!(date.year(), 1981);
+        assert_eq!(date.month(), Month::May);
         assert_eq!(date.day(), Day::Wednesday);
         assert_eq!(date.date(), 6);
         assert_eq!(date.hour(), 19);
@@ -398,4 +1742,394 @@ mod tests {
         assert_eq!(date.minute(), 17);
         assert_eq!(date.second(), 59);
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_checked_and_saturating() {
+        let date = DateTime::from_millis(-1500).with_offset(FixedOffset::east(3600).unwrap());
+
+        let later = date.checked_add(&DateTime::from_millis(2600)).unwrap();
+        assert_eq!((later.secs, later.nanos), (1, 100_000_000));
+        assert_eq!(later.offset().seconds(), 3600);
+
+        assert!(date.checked_sub_secs(i64::MIN).is_none());
+        assert!(date.checked_add_secs(MAX_SECS + 3).is_none());
+        assert_eq!(date.checked_add_secs(MAX_SECS + 2).unwrap().secs, MAX_SECS);
+        assert!(date.checked_sub_secs(-MIN_SECS - 1).is_none());
+        assert_eq!(date.checked_sub_secs(-MIN_SECS - 2).unwrap().secs, MIN_SECS);
+
+        let latest = date.saturating_add_secs(i64::MAX);
+        assert_eq!((latest.secs, latest.nanos), (MAX_SECS, 999_999_999));
+        assert_eq!(latest.offset().seconds(), 3600);
+        assert_eq!(date.saturating_sub_secs(i64::MIN).secs, MAX_SECS);
+        assert_eq!(date.saturating_add_secs(i64::MIN).secs, MIN_SECS);
+        assert_eq!(
+            latest.saturating_add(&DateTime::from_secs(1)).secs,
+            MAX_SECS
+        );
+    }
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let mut date = DateTime::from_millis(1500) - Duration::from_millis(2700);
+        assert_eq!((date.secs, date.millisecond()), (-2, 800));
+
+        date += Duration::new(1, 300_000_000);
+        assert_eq!((date.secs, date.nanos), (0, 100_000_000));
+
+        date -= Duration::from_secs(86_400);
+        assert_eq!(
+            date.as_time_stamp_with_fraction(1),
+            "Wed Dec 31, 1969  0:00:00.1 (UTC)"
+        );
+
+        let date = date + Duration::from_nanos(900_000_000);
+        assert_eq!((date.secs, date.nanos), (-86_399, 0));
+
+        assert!(date
+            .checked_add_duration(Duration::from_secs(u64::MAX))
+            .is_none());
+        assert!(date
+            .checked_sub_duration(Duration::new(62_135_510_401, 1))
+            .is_none());
+        assert!(date
+            .checked_sub_duration(Duration::new(62_135_510_401, 0))
+            .is_some());
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        assert_eq!(DateTime::from_signed_secs(MIN_SECS).day_of_year(), 1);
+        assert_eq!(DateTime::from_secs(951782400).day_of_year(), 60);
+
+        // 2000-12-31 was the 366th day of a leap year
+        let date = DateTime::from_ordinal(2000, 366).unwrap();
+        assert_eq!((date.month(), date.date()), (Month::December, 31));
+        assert_eq!(date.day_of_year(), 366);
+
+        let date = DateTime::from_ordinal(1969, 365).unwrap();
+        assert_eq!(date.secs, -86_400);
+        assert!(DateTime::from_ordinal(0, 1).is_none());
+        assert!(DateTime::from_ordinal(1969, 0).is_none());
+
+        let last = DateTime::MAX.year();
+        assert_eq!(DateTime::from_ordinal(last, 364).unwrap().date(), 30);
+        assert!(DateTime::from_ordinal(last, 365).is_none());
+        #[cfg(target_pointer_width = "64")]
+        assert!(DateTime::from_ordinal(last + 1, 1).is_none());
+    }
+
+    #[test]
+    fn test_days_in_month_and_year() {
+        // 2000-02-29
+        let date = DateTime::from_secs(951782400);
+        assert_eq!((date.days_in_month(), date.days_in_year()), (29, 366));
+
+        let date = DateTime::from_signed_secs(-1);
+        assert_eq!((date.days_in_month(), date.days_in_year()), (31, 365));
+
+        assert_eq!(Month::April.days(2023), 30);
+        assert_eq!(Month::February.days(2100), 28);
+    }
+
+    #[test]
+    fn test_quarter_and_week_of_month() {
+        let date = |input: &str| DateTime::parse_rfc3339(input).unwrap();
+
+        assert_eq!(date("2024-01-01T00:00:00Z").quarter(), 1);
+        assert_eq!(date("2024-03-31T23:59:59Z").quarter(), 1);
+        assert_eq!(date("2024-04-01T00:00:00Z").quarter(), 2);
+        assert_eq!(date("2024-12-31T00:00:00Z").quarter(), 4);
+
+        // June 2024 started on a Saturday and spans six weeks
+        assert_eq!(date("2024-06-01T00:00:00Z").week_of_month(), 1);
+        assert_eq!(date("2024-06-02T00:00:00Z").week_of_month(), 2);
+        assert_eq!(date("2024-06-30T00:00:00Z").week_of_month(), 6);
+        assert_eq!(date("2024-09-01T00:00:00Z").week_of_month(), 1);
+        assert_eq!(date("2024-09-30T00:00:00Z").week_of_month(), 5);
+
+        assert!(date("2023-02-28T12:00:00Z").is_last_day_of_month());
+        assert!(!date("2024-02-28T12:00:00Z").is_last_day_of_month());
+        assert!(DateTime::from_signed_secs(-1).is_last_day_of_month());
+        assert!(!DateTime::from_secs(0).is_last_day_of_month());
+    }
+
+    #[test]
+    fn test_julian_day() {
+        assert_eq!(DateTime::from_secs(0).to_julian_day(), 2_440_588);
+        assert_eq!(DateTime::from_signed_secs(-1).to_julian_day(), 2_440_587);
+
+        // 0001-01-01 in the proleptic Gregorian calendar
+        let date = DateTime::from_julian_day(1_721_426).unwrap();
+        assert_eq!(date.secs, MIN_SECS);
+        assert!(DateTime::from_julian_day(1_721_425).is_none());
+        assert!(DateTime::from_julian_day(i64::MAX).is_none());
+
+        let date = DateTime::from_millis(-43_200_500);
+        assert_eq!(date.julian_date(), 2_440_587.0 - 0.5 / 86_400.0);
+        let date = DateTime::from_julian_date(date.julian_date()).unwrap();
+        assert_eq!((date.secs, date.millisecond()), (-43_201, 500));
+
+        assert!(DateTime::from_julian_date(f64::NAN).is_none());
+        assert!(DateTime::from_julian_date(0.0).is_none());
+    }
+
+    #[test]
+    fn test_iso_week() {
+        // 2008-12-29 is in week 1 of 2009, and 2005-01-02 in week 53 of 2004
+        assert_eq!(DateTime::from_secs(1230508800).iso_week(), (2009, 1));
+        assert_eq!(DateTime::from_secs(1104624000).iso_week(), (2004, 53));
+        assert_eq!(DateTime::from_signed_secs(MIN_SECS).iso_week(), (1, 1));
+
+        for &secs in &[1230508800, 1104624000, 842282624 - 55424] {
+            let date = DateTime::from_secs(secs);
+            let (year, week) = date.iso_week();
+            let start = DateTime::from_iso_week(year, week, date.day()).unwrap();
+            assert_eq!(start.secs, secs as i64);
+        }
+
+        assert!(DateTime::from_iso_week(2004, 53, Day::Sunday).is_some());
+        assert!(DateTime::from_iso_week(2005, 53, Day::Monday).is_none());
+        assert!(DateTime::from_iso_week(2005, 0, Day::Monday).is_none());
+
+        // the last week-based year ends after the last DateTime
+        let last = DateTime::MAX.year();
+        assert!(DateTime::from_iso_week(last - 1, 52, Day::Sunday).is_some());
+        assert!(DateTime::from_iso_week(last, 52, Day::Sunday).is_none());
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert!(DateTime::from_iso_week(5_000_000_000, 1, Day::Monday).is_none());
+            assert!(DateTime::from_iso_week(usize::MAX, 1, Day::Monday).is_none());
+        }
+    }
+
+    #[test]
+    fn test_before_epoch() {
+        let date = DateTime::from_signed_secs(-1);
+
+        assert_eq!(date.year(), 1969);
+        assert_eq!(date.month(), Month::December);
+        assert_eq!(date.day(), Day::Wednesday);
+        assert_eq!(date.date(), 31);
+        assert_eq!(date.hour(), 23);
+        assert_eq!(date.minute(), 59);
+        assert_eq!(date.second(), 59);
+
+        let date = DateTime::from_signed_secs(-62_135_596_800);
+
+        assert_eq!(date.as_time_stamp(), "Mon Jan 1, 1  0:00:00 (UTC)");
+
+        let date = DateTime::from_signed_secs(-11_670_998_400);
+
+        assert_eq!(date.as_time_stamp(), "Tue Feb 29, 1600  0:00:00 (UTC)");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_system_time() {
+        let date = DateTime::try_from(UNIX_EPOCH - Duration::from_millis(1500)).unwrap();
+
+        assert_eq!(date.secs, -2);
+        assert_eq!(date.millisecond(), 500);
+
+        let date = DateTime::try_from(UNIX_EPOCH + Duration::new(842282624, 987654321)).unwrap();
+        assert_eq!((date.secs, date.nanos), (842282624, 987654321));
+
+        if let Some(time) = UNIX_EPOCH.checked_sub(Duration::from_secs(-MIN_SECS as u64 + 1)) {
+            assert_eq!(DateTime::try_from(time), Err(RangeError));
+        }
+        if let Some(time) = UNIX_EPOCH.checked_add(Duration::from_secs(i64::MAX as u64)) {
+            assert_eq!(DateTime::try_from(time), Err(RangeError));
+        }
+        assert!(DateTime::try_now().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_system_time() {
+        for &millis in &[-1500, 0, 842282624250] {
+            let date = DateTime::from_millis(millis);
+            let time = date.to_system_time().unwrap();
+            assert_eq!(DateTime::try_from(time), Ok(date));
+        }
+
+        let deadline = DateTime::from_millis(-1500);
+        let now = UNIX_EPOCH - Duration::from_millis(2750);
+        assert_eq!(
+            deadline.duration_until_from(now),
+            Duration::from_millis(1250)
+        );
+        assert_eq!(deadline.duration_until_from(UNIX_EPOCH), Duration::ZERO);
+
+        // a deadline in the past doesn't sleep at all
+        DateTime::from_secs(0).sleep_until();
+    }
+
+    #[test]
+    fn test_civil_round_trip() {
+        let date = DateTime::from_signed_secs(253_402_300_799);
+        assert_eq!(date.as_time_stamp(), "Fri Dec 31, 9999  23:59:59 (UTC)");
+
+        let date = DateTime::from_signed_secs(-62_135_596_801 + 86_400 * 366);
+        assert_eq!(date.as_time_stamp(), "Tue Jan 1, 2  23:59:59 (UTC)");
+    }
+
+    #[test]
+    fn test_display_and_debug() {
+        let date = DateTime::from_nanos(842282624987654321);
+
+        assert_eq!(date.to_string(), date.as_time_stamp());
+        assert_eq!(format!("{:.4}", date), date.as_time_stamp_with_fraction(4));
+        assert_eq!(
+            format!("{:.12}", date),
+            "Mon Sep 9, 1996  15:23:44.987654321 (UTC)"
+        );
+        assert_eq!(
+            format!("{:?}", date),
+            "DateTime { year: 1996, month: September, date: 9, day: Monday, \
+             hour: 15, minute: 23, second: 44, nanosecond: 987654321, \
+             offset: FixedOffset { secs: 0 }, secs: 842282624 }"
+        );
+    }
+
+    #[test]
+    fn test_ordering_and_hashing() {
+        let early = DateTime::from_millis(842282624100);
+        let late = DateTime::from_millis(842282624200);
+        assert!(early < late);
+        assert_eq!((&early).max(&late), &late);
+
+        // the offset doesn't take part in comparisons
+        let shifted = early.with_offset(FixedOffset::east(3600).unwrap());
+        assert_eq!(shifted, early);
+
+        let set: HashSet<DateTime> = vec![early, shifted, late].into_iter().collect();
+        assert_eq!(set.len(), 2);
+
+        // DateTimes are Copy, so can be used after being moved
+        let copy = shifted;
+        assert_eq!(copy.offset(), shifted.offset());
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let date = std::sync::Arc::new(DateTime::from_secs(842282624));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let date = date.clone();
+                std::thread::spawn(move || (date.year(), date.hour()))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), (1996, 15));
+        }
+    }
+
+    #[test]
+    fn test_subsecond() {
+        let date = DateTime::from_nanos(842282624987654321);
+
+        assert_eq!(date.second(), 44);
+        assert_eq!(date.millisecond(), 987);
+        assert_eq!(date.microsecond(), 987654);
+        assert_eq!(date.nanosecond(), 987654321);
+        assert_eq!(
+            date.as_time_stamp_with_fraction(6),
+            "Mon Sep 9, 1996  15:23:44.987654 (UTC)"
+        );
+        assert_eq!(
+            date.as_time_stamp_with_fraction(12),
+            "Mon Sep 9, 1996  15:23:44.987654321 (UTC)"
+        );
+        assert_eq!(
+            date.as_time_stamp_with_fraction(0),
+            "Mon Sep 9, 1996  15:23:44 (UTC)"
+        );
+
+        let date = DateTime::from_millis(-1) + &DateTime::from_millis(1);
+
+        assert_eq!(date.secs, 0);
+        assert_eq!(date.nanosecond(), 0);
+    }
+
+    #[test]
+    fn test_with_offset() {
+        let date = DateTime::from_secs(0).to_offset(-5, 0).unwrap();
+
+        assert_eq!(date.year(), 1969);
+        assert_eq!(date.month(), Month::December);
+        assert_eq!(date.day(), Day::Wednesday);
+        assert_eq!(date.date(), 31);
+        assert_eq!(date.hour(), 19);
+        assert_eq!(
+            date.as_time_stamp(),
+            "Wed Dec 31, 1969  19:00:00 (UTC-05:00)"
+        );
+
+        let date = DateTime::from_secs(842282624).to_offset(5, 30).unwrap();
+
+        assert_eq!(date.hour(), 20);
+        assert_eq!(date.minute(), 53);
+        assert_eq!(
+            date.as_time_stamp(),
+            "Mon Sep 9, 1996  20:53:44 (UTC+05:30)"
+        );
+    }
+
+    #[test]
+    fn test_day_and_month_names() {
+        assert_eq!(Day::Wednesday.to_string(), "Wednesday");
+        assert_eq!(format!("{:<10}|", Month::May), "May       |");
+        assert_eq!(ShortName(Day::Thursday).to_string(), "Thu");
+        assert_eq!(format!("{:.1}", ShortName(Month::June)), "J");
+        assert_eq!(LongName(Day::Saturday).to_string(), "Saturday");
+        assert_eq!(format!("{:^7}", LongName(Month::March)), " March ");
+
+        let date = DateTime::from_secs(842282624);
+        assert_eq!(
+            format!(
+                "{}, {} {}",
+                date.day(),
+                ShortName(date.month()),
+                date.date()
+            ),
+            "Monday, Sep 9"
+        );
+    }
+
+    #[test]
+    fn test_day_and_month_numbers() {
+        for (i, day) in Day::iter().enumerate() {
+            assert_eq!(Day::from_number(day.number()), Some(day));
+            assert_eq!(day.succ().pred(), day);
+            assert_eq!(day.succ(), super::DAYS[(i + 1) % 7]);
+        }
+        assert_eq!(Day::Saturday.succ(), Day::Sunday);
+        assert_eq!(Day::from_number(0), None);
+        assert_eq!(Day::from_number(8), None);
+        assert_eq!(Day::iter().len(), 7);
+
+        for (i, month) in Month::iter().enumerate() {
+            assert_eq!(month.number(), i + 1);
+            assert_eq!(Month::from_number(i + 1), Some(month));
+            assert_eq!(month.pred().succ(), month);
+        }
+        assert_eq!(Month::from_number(0), None);
+        assert_eq!(Month::iter().next_back(), Some(Month::December));
+    }
+
+    #[test]
+    fn test_parse_day_and_month() {
+        for day in &["Sunday", "sunday", "SUN", "sUn"] {
+            assert_eq!(day.parse(), Ok(Day::Sunday));
+        }
+        assert_eq!("may".parse(), Ok(Month::May));
+        assert_eq!("Dec".parse(), Ok(Month::December));
+
+        assert_eq!("".parse::<Day>(), Err(ParseNameError));
+        assert_eq!("Su".parse::<Day>(), Err(ParseNameError));
+        assert_eq!(" May".parse::<Month>(), Err(ParseNameError));
+        assert_eq!(ParseNameError.to_string(), "not the name of a day or month");
+    }
 }
@@ -0,0 +1,252 @@
+//! names of days and months in other languages
+//!
+//! the English names are used everywhere by default. a [`Locale`] picks
+//! another language for [`DateTime::format_localized`] and for time stamps
+//! (with [`StampOptions::locale`](crate::StampOptions::locale)). only the
+//! names are translated: numbers, the order of the fields and `%p` stay as
+//! they are
+//! ```
+//! # use datetime::{DateTime, Locale};
+//! let date = DateTime::from_secs(842282624);
+//!
+//! assert_eq!(date.format_localized("%A %e %B", Locale::De), "Montag  9 September");
+//! assert_eq!(date.format_localized("%a %e %b", Locale::Fr), "lun.  9 sept.");
+//! ```
+use crate::{Day, Month};
+
+/// a language for the names of days and months
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    /// English
+    #[default]
+    En,
+    /// German
+    De,
+    /// French
+    Fr,
+    /// Spanish
+    Es,
+    /// Italian
+    It,
+}
+
+// the names of one locale, with days in the order of the Day enum
+struct LocaleData {
+    days: [&'static str; 7],
+    short_days: [&'static str; 7],
+    months: [&'static str; 12],
+    short_months: [&'static str; 12],
+}
+
+const EN: LocaleData = LocaleData {
+    days: [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ],
+    short_days: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    short_months: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+};
+
+const DE: LocaleData = LocaleData {
+    days: [
+        "Sonntag",
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+    ],
+    short_days: ["So", "Mo", "Di", "Mi", "Do", "Fr", "Sa"],
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    short_months: [
+        "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+    ],
+};
+
+const FR: LocaleData = LocaleData {
+    days: [
+        "dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi",
+    ],
+    short_days: ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    short_months: [
+        "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.",
+        "déc.",
+    ],
+};
+
+const ES: LocaleData = LocaleData {
+    days: [
+        "domingo",
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+    ],
+    short_days: ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    short_months: [
+        "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+    ],
+};
+
+const IT: LocaleData = LocaleData {
+    days: [
+        "domenica",
+        "lunedì",
+        "martedì",
+        "mercoledì",
+        "giovedì",
+        "venerdì",
+        "sabato",
+    ],
+    short_days: ["dom", "lun", "mar", "mer", "gio", "ven", "sab"],
+    months: [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    short_months: [
+        "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+    ],
+};
+
+impl Locale {
+    const fn data(self) -> &'static LocaleData {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+            Locale::Fr => &FR,
+            Locale::Es => &ES,
+            Locale::It => &IT,
+        }
+    }
+
+    /// returns the name of the day in this locale
+    /// ```
+    /// # use datetime::{Day, Locale};
+    /// assert_eq!(Locale::Es.day_name(Day::Wednesday), "miércoles");
+    /// ```
+    pub const fn day_name(self, day: Day) -> &'static str {
+        self.data().days[day as usize]
+    }
+
+    /// returns the abbreviated name of the day in this locale
+    pub const fn day_abbreviation(self, day: Day) -> &'static str {
+        self.data().short_days[day as usize]
+    }
+
+    /// returns the name of the month in this locale
+    /// ```
+    /// # use datetime::{Locale, Month};
+    /// assert_eq!(Locale::It.month_name(Month::June), "giugno");
+    /// ```
+    pub const fn month_name(self, month: Month) -> &'static str {
+        self.data().months[month as usize]
+    }
+
+    /// returns the abbreviated name of the month in this locale
+    pub const fn month_abbreviation(self, month: Month) -> &'static str {
+        self.data().short_months[month as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locale;
+    use crate::{Day, Month, DAYS, MONTHS};
+
+    #[test]
+    fn test_english_names() {
+        assert_eq!(Locale::default(), Locale::En);
+        for &day in &DAYS {
+            let name = Locale::En.day_name(day);
+            assert_eq!(Locale::En.day_abbreviation(day), &name[..3]);
+        }
+        for &month in &MONTHS {
+            let name = Locale::En.month_name(month);
+            assert_eq!(Locale::En.month_abbreviation(month), &name[..3]);
+        }
+    }
+
+    #[test]
+    fn test_other_locales() {
+        assert_eq!(Locale::De.day_abbreviation(Day::Thursday), "Do");
+        assert_eq!(Locale::De.month_name(Month::March), "März");
+        assert_eq!(Locale::Fr.month_abbreviation(Month::February), "févr.");
+        assert_eq!(Locale::Es.day_name(Day::Saturday), "sábado");
+        assert_eq!(Locale::It.month_abbreviation(Month::September), "set");
+    }
+}
@@ -0,0 +1,248 @@
+//! the partial dates of media metadata
+//!
+//! ID3v2.4 `TDRC` frames, Vorbis comment `DATE` fields and Matroska
+//! `DATE_RELEASED` tags all hold ISO 8601 dates which may stop at the year
+//! or the month, so an album may be dated just `1996` or `1996-09`.
+//! [`PartialDate`] keeps whatever precision a tag had, and
+//! [`PartialDate::complete`] fills in the rest by a [`Completion`] policy
+//! when a full date is needed
+//! ```
+//! # use datetime::media::{Completion, PartialDate};
+//! # use datetime::Month;
+//! let date: PartialDate = "1996-09".parse().unwrap();
+//! assert_eq!(date.month(), Some(Month::September));
+//! assert_eq!(date.date(), None);
+//!
+//! assert_eq!(date.complete(Completion::Start).date(), 1);
+//! assert_eq!(date.complete(Completion::End).date(), 30);
+//! assert_eq!(date.to_string(), "1996-09");
+//! ```
+pub use crate::partial::Completion;
+
+use crate::convert::{days_from_civil, days_in_month};
+use crate::parse::Cursor;
+use crate::{partial, DateTime, Month, PackedDate, ParseError, MONTHS};
+use core::fmt;
+use core::str::FromStr;
+
+/// a date known to the year, the month or the day
+///
+/// unlike [`partial::PartialDate`], which it converts into, the year is
+/// always known, so it can always be completed
+///
+/// PartialDates are ordered by year, then month, then day, with a missing
+/// field before any present one, so `1996` sorts before `1996-01`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartialDate {
+    year: usize,
+    // 1-12
+    month: Option<usize>,
+    date: Option<usize>,
+}
+
+impl PartialDate {
+    /// returns the partial date with the given fields, where `month` is
+    /// 1-12, or None if any is out of range, the year is 0 or after 9999,
+    /// or there is a day without a month
+    /// ```
+    /// # use datetime::media::PartialDate;
+    /// assert!(PartialDate::new(1996, Some(9), None).is_some());
+    /// assert!(PartialDate::new(1996, None, Some(9)).is_none());
+    /// assert!(PartialDate::new(1996, Some(9), Some(31)).is_none());
+    /// ```
+    pub fn new(year: usize, month: Option<usize>, date: Option<usize>) -> Option<Self> {
+        if !(1..=9999).contains(&year) {
+            return None;
+        }
+
+        match (month, date) {
+            (Some(month), _) if !(1..=12).contains(&month) => return None,
+            (Some(month), Some(date)) if date < 1 || date > days_in_month(year, month) => {
+                return None
+            }
+            (None, Some(_)) => return None,
+            _ => {}
+        }
+
+        Some(PartialDate { year, month, date })
+    }
+
+    /// returns the year
+    pub fn year(&self) -> usize {
+        self.year
+    }
+
+    /// returns the month, if known
+    pub fn month(&self) -> Option<Month> {
+        self.month.map(|month| MONTHS[month - 1])
+    }
+
+    /// returns the day of the month, if known
+    pub fn date(&self) -> Option<usize> {
+        self.date
+    }
+
+    /// returns true if the day is known
+    pub fn is_complete(&self) -> bool {
+        self.date.is_some()
+    }
+
+    /// returns the full date, filling in any missing fields by the given
+    /// policy
+    pub fn complete(&self, completion: Completion) -> PackedDate {
+        let month = self.month.unwrap_or(match completion {
+            Completion::Start => 1,
+            Completion::Middle => 7,
+            Completion::End => 12,
+        });
+        let date = self.date.unwrap_or(match (completion, self.month) {
+            (Completion::Start, _) | (Completion::Middle, None) => 1,
+            (Completion::Middle, Some(_)) => 15,
+            (Completion::End, _) => days_in_month(self.year, month),
+        });
+
+        PackedDate::new(self.year, month, date).expect("partial date fields are valid")
+    }
+
+    /// returns midnight UTC on the full date, filling in any missing
+    /// fields by the given policy
+    pub fn to_datetime(&self, completion: Completion) -> DateTime {
+        let date = self.complete(completion);
+        let days = days_from_civil(date.year() as i64, date.month() as usize + 1, date.date());
+        DateTime::from_signed_secs(days * 86_400)
+    }
+}
+
+impl From<PackedDate> for PartialDate {
+    fn from(date: PackedDate) -> Self {
+        PartialDate {
+            year: date.year(),
+            month: Some(date.month() as usize + 1),
+            date: Some(date.date()),
+        }
+    }
+}
+
+impl From<PartialDate> for partial::PartialDate {
+    fn from(date: PartialDate) -> Self {
+        partial::PartialDate::new(Some(date.year), date.month, date.date)
+            .expect("media dates are valid partial dates")
+    }
+}
+
+impl fmt::Display for PartialDate {
+    /// writes the date to the precision it is known, as `YYYY`, `YYYY-MM`
+    /// or `YYYY-MM-DD`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(date) = self.date {
+            write!(f, "-{:02}", date)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PartialDate {
+    type Err = ParseError;
+
+    /// parses `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    ///
+    /// a full date may be followed by a time, as ID3v2.4 allows
+    /// (`THH`, `THH:mm` or `THH:mm:ss`), which is checked and then ignored
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let year = cursor.digits(4)?;
+        let (mut month, mut date) = (None, None);
+
+        if cursor.peek().is_some() {
+            cursor.expect(b'-')?;
+            month = Some(cursor.digits(2)?);
+        }
+        if cursor.peek().is_some() {
+            cursor.expect(b'-')?;
+            date = Some(cursor.digits(2)?);
+        }
+        if cursor.peek().is_some() {
+            cursor.expect(b'T')?;
+            let hour = cursor.digits(2)?;
+            let (mut minute, mut second) = (0, 0);
+            if cursor.peek() == Some(b':') {
+                cursor.next()?;
+                minute = cursor.digits(2)?;
+                if cursor.peek() == Some(b':') {
+                    cursor.next()?;
+                    second = cursor.digits(2)?;
+                }
+            }
+            if hour > 23 || minute > 59 || second > 59 {
+                return Err(ParseError::OutOfRange);
+            }
+        }
+        cursor.finish()?;
+
+        PartialDate::new(year, month, date).ok_or(ParseError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Completion, PartialDate};
+    use crate::{partial, Month, ParseError};
+
+    #[test]
+    fn test_parse_partial_dates() {
+        let parse = |input: &str| input.parse::<PartialDate>();
+
+        assert_eq!(
+            parse("1996"),
+            Ok(PartialDate::new(1996, None, None).unwrap())
+        );
+        assert_eq!(
+            parse("1996-09-09T15:23:44"),
+            Ok(PartialDate::new(1996, Some(9), Some(9)).unwrap())
+        );
+        assert_eq!(
+            parse("1996-09-09T15").map(|date| date.to_string()),
+            Ok("1996-09-09".into())
+        );
+
+        assert_eq!(parse("96"), Err(ParseError::TooShort));
+        assert_eq!(parse("1996/09"), Err(ParseError::Invalid(4)));
+        assert_eq!(parse("1996-13"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("0000"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("1996-09-09T24"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("1996-09-09T15:23:44:00"), Err(ParseError::TooLong));
+        assert_eq!(parse("1996-09-09T"), Err(ParseError::TooShort));
+    }
+
+    #[test]
+    fn test_completion() {
+        let year: PartialDate = "1996".parse().unwrap();
+        let month: PartialDate = "1996-02".parse().unwrap();
+
+        let complete = |date: PartialDate, completion| {
+            let date = date.complete(completion);
+            (date.month(), date.date())
+        };
+        assert_eq!(complete(year, Completion::Start), (Month::January, 1));
+        assert_eq!(complete(year, Completion::Middle), (Month::July, 1));
+        assert_eq!(complete(year, Completion::End), (Month::December, 31));
+        assert_eq!(complete(month, Completion::Middle), (Month::February, 15));
+        assert_eq!(complete(month, Completion::End), (Month::February, 29));
+
+        let date = month.to_datetime(Completion::End);
+        assert_eq!(date.to_rfc3339(), "1996-02-29T00:00:00Z");
+        assert!(year < month && month < PartialDate::from(month.complete(Completion::Start)));
+
+        let general = partial::PartialDate::from(month);
+        assert_eq!(general.to_string(), "1996-02");
+        assert_eq!(
+            general.complete(Completion::End),
+            Some(month.complete(Completion::End))
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! timestamps for Prometheus and OpenMetrics samples
+//!
+//! exporters can attach an explicit timestamp to a sample. the Prometheus
+//! text format takes it as whole Unix milliseconds, while OpenMetrics takes
+//! Unix seconds, which may have a fraction
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_millis(1520879607789);
+//!
+//! assert_eq!(
+//!     format!("http_requests_total 1027 {}", date.to_exposition_timestamp()),
+//!     "http_requests_total 1027 1520879607789"
+//! );
+//! assert_eq!(
+//!     format!("http_requests_total 1027 {}", date.to_openmetrics_timestamp()),
+//!     "http_requests_total 1027 1520879607.789"
+//! );
+//! ```
+//!
+//! latency histograms need the same bucket boundaries everywhere their
+//! samples are merged. [`SRE_LATENCY_BUCKETS`] and
+//! [`POWER_OF_TWO_BUCKETS`] are two common sets of upper bounds, and
+//! [`bucket_index`] finds the bucket a Duration falls in
+//! ```
+//! # use datetime::metrics::{bucket_index, SRE_LATENCY_BUCKETS};
+//! # use std::time::Duration;
+//! let index = bucket_index(&SRE_LATENCY_BUCKETS, Duration::from_millis(180));
+//! assert_eq!(SRE_LATENCY_BUCKETS[index], Duration::from_millis(250));
+//! ```
+use crate::DateTime;
+use alloc::format;
+use alloc::string::String;
+use core::time::Duration;
+
+/// the upper bounds of latency buckets from a millisecond to ten seconds,
+/// in steps of 1, 2.5 and 5, a superset of the Prometheus client
+/// libraries' defaults
+pub const SRE_LATENCY_BUCKETS: [Duration; 13] = [
+    Duration::from_millis(1),
+    Duration::from_micros(2500),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// the upper bounds of latency buckets doubling from a millisecond to
+/// about 16 seconds
+pub const POWER_OF_TWO_BUCKETS: [Duration; 15] = {
+    let mut bounds = [Duration::ZERO; 15];
+    let mut i = 0;
+    while i < bounds.len() {
+        bounds[i] = Duration::from_millis(1 << i);
+        i += 1;
+    }
+    bounds
+};
+
+/// returns the index of the bucket `duration` falls in, given the buckets'
+/// upper bounds in increasing order: that of the first bound at least as
+/// long as it, or `bounds.len()` for the overflow (`+Inf`) bucket
+///
+/// as in Prometheus, a Duration equal to a bound falls in that bound's
+/// bucket
+/// ```
+/// # use datetime::metrics::{bucket_index, POWER_OF_TWO_BUCKETS};
+/// # use std::time::Duration;
+/// let bounds = &POWER_OF_TWO_BUCKETS;
+///
+/// assert_eq!(bucket_index(bounds, Duration::from_millis(4)), 2);
+/// assert_eq!(bucket_index(bounds, Duration::from_millis(5)), 3);
+/// assert_eq!(bucket_index(bounds, Duration::from_secs(60)), bounds.len());
+/// ```
+pub fn bucket_index(bounds: &[Duration], duration: Duration) -> usize {
+    bounds.partition_point(|&bound| bound < duration)
+}
+
+impl DateTime {
+    /// returns the DateTime in Unix seconds, to the millisecond, as
+    /// Prometheus's client libraries and remote APIs take it
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_nanos(1_500_999_999);
+    ///
+    /// assert_eq!(date.to_prometheus_timestamp(), 1.5);
+    /// ```
+    pub fn to_prometheus_timestamp(&self) -> f64 {
+        self.secs as f64 + (self.nanos / 1_000_000) as f64 / 1000.0
+    }
+
+    /// returns the DateTime in whole Unix milliseconds, as written after a
+    /// sample's value in the Prometheus text exposition format; any finer
+    /// fraction is dropped, and DateTimes too far from the epoch saturate
+    pub fn to_exposition_timestamp(&self) -> i64 {
+        self.to_unix_pexpiry()
+    }
+
+    /// returns the DateTime in Unix seconds with a three-digit fraction, as
+    /// written after a sample's value in the OpenMetrics text format
+    ///
+    /// unlike [`to_prometheus_timestamp`](DateTime::to_prometheus_timestamp)
+    /// this is exact, however far the DateTime is from the epoch
+    pub fn to_openmetrics_timestamp(&self) -> String {
+        let millis = self.to_unix_pexpiry();
+        let sign = if millis < 0 { "-" } else { "" };
+        let millis = millis.unsigned_abs();
+
+        format!("{}{}.{:03}", sign, millis / 1000, millis % 1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_index, POWER_OF_TWO_BUCKETS, SRE_LATENCY_BUCKETS};
+    use crate::DateTime;
+    use core::time::Duration;
+
+    #[test]
+    fn test_prometheus_timestamp() {
+        let date = DateTime::from_millis(842282624123);
+        assert_eq!(date.to_prometheus_timestamp(), 842282624.123);
+        assert_eq!(date.to_exposition_timestamp(), 842282624123);
+
+        assert_eq!(DateTime::from_millis(-1500).to_prometheus_timestamp(), -1.5);
+    }
+
+    #[test]
+    fn test_buckets() {
+        for bounds in &[&SRE_LATENCY_BUCKETS[..], &POWER_OF_TWO_BUCKETS[..]] {
+            assert!(bounds.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(bounds[0], Duration::from_millis(1));
+            assert_eq!(bucket_index(bounds, Duration::ZERO), 0);
+        }
+        assert_eq!(POWER_OF_TWO_BUCKETS[14], Duration::from_millis(16_384));
+        assert_eq!(SRE_LATENCY_BUCKETS[12], Duration::from_secs(10));
+
+        let bounds = &SRE_LATENCY_BUCKETS;
+        assert_eq!(bucket_index(bounds, Duration::from_millis(1)), 0);
+        assert_eq!(bucket_index(bounds, Duration::from_nanos(1_000_001)), 1);
+        assert_eq!(bucket_index(bounds, Duration::from_secs(10)), 12);
+        assert_eq!(bucket_index(bounds, Duration::MAX), 13);
+        assert_eq!(bucket_index(&[], Duration::from_secs(1)), 0);
+    }
+
+    #[test]
+    fn test_openmetrics_timestamp() {
+        assert_eq!(DateTime::from_secs(0).to_openmetrics_timestamp(), "0.000");
+        assert_eq!(
+            DateTime::from_millis(-1500).to_openmetrics_timestamp(),
+            "-1.500"
+        );
+        assert_eq!(
+            DateTime::from_nanos(-1).to_openmetrics_timestamp(),
+            "-0.001"
+        );
+    }
+}
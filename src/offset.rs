@@ -0,0 +1,136 @@
+//! fixed offsets from UTC
+use crate::TimestampSink;
+use core::fmt;
+
+/// a fixed offset from UTC, such as `+05:30` or `-08:00`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FixedOffset {
+    secs: i32,
+}
+
+impl FixedOffset {
+    /// returns the zero offset used by UTC
+    pub const fn utc() -> Self {
+        FixedOffset { secs: 0 }
+    }
+
+    /// returns an offset the given number of seconds east of UTC, or
+    /// None if it is not strictly less than a day in either direction
+    /// ```
+    /// # use datetime::FixedOffset;
+    /// let offset = FixedOffset::east(19800).unwrap();
+    ///
+    /// assert_eq!(offset.to_string(), "+05:30");
+    /// assert!(FixedOffset::east(86400).is_none());
+    /// ```
+    pub const fn east(secs: i32) -> Option<Self> {
+        if secs.unsigned_abs() < 86_400 {
+            Some(FixedOffset { secs })
+        } else {
+            None
+        }
+    }
+
+    /// returns an offset the given number of seconds west of UTC, or
+    /// None if it is not strictly less than a day in either direction
+    pub const fn west(secs: i32) -> Option<Self> {
+        match secs.checked_neg() {
+            Some(secs) => FixedOffset::east(secs),
+            None => None,
+        }
+    }
+
+    /// returns an offset of the given hours and minutes from UTC; the
+    /// sign of `hours` applies to the whole offset, so `(-3, 30)` is
+    /// `-03:30`. use a negative `minutes` for offsets such as `-00:30`
+    /// ```
+    /// # use datetime::FixedOffset;
+    /// let offset = FixedOffset::from_hours_minutes(-3, 30).unwrap();
+    ///
+    /// assert_eq!(offset.seconds(), -12600);
+    /// assert!(FixedOffset::from_hours_minutes(5, 60).is_none());
+    /// ```
+    pub const fn from_hours_minutes(hours: i32, minutes: i32) -> Option<Self> {
+        if hours.unsigned_abs() > 23 || minutes.unsigned_abs() > 59 || (hours != 0 && minutes < 0) {
+            return None;
+        }
+
+        let secs = (hours.unsigned_abs() * 3600 + minutes.unsigned_abs() * 60) as i32;
+        if hours < 0 || minutes < 0 {
+            FixedOffset::east(-secs)
+        } else {
+            FixedOffset::east(secs)
+        }
+    }
+
+    /// returns the offset in seconds east of UTC
+    pub const fn seconds(&self) -> i32 {
+        self.secs
+    }
+
+    /// returns true if this is the zero offset
+    pub const fn is_utc(&self) -> bool {
+        self.secs == 0
+    }
+
+    // writes the offset as `+hh:mm`, or `+hhmm` without the colon, adding
+    // `:ss` only when the offset is not a whole number of minutes
+    pub(crate) fn write<S: TimestampSink + ?Sized>(&self, w: &mut S, colon: bool) -> fmt::Result {
+        let sign = if self.secs < 0 { "-" } else { "+" };
+        let secs = self.secs.unsigned_abs();
+        let sep = if colon { ":" } else { "" };
+
+        w.write_str(sign)?;
+        w.write_u32_padded(secs / 3600, 2)?;
+        w.write_str(sep)?;
+        w.write_u32_padded(secs / 60 % 60, 2)?;
+        if !secs.is_multiple_of(60) {
+            w.write_str(sep)?;
+            w.write_u32_padded(secs % 60, 2)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FixedOffset {
+    /// formats the offset as `+hh:mm`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedOffset;
+
+    #[test]
+    fn test_from_hours_minutes() {
+        let offset = FixedOffset::from_hours_minutes(5, 45).unwrap();
+        assert_eq!(offset.seconds(), 20700);
+        assert_eq!(offset.to_string(), "+05:45");
+
+        let offset = FixedOffset::from_hours_minutes(0, -30).unwrap();
+        assert_eq!(offset.to_string(), "-00:30");
+
+        assert!(FixedOffset::from_hours_minutes(-1, -30).is_none());
+        assert!(FixedOffset::from_hours_minutes(24, 0).is_none());
+        assert!(FixedOffset::from_hours_minutes(1_000_000, 0).is_none());
+        assert!(FixedOffset::from_hours_minutes(i32::MIN, 0).is_none());
+        assert!(FixedOffset::from_hours_minutes(0, i32::MIN).is_none());
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert!(FixedOffset::east(i32::MIN).is_none());
+        assert!(FixedOffset::west(i32::MIN).is_none());
+        assert!(FixedOffset::east(-86_399).is_some());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(FixedOffset::utc().to_string(), "+00:00");
+        assert_eq!(FixedOffset::west(3600 * 8).unwrap().to_string(), "-08:00");
+        assert_eq!(FixedOffset::east(-2079).unwrap().to_string(), "-00:34:39");
+    }
+}
@@ -0,0 +1,107 @@
+//! OpenTelemetry timestamps
+//!
+//! OpenTelemetry's protocol carries times as `fixed64` nanoseconds since
+//! the Unix epoch, such as a span's `start_time_unix_nano` and
+//! `end_time_unix_nano`, with 0 meaning the time is unset. that covers the
+//! years 1970 to 2554, so DateTimes outside them can't be converted
+//! ```
+//! # use datetime::otel;
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let start = DateTime::from_millis(842282624123).to_otel_nanos().unwrap();
+//! let end = otel::span_end(start, Duration::from_micros(1500)).unwrap();
+//!
+//! assert_eq!(otel::span_duration(start, end), Some(Duration::from_micros(1500)));
+//! assert_eq!(DateTime::from_otel_nanos(end).unwrap().microsecond(), 124_500);
+//! ```
+use crate::{DateTime, FixedOffset};
+use core::convert::TryFrom;
+use core::time::Duration;
+
+impl DateTime {
+    /// returns the DateTime in nanoseconds since the Unix epoch, or None if
+    /// it is at or before the epoch (which OpenTelemetry can't carry) or
+    /// too late to fit in a u64
+    pub fn to_otel_nanos(&self) -> Option<u64> {
+        if self.secs < 0 || (self.secs, self.nanos) == (0, 0) {
+            return None;
+        }
+
+        u64::try_from(self.secs)
+            .ok()?
+            .checked_mul(1_000_000_000)?
+            .checked_add(u64::from(self.nanos))
+    }
+
+    /// returns the DateTime in UTC for OpenTelemetry nanoseconds since the
+    /// Unix epoch, or None if they are 0, meaning the time is unset
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_otel_nanos(842282624_123456789).unwrap();
+    ///
+    /// assert_eq!(date.nanosecond(), 123456789);
+    /// assert!(DateTime::from_otel_nanos(0).is_none());
+    /// ```
+    pub fn from_otel_nanos(nanos: u64) -> Option<DateTime> {
+        if nanos == 0 {
+            return None;
+        }
+
+        let secs = (nanos / 1_000_000_000) as i64;
+        let nanos = (nanos % 1_000_000_000) as u32;
+        Some(DateTime::from_parts(secs, nanos, FixedOffset::utc()))
+    }
+}
+
+/// returns the time between a span's start and end, given in OpenTelemetry
+/// nanoseconds, or None if either is unset or the end is before the start
+pub fn span_duration(start_unix_nano: u64, end_unix_nano: u64) -> Option<Duration> {
+    if start_unix_nano == 0 || end_unix_nano < start_unix_nano {
+        return None;
+    }
+
+    Some(Duration::from_nanos(end_unix_nano - start_unix_nano))
+}
+
+/// returns the end of a span in OpenTelemetry nanoseconds, from its start
+/// and duration, or None if the start is unset or the end doesn't fit in a
+/// u64
+pub fn span_end(start_unix_nano: u64, duration: Duration) -> Option<u64> {
+    if start_unix_nano == 0 {
+        return None;
+    }
+
+    let nanos = u64::try_from(duration.as_nanos()).ok()?;
+    start_unix_nano.checked_add(nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{span_duration, span_end};
+    use crate::DateTime;
+    use std::time::Duration;
+
+    #[test]
+    fn test_otel_nanos() {
+        let date = DateTime::from_nanos(842282624_123456789);
+        assert_eq!(date.to_otel_nanos(), Some(842282624_123456789));
+        assert_eq!(DateTime::from_otel_nanos(842282624_123456789), Some(date));
+
+        assert_eq!(DateTime::from_otel_nanos(u64::MAX).unwrap().year(), 2554);
+        assert!(DateTime::from_secs(0).to_otel_nanos().is_none());
+        assert!(DateTime::from_nanos(-1).to_otel_nanos().is_none());
+        assert!(DateTime::from_secs(1 << 40).to_otel_nanos().is_none());
+    }
+
+    #[test]
+    fn test_span_helpers() {
+        assert_eq!(span_duration(1000, 3500), Some(Duration::from_nanos(2500)));
+        assert_eq!(span_duration(1000, 1000), Some(Duration::from_nanos(0)));
+        assert!(span_duration(3500, 1000).is_none());
+        assert!(span_duration(0, 1000).is_none());
+
+        assert_eq!(span_end(1000, Duration::from_micros(1)), Some(2000));
+        assert!(span_end(0, Duration::from_micros(1)).is_none());
+        assert!(span_end(u64::MAX, Duration::from_nanos(1)).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! dates and times packed into four bytes each
+//!
+//! [`PackedDate`] and [`PackedTime`] are for storing many values compactly,
+//! e.g. in a columnar store, and converting to the full types at the edges.
+//! both pack their fields most significant first, so comparing the raw
+//! bits gives the same order as comparing the values
+//!
+//! | type         | bits   | field                |
+//! |--------------|--------|----------------------|
+//! | `PackedDate` | 31..9  | year (1-8388607)     |
+//! |              | 8..5   | month (1-12)         |
+//! |              | 4..0   | day of the month     |
+//! | `PackedTime` | 31..27 | hour (0-23)          |
+//! |              | 26..21 | minute (0-59)        |
+//! |              | 20..15 | second (0-59)        |
+//! |              | 14..5  | millisecond (0-999)  |
+//! |              | 4..0   | always zero          |
+//! ```
+//! # use datetime::{DateTime, PackedDate, PackedTime};
+//! let date = DateTime::from_millis(842282624123);
+//!
+//! let day = PackedDate::from_datetime(&date).unwrap();
+//! let time = PackedTime::from_datetime(&date);
+//! assert_eq!(day.to_bits(), 1996 << 9 | 9 << 5 | 9);
+//!
+//! let civil = day.and_time(time);
+//! assert_eq!(civil.to_string(), "1996-09-09T15:23:44.123000000");
+//! ```
+use crate::convert::is_valid_date;
+use crate::{CivilTime, DateTime, InvalidDate, Month, MONTHS};
+
+const YEAR_BITS: u32 = 23;
+
+/// a calendar date packed into a u32; see the [module docs](self) for
+/// the layout
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackedDate(u32);
+
+impl PackedDate {
+    /// the latest year a PackedDate can hold
+    pub const MAX_YEAR: usize = (1 << YEAR_BITS) - 1;
+
+    /// returns the packed date, where `month` is 1-12, or the first field
+    /// that is out of range; the year can't be 0 or after
+    /// [`MAX_YEAR`](PackedDate::MAX_YEAR)
+    pub fn new(year: usize, month: usize, date: usize) -> Result<Self, InvalidDate> {
+        if year > PackedDate::MAX_YEAR {
+            return Err(InvalidDate::Year);
+        }
+        is_valid_date(year, month, date)?;
+
+        Ok(PackedDate((year << 9 | month << 5 | date) as u32))
+    }
+
+    /// returns the date packed in `bits`, or None if they don't hold a
+    /// valid date
+    pub fn from_bits(bits: u32) -> Option<Self> {
+        let year = (bits >> 9) as usize;
+        let month = (bits >> 5 & 0xf) as usize;
+        let date = (bits & 0x1f) as usize;
+
+        PackedDate::new(year, month, date).ok()
+    }
+
+    /// returns the packed bits
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// returns the date of a DateTime, as reported in its offset, or None
+    /// if its year is after [`MAX_YEAR`](PackedDate::MAX_YEAR)
+    pub fn from_datetime(date: &DateTime) -> Option<Self> {
+        PackedDate::new(date.year(), date.month() as usize + 1, date.date()).ok()
+    }
+
+    /// returns the year
+    pub const fn year(self) -> usize {
+        (self.0 >> 9) as usize
+    }
+
+    /// returns the month
+    pub const fn month(self) -> Month {
+        MONTHS[(self.0 >> 5 & 0xf) as usize - 1]
+    }
+
+    /// returns the day of the month
+    pub const fn date(self) -> usize {
+        (self.0 & 0x1f) as usize
+    }
+
+    /// returns the wall-clock time at the given time on this date
+    pub fn and_time(self, time: PackedTime) -> CivilTime {
+        let month = self.month() as usize + 1;
+        let civil = CivilTime::new(
+            self.year(),
+            month,
+            self.date(),
+            time.hour(),
+            time.minute(),
+            time.second(),
+        )
+        .expect("packed fields are always valid");
+
+        civil
+            .with_nanosecond(time.millisecond() as u32 * 1_000_000)
+            .expect("packed fields are always valid")
+    }
+}
+
+/// a time of day to the millisecond packed into a u32; see the
+/// [module docs](self) for the layout
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackedTime(u32);
+
+impl PackedTime {
+    /// returns the packed time, or None if any field is out of range
+    pub fn new(hour: usize, minute: usize, second: usize, millisecond: usize) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 || millisecond > 999 {
+            return None;
+        }
+
+        let bits = hour << 27 | minute << 21 | second << 15 | millisecond << 5;
+        Some(PackedTime(bits as u32))
+    }
+
+    /// returns the time packed in `bits`, or None if they don't hold a
+    /// valid time
+    pub fn from_bits(bits: u32) -> Option<Self> {
+        if bits & 0x1f != 0 {
+            return None;
+        }
+
+        let field = |shift: u32, width: u32| (bits >> shift & ((1 << width) - 1)) as usize;
+        PackedTime::new(field(27, 5), field(21, 6), field(15, 6), field(5, 10))
+    }
+
+    /// returns the packed bits
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// returns the time of day of a DateTime, as reported in its offset,
+    /// truncated to the millisecond
+    pub fn from_datetime(date: &DateTime) -> Self {
+        PackedTime::new(
+            date.hour(),
+            date.minute(),
+            date.second(),
+            date.millisecond(),
+        )
+        .expect("DateTime fields are always valid")
+    }
+
+    /// returns the hour
+    pub const fn hour(self) -> usize {
+        (self.0 >> 27) as usize
+    }
+
+    /// returns the minute
+    pub const fn minute(self) -> usize {
+        (self.0 >> 21 & 0x3f) as usize
+    }
+
+    /// returns the second
+    pub const fn second(self) -> usize {
+        (self.0 >> 15 & 0x3f) as usize
+    }
+
+    /// returns the millisecond
+    pub const fn millisecond(self) -> usize {
+        (self.0 >> 5 & 0x3ff) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PackedDate, PackedTime};
+    use crate::{DateTime, InvalidDate, Month};
+
+    #[test]
+    fn test_packed_date() {
+        let date = PackedDate::new(2000, 2, 29).unwrap();
+        assert_eq!(
+            (date.year(), date.month(), date.date()),
+            (2000, Month::February, 29)
+        );
+        assert_eq!(PackedDate::from_bits(date.to_bits()), Some(date));
+
+        assert_eq!(PackedDate::new(2001, 2, 29), Err(InvalidDate::LeapDay));
+        assert_eq!(PackedDate::new(2001, 4, 31), Err(InvalidDate::Date));
+        assert_eq!(
+            PackedDate::new(PackedDate::MAX_YEAR + 1, 1, 1),
+            Err(InvalidDate::Year)
+        );
+        assert!(PackedDate::from_bits(2000 << 9 | 13 << 5 | 1).is_none());
+
+        // the bits sort in date order
+        let later = PackedDate::new(2000, 3, 1).unwrap();
+        assert!(date.to_bits() < later.to_bits() && date < later);
+    }
+
+    #[test]
+    fn test_packed_time() {
+        let date = DateTime::from_nanos(-1);
+        let time = PackedTime::from_datetime(&date);
+
+        assert_eq!(
+            (
+                time.hour(),
+                time.minute(),
+                time.second(),
+                time.millisecond()
+            ),
+            (23, 59, 59, 999)
+        );
+        assert_eq!(PackedTime::from_bits(time.to_bits()), Some(time));
+        assert!(PackedTime::from_bits(time.to_bits() | 1).is_none());
+        assert!(PackedTime::new(24, 0, 0, 0).is_none());
+
+        let day = PackedDate::from_datetime(&date).unwrap();
+        assert_eq!(
+            day.and_time(time).to_string(),
+            "1969-12-31T23:59:59.999000000"
+        );
+    }
+}
@@ -0,0 +1,1088 @@
+//! parsing of textual timestamps into DateTimes
+use crate::calendar_system::{Calendar, Gregorian};
+use crate::convert::{days_from_civil, weekday_from_days};
+use crate::epoch::{guess_epoch_unit, EpochUnit};
+use crate::{DateTime, FixedOffset, MAX_SECS, MIN_SECS, MONTHS};
+use core::error::Error;
+use core::fmt;
+use core::str::FromStr;
+
+/// an error returned when a string could not be parsed into a DateTime
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// the input ended before a complete timestamp was read
+    TooShort,
+    /// the input had extra characters after a complete timestamp
+    TooLong,
+    /// an unexpected character was found at the given byte offset
+    Invalid(usize),
+    /// a field was outside of its valid range (e.g. month 13 or Feb 30),
+    /// or the timestamp cannot be represented by a DateTime
+    OutOfRange,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::TooShort => write!(f, "premature end of input"),
+            ParseError::TooLong => write!(f, "trailing input"),
+            ParseError::Invalid(pos) => write!(f, "unexpected character at position {}", pos),
+            ParseError::OutOfRange => write!(f, "field out of range"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// how much input that bends a timestamp format is tolerated, set once by
+/// an application and passed to parsers such as
+/// [`DateTime::parse_rfc3339_with`]
+///
+/// [`DateTime::parse_rfc3339`] sits between the two, accepting exactly
+/// what RFC 3339 allows: a lowercase `t` or `z`, or a space instead of the
+/// `T`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ParseProfile {
+    /// only the canonical form: an uppercase `T` and `Z`, seconds always
+    /// present, and nothing around the timestamp
+    Strict,
+    /// anything reasonably unambiguous: whitespace around the timestamp,
+    /// missing seconds, lowercase `t` and `z`, a space instead of the `T`
+    /// and a comma before fractional seconds
+    Lenient,
+}
+
+impl ParseProfile {
+    /// returns true if spaces and tabs are allowed before and after the
+    /// timestamp
+    pub const fn allows_surrounding_whitespace(self) -> bool {
+        self.tolerances().whitespace
+    }
+
+    /// returns true if the seconds may be left out, as in `15:23Z`
+    pub const fn allows_missing_seconds(self) -> bool {
+        self.tolerances().missing_seconds
+    }
+
+    /// returns true if the `T` and `Z` may be lowercase
+    pub const fn allows_lowercase(self) -> bool {
+        self.tolerances().lowercase
+    }
+
+    /// returns true if fractional seconds may follow a comma, as ISO 8601
+    /// allows, rather than a full stop
+    pub const fn allows_comma_fraction(self) -> bool {
+        self.tolerances().comma_fraction
+    }
+
+    const fn tolerances(self) -> Tolerances {
+        match self {
+            ParseProfile::Strict => Tolerances {
+                whitespace: false,
+                missing_seconds: false,
+                lowercase: false,
+                space_separator: false,
+                comma_fraction: false,
+            },
+            ParseProfile::Lenient => Tolerances {
+                whitespace: true,
+                missing_seconds: true,
+                lowercase: true,
+                space_separator: true,
+                comma_fraction: true,
+            },
+        }
+    }
+}
+
+// the individual tolerances of a parser
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Tolerances {
+    whitespace: bool,
+    missing_seconds: bool,
+    lowercase: bool,
+    space_separator: bool,
+    comma_fraction: bool,
+}
+
+impl Tolerances {
+    // what RFC 3339 itself allows
+    pub(crate) const RFC3339: Tolerances = Tolerances {
+        whitespace: false,
+        missing_seconds: false,
+        lowercase: true,
+        space_separator: true,
+        comma_fraction: false,
+    };
+}
+
+// a cursor over the bytes of the input shared by the parsers in this module
+pub(crate) struct Cursor<'a> {
+    input: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Cursor {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).cloned()
+    }
+
+    pub(crate) fn next(&mut self) -> Result<u8, ParseError> {
+        let b = self.peek().ok_or(ParseError::TooShort)?;
+        self.pos += 1;
+
+        Ok(b)
+    }
+
+    // consumes the given byte, failing if anything else is found
+    pub(crate) fn expect(&mut self, expected: u8) -> Result<(), ParseError> {
+        self.expect_any(&[expected]).map(|_| ())
+    }
+
+    // consumes one of the given bytes and returns it
+    pub(crate) fn expect_any(&mut self, expected: &[u8]) -> Result<u8, ParseError> {
+        let b = self.next()?;
+        if expected.contains(&b) {
+            Ok(b)
+        } else {
+            Err(ParseError::Invalid(self.pos - 1))
+        }
+    }
+
+    // reads exactly `count` ASCII digits as a number
+    pub(crate) fn digits(&mut self, count: usize) -> Result<usize, ParseError> {
+        let mut value = 0;
+        for _ in 0..count {
+            let b = self.next()?;
+            if !b.is_ascii_digit() {
+                return Err(ParseError::Invalid(self.pos - 1));
+            }
+            value = value * 10 + (b - b'0') as usize;
+        }
+
+        Ok(value)
+    }
+
+    // skips over any ASCII digits, returning how many were found
+    pub(crate) fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.pos - start
+    }
+
+    // reads the digits of a fractional second of any length as nanoseconds,
+    // ignoring any digits past the ninth
+    pub(crate) fn fraction(&mut self) -> Result<u32, ParseError> {
+        let start = self.pos;
+        if self.skip_digits() == 0 {
+            return Err(ParseError::Invalid(start));
+        }
+
+        let digits = &self.input[start..self.pos];
+        let nanos = (0..9).fold(0, |nanos, i| {
+            let digit = digits.get(i).map_or(0, |b| b - b'0');
+            nanos * 10 + digit as u32
+        });
+
+        Ok(nanos)
+    }
+
+    // skips over any spaces and tabs, returning how many were found
+    pub(crate) fn skip_spaces(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b == b' ' || b == b'\t') {
+            self.pos += 1;
+        }
+
+        self.pos - start
+    }
+
+    // reads a run of ASCII letters, failing if there are none
+    pub(crate) fn letters(&mut self) -> Result<&'a [u8], ParseError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            Err(self
+                .peek()
+                .map_or(ParseError::TooShort, |_| ParseError::Invalid(start)))
+        } else {
+            Ok(&self.input[start..self.pos])
+        }
+    }
+
+    // reads between `min` and `max` ASCII digits as a number, returning it
+    // and how many digits were read
+    pub(crate) fn digits_between(
+        &mut self,
+        min: usize,
+        max: usize,
+    ) -> Result<(usize, usize), ParseError> {
+        let start = self.pos;
+        let count = self.skip_digits();
+        if count < min {
+            return Err(self
+                .peek()
+                .map_or(ParseError::TooShort, |_| ParseError::Invalid(self.pos)));
+        }
+        if count > max {
+            return Err(ParseError::Invalid(start + max));
+        }
+
+        let value = self.input[start..self.pos]
+            .iter()
+            .try_fold(0usize, |value, b| {
+                value.checked_mul(10)?.checked_add((b - b'0') as usize)
+            })
+            .ok_or(ParseError::OutOfRange)?;
+
+        Ok((value, count))
+    }
+
+    pub(crate) fn finish(&self) -> Result<(), ParseError> {
+        if self.pos == self.input.len() {
+            Ok(())
+        } else {
+            Err(ParseError::TooLong)
+        }
+    }
+}
+
+// validates broken-down fields given in a zone `offset` seconds ahead of
+// UTC and converts them into a DateTime
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn from_fields(
+    year: usize,
+    month: usize,
+    date: usize,
+    hour: usize,
+    minute: usize,
+    second: usize,
+    nanos: u32,
+    offset: i64,
+) -> Result<DateTime, ParseError> {
+    from_fields_in(
+        &Gregorian, year, month, date, hour, minute, second, nanos, offset,
+    )
+}
+
+// like from_fields, with the date in the given calendar
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn from_fields_in<C: Calendar + ?Sized>(
+    calendar: &C,
+    year: usize,
+    month: usize,
+    date: usize,
+    hour: usize,
+    minute: usize,
+    second: usize,
+    nanos: u32,
+    offset: i64,
+) -> Result<DateTime, ParseError> {
+    let year = year as i64;
+    if !(1..=12).contains(&month) || date < 1 || date > calendar.days_in_month(year, month) {
+        return Err(ParseError::OutOfRange);
+    }
+    // a second value of 60 is allowed for leap seconds, which only come
+    // at the end of the last minute of a UTC day, and rolls over into the
+    // next day
+    if hour > 23 || minute > 59 || second > 60 {
+        return Err(ParseError::OutOfRange);
+    }
+    if second == 60 && ((hour * 3600 + minute * 60) as i64 - offset).rem_euclid(86_400) != 86_340 {
+        return Err(ParseError::OutOfRange);
+    }
+
+    let days = calendar.days_from_civil(year, month, date);
+    let secs = days * 86_400 + (hour * 3600 + minute * 60 + second) as i64 - offset;
+
+    if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+        return Err(ParseError::OutOfRange);
+    }
+
+    Ok(DateTime::from_parts(secs, nanos, FixedOffset::utc()))
+}
+
+impl DateTime {
+    /// parses an RFC 3339 (ISO 8601) timestamp such as
+    /// `1996-09-09T15:23:44Z` or `1996-09-09T17:23:44+02:00`
+    ///
+    /// the result is converted to UTC. fractional seconds may have any
+    /// number of digits, but only the first nine (down to nanoseconds) are
+    /// kept
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc3339("1996-09-09T17:23:44+02:00").unwrap();
+    ///
+    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+    ///
+    /// let date = DateTime::parse_rfc3339("1996-09-09T15:23:44.12345Z").unwrap();
+    /// assert_eq!(date.microsecond(), 123450);
+    /// ```
+    pub fn parse_rfc3339(input: &str) -> Result<DateTime, ParseError> {
+        DateTime::parse_rfc3339_preserving_offset(input).map(|(date, _)| date)
+    }
+
+    /// parses an RFC 3339 timestamp like [`DateTime::parse_rfc3339`], and
+    /// also returns the offset it was written in, so that it can later be
+    /// shown in the original zone with [`DateTime::with_offset`]
+    ///
+    /// the DateTime itself is still in UTC. `Z` gives the zero offset
+    /// ```
+    /// # use datetime::DateTime;
+    /// let (date, offset) =
+    ///     DateTime::parse_rfc3339_preserving_offset("1996-09-09T17:23:44+02:00").unwrap();
+    ///
+    /// assert_eq!(date.hour(), 15);
+    /// assert_eq!(offset.to_string(), "+02:00");
+    /// assert_eq!(date.with_offset(offset).hour(), 17);
+    /// ```
+    pub fn parse_rfc3339_preserving_offset(
+        input: &str,
+    ) -> Result<(DateTime, FixedOffset), ParseError> {
+        rfc3339_in(input, &Gregorian, Tolerances::RFC3339)
+    }
+
+    /// parses an RFC 3339 timestamp like [`DateTime::parse_rfc3339`], but
+    /// with the tolerances of the given profile
+    /// ```
+    /// # use datetime::{DateTime, ParseError, ParseProfile};
+    /// let date = DateTime::parse_rfc3339_with(" 1996-09-09t15:23z ", ParseProfile::Lenient);
+    /// assert_eq!(date.unwrap().to_rfc3339(), "1996-09-09T15:23:00Z");
+    ///
+    /// let date = DateTime::parse_rfc3339_with("1996-09-09T15:23:44,5Z", ParseProfile::Lenient);
+    /// assert_eq!(date.unwrap().millisecond(), 500);
+    ///
+    /// let date = DateTime::parse_rfc3339_with("1996-09-09 15:23:44Z", ParseProfile::Strict);
+    /// assert_eq!(date, Err(ParseError::Invalid(10)));
+    /// ```
+    pub fn parse_rfc3339_with(input: &str, profile: ParseProfile) -> Result<DateTime, ParseError> {
+        rfc3339_in(input, &Gregorian, profile.tolerances()).map(|(date, _)| date)
+    }
+}
+
+// parses an RFC 3339 timestamp whose date is in the given calendar,
+// returning it in UTC along with the offset it was written in
+pub(crate) fn rfc3339_in<C: Calendar + ?Sized>(
+    input: &str,
+    calendar: &C,
+    tolerances: Tolerances,
+) -> Result<(DateTime, FixedOffset), ParseError> {
+    let mut cursor = Cursor::new(input);
+    if tolerances.whitespace {
+        cursor.skip_spaces();
+    }
+
+    let year = cursor.digits(4)?;
+    cursor.expect(b'-')?;
+    let month = cursor.digits(2)?;
+    cursor.expect(b'-')?;
+    let date = cursor.digits(2)?;
+    let separators: &[u8] = match (tolerances.lowercase, tolerances.space_separator) {
+        (true, true) => b"Tt ",
+        (true, false) => b"Tt",
+        (false, true) => b"T ",
+        (false, false) => b"T",
+    };
+    cursor.expect_any(separators)?;
+    let hour = cursor.digits(2)?;
+    cursor.expect(b':')?;
+    let minute = cursor.digits(2)?;
+
+    // a fraction can only follow the seconds, since after the minutes it
+    // would be a fraction of a minute
+    let (mut second, mut nanos) = (0, 0);
+    if !tolerances.missing_seconds || cursor.peek() == Some(b':') {
+        cursor.expect(b':')?;
+        second = cursor.digits(2)?;
+
+        let marks: &[u8] = if tolerances.comma_fraction {
+            b".,"
+        } else {
+            b"."
+        };
+        if cursor.peek().is_some_and(|b| marks.contains(&b)) {
+            cursor.next()?;
+            nanos = cursor.fraction()?;
+        }
+    }
+
+    let zones: &[u8] = if tolerances.lowercase {
+        b"Zz+-"
+    } else {
+        b"Z+-"
+    };
+    let offset = match cursor.expect_any(zones)? {
+        b'Z' | b'z' => 0,
+        sign => {
+            let hours = cursor.digits(2)?;
+            cursor.expect(b':')?;
+            let minutes = cursor.digits(2)?;
+            if hours > 23 || minutes > 59 {
+                return Err(ParseError::OutOfRange);
+            }
+
+            let offset = (hours * 3600 + minutes * 60) as i64;
+            if sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+    };
+
+    if tolerances.whitespace {
+        cursor.skip_spaces();
+    }
+    cursor.finish()?;
+
+    let date = from_fields_in(
+        calendar, year, month, date, hour, minute, second, nanos, offset,
+    )?;
+    let offset = FixedOffset::east(offset as i32).ok_or(ParseError::OutOfRange)?;
+
+    Ok((date, offset))
+}
+
+// reads an ISO 8601 offset, `Z` or `+hh:mm`
+pub(crate) fn zone(cursor: &mut Cursor) -> Result<FixedOffset, ParseError> {
+    let sign = cursor.expect_any(b"Z+-")?;
+    if sign == b'Z' {
+        return Ok(FixedOffset::utc());
+    }
+
+    let hours = cursor.digits(2)?;
+    cursor.expect(b':')?;
+    let minutes = cursor.digits(2)?;
+    if hours > 23 || minutes > 59 {
+        return Err(ParseError::OutOfRange);
+    }
+
+    let secs = (hours * 3600 + minutes * 60) as i32;
+    let secs = if sign == b'-' { -secs } else { secs };
+    FixedOffset::east(secs).ok_or(ParseError::OutOfRange)
+}
+
+// the offsets of the zone names RFC 2822 still accepts from RFC 822, in
+// seconds east of UTC
+pub(crate) fn obsolete_zone(name: &[u8]) -> Option<i64> {
+    let hours = match name.to_ascii_uppercase().as_slice() {
+        b"UT" | b"GMT" => 0,
+        b"EDT" => -4,
+        b"EST" | b"CDT" => -5,
+        b"CST" | b"MDT" => -6,
+        b"MST" | b"PDT" => -7,
+        b"PST" => -8,
+        // the military zones were so often given with the wrong sign that
+        // RFC 2822 says to treat them all as UTC
+        [b] if b.is_ascii_alphabetic() && *b != b'J' => 0,
+        _ => return None,
+    };
+
+    Some(hours * 3600)
+}
+
+impl DateTime {
+    /// parses an RFC 2822 timestamp, as used in the `Date:` header of
+    /// emails, such as `Mon, 09 Sep 1996 15:23:44 +0000`
+    ///
+    /// the result is converted to UTC. the day of the week and the seconds
+    /// are optional, and the obsolete forms RFC 2822 says to accept (two-
+    /// digit years and zone names such as `GMT` or `EST`) are understood.
+    /// if a day of the week is given it must match the date
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc2822("Mon, 09 Sep 1996 11:23:44 -0400").unwrap();
+    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+    ///
+    /// let date = DateTime::parse_rfc2822("9 Sep 96 15:23 GMT").unwrap();
+    /// assert_eq!(date.second(), 0);
+    /// ```
+    pub fn parse_rfc2822(input: &str) -> Result<DateTime, ParseError> {
+        let mut cursor = Cursor::new(input);
+        cursor.skip_spaces();
+
+        let mut day = None;
+        if cursor.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            let start = cursor.pos;
+            day = Some((start, cursor.letters()?));
+            cursor.skip_spaces();
+            cursor.expect(b',')?;
+            cursor.skip_spaces();
+        }
+
+        let (date, _) = cursor.digits_between(1, 2)?;
+        cursor.skip_spaces();
+
+        let month = month_abbreviation(&mut cursor)?;
+        cursor.skip_spaces();
+
+        let year = match cursor.digits_between(2, 4)? {
+            (year, 2) if year < 50 => year + 2000,
+            (year, 2) | (year, 3) => year + 1900,
+            (year, _) => year,
+        };
+        if cursor.skip_spaces() == 0 {
+            return Err(cursor
+                .peek()
+                .map_or(ParseError::TooShort, |_| ParseError::Invalid(cursor.pos)));
+        }
+
+        let hour = cursor.digits(2)?;
+        cursor.expect(b':')?;
+        let minute = cursor.digits(2)?;
+        let mut second = 0;
+        if cursor.peek() == Some(b':') {
+            cursor.next()?;
+            second = cursor.digits(2)?;
+        }
+        if cursor.skip_spaces() == 0 {
+            return Err(cursor
+                .peek()
+                .map_or(ParseError::TooShort, |_| ParseError::Invalid(cursor.pos)));
+        }
+
+        let start = cursor.pos;
+        let offset = match cursor.peek() {
+            Some(sign @ (b'+' | b'-')) => {
+                cursor.next()?;
+                let hours = cursor.digits(2)?;
+                let minutes = cursor.digits(2)?;
+                if minutes > 59 {
+                    return Err(ParseError::OutOfRange);
+                }
+
+                let offset = (hours * 3600 + minutes * 60) as i64;
+                if sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => obsolete_zone(cursor.letters()?).ok_or(ParseError::Invalid(start))?,
+        };
+
+        cursor.skip_spaces();
+        cursor.finish()?;
+
+        // the date has to exist before its weekday can be checked
+        let result = from_fields(year, month, date, hour, minute, second, 0, offset)?;
+        if let Some((start, name)) = day {
+            let days = days_from_civil(year as i64, month, date);
+            if !weekday_from_days(days)
+                .abbreviation()
+                .as_bytes()
+                .eq_ignore_ascii_case(name)
+            {
+                return Err(ParseError::Invalid(start));
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+// reads a month abbreviation, returning the month as 1-12
+fn month_abbreviation(cursor: &mut Cursor) -> Result<usize, ParseError> {
+    let start = cursor.pos;
+    let name = cursor.letters()?;
+
+    MONTHS
+        .iter()
+        .position(|month| month.abbreviation().as_bytes().eq_ignore_ascii_case(name))
+        .map(|month| month + 1)
+        .ok_or(ParseError::Invalid(start))
+}
+
+// reads `hh:mm:ss`
+fn time_of_day(cursor: &mut Cursor) -> Result<(usize, usize, usize), ParseError> {
+    let hour = cursor.digits(2)?;
+    cursor.expect(b':')?;
+    let minute = cursor.digits(2)?;
+    cursor.expect(b':')?;
+    let second = cursor.digits(2)?;
+
+    Ok((hour, minute, second))
+}
+
+impl DateTime {
+    /// parses an HTTP date, as found in `Date` and `Last-Modified` headers
+    ///
+    /// as RFC 7231 requires, all three of its forms are accepted:
+    ///
+    /// - IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`
+    /// - the obsolete RFC 850 form: `Sunday, 06-Nov-94 08:49:37 GMT`
+    /// - the obsolete asctime form: `Sun Nov  6 08:49:37 1994`
+    ///
+    /// two-digit RFC 850 years from 70 onwards are taken to be in the
+    /// 1900s, and earlier ones in the 2000s. the day of the week must match
+    /// the date
+    /// ```
+    /// # use datetime::DateTime;
+    /// let fixdate = DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    /// let rfc850 = DateTime::parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT").unwrap();
+    /// let asctime = DateTime::parse_http_date("Sun Nov  6 08:49:37 1994").unwrap();
+    ///
+    /// assert_eq!(fixdate.to_http_date(), "Sun, 06 Nov 1994 08:49:37 GMT");
+    /// assert!(fixdate == rfc850 && rfc850 == asctime);
+    /// ```
+    pub fn parse_http_date(input: &str) -> Result<DateTime, ParseError> {
+        let mut cursor = Cursor::new(input);
+        let day = cursor.letters()?;
+
+        // the fields, and whether the day of the week is spelled out
+        let fields = if cursor.peek() == Some(b',') {
+            cursor.next()?;
+            cursor.expect(b' ')?;
+            let date = cursor.digits(2)?;
+
+            let fields = if cursor.expect_any(b" -")? == b' ' {
+                // IMF-fixdate
+                let month = month_abbreviation(&mut cursor)?;
+                cursor.expect(b' ')?;
+                let year = cursor.digits(4)?;
+                cursor.expect(b' ')?;
+                (year, month, date, time_of_day(&mut cursor)?, false)
+            } else {
+                // RFC 850
+                let month = month_abbreviation(&mut cursor)?;
+                cursor.expect(b'-')?;
+                let year = match cursor.digits(2)? {
+                    year if year >= 70 => year + 1900,
+                    year => year + 2000,
+                };
+                cursor.expect(b' ')?;
+                (year, month, date, time_of_day(&mut cursor)?, true)
+            };
+
+            cursor.expect(b' ')?;
+            let start = cursor.pos;
+            if cursor.letters()? != b"GMT" {
+                return Err(ParseError::Invalid(start));
+            }
+
+            fields
+        } else {
+            // asctime, which has no zone and puts the year last
+            cursor.expect(b' ')?;
+            let month = month_abbreviation(&mut cursor)?;
+            cursor.expect(b' ')?;
+            let date = if cursor.peek() == Some(b' ') {
+                cursor.next()?;
+                cursor.digits(1)?
+            } else {
+                cursor.digits(2)?
+            };
+            cursor.expect(b' ')?;
+            let time = time_of_day(&mut cursor)?;
+            cursor.expect(b' ')?;
+            let year = cursor.digits(4)?;
+
+            (year, month, date, time, false)
+        };
+
+        cursor.finish()?;
+
+        let (year, month, date, (hour, minute, second), full_name) = fields;
+        let parsed = from_fields(year, month, date, hour, minute, second, 0, 0)?;
+
+        let weekday = parsed.day();
+        let expected = if full_name {
+            weekday.name()
+        } else {
+            weekday.abbreviation()
+        };
+        if !expected.as_bytes().eq_ignore_ascii_case(day) {
+            return Err(ParseError::Invalid(0));
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// the format [`DateTime::parse_any`] recognised a timestamp as
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DetectedFormat {
+    /// an RFC 3339 timestamp, such as `1996-09-09T15:23:44Z`
+    Rfc3339,
+    /// an RFC 2822 timestamp, such as `Mon, 09 Sep 1996 15:23:44 +0000`
+    Rfc2822,
+    /// one of the obsolete HTTP date forms, such as
+    /// `Mon Sep  9 15:23:44 1996`
+    HttpDate,
+    /// `yyyy-mm-dd hh:mm:ss`, or with a `T`, with no offset; taken to be in
+    /// UTC
+    NoOffset,
+    /// an integer Unix timestamp, in the unit [`guess_epoch_unit`] picked
+    Epoch(EpochUnit),
+}
+
+impl DateTime {
+    /// parses a timestamp in whichever of several common formats it is
+    /// written in, returning the DateTime in UTC and the format found
+    ///
+    /// input that starts with a letter is tried as RFC 2822, then as an
+    /// HTTP date. an optional `-` and digits alone are a Unix timestamp in
+    /// the unit [`guess_epoch_unit`] picks. anything else is tried as
+    /// RFC 3339, or as a date and time with no offset if it ends too soon
+    /// for that
+    ///
+    /// if no format matches, the error is from the last format tried,
+    /// except that RFC 2822's is kept over an HTTP date's
+    /// ```
+    /// # use datetime::{DateTime, DetectedFormat};
+    /// # use datetime::epoch::EpochUnit;
+    /// let inputs = [
+    ///     ("1996-09-09T15:23:44Z", DetectedFormat::Rfc3339),
+    ///     ("Mon, 09 Sep 1996 15:23:44 +0000", DetectedFormat::Rfc2822),
+    ///     ("1996-09-09 15:23:44", DetectedFormat::NoOffset),
+    ///     ("842282624", DetectedFormat::Epoch(EpochUnit::Seconds)),
+    ///     ("842282624000", DetectedFormat::Epoch(EpochUnit::Millis)),
+    /// ];
+    ///
+    /// for &(input, format) in &inputs {
+    ///     let parsed = DateTime::parse_any(input).unwrap();
+    ///     assert_eq!(parsed, (DateTime::from_secs(842282624), format));
+    /// }
+    /// ```
+    pub fn parse_any(input: &str) -> Result<(DateTime, DetectedFormat), ParseError> {
+        let digits = input.strip_prefix('-').unwrap_or(input);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            let value: i64 = input.parse().map_err(|_| ParseError::OutOfRange)?;
+            let unit = guess_epoch_unit(value).ok_or(ParseError::OutOfRange)?;
+            let date = unit.to_datetime(value).ok_or(ParseError::OutOfRange)?;
+            return Ok((date, DetectedFormat::Epoch(unit)));
+        }
+
+        if input.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return match DateTime::parse_rfc2822(input) {
+                Ok(date) => Ok((date, DetectedFormat::Rfc2822)),
+                Err(e) => DateTime::parse_http_date(input)
+                    .map(|date| (date, DetectedFormat::HttpDate))
+                    .map_err(|_| e),
+            };
+        }
+
+        match DateTime::parse_rfc3339(input) {
+            Ok(date) => Ok((date, DetectedFormat::Rfc3339)),
+            // the input may just have no offset
+            Err(ParseError::TooShort) => {
+                no_offset(input).map(|date| (date, DetectedFormat::NoOffset))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// parses `yyyy-mm-dd hh:mm:ss[.fff]`, with a space or a `T`, as UTC
+fn no_offset(input: &str) -> Result<DateTime, ParseError> {
+    let mut cursor = Cursor::new(input);
+    let year = cursor.digits(4)?;
+    cursor.expect(b'-')?;
+    let month = cursor.digits(2)?;
+    cursor.expect(b'-')?;
+    let date = cursor.digits(2)?;
+    cursor.expect_any(b" T")?;
+    let (hour, minute, second) = time_of_day(&mut cursor)?;
+    let nanos = if cursor.peek() == Some(b'.') {
+        cursor.next()?;
+        cursor.fraction()?
+    } else {
+        0
+    };
+    cursor.finish()?;
+
+    from_fields(year, month, date, hour, minute, second, nanos, 0)
+}
+
+impl FromStr for DateTime {
+    type Err = ParseError;
+
+    /// parses an RFC 3339 timestamp; see [`DateTime::parse_rfc3339`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DateTime::parse_rfc3339(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{no_offset, DetectedFormat, ParseError, ParseProfile};
+    use crate::epoch::EpochUnit;
+    use crate::{DateTime, FixedOffset};
+
+    #[test]
+    fn test_parse_any() {
+        let cases = [
+            ("1996-09-09T10:53:44.5-04:30", DetectedFormat::Rfc3339),
+            ("Mon, 9 Sep 1996 15:23:44 GMT", DetectedFormat::Rfc2822),
+            ("Monday, 09-Sep-96 15:23:44 GMT", DetectedFormat::HttpDate),
+            ("1996-09-09T15:23:44.5", DetectedFormat::NoOffset),
+            ("842282624500", DetectedFormat::Epoch(EpochUnit::Millis)),
+        ];
+        for &(input, format) in &cases {
+            let (date, found) = DateTime::parse_any(input).unwrap();
+            assert_eq!(found, format, "{}", input);
+            assert_eq!(date.secs, 842282624, "{}", input);
+        }
+
+        // failures are reported by the last format tried, except that
+        // RFC 2822's is kept over an HTTP date's
+        assert_eq!(
+            DateTime::parse_any("1996-09-09T15:23:44+2"),
+            no_offset("1996-09-09T15:23:44+2").map(|d| (d, DetectedFormat::NoOffset))
+        );
+        assert_eq!(
+            DateTime::parse_any("1996-09-09T15:23:44+2"),
+            Err(ParseError::TooLong)
+        );
+        assert_eq!(
+            DateTime::parse_any("Mon, 09 Sep 1996 15:23:44 XYZ"),
+            DateTime::parse_rfc2822("Mon, 09 Sep 1996 15:23:44 XYZ")
+                .map(|d| (d, DetectedFormat::Rfc2822))
+        );
+        assert_eq!(
+            DateTime::parse_any("-"),
+            DateTime::parse_rfc3339("-").map(|d| (d, DetectedFormat::Rfc3339))
+        );
+        assert_eq!(
+            DateTime::parse_any("99999999999999999999"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_any("1996-02-30 00:00:00"),
+            Err(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let date = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+        assert_eq!(date.secs, 842282624);
+
+        let date: DateTime = "1996-09-09t10:53:44.123-04:30".parse().unwrap();
+        assert_eq!(date.secs, 842282624);
+        assert_eq!(date.nanos, 123_000_000);
+
+        let date = DateTime::parse_rfc3339("2000-02-29 00:00:00+00:00").unwrap();
+        assert_eq!(date.secs, 951782400);
+
+        let date = DateTime::parse_rfc3339("1969-12-31T23:59:59Z").unwrap();
+        assert_eq!(date.secs, -1);
+
+        // a leap second is only allowed in the last minute of a UTC day
+        let date = DateTime::parse_rfc3339("2016-12-31T23:59:60Z").unwrap();
+        assert_eq!(date.to_rfc3339(), "2017-01-01T00:00:00Z");
+        let date = DateTime::parse_rfc3339("2016-12-31T18:59:60-05:00").unwrap();
+        assert_eq!(date.to_rfc3339(), "2017-01-01T00:00:00Z");
+        assert_eq!(
+            DateTime::parse_rfc3339("2016-12-31T12:00:60Z"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("2016-12-31T23:59:60+01:00"),
+            Err(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_parse_fraction_lengths() {
+        let cases = [
+            ("1", 100_000_000),
+            ("123456", 123_456_000),
+            ("123456789", 123_456_789),
+            ("1234567891234", 123_456_789),
+            ("000000001", 1),
+        ];
+
+        for (fraction, nanos) in cases {
+            let input = format!("1969-12-31T23:59:59.{}Z", fraction);
+            let date = DateTime::parse_rfc3339(&input).unwrap();
+            assert_eq!((date.secs, date.nanos), (-1, nanos));
+        }
+
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-09-09T15:23:44.Z").err(),
+            Some(ParseError::Invalid(20))
+        );
+    }
+
+    #[test]
+    fn test_preserving_offset() {
+        let (date, offset) =
+            DateTime::parse_rfc3339_preserving_offset("1996-09-09T10:53:44-04:30").unwrap();
+        assert_eq!(date.secs, 842282624);
+        assert_eq!(offset.seconds(), -16200);
+        assert!(date.offset().is_utc());
+
+        let (_, offset) =
+            DateTime::parse_rfc3339_preserving_offset("1996-09-09T15:23:44Z").unwrap();
+        assert!(offset.is_utc());
+
+        assert_eq!(
+            DateTime::parse_rfc3339_preserving_offset("1996-09-09T15:23:44+24:00").err(),
+            Some(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let parse = |input, profile| DateTime::parse_rfc3339_with(input, profile);
+        let canonical = "1996-09-09T15:23:44.5Z";
+        let expected = DateTime::parse_rfc3339(canonical).unwrap();
+        assert_eq!(parse(canonical, ParseProfile::Strict), Ok(expected));
+        assert_eq!(parse(canonical, ParseProfile::Lenient), Ok(expected));
+
+        for input in [
+            "\t1996-09-09T15:23:44.5Z  ",
+            "1996-09-09t15:23:44.5z",
+            "1996-09-09 15:23:44.5Z",
+            "1996-09-09T15:23:44,5Z",
+        ] {
+            assert_eq!(parse(input, ParseProfile::Lenient), Ok(expected));
+            assert!(parse(input, ParseProfile::Strict).is_err());
+        }
+
+        let date = parse("1996-09-09T17:23+02:00", ParseProfile::Lenient).unwrap();
+        assert_eq!(date.secs, 842282580);
+        assert_eq!(
+            parse("1996-09-09T17:23+02:00", ParseProfile::Strict),
+            Err(ParseError::Invalid(16))
+        );
+        assert_eq!(
+            parse("1996-09-09T17:23,5Z", ParseProfile::Lenient),
+            Err(ParseError::Invalid(16))
+        );
+        assert!(!ParseProfile::Strict.allows_lowercase());
+        assert!(ParseProfile::Lenient.allows_missing_seconds());
+    }
+
+    #[test]
+    fn test_parse_rfc3339_errors() {
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-09-09T15:23").err(),
+            Some(ParseError::TooShort)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-09-09T15:23:44Zx").err(),
+            Some(ParseError::TooLong)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996/09/09T15:23:44Z").err(),
+            Some(ParseError::Invalid(4))
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1999-02-29T00:00:00Z").err(),
+            Some(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("0000-12-31T23:59:59Z").err(),
+            Some(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc2822() {
+        let date = DateTime::parse_rfc2822("Mon, 09 Sep 1996 15:23:44 +0000").unwrap();
+        assert_eq!(date.secs, 842282624);
+        assert_eq!(date.to_rfc2822(), "Mon, 09 Sep 1996 15:23:44 +0000");
+
+        let date = DateTime::parse_rfc2822("  mon ,9 SEP 1996 10:53:44 -0430 ").unwrap();
+        assert_eq!(date.secs, 842282624);
+
+        // obsolete two-digit years and zone names
+        let date = DateTime::parse_rfc2822("Thu, 1 Jan 70 00:00 EST").unwrap();
+        assert_eq!(date.secs, 5 * 3600);
+        let date = DateTime::parse_rfc2822("1 Jan 01 00:00:00 z").unwrap();
+        assert_eq!(date.year(), 2001);
+
+        let date = DateTime::from_secs(842282624).with_offset(FixedOffset::east(20700).unwrap());
+        let round_trip = DateTime::parse_rfc2822(&date.to_rfc2822()).unwrap();
+        assert_eq!(round_trip, date);
+    }
+
+    #[test]
+    fn test_parse_rfc2822_errors() {
+        // 1996-09-09 was a Monday
+        assert_eq!(
+            DateTime::parse_rfc2822("Tue, 09 Sep 1996 15:23:44 +0000").err(),
+            Some(ParseError::Invalid(0))
+        );
+        assert_eq!(
+            DateTime::parse_rfc2822("09 Sept 1996 15:23:44 +0000").err(),
+            Some(ParseError::Invalid(3))
+        );
+        assert_eq!(
+            DateTime::parse_rfc2822("09 Sep 1996 15:23:44").err(),
+            Some(ParseError::TooShort)
+        );
+        assert_eq!(
+            DateTime::parse_rfc2822("31 Sep 1996 15:23:44 +0000").err(),
+            Some(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_rfc2822("Mon, 31 Sep 1996 15:23:44 +0000").err(),
+            Some(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_rfc2822("09 Sep 1996 15:23:44 CEST").err(),
+            Some(ParseError::Invalid(21))
+        );
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        let forms = [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ];
+        for form in forms {
+            let date = DateTime::parse_http_date(form).unwrap();
+            assert_eq!(date.secs, 784111777);
+            assert_eq!(date.to_http_date(), forms[0]);
+        }
+
+        let date = DateTime::parse_http_date("Tuesday, 01-Jan-30 00:00:00 GMT").unwrap();
+        assert_eq!(date.year(), 2030);
+        let date = DateTime::parse_http_date("Thu Dec 31 23:59:59 1970").unwrap();
+        assert_eq!(date.year(), 1970);
+    }
+
+    #[test]
+    fn test_parse_http_date_errors() {
+        // the day must match the date, in the form's own spelling
+        assert_eq!(
+            DateTime::parse_http_date("Mon, 06 Nov 1994 08:49:37 GMT").err(),
+            Some(ParseError::Invalid(0))
+        );
+        assert_eq!(
+            DateTime::parse_http_date("Sun, 06-Nov-94 08:49:37 GMT").err(),
+            Some(ParseError::Invalid(0))
+        );
+        assert_eq!(
+            DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 UTC").err(),
+            Some(ParseError::Invalid(26))
+        );
+        assert_eq!(
+            DateTime::parse_http_date("Sun, 6 Nov 1994 08:49:37 GMT").err(),
+            Some(ParseError::Invalid(6))
+        );
+        assert_eq!(
+            DateTime::parse_http_date("Sun Nov  6 08:49:37").err(),
+            Some(ParseError::TooShort)
+        );
+    }
+}
@@ -0,0 +1,534 @@
+//! dates and times with unknown components
+//!
+//! ISO 8601 lets a date leave out whatever isn't known: `1996-03` is March
+//! 1996, `1996` the whole year, and `--09-09` (as in vCard birthdays) the
+//! 9th of September in any year. [`PartialDate`] and [`PartialDateTime`]
+//! keep exactly the components that were given, and can be completed to a
+//! full date or [`DateTime`] once the rest is known
+//! ```
+//! # use datetime::partial::{PartialDate, PartialDateTime};
+//! # use datetime::{DateTime, Month};
+//! let birthday: PartialDate = "--09-09".parse().unwrap();
+//! assert_eq!(birthday.year(), None);
+//! assert_eq!(birthday.month(), Some(Month::September));
+//! assert!(birthday.matches(&DateTime::from_secs(842282624)));
+//!
+//! let meeting: PartialDateTime = "--09-09T15:23".parse().unwrap();
+//! let now = DateTime::parse_rfc3339("2026-01-01T00:00:00Z").unwrap();
+//! let next = meeting.complete_with(&now).unwrap();
+//! assert_eq!(next.to_rfc3339(), "2026-09-09T15:23:00Z");
+//! ```
+use crate::convert::{days_from_civil, days_in_month};
+use crate::parse::{from_fields, zone, Cursor};
+use crate::{DateTime, FixedOffset, Month, PackedDate, ParseError, MONTHS};
+use core::fmt;
+use core::str::FromStr;
+
+/// how to fill in the fields a [`PartialDate`] is missing
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Completion {
+    /// the earliest date it could be, the 1st of January or of the month
+    #[default]
+    Start,
+    /// the middle of the period, the 1st of July or the 15th of the month
+    Middle,
+    /// the latest date it could be, the 31st of December or the last day
+    /// of the month
+    End,
+}
+
+/// a date with any of its year, month and day unknown
+///
+/// PartialDates are ordered by year, then month, then day, with a missing
+/// field before any present one, so `1996` sorts before `1996-01`, and
+/// `--09-09` before both
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartialDate {
+    year: Option<usize>,
+    // 1-12
+    month: Option<usize>,
+    date: Option<usize>,
+}
+
+impl PartialDate {
+    /// returns the partial date with the given fields, where `month` is
+    /// 1-12, or None if any is out of range, the year is 0 or after 9999,
+    /// all are unknown, or the year and day are known but the month isn't
+    ///
+    /// without a year, the 29th of February is allowed
+    /// ```
+    /// # use datetime::partial::PartialDate;
+    /// assert!(PartialDate::new(Some(1996), Some(9), None).is_some());
+    /// assert!(PartialDate::new(None, Some(2), Some(29)).is_some());
+    /// assert!(PartialDate::new(Some(1996), None, Some(9)).is_none());
+    /// assert!(PartialDate::new(Some(1996), Some(9), Some(31)).is_none());
+    /// ```
+    pub fn new(year: Option<usize>, month: Option<usize>, date: Option<usize>) -> Option<Self> {
+        if year.is_some_and(|year| !(1..=9999).contains(&year)) {
+            return None;
+        }
+        if month.is_some_and(|month| !(1..=12).contains(&month)) {
+            return None;
+        }
+
+        // 2000 is a leap year, so allows every day any year could have
+        let max_date = match month {
+            Some(month) => days_in_month(year.unwrap_or(2000), month),
+            None => 31,
+        };
+        match (year, month, date) {
+            (None, None, None) | (Some(_), None, Some(_)) => None,
+            (_, _, Some(date)) if date < 1 || date > max_date => None,
+            _ => Some(PartialDate { year, month, date }),
+        }
+    }
+
+    /// returns the year, if known
+    pub fn year(&self) -> Option<usize> {
+        self.year
+    }
+
+    /// returns the month, if known
+    pub fn month(&self) -> Option<Month> {
+        self.month.map(|month| MONTHS[month - 1])
+    }
+
+    /// returns the day of the month, if known
+    pub fn date(&self) -> Option<usize> {
+        self.date
+    }
+
+    /// returns true if the year, month and day are all known
+    pub fn is_complete(&self) -> bool {
+        self.year.is_some() && self.month.is_some() && self.date.is_some()
+    }
+
+    /// returns the same date in the given year, or None if it doesn't
+    /// exist in that year (as with the 29th of February)
+    pub fn with_year(&self, year: usize) -> Option<Self> {
+        PartialDate::new(Some(year), self.month, self.date)
+    }
+
+    /// returns true if the date of the given DateTime, as reported in its
+    /// offset, agrees with every known field
+    pub fn matches(&self, date: &DateTime) -> bool {
+        self.year.is_none_or(|year| year == date.year())
+            && self
+                .month
+                .is_none_or(|month| month == date.month() as usize + 1)
+            && self.date.is_none_or(|day| day == date.date())
+    }
+
+    /// returns the full date, filling in a missing month and day by the
+    /// given policy, or None if the year is unknown
+    pub fn complete(&self, completion: Completion) -> Option<PackedDate> {
+        let year = self.year?;
+        let month = self.month.unwrap_or(match completion {
+            Completion::Start => 1,
+            Completion::Middle => 7,
+            Completion::End => 12,
+        });
+        let date = self.date.unwrap_or(match (completion, self.month) {
+            (Completion::Start, _) | (Completion::Middle, None) => 1,
+            (Completion::Middle, Some(_)) => 15,
+            (Completion::End, _) => days_in_month(year, month),
+        });
+
+        PackedDate::new(year, month, date).ok()
+    }
+
+    /// returns midnight UTC on the full date, filling in a missing month
+    /// and day by the given policy, or None if the year is unknown
+    pub fn to_datetime(&self, completion: Completion) -> Option<DateTime> {
+        let date = self.complete(completion)?;
+        let days = days_from_civil(date.year() as i64, date.month() as usize + 1, date.date());
+        Some(DateTime::from_signed_secs(days * 86_400))
+    }
+
+    // reads a partial date, leaving the cursor after it
+    fn read(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        let (mut year, mut month, mut date) = (None, None, None);
+
+        if cursor.peek() == Some(b'-') {
+            // --MM-DD, --MM or ---DD
+            cursor.expect(b'-')?;
+            cursor.expect(b'-')?;
+            if cursor.peek() == Some(b'-') {
+                cursor.next()?;
+                date = Some(cursor.digits(2)?);
+            } else {
+                month = Some(cursor.digits(2)?);
+                if cursor.peek() == Some(b'-') {
+                    cursor.next()?;
+                    date = Some(cursor.digits(2)?);
+                }
+            }
+        } else {
+            year = Some(cursor.digits(4)?);
+            if cursor.peek() == Some(b'-') {
+                cursor.next()?;
+                month = Some(cursor.digits(2)?);
+                if cursor.peek() == Some(b'-') {
+                    cursor.next()?;
+                    date = Some(cursor.digits(2)?);
+                }
+            }
+        }
+
+        PartialDate::new(year, month, date).ok_or(ParseError::OutOfRange)
+    }
+}
+
+impl From<PackedDate> for PartialDate {
+    fn from(date: PackedDate) -> Self {
+        PartialDate {
+            year: Some(date.year()),
+            month: Some(date.month() as usize + 1),
+            date: Some(date.date()),
+        }
+    }
+}
+
+impl fmt::Display for PartialDate {
+    /// writes the known fields as ISO 8601 does: `YYYY-MM-DD`, `YYYY-MM`,
+    /// `YYYY`, `--MM-DD`, `--MM` or `---DD`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.year {
+            Some(year) => write!(f, "{:04}", year)?,
+            None => f.write_str("-")?,
+        }
+        match self.month {
+            Some(month) => write!(f, "-{:02}", month)?,
+            None if self.year.is_none() => f.write_str("-")?,
+            None => {}
+        }
+        if let Some(date) = self.date {
+            write!(f, "-{:02}", date)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PartialDate {
+    type Err = ParseError;
+
+    /// parses any of the forms [`Display`](fmt::Display) writes
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let date = PartialDate::read(&mut cursor)?;
+        cursor.finish()?;
+
+        Ok(date)
+    }
+}
+
+/// a [`PartialDate`] with a time of day that may also be partly unknown,
+/// and an offset from UTC that may be unknown
+///
+/// the time is known to the hour, the minute or the second (with any
+/// fraction), or not at all
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PartialDateTime {
+    date: PartialDate,
+    hour: Option<usize>,
+    minute: Option<usize>,
+    second: Option<usize>,
+    nanos: u32,
+    offset: Option<FixedOffset>,
+}
+
+impl PartialDateTime {
+    /// returns the partial date with the time and offset unknown
+    pub fn from_date(date: PartialDate) -> Self {
+        PartialDateTime {
+            date,
+            hour: None,
+            minute: None,
+            second: None,
+            nanos: 0,
+            offset: None,
+        }
+    }
+
+    /// returns the same date at the given time, or None if a field is out
+    /// of range or there are seconds without minutes
+    pub fn with_time(
+        &self,
+        hour: usize,
+        minute: Option<usize>,
+        second: Option<usize>,
+    ) -> Option<Self> {
+        if hour > 23 || minute.is_some_and(|minute| minute > 59) {
+            return None;
+        }
+        match (minute, second) {
+            (None, Some(_)) => return None,
+            (_, Some(second)) if second > 59 => return None,
+            _ => {}
+        }
+
+        Some(PartialDateTime {
+            hour: Some(hour),
+            minute,
+            second,
+            nanos: 0,
+            ..*self
+        })
+    }
+
+    /// returns the same date and time with the given fraction of a second,
+    /// or None if the seconds are unknown or `nanos` isn't less than a
+    /// second
+    pub fn with_nanosecond(&self, nanos: u32) -> Option<Self> {
+        if self.second.is_none() || nanos >= 1_000_000_000 {
+            return None;
+        }
+
+        Some(PartialDateTime { nanos, ..*self })
+    }
+
+    /// returns the same date and time in the given offset from UTC
+    pub fn with_offset(&self, offset: FixedOffset) -> Self {
+        PartialDateTime {
+            offset: Some(offset),
+            ..*self
+        }
+    }
+
+    /// returns the date
+    pub fn date(&self) -> PartialDate {
+        self.date
+    }
+
+    /// returns the hour, if known
+    pub fn hour(&self) -> Option<usize> {
+        self.hour
+    }
+
+    /// returns the minute, if known
+    pub fn minute(&self) -> Option<usize> {
+        self.minute
+    }
+
+    /// returns the second, if known
+    pub fn second(&self) -> Option<usize> {
+        self.second
+    }
+
+    /// returns the fraction of the second in nanoseconds, which is 0 if
+    /// the seconds are unknown
+    pub fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+
+    /// returns the offset from UTC, if known
+    pub fn offset(&self) -> Option<FixedOffset> {
+        self.offset
+    }
+
+    /// returns true if the given DateTime agrees with every known field,
+    /// reading its fields in this offset if it is known, and otherwise in
+    /// the DateTime's own
+    pub fn matches(&self, date: &DateTime) -> bool {
+        let date = self.offset.map_or(*date, |offset| date.with_offset(offset));
+        let nanos_match = self.second.is_none() || self.nanos == date.nanos;
+
+        self.date.matches(&date)
+            && self.hour.is_none_or(|hour| hour == date.hour())
+            && self.minute.is_none_or(|minute| minute == date.minute())
+            && self.second.is_none_or(|second| second == date.second())
+            && nanos_match
+    }
+
+    /// returns the DateTime with the unknown fields filled in, or None if
+    /// the result doesn't exist (such as the 29th of February in a year
+    /// that isn't a leap year)
+    ///
+    /// unknown date fields, and the whole time of day if no part of it is
+    /// known, are taken from `defaults` as reported in its offset. a time
+    /// known only to the hour or minute is the start of that hour or
+    /// minute, so `T15` completes to 15:00:00 whatever the defaults
+    ///
+    /// the result is in this offset if it is known, and otherwise in the
+    /// offset of `defaults`
+    pub fn complete_with(&self, defaults: &DateTime) -> Option<DateTime> {
+        let offset = self.offset.unwrap_or(defaults.offset());
+        let date = &self.date;
+        let (hour, minute, second, nanos) = match self.hour {
+            Some(hour) => (
+                hour,
+                self.minute.unwrap_or(0),
+                self.second.unwrap_or(0),
+                self.nanos,
+            ),
+            None => (
+                defaults.hour(),
+                defaults.minute(),
+                defaults.second(),
+                defaults.nanos,
+            ),
+        };
+
+        let result = from_fields(
+            date.year.unwrap_or(defaults.year()),
+            date.month.unwrap_or(defaults.month() as usize + 1),
+            date.date.unwrap_or(defaults.date()),
+            hour,
+            minute,
+            second,
+            nanos,
+            offset.seconds() as i64,
+        );
+        result.ok().map(|date| date.with_offset(offset))
+    }
+}
+
+impl From<PartialDate> for PartialDateTime {
+    fn from(date: PartialDate) -> Self {
+        PartialDateTime::from_date(date)
+    }
+}
+
+impl fmt::Display for PartialDateTime {
+    /// writes the date as [`PartialDate`] does, then any known time as
+    /// `Thh`, `Thh:mm` or `Thh:mm:ss[.fff]`, and any known offset
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.date)?;
+
+        if let Some(hour) = self.hour {
+            write!(f, "T{:02}", hour)?;
+        }
+        if let Some(minute) = self.minute {
+            write!(f, ":{:02}", minute)?;
+        }
+        if let Some(second) = self.second {
+            write!(f, ":{:02}", second)?;
+            if self.nanos > 0 {
+                let fraction = alloc::format!("{:09}", self.nanos);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+        }
+        match self.offset {
+            Some(offset) if self.hour.is_some() && offset.is_utc() => f.write_str("Z")?,
+            Some(offset) if self.hour.is_some() => write!(f, "{}", offset)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PartialDateTime {
+    type Err = ParseError;
+
+    /// parses a [`PartialDate`], optionally followed by `T` and a time of
+    /// `hh`, `hh:mm`, `hh:mm:ss` or `hh:mm:ss.fff`, and then optionally an
+    /// offset of `Z` or `+hh:mm`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let mut result = PartialDateTime::from_date(PartialDate::read(&mut cursor)?);
+
+        if cursor.peek().is_some() {
+            cursor.expect(b'T')?;
+            let hour = cursor.digits(2)?;
+            let (mut minute, mut second, mut nanos) = (None, None, 0);
+            if cursor.peek() == Some(b':') {
+                cursor.next()?;
+                minute = Some(cursor.digits(2)?);
+                if cursor.peek() == Some(b':') {
+                    cursor.next()?;
+                    second = Some(cursor.digits(2)?);
+                    if cursor.peek() == Some(b'.') {
+                        cursor.next()?;
+                        nanos = cursor.fraction()?;
+                    }
+                }
+            }
+            result = result
+                .with_time(hour, minute, second)
+                .ok_or(ParseError::OutOfRange)?;
+            result.nanos = nanos;
+
+            if cursor.peek().is_some() {
+                result.offset = Some(zone(&mut cursor)?);
+            }
+        }
+        cursor.finish()?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Completion, PartialDate, PartialDateTime};
+    use crate::{DateTime, FixedOffset, Month, ParseError};
+
+    #[test]
+    fn test_partial_dates() {
+        for input in &["1996-09-09", "1996-09", "1996", "--09-09", "--09", "---09"] {
+            let date: PartialDate = input.parse().unwrap();
+            assert_eq!(date.to_string(), *input);
+        }
+
+        let parse = |input: &str| input.parse::<PartialDate>();
+        assert_eq!(parse("--02-29").unwrap().with_year(1997), None);
+        assert_eq!(parse("--02-30"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("1997-02-29"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("-09"), Err(ParseError::Invalid(1)));
+        assert_eq!(parse("1996-09-09T"), Err(ParseError::TooLong));
+
+        let date = DateTime::from_secs(842282624);
+        assert!(parse("1996").unwrap().matches(&date));
+        assert!(parse("---09").unwrap().matches(&date));
+        assert!(!parse("--10").unwrap().matches(&date));
+
+        assert!(parse("--09-09").unwrap() < parse("1996").unwrap());
+        assert!(parse("1996").unwrap() < parse("1996-01").unwrap());
+        assert_eq!(parse("--09").unwrap().complete(Completion::End), None);
+        let end = parse("1996-09").unwrap().complete(Completion::End).unwrap();
+        assert_eq!((end.month(), end.date()), (Month::September, 30));
+    }
+
+    #[test]
+    fn test_partial_date_times() {
+        let parse = |input: &str| input.parse::<PartialDateTime>();
+
+        for input in &[
+            "1996-09-09T15:23:44.5Z",
+            "--09-09T15+02:00",
+            "1996T15:23",
+            "---09T15:23:44-05:30",
+        ] {
+            assert_eq!(parse(input).unwrap().to_string(), *input);
+        }
+        assert_eq!(parse("1996-09-09T24"), Err(ParseError::OutOfRange));
+        assert_eq!(
+            parse("1996-09-09T15:23:44+0200"),
+            Err(ParseError::Invalid(22))
+        );
+
+        let time = parse("--09-09T17:23+02:00").unwrap();
+        assert_eq!(time.offset(), FixedOffset::east(7200));
+        assert!(time.matches(&DateTime::from_secs(842282624)));
+        assert!(!time.matches(&DateTime::from_secs(842282624 + 3600)));
+
+        let defaults = DateTime::from_millis(1_000_000_000_250);
+        let complete = time.complete_with(&defaults).unwrap();
+        assert_eq!(complete.to_rfc3339(), "2001-09-09T17:23:00+02:00");
+        let complete = parse("1996").unwrap().complete_with(&defaults).unwrap();
+        assert_eq!(complete.to_rfc3339(), "1996-09-09T01:46:40.250Z");
+
+        let leap_day = parse("--02-29").unwrap();
+        assert!(leap_day.complete_with(&defaults).is_none());
+        assert_eq!(
+            PartialDateTime::from(PartialDate::new(Some(1996), None, None).unwrap()).with_time(
+                12,
+                None,
+                Some(0)
+            ),
+            None
+        );
+    }
+}
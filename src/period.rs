@@ -0,0 +1,385 @@
+//! ISO 8601 durations, such as `P1DT2H30M`
+//!
+//! a [`Period`] keeps the calendar parts of a duration (years, months and
+//! days), whose lengths depend on when they start, apart from the exact
+//! time (hours, minutes and seconds) held as a [`Span`]. adding one to a
+//! DateTime moves by calendar months first, as
+//! [`checked_add_months`](DateTime::checked_add_months) does, then by
+//! days, then by the exact time
+//! ```
+//! # use datetime::{DateTime, Period};
+//! let period: Period = "P1M2DT3H".parse().unwrap();
+//! assert_eq!((period.months(), period.days(), period.span().seconds()), (1, 2, 3 * 3600));
+//! assert_eq!(period.to_string(), "P1M2DT3H");
+//!
+//! let date = DateTime::parse_rfc3339("2024-01-31T09:30:00Z").unwrap();
+//! let later = date.checked_add_period(&period).unwrap();
+//! assert_eq!(later.to_rfc3339(), "2024-03-02T12:30:00Z");
+//! ```
+//!
+//! a Period made only of exact time converts to and from a Span, and
+//! counts days as 24 hours when asked to
+//! ```
+//! # use datetime::{Period, Span};
+//! let period = Period::from(Span::from_secs(90));
+//! assert_eq!(period.to_string(), "PT1M30S");
+//! assert_eq!("PT1M30S".parse::<Period>().unwrap().to_span(), Some(Span::from_secs(90)));
+//! assert_eq!("P1M".parse::<Period>().unwrap().to_span(), None);
+//! ```
+use crate::parse::{Cursor, ParseError};
+use crate::{DateTime, Span};
+use core::fmt;
+use core::str::FromStr;
+
+/// a duration in calendar years, months and days and an exact [`Span`];
+/// see the [module docs](self)
+///
+/// each part may have its own sign
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Period {
+    years: i64,
+    months: i64,
+    days: i64,
+    span: Span,
+}
+
+impl Period {
+    /// a Period of no time at all
+    pub const ZERO: Period = Period {
+        years: 0,
+        months: 0,
+        days: 0,
+        span: Span::ZERO,
+    };
+
+    /// returns the Period with the given parts
+    pub fn new(years: i64, months: i64, days: i64, span: Span) -> Self {
+        Period {
+            years,
+            months,
+            days,
+            span,
+        }
+    }
+
+    /// returns the calendar years
+    pub fn years(&self) -> i64 {
+        self.years
+    }
+
+    /// returns the calendar months, not counting the years
+    pub fn months(&self) -> i64 {
+        self.months
+    }
+
+    /// returns the calendar days, including any weeks it was written with
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    /// returns the exact time
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// returns the Period as a Span, counting days as 24 hours, or None if
+    /// it has years or months, which have no fixed length, or overflows
+    pub fn to_span(&self) -> Option<Span> {
+        if self.years != 0 || self.months != 0 {
+            return None;
+        }
+
+        self.span
+            .checked_add(Span::from_secs(self.days.checked_mul(86_400)?))
+    }
+
+    /// returns `-self`, or None if that overflows
+    pub fn checked_neg(&self) -> Option<Period> {
+        Some(Period {
+            years: self.years.checked_neg()?,
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            span: self.span.checked_neg()?,
+        })
+    }
+}
+
+impl From<Span> for Period {
+    fn from(span: Span) -> Self {
+        Period {
+            span,
+            ..Period::ZERO
+        }
+    }
+}
+
+impl DateTime {
+    /// returns the DateTime moved by `period`, or None if that is out of
+    /// range; see the [`period`](crate::period) module
+    pub fn checked_add_period(&self, period: &Period) -> Option<DateTime> {
+        let months = period.years.checked_mul(12)?.checked_add(period.months)?;
+        self.checked_add_months(months)?
+            .checked_add_secs(period.days.checked_mul(86_400)?)?
+            .checked_add_span(period.span)
+    }
+
+    /// returns the DateTime moved back by `period`, or None if that is out
+    /// of range
+    ///
+    /// this is adding the negated Period, so it isn't always the inverse
+    /// of [`checked_add_period`](DateTime::checked_add_period): one month
+    /// after 31 January is 29 February, and one month before that is 29
+    /// January
+    pub fn checked_sub_period(&self, period: &Period) -> Option<DateTime> {
+        self.checked_add_period(&period.checked_neg()?)
+    }
+}
+
+impl fmt::Display for Period {
+    /// formats the Period as an ISO 8601 duration, such as `P1Y2M3DT4H5M6.5S`
+    ///
+    /// a Period with no positive parts and some negative ones has a
+    /// leading `-`, and otherwise each negative part has its own, which
+    /// ISO 8601 doesn't allow but is widely understood; no time at all is
+    /// `PT0S`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let positive = self.years > 0 || self.months > 0 || self.days > 0 || self.span > Span::ZERO;
+        let period = match self.checked_neg() {
+            Some(negated) if !positive && *self != Period::ZERO => {
+                write!(f, "-")?;
+                negated
+            }
+            _ => *self,
+        };
+
+        write!(f, "P")?;
+        for &(value, unit) in &[
+            (period.years, 'Y'),
+            (period.months, 'M'),
+            (period.days, 'D'),
+        ] {
+            if value != 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+        if period == Period::ZERO {
+            return write!(f, "T0S");
+        }
+        if period.span == Span::ZERO {
+            return Ok(());
+        }
+
+        write!(f, "T")?;
+        let sign = if period.span.is_negative() { "-" } else { "" };
+        let length = period.span.unsigned_abs();
+        let secs = length.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if hours != 0 {
+            write!(f, "{}{}H", sign, hours)?;
+        }
+        if minutes != 0 {
+            write!(f, "{}{}M", sign, minutes)?;
+        }
+
+        let nanos = length.subsec_nanos();
+        if seconds != 0 || nanos != 0 {
+            write!(f, "{}{}", sign, seconds)?;
+            if nanos != 0 {
+                // without trailing zeros
+                let (mut fraction, mut width) = (nanos, 9);
+                while fraction % 10 == 0 {
+                    fraction /= 10;
+                    width -= 1;
+                }
+                write!(f, ".{:0width$}", fraction, width = width)?;
+            }
+            write!(f, "S")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Period {
+    type Err = ParseError;
+
+    /// parses an ISO 8601 duration such as `P1Y2M3DT4H5M6.5S` or `P2W`
+    ///
+    /// a leading `-` negates the whole Period, and a `-` before a part
+    /// negates that part. only the seconds may have a fraction, which may
+    /// follow a full stop or a comma, and weeks are counted as 7 days
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let negative = cursor.peek() == Some(b'-');
+        if negative {
+            cursor.next()?;
+        }
+        cursor.expect(b'P')?;
+
+        let mut period = Period::ZERO;
+        // the order of the last part read, so that parts can't repeat or
+        // come out of order
+        let mut last = 0;
+        let mut time = None;
+        while cursor.peek().is_some() {
+            if cursor.peek() == Some(b'T') {
+                if time.is_some() {
+                    return Err(ParseError::Invalid(cursor.pos));
+                }
+                cursor.next()?;
+                time = Some(last);
+                continue;
+            }
+
+            let part_negative = cursor.peek() == Some(b'-');
+            if part_negative {
+                cursor.next()?;
+            }
+            let start = cursor.pos;
+            match cursor.skip_digits() {
+                0 if cursor.peek().is_none() => return Err(ParseError::TooShort),
+                0 => return Err(ParseError::Invalid(start)),
+                _ => {}
+            }
+            let value: i64 = input[start..cursor.pos]
+                .parse()
+                .map_err(|_| ParseError::OutOfRange)?;
+            let value = if part_negative { -value } else { value };
+
+            let fraction = match cursor.peek() {
+                Some(b'.') | Some(b',') => {
+                    cursor.next()?;
+                    Some(cursor.fraction()?)
+                }
+                _ => None,
+            };
+
+            let unit_pos = cursor.pos;
+            let order = match (cursor.next()?, time.is_some()) {
+                (b'Y', false) => 1,
+                (b'M', false) => 2,
+                (b'W', false) => 3,
+                (b'D', false) => 4,
+                (b'H', true) => 5,
+                (b'M', true) => 6,
+                (b'S', true) => 7,
+                _ => return Err(ParseError::Invalid(unit_pos)),
+            };
+            if order <= last || fraction.is_some() && order != 7 {
+                return Err(ParseError::Invalid(unit_pos));
+            }
+            last = order;
+
+            let overflow = || ParseError::OutOfRange;
+            match order {
+                1 => period.years = value,
+                2 => period.months = value,
+                3 => period.days = value.checked_mul(7).ok_or_else(overflow)?,
+                4 => {
+                    period.days = period.days.checked_add(value).ok_or_else(overflow)?;
+                }
+                _ => {
+                    let secs = match order {
+                        5 => value.checked_mul(3600),
+                        6 => value.checked_mul(60),
+                        _ => Some(value),
+                    };
+                    let mut span = Span::from_secs(secs.ok_or_else(overflow)?);
+                    if let Some(nanos) = fraction {
+                        let nanos = Span::new(0, nanos).expect("fraction is under a second");
+                        span = if part_negative {
+                            span.checked_sub(nanos)
+                        } else {
+                            span.checked_add(nanos)
+                        }
+                        .ok_or_else(overflow)?;
+                    }
+                    period.span = period.span.checked_add(span).ok_or_else(overflow)?;
+                }
+            }
+        }
+
+        // a `T` must be followed by a time, and there must be some part
+        if time == Some(last) || last == 0 {
+            return Err(ParseError::TooShort);
+        }
+
+        if negative {
+            period.checked_neg().ok_or(ParseError::OutOfRange)
+        } else {
+            Ok(period)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Period;
+    use crate::{DateTime, ParseError, Span};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_round_trip() {
+        for &input in &[
+            "P1Y2M3DT4H5M6S",
+            "P3DT4H59M",
+            "PT36H",
+            "PT0.25S",
+            "PT1M0.000000001S",
+            "P1M",
+            "PT0S",
+            "-P1DT2H",
+            "-PT1.5S",
+            "P1M-1D",
+            "P1DT-1H-30M",
+        ] {
+            let period: Period = input.parse().unwrap();
+            assert_eq!(period.to_string(), input);
+        }
+
+        let parse = |input: &str| input.parse::<Period>().unwrap();
+        assert_eq!(parse("P2W").days(), 14);
+        assert_eq!(parse("PT1,5S").span(), Span::from_millis(1500));
+        assert_eq!(parse("-PT1.5S").span(), Span::from_millis(-1500));
+        assert_eq!(parse("PT90M").to_string(), "PT1H30M");
+        assert_eq!(parse("P1D").to_span(), Some(Span::from_secs(86_400)));
+        assert_eq!(parse("P0D"), Period::ZERO);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input: &str| input.parse::<Period>();
+
+        assert_eq!(parse(""), Err(ParseError::TooShort));
+        assert_eq!(parse("P"), Err(ParseError::TooShort));
+        assert_eq!(parse("P1DT"), Err(ParseError::TooShort));
+        assert_eq!(parse("PT1"), Err(ParseError::TooShort));
+        assert_eq!(parse("1D"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("P1H"), Err(ParseError::Invalid(2)));
+        assert_eq!(parse("PT1D"), Err(ParseError::Invalid(3)));
+        assert_eq!(parse("P1D1Y"), Err(ParseError::Invalid(4)));
+        assert_eq!(parse("P1.5D"), Err(ParseError::Invalid(4)));
+        assert_eq!(parse("PTT1S"), Err(ParseError::Invalid(2)));
+        assert_eq!(parse("PxD"), Err(ParseError::Invalid(1)));
+        assert_eq!(parse("P99999999999999999999Y"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("PT9223372036854775807H"), Err(ParseError::OutOfRange));
+    }
+
+    #[test]
+    fn test_checked_add_period() {
+        let date = DateTime::parse_rfc3339("2024-02-29T12:00:00Z").unwrap();
+        let add = |input: &str| {
+            date.checked_add_period(&input.parse().unwrap())
+                .map(|date| date.to_rfc3339())
+        };
+
+        assert_eq!(add("P1Y").unwrap(), "2025-02-28T12:00:00Z");
+        assert_eq!(add("P1M1D").unwrap(), "2024-03-30T12:00:00Z");
+        assert_eq!(add("-P1DT12H").unwrap(), "2024-02-28T00:00:00Z");
+        assert_eq!(add("PT0.5S").unwrap(), "2024-02-29T12:00:00.500Z");
+        assert!(add("P9223372036854775807D").is_none());
+
+        let back = date.checked_sub_period(&"P1M".parse().unwrap()).unwrap();
+        assert_eq!(back.to_rfc3339(), "2024-01-29T12:00:00Z");
+    }
+}
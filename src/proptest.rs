@@ -0,0 +1,117 @@
+//! property-testing strategies for DateTimes, Days and Months, enabled
+//! with the `proptest` feature
+//!
+//! DateTimes, Days and Months implement `proptest`'s `Arbitrary`, so they
+//! can be used with `any::<DateTime>()` and as arguments in `proptest!`.
+//! an arbitrary DateTime can be any instant a DateTime can hold, in any
+//! offset, which finds overflows but rarely looks like real data;
+//! [`DateTime::arbitrary_range`] keeps to the instants between two
+//! DateTimes instead
+//! ```
+//! # use datetime::DateTime;
+//! use proptest::prelude::*;
+//!
+//! let start = DateTime::parse_rfc3339("2000-01-01T00:00:00Z").unwrap();
+//! let end = DateTime::parse_rfc3339("2100-01-01T00:00:00Z").unwrap();
+//!
+//! proptest!(|(date in DateTime::arbitrary_range(&start, &end))| {
+//!     prop_assert!((2000..2100).contains(&date.year()));
+//! });
+//! ```
+use crate::{DateTime, Day, FixedOffset, Month, DAYS, MAX_SECS, MIN_SECS, MONTHS};
+use ::proptest::arbitrary::Arbitrary;
+use ::proptest::sample::{select, Select};
+use ::proptest::strategy::{BoxedStrategy, Strategy};
+
+impl Arbitrary for DateTime {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<DateTime>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (
+            MIN_SECS..=MAX_SECS,
+            0..1_000_000_000u32,
+            -86_399..=86_399i32,
+        )
+            .prop_map(|(secs, nanos, offset)| {
+                let offset = FixedOffset::east(offset).expect("offset is in range");
+                DateTime::from_parts(secs, nanos, offset)
+            })
+            .boxed()
+    }
+}
+
+impl DateTime {
+    /// returns a strategy giving DateTimes from `start` up to but not
+    /// including `end`, in UTC
+    ///
+    /// # Panics
+    ///
+    /// panics if `end` is not after `start`
+    pub fn arbitrary_range(start: &DateTime, end: &DateTime) -> BoxedStrategy<DateTime> {
+        let start = start.secs as i128 * 1_000_000_000 + start.nanos as i128;
+        let end = end.secs as i128 * 1_000_000_000 + end.nanos as i128;
+        assert!(start < end, "empty range of DateTimes");
+
+        (start..end)
+            .prop_map(|nanos| {
+                let secs = nanos.div_euclid(1_000_000_000) as i64;
+                let nanos = nanos.rem_euclid(1_000_000_000) as u32;
+                DateTime::from_parts(secs, nanos, FixedOffset::utc())
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Day {
+    type Parameters = ();
+    type Strategy = Select<Day>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(DAYS.to_vec())
+    }
+}
+
+impl Arbitrary for Month {
+    type Parameters = ();
+    type Strategy = Select<Month>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(MONTHS.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CivilTime, DateTime, Day, Month, MAX_SECS, MIN_SECS};
+    use ::proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_fields_round_trip(date in any::<DateTime>()) {
+            // the local fields at the very ends of the range aren't in
+            // years a CivilTime can hold
+            prop_assume!((MIN_SECS + 86_400..MAX_SECS - 86_400).contains(&date.secs));
+
+            let civil = CivilTime::from(&date);
+            let instant = civil.to_absolute(date.offset());
+            prop_assert_eq!(instant.seconds(), date.secs);
+            prop_assert_eq!(instant.nanosecond(), date.nanos);
+        }
+
+        #[test]
+        fn test_rfc3339_round_trip(date in DateTime::arbitrary_range(
+            &DateTime::from_secs(0),
+            &DateTime::from_secs(253_402_300_800),
+        )) {
+            let parsed = DateTime::parse_rfc3339(&date.to_rfc3339()).unwrap();
+            prop_assert_eq!(parsed, date);
+        }
+
+        #[test]
+        fn test_names_round_trip(day in any::<Day>(), month in any::<Month>()) {
+            prop_assert_eq!(day.name().parse::<Day>(), Ok(day));
+            prop_assert_eq!(month.name().parse::<Month>(), Ok(month));
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! iterating over evenly spaced DateTimes
+//!
+//! [`DateTime::range`] steps from a start time up to (but not including) an
+//! end time, which is handy for building report buckets or filling gaps
+//! in a time series
+//! ```
+//! # use datetime::range::Step;
+//! # use datetime::DateTime;
+//! let start = DateTime::from_secs(842282624);
+//! let end = DateTime::from_secs(842282624 + 3 * 86_400);
+//!
+//! let days: Vec<_> = DateTime::range(&start, &end, Step::Days(1))
+//!     .map(|date| date.date())
+//!     .collect();
+//! assert_eq!(days, [9, 10, 11]);
+//! ```
+use crate::{DateTime, FixedOffset, MAX_SECS, MIN_SECS};
+use core::convert::TryFrom;
+use core::iter::FusedIterator;
+
+/// how far apart the DateTimes of a [`DateRange`] are
+///
+/// steps of a fixed length are added to the instant, while months are
+/// counted on the calendar in the start's offset. a step of months keeps
+/// the start's day of the month and time of day, moving to the last day of
+/// shorter months, so stepping from January 31 gives February 28 (or 29)
+/// and then March 31
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Step {
+    Seconds(u64),
+    Minutes(u64),
+    Hours(u64),
+    Days(u64),
+    Weeks(u64),
+    Months(u64),
+}
+
+impl Step {
+    // the length of the step in seconds, saturating for absurdly long steps,
+    // or None for calendar months
+    fn secs(self) -> Option<u64> {
+        match self {
+            Step::Seconds(n) => Some(n),
+            Step::Minutes(n) => Some(n.saturating_mul(60)),
+            Step::Hours(n) => Some(n.saturating_mul(3600)),
+            Step::Days(n) => Some(n.saturating_mul(86_400)),
+            Step::Weeks(n) => Some(n.saturating_mul(7 * 86_400)),
+            Step::Months(_) => None,
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        match self {
+            Step::Seconds(n)
+            | Step::Minutes(n)
+            | Step::Hours(n)
+            | Step::Days(n)
+            | Step::Weeks(n)
+            | Step::Months(n) => n == 0,
+        }
+    }
+}
+
+/// an iterator over DateTimes from a start up to an exclusive end; see
+/// [`DateTime::range`]
+///
+/// every DateTime is reported in the start's offset, and the iterator
+/// stops early if the next step would leave the range a DateTime can
+/// represent
+#[derive(Debug, Clone)]
+pub struct DateRange {
+    secs: i64,
+    nanos: u32,
+    offset: FixedOffset,
+    end: (i64, u32),
+    step: Step,
+    // the number of steps taken so far
+    index: u64,
+    done: bool,
+}
+
+impl DateRange {
+    // the DateTime `index` steps after the start, or None if that is out of
+    // range
+    fn nth_step(&self, index: u64) -> Option<DateTime> {
+        let start = DateTime::from_parts(self.secs, self.nanos, self.offset);
+        let secs = match (self.step, self.step.secs()) {
+            (Step::Months(n), _) => {
+                return start.checked_add_months(i64::try_from(index.checked_mul(n)?).ok()?)
+            }
+            (_, Some(step)) => {
+                let delta = i64::try_from(index.checked_mul(step)?).ok()?;
+                self.secs.checked_add(delta)?
+            }
+            (_, None) => unreachable!("only months have no fixed length"),
+        };
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(secs, self.nanos, self.offset))
+        } else {
+            None
+        }
+    }
+}
+
+impl Iterator for DateRange {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        if self.done {
+            return None;
+        }
+
+        match self.nth_step(self.index) {
+            Some(date) if (date.secs, date.nanos) < self.end => {
+                self.index += 1;
+                Some(date)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl FusedIterator for DateRange {}
+
+impl DateTime {
+    /// returns an iterator over the DateTimes from `start` up to but not
+    /// including `end`, `step` apart, in `start`'s offset
+    ///
+    /// # Panics
+    ///
+    /// panics if `step` is zero
+    /// ```
+    /// # use datetime::range::Step;
+    /// # use datetime::DateTime;
+    /// // 2000-01-31 and 2000-06-01
+    /// let start = DateTime::from_secs(949276800);
+    /// let end = DateTime::from_secs(959817600);
+    ///
+    /// let month_ends: Vec<_> = DateTime::range(&start, &end, Step::Months(1))
+    ///     .map(|date| date.date())
+    ///     .collect();
+    /// assert_eq!(month_ends, [31, 29, 31, 30, 31]);
+    /// ```
+    pub fn range(start: &DateTime, end: &DateTime, step: Step) -> DateRange {
+        assert!(!step.is_zero(), "DateTime::range with a step of zero");
+
+        DateRange {
+            secs: start.secs,
+            nanos: start.nanos,
+            offset: start.offset,
+            end: (end.secs, end.nanos),
+            step,
+            index: 0,
+            done: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Step;
+    use crate::{DateTime, FixedOffset};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_fixed_steps() {
+        let start = DateTime::from_millis(-1500);
+        let end = DateTime::from_secs(2);
+
+        let secs: Vec<_> = DateTime::range(&start, &end, Step::Seconds(1))
+            .map(|date| (date.secs, date.nanos))
+            .collect();
+        assert_eq!(
+            secs,
+            [
+                (-2, 500_000_000),
+                (-1, 500_000_000),
+                (0, 500_000_000),
+                (1, 500_000_000)
+            ]
+        );
+
+        let end = DateTime::from_secs(0);
+        assert_eq!(DateTime::range(&end, &start, Step::Hours(1)).count(), 0);
+        assert_eq!(
+            DateTime::range(&start, &end, Step::Weeks(u64::MAX)).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_month_steps() {
+        // 1999-11-30 23:00 at +01:00
+        let offset = FixedOffset::east(3600).unwrap();
+        let start = DateTime::from_secs(943999200).with_offset(offset);
+        let end = DateTime::from_secs(954547200);
+
+        let dates: Vec<_> = DateTime::range(&start, &end, Step::Months(1))
+            .map(|date| {
+                (
+                    date.year(),
+                    date.month() as usize + 1,
+                    date.date(),
+                    date.hour(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            dates,
+            [
+                (1999, 11, 30, 23),
+                (1999, 12, 30, 23),
+                (2000, 1, 30, 23),
+                (2000, 2, 29, 23),
+                (2000, 3, 30, 23)
+            ]
+        );
+
+        let far = DateTime::MAX;
+        assert_eq!(
+            DateTime::range(&start, &far, Step::Months(u64::MAX)).count(),
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_step() {
+        let date = DateTime::from_secs(0);
+        DateTime::range(&date, &date, Step::Days(0));
+    }
+}
@@ -0,0 +1,279 @@
+//! explaining why two timestamps differ
+//!
+//! when the same event is recorded by several systems, its timestamps
+//! rarely agree exactly, and the way they disagree usually points at the
+//! cause: a local time stored as UTC is off by whole hours, and a column
+//! with millisecond precision is off by less than a millisecond.
+//! [`explain_diff`] works out which of these two timestamps look like
+//! ```
+//! # use datetime::reconcile::{explain_diff, DiffCause};
+//! # use datetime::{DateTime, FixedOffset};
+//! let logged = DateTime::parse_rfc3339("2024-03-01T09:00:00.123456Z").unwrap();
+//! let stored = DateTime::parse_rfc3339("2024-03-01T09:00:00.123Z").unwrap();
+//!
+//! let report = explain_diff(&logged, &stored);
+//! assert_eq!(report.cause(), DiffCause::Truncated(3));
+//! assert_eq!(report.to_string(), "differs by -0.000456s: fractional seconds cut to 3 digits");
+//!
+//! // the same wall-clock time, read as UTC and as Berlin time
+//! let berlin = FixedOffset::east(3600).unwrap();
+//! let local = DateTime::parse_rfc3339("2024-03-01T09:00:00+01:00").unwrap().with_offset(berlin);
+//! let utc = DateTime::parse_rfc3339("2024-03-01T09:00:00Z").unwrap();
+//! assert_eq!(explain_diff(&local, &utc).to_string(), "differs by 1h: the same wall-clock time in different offsets");
+//! ```
+use crate::{DateTime, Span};
+use core::fmt;
+
+/// the likely cause of a difference between two timestamps
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiffCause {
+    /// the same instant in the same offset
+    Identical,
+    /// the same instant, shown in different offsets
+    OffsetOnly,
+    /// the same wall-clock time read in different offsets, as when a local
+    /// time is stored as UTC
+    WallClock,
+    /// a whole number of quarter hours, up to a day, as when an offset was
+    /// applied twice or the wrong way round
+    WholeOffset,
+    /// one is the other with its fraction of a second cut to the given
+    /// number of digits
+    Truncated(u32),
+    /// one is the other with its fraction of a second rounded to the given
+    /// number of digits
+    Rounded(u32),
+    /// none of the above, so the clocks or data genuinely disagree
+    Drift,
+}
+
+/// what [`explain_diff`] found about two timestamps
+///
+/// its Display output is a sentence like `differs by 2h: a whole offset`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DiffReport {
+    difference: Span,
+    cause: DiffCause,
+}
+
+impl DiffReport {
+    /// returns the Span from the first timestamp to the second
+    pub fn difference(&self) -> Span {
+        self.difference
+    }
+
+    /// returns the likely cause of the difference
+    pub fn cause(&self) -> DiffCause {
+        self.cause
+    }
+}
+
+// writes a Span like `-1d 2h 30m 5.25s`, leaving out zero units
+fn write_span(f: &mut fmt::Formatter, span: Span) -> fmt::Result {
+    let length = span.unsigned_abs();
+    if span.is_negative() {
+        write!(f, "-")?;
+    }
+
+    let secs = length.as_secs();
+    let units = [
+        (secs / 86_400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+    ];
+    let mut first = true;
+    for &(value, unit) in units.iter().filter(|&&(value, _)| value > 0) {
+        if !first {
+            write!(f, " ")?;
+        }
+        write!(f, "{}{}", value, unit)?;
+        first = false;
+    }
+
+    let (secs, nanos) = (secs % 60, length.subsec_nanos());
+    if secs > 0 || nanos > 0 || first {
+        if !first {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", secs)?;
+        if nanos > 0 {
+            let fraction = alloc::format!("{:09}", nanos);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        write!(f, "s")?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.cause {
+            DiffCause::Identical => return write!(f, "identical"),
+            DiffCause::OffsetOnly => return write!(f, "the same instant in different offsets"),
+            _ => {}
+        }
+
+        write!(f, "differs by ")?;
+        write_span(f, self.difference)?;
+        match self.cause {
+            DiffCause::WallClock => write!(f, ": the same wall-clock time in different offsets"),
+            DiffCause::WholeOffset => write!(f, ": a whole offset"),
+            DiffCause::Truncated(digits) => {
+                write!(f, ": fractional seconds cut to {} digits", digits)
+            }
+            DiffCause::Rounded(digits) => {
+                write!(f, ": fractional seconds rounded to {} digits", digits)
+            }
+            _ => write!(f, " of drift"),
+        }
+    }
+}
+
+// nanoseconds since the Unix epoch
+fn total_nanos(date: &DateTime) -> i128 {
+    date.secs as i128 * 1_000_000_000 + date.nanos as i128
+}
+
+// the number of fractional digits needed to write the DateTime's
+// nanoseconds
+fn fraction_digits(date: &DateTime) -> u32 {
+    let mut nanos = date.nanos;
+    let mut digits = 9;
+    while digits > 0 && nanos.is_multiple_of(10) {
+        nanos /= 10;
+        digits -= 1;
+    }
+
+    digits
+}
+
+// the number of fractional digits, below 9, that `cut` could be `full` cut
+// or rounded to, with whether it was rounded
+fn precision_loss(full: i128, cut: i128) -> Option<(u32, bool)> {
+    (0..9).rev().find_map(|digits| {
+        let unit = 10i128.pow(9 - digits);
+        if cut % unit != 0 {
+            None
+        } else if full.div_euclid(unit) * unit == cut {
+            Some((digits, false))
+        } else if (full + unit / 2).div_euclid(unit) * unit == cut {
+            Some((digits, true))
+        } else {
+            None
+        }
+    })
+}
+
+/// returns what two timestamps have in common, and the likely cause of
+/// any difference between them
+///
+/// the causes are tried in the order of [`DiffCause`], so a difference of
+/// exactly an hour between matching wall-clock times is put down to their
+/// offsets rather than drift
+///
+/// # Panics
+///
+/// panics if the timestamps are too far apart for a Span
+pub fn explain_diff(a: &DateTime, b: &DateTime) -> DiffReport {
+    let difference = b - a;
+    let secs = b.secs as i128 - a.secs as i128;
+
+    let cause = if difference == Span::ZERO {
+        if a.offset == b.offset {
+            DiffCause::Identical
+        } else {
+            DiffCause::OffsetOnly
+        }
+    } else if a.nanos == b.nanos && secs == a.offset.seconds() as i128 - b.offset.seconds() as i128
+    {
+        DiffCause::WallClock
+    } else if a.nanos == b.nanos && secs % 900 == 0 && secs.abs() <= 86_400 {
+        DiffCause::WholeOffset
+    } else {
+        // the one with fewer fractional digits is the one that lost them
+        let (full, cut) = if fraction_digits(a) > fraction_digits(b) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let (full, cut) = (total_nanos(full), total_nanos(cut));
+
+        match precision_loss(full, cut) {
+            Some((digits, false)) => DiffCause::Truncated(digits),
+            Some((digits, true)) => DiffCause::Rounded(digits),
+            None => DiffCause::Drift,
+        }
+    };
+
+    DiffReport { difference, cause }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain_diff, DiffCause};
+    use crate::{DateTime, FixedOffset, Span};
+
+    fn date(input: &str) -> DateTime {
+        DateTime::parse_rfc3339(input).unwrap()
+    }
+
+    #[test]
+    fn test_causes() {
+        let cause = |a, b| explain_diff(&date(a), &date(b)).cause();
+
+        let base = "2024-03-01T09:00:00.5Z";
+        assert_eq!(cause(base, base), DiffCause::Identical);
+        assert_eq!(
+            explain_diff(
+                &date(base),
+                &date(base).with_offset(FixedOffset::east(3600).unwrap())
+            )
+            .cause(),
+            DiffCause::OffsetOnly
+        );
+        assert_eq!(
+            cause(base, "2024-03-01T11:00:00.5Z"),
+            DiffCause::WholeOffset
+        );
+        assert_eq!(
+            cause(base, "2024-03-01T05:30:00.5Z"),
+            DiffCause::WholeOffset
+        );
+        assert_eq!(cause(base, "2024-03-01T09:10:00.5Z"), DiffCause::Drift);
+        assert_eq!(cause(base, "2024-03-03T09:00:00.5Z"), DiffCause::Drift);
+
+        let fine = "2024-03-01T09:00:00.123456789Z";
+        assert_eq!(
+            cause(fine, "2024-03-01T09:00:00.123Z"),
+            DiffCause::Truncated(3)
+        );
+        assert_eq!(
+            cause("2024-03-01T09:00:00.123457Z", fine),
+            DiffCause::Rounded(6)
+        );
+        assert_eq!(cause(fine, "2024-03-01T09:00:00Z"), DiffCause::Truncated(0));
+        assert_eq!(cause(fine, "2024-03-01T09:00:00.2Z"), DiffCause::Drift);
+        // rounding can carry into the next second, even before the epoch
+        assert_eq!(
+            cause("1969-12-31T23:59:59.9996Z", "1970-01-01T00:00:00Z"),
+            DiffCause::Rounded(3)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let report = explain_diff(
+            &date("2024-03-01T09:00:00Z"),
+            &date("2024-03-02T11:30:05.25Z"),
+        );
+        assert_eq!(report.difference(), Span::new(95405, 250_000_000).unwrap());
+        assert_eq!(report.to_string(), "differs by 1d 2h 30m 5.25s of drift");
+
+        let report = explain_diff(&date("2024-03-01T11:00:00Z"), &date("2024-03-01T09:00:00Z"));
+        assert_eq!(report.to_string(), "differs by -2h: a whole offset");
+
+        let base = date("2024-03-01T09:00:00Z");
+        assert_eq!(explain_diff(&base, &base).to_string(), "identical");
+    }
+}
@@ -0,0 +1,406 @@
+//! describing a DateTime relative to another, as in "3 hours ago"
+//!
+//! [`DateTime::relative_to`] gives the difference in the largest whole unit
+//! it contains, rounded towards zero, so 90 minutes is "1 hour". months are
+//! counted as 30 days and years as 365, which is close enough for the
+//! rough descriptions shown in feeds and chat logs
+//! ```
+//! # use datetime::DateTime;
+//! let now = DateTime::from_secs(842282624);
+//!
+//! assert_eq!(DateTime::from_secs(842282624 - 3 * 3600).relative_to(&now), "3 hours ago");
+//! assert_eq!(DateTime::from_secs(842282624 + 300).relative_to(&now), "in 5 minutes");
+//! assert_eq!(DateTime::from_secs(842282600).relative_to(&now), "just now");
+//! ```
+//!
+//! where each unit takes over, whether weeks are used, whether "yesterday"
+//! and "last week" are named, and when to give up and show the date are
+//! set with a [`RelativeConfig`]
+//! ```
+//! # use datetime::relative::{Granularity, RelativeConfig};
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let config = RelativeConfig::new()
+//!     .switch_at(Granularity::Hours, Duration::from_secs(2 * 3600))
+//!     .weeks(true)
+//!     .named(true)
+//!     .date_after(Duration::from_secs(30 * 86_400), "%b %e, %Y");
+//! let now = DateTime::from_secs(842282624);
+//! let ago = |secs: usize| DateTime::from_secs(842282624 - secs).relative_to_config(&now, &config);
+//!
+//! assert_eq!(ago(90 * 60), "90 minutes ago");
+//! assert_eq!(ago(86_400), "yesterday");
+//! assert_eq!(ago(15 * 86_400), "2 weeks ago");
+//! assert_eq!(ago(45 * 86_400), "Jul 26, 1996");
+//! ```
+//!
+//! [`describe_reschedule`] describes how far an event moved instead, in
+//! days on the calendar and time on the clock, for telling people that
+//! their booking changed
+use crate::DateTime;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// the smallest unit a relative description is given in; differences
+/// smaller than it are described as "just now"
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Granularity {
+    Seconds,
+    #[default]
+    Minutes,
+    Hours,
+    Days,
+    Months,
+    Years,
+}
+
+// each unit's granularity, length in seconds and name, largest first.
+// weeks are allowed wherever days are
+const UNITS: [(Granularity, i64, &str); 7] = [
+    (Granularity::Years, 365 * 86_400, "year"),
+    (Granularity::Months, 30 * 86_400, "month"),
+    (Granularity::Days, 7 * 86_400, "week"),
+    (Granularity::Days, 86_400, "day"),
+    (Granularity::Hours, 3600, "hour"),
+    (Granularity::Minutes, 60, "minute"),
+    (Granularity::Seconds, 1, "second"),
+];
+
+// what one of each unit in UNITS is called in the past and the future
+const NAMED: [Option<(&str, &str)>; 7] = [
+    Some(("last year", "next year")),
+    Some(("last month", "next month")),
+    Some(("last week", "next week")),
+    Some(("yesterday", "tomorrow")),
+    None,
+    None,
+    None,
+];
+
+// the index of weeks in UNITS
+const WEEKS: usize = 2;
+
+/// options for describing a DateTime relative to another; see
+/// [`DateTime::relative_to_config`]
+///
+/// the defaults give the same descriptions as [`DateTime::relative_to`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativeConfig {
+    granularity: Granularity,
+    // the distance in seconds from which each unit in UNITS is used
+    starts: [i64; 7],
+    weeks: bool,
+    named: bool,
+    // the distance from which the date is shown instead, and its format
+    date_after: Option<(i64, String)>,
+}
+
+impl RelativeConfig {
+    /// returns the default options: units from minutes to years, each
+    /// used from one of it, without weeks, names or dates
+    pub fn new() -> Self {
+        let mut starts = [0; 7];
+        for (start, &(_, len, _)) in starts.iter_mut().zip(&UNITS) {
+            *start = len;
+        }
+
+        RelativeConfig {
+            granularity: Granularity::Minutes,
+            starts,
+            weeks: false,
+            named: false,
+            date_after: None,
+        }
+    }
+
+    /// sets the smallest unit used; differences smaller than it are "just
+    /// now"
+    pub fn granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// sets the difference from which `unit` is used instead of the one
+    /// below it, such as two hours to keep "90 minutes ago"; a difference
+    /// shorter than one of the unit still counts as one
+    pub fn switch_at(mut self, unit: Granularity, after: Duration) -> Self {
+        let i = UNITS.iter().rposition(|&(g, _, _)| g == unit);
+        // days are last of the two units at their granularity
+        self.starts[i.expect("every granularity has a unit")] =
+            after.as_secs().min(i64::MAX as u64) as i64;
+        self
+    }
+
+    /// sets whether differences from a week to a month are given in weeks
+    pub fn weeks(mut self, weeks: bool) -> Self {
+        self.weeks = weeks;
+        self
+    }
+
+    /// sets whether a difference of one day, week, month or year is named,
+    /// as in "yesterday" or "next month"
+    pub fn named(mut self, named: bool) -> Self {
+        self.named = named;
+        self
+    }
+
+    /// sets the difference from which the DateTime is shown as a date
+    /// instead, written with a strftime-style format (see the
+    /// [`format`](crate::format) module)
+    pub fn date_after(mut self, after: Duration, format: &str) -> Self {
+        let after = after.as_secs().min(i64::MAX as u64) as i64;
+        self.date_after = Some((after, format.to_string()));
+        self
+    }
+}
+
+impl Default for RelativeConfig {
+    fn default() -> Self {
+        RelativeConfig::new()
+    }
+}
+
+impl DateTime {
+    /// describes this DateTime relative to `base`, to the minute
+    pub fn relative_to(&self, base: &DateTime) -> String {
+        self.relative_to_with(base, Granularity::Minutes)
+    }
+
+    /// describes this DateTime relative to `base`, in units no smaller than
+    /// `granularity`
+    /// ```
+    /// # use datetime::relative::Granularity;
+    /// # use datetime::DateTime;
+    /// let now = DateTime::from_secs(842282624);
+    /// let earlier = DateTime::from_secs(842282624 - 45);
+    ///
+    /// assert_eq!(earlier.relative_to_with(&now, Granularity::Seconds), "45 seconds ago");
+    /// assert_eq!(earlier.relative_to_with(&now, Granularity::Minutes), "just now");
+    /// ```
+    pub fn relative_to_with(&self, base: &DateTime, granularity: Granularity) -> String {
+        self.relative_to_config(base, &RelativeConfig::new().granularity(granularity))
+    }
+
+    /// describes this DateTime relative to `base`, as `config` says
+    /// ```
+    /// # use datetime::relative::{Granularity, RelativeConfig};
+    /// # use datetime::DateTime;
+    /// let config = RelativeConfig::new().granularity(Granularity::Days).named(true);
+    /// let now = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(DateTime::from_secs(842282624 + 86_400).relative_to_config(&now, &config), "tomorrow");
+    /// ```
+    pub fn relative_to_config(&self, base: &DateTime, config: &RelativeConfig) -> String {
+        // the whole seconds between the two, rounded towards zero; both are
+        // in range, so this can't overflow
+        let mut secs = self.secs - base.secs;
+        if secs > 0 && self.nanos < base.nanos {
+            secs -= 1;
+        } else if secs < 0 && self.nanos > base.nanos {
+            secs += 1;
+        }
+        let distance = secs.abs();
+
+        if let Some((after, format)) = &config.date_after {
+            if distance >= *after {
+                return self.format(format);
+            }
+        }
+
+        let unit = (0..UNITS.len())
+            .filter(|&i| UNITS[i].0 >= config.granularity && (i != WEEKS || config.weeks))
+            .find(|&i| distance >= config.starts[i]);
+        let i = match unit {
+            Some(i) => i,
+            None => return "just now".to_string(),
+        };
+        let (_, len, name) = UNITS[i];
+        let count = (distance / len).max(1);
+
+        if let (true, 1, Some((past, future))) = (config.named, count, NAMED[i]) {
+            return if secs < 0 { past } else { future }.to_string();
+        }
+
+        let plural = if count == 1 { "" } else { "s" };
+        if secs < 0 {
+            format!("{} {}{} ago", count, name, plural)
+        } else {
+            format!("in {} {}{}", count, name, plural)
+        }
+    }
+
+    /// describes this DateTime relative to the current time, to the minute
+    /// ```
+    /// # use datetime::DateTime;
+    /// assert_eq!(DateTime::now().humanize(), "just now");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn humanize(&self) -> String {
+        self.relative_to(&DateTime::now())
+    }
+}
+
+/// describes how an event moved from `old` to `new`, as the change in its
+/// date and the change in its time of day, both on `old`'s wall clock
+///
+/// the two are described separately even when they go different ways, so
+/// moving from 23:00 to 01:00 the next day is "1 day later, 22 hours
+/// earlier", since that is what changed on the calendar. an event that
+/// didn't move is "not moved"
+/// ```
+/// # use datetime::relative::describe_reschedule;
+/// # use datetime::DateTime;
+/// let old = DateTime::parse_rfc3339("1996-09-09T15:00:00Z").unwrap();
+/// let new = DateTime::parse_rfc3339("1996-09-11T14:00:00Z").unwrap();
+/// assert_eq!(describe_reschedule(&old, &new), "2 days later, 1 hour earlier");
+///
+/// let new = DateTime::parse_rfc3339("1996-09-09T16:30:00Z").unwrap();
+/// assert_eq!(describe_reschedule(&old, &new), "1 hour 30 minutes later");
+/// ```
+pub fn describe_reschedule(old: &DateTime, new: &DateTime) -> String {
+    let offset = old.offset.seconds() as i64;
+    let (old, new) = (old.secs + offset, new.secs + offset);
+    let days = new.div_euclid(86_400) - old.div_euclid(86_400);
+    let secs = new.rem_euclid(86_400) - old.rem_euclid(86_400);
+
+    let mut parts = Vec::new();
+    if days != 0 {
+        parts.push(moved(&[(days.abs(), "day")], days));
+    }
+    if secs != 0 {
+        let abs = secs.abs();
+        let units = [
+            (abs / 3600, "hour"),
+            (abs / 60 % 60, "minute"),
+            (abs % 60, "second"),
+        ];
+        parts.push(moved(&units, secs));
+    }
+
+    if parts.is_empty() {
+        "not moved".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+// the non-zero counts of units, then "later" or "earlier" for the sign
+fn moved(units: &[(i64, &str)], sign: i64) -> String {
+    let mut text = String::new();
+    for &(count, name) in units.iter().filter(|&&(count, _)| count != 0) {
+        let plural = if count == 1 { "" } else { "s" };
+        text += &format!("{} {}{} ", count, name, plural);
+    }
+    text += if sign > 0 { "later" } else { "earlier" };
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_reschedule, Granularity, RelativeConfig};
+    use crate::{DateTime, FixedOffset};
+    use core::time::Duration;
+
+    #[test]
+    fn test_relative_to() {
+        let now = DateTime::from_secs(842282624);
+        let at = |secs: i64| DateTime::from_signed_secs(842282624 + secs);
+
+        assert_eq!(at(-60).relative_to(&now), "1 minute ago");
+        assert_eq!(at(-5399).relative_to(&now), "1 hour ago");
+        assert_eq!(at(2 * 86_400).relative_to(&now), "in 2 days");
+        assert_eq!(at(-45 * 86_400).relative_to(&now), "1 month ago");
+        assert_eq!(at(800 * 86_400).relative_to(&now), "in 2 years");
+        assert_eq!(at(0).relative_to(&now), "just now");
+    }
+
+    #[test]
+    fn test_granularity() {
+        let now = DateTime::from_millis(1500);
+        let earlier = DateTime::from_millis(-999);
+
+        // 2.499 seconds apart
+        assert_eq!(
+            earlier.relative_to_with(&now, Granularity::Seconds),
+            "2 seconds ago"
+        );
+        assert_eq!(
+            now.relative_to_with(&earlier, Granularity::Seconds),
+            "in 2 seconds"
+        );
+
+        let later = DateTime::from_secs(5 * 3600);
+        assert_eq!(later.relative_to_with(&now, Granularity::Days), "just now");
+        assert_eq!(
+            later.relative_to_with(&now, Granularity::Hours),
+            "in 4 hours"
+        );
+    }
+
+    #[test]
+    fn test_relative_config() {
+        let now = DateTime::from_secs(842282624);
+        let at = |secs: i64| DateTime::from_signed_secs(842282624 + secs);
+        let describe = |secs, config: &RelativeConfig| at(secs).relative_to_config(&now, config);
+
+        let config = RelativeConfig::new();
+        assert_eq!(describe(-86_400, &config), "1 day ago");
+        assert_eq!(describe(-8 * 86_400, &config), "8 days ago");
+        assert_eq!(config, RelativeConfig::default());
+
+        // later switches, and earlier ones counting as one of the unit
+        let config = RelativeConfig::new()
+            .switch_at(Granularity::Hours, Duration::from_secs(3 * 3600))
+            .switch_at(Granularity::Days, Duration::from_secs(12 * 3600));
+        assert_eq!(describe(-150 * 60, &config), "150 minutes ago");
+        assert_eq!(describe(4 * 3600, &config), "in 4 hours");
+        assert_eq!(describe(13 * 3600, &config), "in 1 day");
+
+        let config = RelativeConfig::new().weeks(true).named(true);
+        assert_eq!(describe(-86_400, &config), "yesterday");
+        assert_eq!(describe(2 * 86_400, &config), "in 2 days");
+        assert_eq!(describe(-7 * 86_400, &config), "last week");
+        assert_eq!(describe(20 * 86_400, &config), "in 2 weeks");
+        assert_eq!(describe(31 * 86_400, &config), "next month");
+        assert_eq!(describe(-400 * 86_400, &config), "last year");
+        assert_eq!(describe(-3600, &config), "1 hour ago");
+
+        // weeks are days, as far as the granularity goes
+        let config = config.granularity(Granularity::Months);
+        assert_eq!(describe(-20 * 86_400, &config), "just now");
+
+        let config = RelativeConfig::new().date_after(Duration::from_secs(86_400), "%F");
+        assert_eq!(describe(-86_399, &config), "23 hours ago");
+        assert_eq!(describe(86_400, &config), "1996-09-10");
+    }
+
+    #[test]
+    fn test_describe_reschedule() {
+        let date = |s| DateTime::parse_rfc3339(s).unwrap();
+        let describe = |old, new| describe_reschedule(&date(old), &date(new));
+
+        let old = "1996-09-09T15:00:00Z";
+        assert_eq!(describe(old, old), "not moved");
+        assert_eq!(describe(old, "1996-09-10T15:00:00Z"), "1 day later");
+        assert_eq!(describe(old, "1996-08-30T15:00:00Z"), "10 days earlier");
+        assert_eq!(describe(old, "1996-09-09T14:59:59Z"), "1 second earlier");
+        assert_eq!(
+            describe(old, "1996-09-07T17:01:00Z"),
+            "2 days earlier, 2 hours 1 minute later"
+        );
+        assert_eq!(
+            describe("1996-09-09T23:00:00Z", "1996-09-10T01:00:00Z"),
+            "1 day later, 22 hours earlier"
+        );
+
+        // on the old date's wall clock, an hour after 23:00 is the next day
+        let offset = FixedOffset::east(3600).unwrap();
+        let old = date("1996-09-09T22:00:00Z").with_offset(offset);
+        assert_eq!(
+            describe_reschedule(&old, &date("1996-09-09T23:00:00Z")),
+            "1 day later, 23 hours earlier"
+        );
+    }
+}
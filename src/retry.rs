@@ -0,0 +1,121 @@
+//! HTTP `Retry-After` headers
+//!
+//! a server can ask a client to wait before retrying either with a number
+//! of seconds or with an HTTP date to retry at
+//! ([RFC 9110 §10.2.3](https://www.rfc-editor.org/rfc/rfc9110#section-10.2.3)).
+//! [`RetryAfter`] holds either form, and converts to whichever the client
+//! needs given the current time
+//! ```
+//! # use datetime::retry::RetryAfter;
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let now = DateTime::from_secs(784111777);
+//!
+//! let delay: RetryAfter = "120".parse().unwrap();
+//! assert_eq!(delay.retry_at(&now).unwrap().to_http_date(), "Sun, 06 Nov 1994 08:51:37 GMT");
+//!
+//! let date: RetryAfter = "Sun, 06 Nov 1994 08:50:37 GMT".parse().unwrap();
+//! assert_eq!(date.delay_from(&now), Duration::from_secs(60));
+//! ```
+use crate::{DateTime, ParseError};
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+/// the value of a `Retry-After` header
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RetryAfter {
+    /// retry after waiting this long
+    Delay(Duration),
+    /// retry at this time
+    Date(DateTime),
+}
+
+impl RetryAfter {
+    /// returns the time to retry at, given the time the response was
+    /// received, or None if a delay would take it out of the range a
+    /// DateTime can represent
+    pub fn retry_at(&self, now: &DateTime) -> Option<DateTime> {
+        match self {
+            RetryAfter::Delay(delay) => now.checked_add_duration(*delay),
+            // a copy of the date, as DateTimes aren't Clone
+            RetryAfter::Date(date) => Some(*date),
+        }
+    }
+
+    /// returns how long to wait before retrying, given the current time;
+    /// a date which has already passed means retrying straight away
+    pub fn delay_from(&self, now: &DateTime) -> Duration {
+        match self {
+            RetryAfter::Delay(delay) => *delay,
+            RetryAfter::Date(date) => now.ttl_until(date).unwrap_or_default(),
+        }
+    }
+}
+
+impl FromStr for RetryAfter {
+    type Err = ParseError;
+
+    /// parses either a number of seconds or an HTTP date (see
+    /// [`DateTime::parse_http_date`])
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+            return DateTime::parse_http_date(input).map(RetryAfter::Date);
+        }
+
+        let secs = input.parse().map_err(|_| ParseError::OutOfRange)?;
+        Ok(RetryAfter::Delay(Duration::from_secs(secs)))
+    }
+}
+
+impl fmt::Display for RetryAfter {
+    /// writes the header value; a delay with a fraction of a second is
+    /// rounded up, so that the client doesn't retry too soon
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RetryAfter::Delay(delay) => {
+                let round_up = delay.subsec_nanos() > 0;
+                write!(f, "{}", delay.as_secs().saturating_add(round_up as u64))
+            }
+            RetryAfter::Date(date) => f.write_str(&date.to_http_date()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryAfter;
+    use crate::{DateTime, ParseError};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("0".parse(), Ok(RetryAfter::Delay(Duration::from_secs(0))));
+        assert_eq!(
+            "Sunday, 06-Nov-94 08:49:37 GMT".parse(),
+            Ok(RetryAfter::Date(DateTime::from_secs(784111777)))
+        );
+
+        assert_eq!(
+            "99999999999999999999".parse::<RetryAfter>(),
+            Err(ParseError::OutOfRange)
+        );
+        assert!("-1".parse::<RetryAfter>().is_err());
+        assert!("".parse::<RetryAfter>().is_err());
+    }
+
+    #[test]
+    fn test_format_and_convert() {
+        let now = DateTime::from_secs(784111777);
+
+        let delay = RetryAfter::Delay(Duration::from_millis(1500));
+        assert_eq!(delay.to_string(), "2");
+        assert_eq!(delay.delay_from(&now), Duration::from_millis(1500));
+
+        let date = RetryAfter::Date(DateTime::from_secs(784111770));
+        assert_eq!(date.to_string(), "Sun, 06 Nov 1994 08:49:30 GMT");
+        // the date has passed, so retry now
+        assert_eq!(date.delay_from(&now), Duration::from_secs(0));
+        assert_eq!(date.retry_at(&now), Some(DateTime::from_secs(784111770)));
+    }
+}
@@ -0,0 +1,432 @@
+//! simple schedules for jobs configured in text
+//!
+//! a [`Schedule`] is a much smaller language than [`Cron`](crate::cron::Cron),
+//! for the two kinds of job most config files need: one that runs once a
+//! day at a set time, and one that runs at a fixed period
+//!
+//! - `daily@03:00` or `daily@03:00:30`: every day at that time
+//! - `daily`: every day at midnight
+//! - `every 6h`, `every 1h30m`, `every 90s`: at a fixed period, given in
+//!   days (`d`), hours (`h`), minutes (`m`) and seconds (`s`), largest
+//!   first
+//! - `hourly`: every hour
+//!
+//! times are matched in the offset of the DateTime they are found after,
+//! and periods are counted from midnight on 1970-01-01 in that offset, so
+//! that `every 6h` runs at midnight, 6am, noon and 6pm
+//! ```
+//! # use datetime::schedule::Schedule;
+//! # use datetime::DateTime;
+//! let now = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+//!
+//! let nightly: Schedule = "daily@03:00".parse().unwrap();
+//! assert_eq!(nightly.next_after(&now).unwrap().to_rfc3339(), "1996-09-10T03:00:00Z");
+//!
+//! let sync: Schedule = "every 6h".parse().unwrap();
+//! assert_eq!(sync.next_after(&now).unwrap().to_rfc3339(), "1996-09-09T18:00:00Z");
+//! assert_eq!(sync.to_string(), "every 6h");
+//! ```
+//!
+//! a job that was down for a while can find the runs it missed with
+//! [`Schedule::missed_runs`], and a [`CatchUp`] policy to say which of
+//! them it should make up for
+use crate::parse::{Cursor, ParseError};
+use crate::{DateTime, Time};
+use core::fmt;
+use core::iter::FusedIterator;
+use core::str::FromStr;
+use core::time::Duration;
+
+/// a job that runs once a day, at a time of day in the offset of the
+/// DateTimes it is asked about
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DailyLocalSchedule {
+    time: Time,
+}
+
+impl DailyLocalSchedule {
+    /// returns the schedule running every day at `time`
+    pub fn new(time: Time) -> Self {
+        DailyLocalSchedule { time }
+    }
+
+    /// returns the time of day the job runs
+    pub fn time(&self) -> Time {
+        self.time
+    }
+
+    /// returns the first time after `date` that the job runs, in `date`'s
+    /// offset, or None if that is out of range
+    pub fn next_after(&self, date: &DateTime) -> Option<DateTime> {
+        let today = self.on_day_of(date)?;
+        if today > *date {
+            Some(today)
+        } else {
+            today.checked_add_secs(86_400)
+        }
+    }
+
+    // the latest time at or before `date` that the job runs
+    fn last_at_or_before(&self, date: &DateTime) -> Option<DateTime> {
+        let today = self.on_day_of(date)?;
+        if today <= *date {
+            Some(today)
+        } else {
+            today.checked_sub_secs(86_400)
+        }
+    }
+
+    // the time the job runs on the same day as `date`
+    fn on_day_of(&self, date: &DateTime) -> Option<DateTime> {
+        let since_midnight = Duration::new(
+            self.time.seconds_from_midnight() as u64,
+            self.time.nanosecond(),
+        );
+        date.start_of_day().checked_add_duration(since_midnight)
+    }
+}
+
+/// a parsed schedule; see the [module docs](self)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Schedule {
+    /// once a day at a time of day
+    Daily(DailyLocalSchedule),
+    /// at a fixed period of whole seconds
+    Every(Duration),
+}
+
+impl Schedule {
+    /// returns the first time after `date` that the job runs, in `date`'s
+    /// offset, or None if that is out of range
+    pub fn next_after(&self, date: &DateTime) -> Option<DateTime> {
+        match self {
+            Schedule::Daily(daily) => daily.next_after(date),
+            Schedule::Every(period) => date.floor_to(*period).checked_add_duration(*period),
+        }
+    }
+
+    /// returns an iterator over the times the job runs after `after` and
+    /// up to and including `until`, in `after`'s offset
+    /// ```
+    /// # use datetime::schedule::Schedule;
+    /// # use datetime::DateTime;
+    /// let schedule: Schedule = "every 6h".parse().unwrap();
+    /// let after = DateTime::parse_rfc3339("1996-09-09T06:00:00Z").unwrap();
+    /// let until = DateTime::parse_rfc3339("1996-09-10T00:00:00Z").unwrap();
+    ///
+    /// let runs: Vec<_> = schedule.runs_between(&after, &until).map(|date| date.hour()).collect();
+    /// assert_eq!(runs, [12, 18, 0]);
+    /// ```
+    pub fn runs_between(&self, after: &DateTime, until: &DateTime) -> Runs {
+        Runs {
+            schedule: *self,
+            next: self.next_after(after).filter(|next| next <= until),
+            until: *until,
+        }
+    }
+
+    /// returns the runs to make up for at `now`, for a job that last ran
+    /// at `last` and has been down since, as `policy` decides
+    ///
+    /// `last` is when the job actually ran, so a run that started a little
+    /// late is not counted as missed. the runs are in `last`'s offset
+    /// ```
+    /// # use datetime::schedule::{CatchUp, Schedule};
+    /// # use datetime::DateTime;
+    /// let schedule: Schedule = "daily@03:00".parse().unwrap();
+    /// let last = DateTime::parse_rfc3339("1996-09-09T03:00:02Z").unwrap();
+    /// let now = DateTime::parse_rfc3339("1996-09-12T12:00:00Z").unwrap();
+    ///
+    /// assert_eq!(schedule.missed_runs(&last, &now, CatchUp::RunAll).count(), 3);
+    /// let once: Vec<_> = schedule.missed_runs(&last, &now, CatchUp::RunOnce).collect();
+    /// assert_eq!(once[0].to_rfc3339(), "1996-09-12T03:00:00Z");
+    /// assert_eq!(schedule.missed_runs(&last, &now, CatchUp::Skip).count(), 0);
+    /// ```
+    pub fn missed_runs(&self, last: &DateTime, now: &DateTime, policy: CatchUp) -> Runs {
+        let mut runs = self.runs_between(last, now);
+        match policy {
+            CatchUp::RunAll => {}
+            CatchUp::RunOnce => {
+                // the latest run at or before `now`, if it is after `last`
+                let now = now.with_offset(last.offset);
+                runs.next = runs.next.and(self.last_at_or_before(&now));
+            }
+            CatchUp::Skip => runs.next = None,
+        }
+        runs
+    }
+
+    // the latest time at or before `date` that the job runs
+    fn last_at_or_before(&self, date: &DateTime) -> Option<DateTime> {
+        match self {
+            Schedule::Daily(daily) => daily.last_at_or_before(date),
+            Schedule::Every(period) => Some(date.floor_to(*period)),
+        }
+    }
+}
+
+/// what a job does about the runs it missed while it was down; see
+/// [`Schedule::missed_runs`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CatchUp {
+    /// run once for all of them, as of the latest
+    RunOnce,
+    /// run once for each of them, in order
+    RunAll,
+    /// run for none of them, and wait for the next
+    Skip,
+}
+
+/// an iterator over the times a [`Schedule`] runs in a range; see
+/// [`Schedule::runs_between`]
+#[derive(Debug, Clone)]
+pub struct Runs {
+    schedule: Schedule,
+    next: Option<DateTime>,
+    until: DateTime,
+}
+
+impl Iterator for Runs {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        let run = self.next?;
+        self.next = self
+            .schedule
+            .next_after(&run)
+            .filter(|next| *next <= self.until);
+        Some(run)
+    }
+}
+
+impl FusedIterator for Runs {}
+
+// the units of a period, largest first, and their lengths in seconds
+const UNITS: [(u8, u64); 4] = [(b'd', 86_400), (b'h', 3600), (b'm', 60), (b's', 1)];
+
+impl FromStr for Schedule {
+    type Err = ParseError;
+
+    /// parses a schedule such as `daily@03:00` or `every 6h`; a period must
+    /// not be zero
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "" => return Err(ParseError::TooShort),
+            "daily" => return Ok(Schedule::Daily(DailyLocalSchedule::new(Time::MIDNIGHT))),
+            "hourly" => return Ok(Schedule::Every(Duration::from_secs(3600))),
+            _ => {}
+        }
+
+        if let Some(time) = input.strip_prefix("daily@") {
+            let time = time.parse().map_err(|e| match e {
+                ParseError::Invalid(pos) => ParseError::Invalid(pos + 6),
+                e => e,
+            })?;
+            return Ok(Schedule::Daily(DailyLocalSchedule::new(time)));
+        }
+
+        let mut cursor = Cursor::new(input);
+        for &b in b"every " {
+            cursor.expect(b)?;
+        }
+
+        let mut secs: u64 = 0;
+        let mut units = UNITS.iter();
+        loop {
+            let start = cursor.pos;
+            if cursor.skip_digits() == 0 {
+                return match cursor.peek() {
+                    None => Err(ParseError::TooShort),
+                    Some(_) => Err(ParseError::Invalid(cursor.pos)),
+                };
+            }
+            let count: u64 = input[start..cursor.pos]
+                .parse()
+                .map_err(|_| ParseError::OutOfRange)?;
+
+            // each unit may appear once, and in order
+            let unit = cursor.next()?;
+            let &(_, length) = units
+                .find(|&&(name, _)| name == unit)
+                .ok_or(ParseError::Invalid(cursor.pos - 1))?;
+            secs = count
+                .checked_mul(length)
+                .and_then(|part| secs.checked_add(part))
+                .ok_or(ParseError::OutOfRange)?;
+
+            if cursor.peek().is_none() {
+                break;
+            }
+        }
+
+        if secs == 0 {
+            return Err(ParseError::OutOfRange);
+        }
+        Ok(Schedule::Every(Duration::from_secs(secs)))
+    }
+}
+
+impl fmt::Display for Schedule {
+    /// formats the schedule as it is parsed, with the time of a daily
+    /// schedule as `hh:mm`, or `hh:mm:ss` if it has seconds
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Schedule::Daily(daily) => {
+                let time = daily.time;
+                write!(f, "daily@{:02}:{:02}", time.hour(), time.minute())?;
+                if time.second() != 0 {
+                    write!(f, ":{:02}", time.second())?;
+                }
+                Ok(())
+            }
+            Schedule::Every(period) => {
+                f.write_str("every ")?;
+                let mut secs = period.as_secs();
+                for &(name, length) in &UNITS {
+                    if secs >= length {
+                        write!(f, "{}{}", secs / length, name as char)?;
+                        secs %= length;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CatchUp, DailyLocalSchedule, Schedule};
+    use crate::{DateTime, FixedOffset, ParseError, Time};
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    fn parse(input: &str) -> Result<Schedule, ParseError> {
+        input.parse()
+    }
+
+    #[test]
+    fn test_parse() {
+        let daily = |h, m, s| {
+            Ok(Schedule::Daily(DailyLocalSchedule::new(
+                Time::new(h, m, s).unwrap(),
+            )))
+        };
+        assert_eq!(parse("daily@03:00"), daily(3, 0, 0));
+        assert_eq!(parse("daily@23:59:30"), daily(23, 59, 30));
+        assert_eq!(parse("daily"), daily(0, 0, 0));
+        assert_eq!(
+            parse("hourly"),
+            Ok(Schedule::Every(Duration::from_secs(3600)))
+        );
+        assert_eq!(
+            parse("every 1d2h3m4s"),
+            Ok(Schedule::Every(Duration::from_secs(93_784)))
+        );
+        assert_eq!(
+            parse("every 90s"),
+            Ok(Schedule::Every(Duration::from_secs(90)))
+        );
+
+        for input in &[
+            "daily@03:00",
+            "daily@23:59:30",
+            "every 1h30m",
+            "every 2d",
+            "every 1d1s",
+        ] {
+            assert_eq!(parse(input).unwrap().to_string(), *input);
+        }
+        assert_eq!(parse("every 90s").unwrap().to_string(), "every 1m30s");
+
+        assert_eq!(parse(""), Err(ParseError::TooShort));
+        assert_eq!(parse("weekly"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("daily@3:00"), Err(ParseError::Invalid(7)));
+        assert_eq!(parse("daily@24:00"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("every "), Err(ParseError::TooShort));
+        assert_eq!(parse("every 6"), Err(ParseError::TooShort));
+        assert_eq!(parse("every 6x"), Err(ParseError::Invalid(7)));
+        assert_eq!(parse("every 30m1h"), Err(ParseError::Invalid(10)));
+        assert_eq!(parse("every 1h 30m"), Err(ParseError::Invalid(8)));
+        assert_eq!(parse("every 0h"), Err(ParseError::OutOfRange));
+        assert_eq!(
+            parse("every 99999999999999999999s"),
+            Err(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_next_after() {
+        let offset = FixedOffset::west(5 * 3600).unwrap();
+        let date = DateTime::parse_rfc3339("1996-09-09T15:23:44Z")
+            .unwrap()
+            .with_offset(offset);
+
+        // 10:23:44 locally, so 03:00 has passed but 10:23:45 hasn't
+        let next = parse("daily@03:00").unwrap().next_after(&date).unwrap();
+        assert_eq!(next.to_rfc3339(), "1996-09-10T03:00:00-05:00");
+        let next = parse("daily@10:23:45").unwrap().next_after(&date).unwrap();
+        assert_eq!(next.to_rfc3339(), "1996-09-09T10:23:45-05:00");
+
+        // a run exactly now is not after now
+        let now = parse("daily@10:23:44").unwrap().next_after(&date).unwrap();
+        assert_eq!(now.to_rfc3339(), "1996-09-10T10:23:44-05:00");
+
+        let every = parse("every 6h").unwrap();
+        let next = every.next_after(&date).unwrap();
+        assert_eq!(next.to_rfc3339(), "1996-09-09T12:00:00-05:00");
+        assert_eq!(
+            every.next_after(&next).unwrap().to_rfc3339(),
+            "1996-09-09T18:00:00-05:00"
+        );
+
+        assert!(every.next_after(&DateTime::MAX).is_none());
+    }
+
+    #[test]
+    fn test_missed_runs() {
+        let date = |s| DateTime::parse_rfc3339(s).unwrap();
+        let runs = |schedule: &str, last, now, policy| -> Vec<String> {
+            parse(schedule)
+                .unwrap()
+                .missed_runs(&date(last), &date(now), policy)
+                .map(|date| date.to_rfc3339())
+                .collect()
+        };
+
+        let (last, now) = ("1996-09-09T15:23:44Z", "1996-09-09T18:00:00Z");
+        assert_eq!(
+            runs("every 1h", last, now, CatchUp::RunAll),
+            [
+                "1996-09-09T16:00:00Z",
+                "1996-09-09T17:00:00Z",
+                "1996-09-09T18:00:00Z"
+            ]
+        );
+        assert_eq!(
+            runs("every 1h", last, now, CatchUp::RunOnce),
+            ["1996-09-09T18:00:00Z"]
+        );
+        assert!(runs("every 1h", last, now, CatchUp::Skip).is_empty());
+
+        // nothing was missed before the next run is due
+        let now = "1996-09-09T15:59:59Z";
+        assert!(runs("every 1h", last, now, CatchUp::RunAll).is_empty());
+        assert!(runs("every 1h", last, now, CatchUp::RunOnce).is_empty());
+        let now = "1996-09-10T02:59:59Z";
+        assert_eq!(
+            runs("daily@03:00", "1996-09-08T03:00:00Z", now, CatchUp::RunOnce),
+            ["1996-09-09T03:00:00Z"]
+        );
+
+        // in the offset of the last run
+        let offset = FixedOffset::east(3600).unwrap();
+        let last = date(last).with_offset(offset);
+        let schedule = parse("daily@17:00").unwrap();
+        let once: Vec<_> = schedule
+            .missed_runs(&last, &date("1996-09-11T00:00:00Z"), CatchUp::RunOnce)
+            .collect();
+        assert_eq!(once[0].to_rfc3339(), "1996-09-10T17:00:00+01:00");
+    }
+}
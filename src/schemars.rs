@@ -0,0 +1,89 @@
+//! JSON schemas for DateTimes, Days and Months, enabled with the
+//! `schemars` feature
+//!
+//! the schemas describe the default representations of the `serde`
+//! feature: a DateTime is an RFC 3339 string (`format: date-time`), and a
+//! Day or Month is its English name
+//! ```
+//! # use datetime::DateTime;
+//! let schema = schemars::schema_for!(DateTime);
+//! let json = serde_json::to_value(&schema).unwrap();
+//!
+//! assert_eq!(json["type"], "string");
+//! assert_eq!(json["format"], "date-time");
+//! ```
+use crate::{DateTime, Day, Month, DAYS, MONTHS};
+use ::schemars::gen::SchemaGenerator;
+use ::schemars::schema::{InstanceType, Schema, SchemaObject};
+use ::schemars::JsonSchema;
+
+// a string schema allowing only the given names
+fn names_schema(names: impl Iterator<Item = &'static str>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(names.map(|name| name.into()).collect()),
+        ..Default::default()
+    }
+    .into()
+}
+
+impl JsonSchema for DateTime {
+    fn schema_name() -> String {
+        "DateTime".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("date-time".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl JsonSchema for Day {
+    fn schema_name() -> String {
+        "Day".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        names_schema(DAYS.iter().map(|day| day.name()))
+    }
+}
+
+impl JsonSchema for Month {
+    fn schema_name() -> String {
+        "Month".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        names_schema(MONTHS.iter().map(|month| month.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, Day, Month};
+    use ::schemars::{schema_for, JsonSchema};
+
+    #[test]
+    fn test_datetime_schema() {
+        let json = serde_json::to_value(schema_for!(DateTime)).unwrap();
+
+        assert_eq!(json["type"], "string");
+        assert_eq!(json["format"], "date-time");
+        assert_eq!(DateTime::schema_name(), "DateTime");
+    }
+
+    #[test]
+    fn test_name_schemas() {
+        let json = serde_json::to_value(schema_for!(Day)).unwrap();
+        assert_eq!(json["type"], "string");
+        assert_eq!(json["enum"][0], "Sunday");
+        assert_eq!(json["enum"].as_array().unwrap().len(), 7);
+
+        let json = serde_json::to_value(schema_for!(Month)).unwrap();
+        assert_eq!(json["enum"][11], "December");
+    }
+}
@@ -0,0 +1,270 @@
+//! the seasons of the year
+//!
+//! a season can be reckoned two ways. meteorological seasons are whole
+//! months, March to May being spring in the northern hemisphere, and are
+//! what weather services and retailers use. astronomical seasons start at
+//! the equinoxes and solstices, which [`equinoxes_and_solstices`] works
+//! out. either way the southern hemisphere's seasons are the opposite of
+//! the northern's
+//! ```
+//! # use datetime::season::{Hemisphere, Season};
+//! # use datetime::DateTime;
+//! // 1996-09-09
+//! let date = DateTime::from_secs(842282624);
+//!
+//! assert_eq!(date.season(Hemisphere::Northern), Season::Autumn);
+//! assert_eq!(date.season(Hemisphere::Southern), Season::Spring);
+//! assert_eq!(
+//!     date.astronomical_season(Hemisphere::Northern),
+//!     Some(Season::Summer)
+//! );
+//! ```
+use crate::{DateTime, Month};
+
+/// one of the four seasons
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// returns the season in the other hemisphere at the same time
+    /// ```
+    /// # use datetime::season::Season;
+    /// assert_eq!(Season::Summer.opposite(), Season::Winter);
+    /// ```
+    pub const fn opposite(self) -> Season {
+        match self {
+            Season::Spring => Season::Autumn,
+            Season::Summer => Season::Winter,
+            Season::Autumn => Season::Spring,
+            Season::Winter => Season::Summer,
+        }
+    }
+
+    // the season in the given hemisphere, given the northern one
+    const fn in_hemisphere(self, hemisphere: Hemisphere) -> Season {
+        match hemisphere {
+            Hemisphere::Northern => self,
+            Hemisphere::Southern => self.opposite(),
+        }
+    }
+}
+
+/// a hemisphere of the Earth
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    Northern,
+    Southern,
+}
+
+// Meeus's mean March equinox, June solstice, September equinox and
+// December solstice, as polynomials in thousands of years, for the years
+// -1000 to 1000 (from year 0) and 1000 to 3000 (from year 2000)
+const MEAN_BEFORE_1000: [[f64; 5]; 4] = [
+    [1721139.29189, 365242.13740, 0.06134, 0.00111, -0.00071],
+    [1721233.25401, 365241.72562, -0.05323, 0.00907, 0.00025],
+    [1721325.70455, 365242.49558, -0.11677, -0.00297, 0.00074],
+    [1721414.39987, 365242.88257, -0.00769, -0.00933, -0.00006],
+];
+const MEAN_AFTER_1000: [[f64; 5]; 4] = [
+    [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+    [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+    [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+    [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032],
+];
+
+// the periodic terms correcting the mean times, as (A, B, C)
+const PERIODIC: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136),
+    (203.0, 337.23, 32964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112),
+    (156.0, 73.14, 45036.886),
+    (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934),
+    (74.0, 296.72, 3034.906),
+    (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562),
+    (44.0, 325.15, 31555.956),
+    (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328),
+    (17.0, 288.79, 4562.452),
+    (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921),
+    (12.0, 95.39, 14577.848),
+    (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259),
+    (9.0, 227.73, 1222.114),
+    (8.0, 15.45, 16859.074),
+];
+
+// the cosine of an angle in degrees, as f64::cos needs std
+fn cos_degrees(degrees: f64) -> f64 {
+    let mut x = (degrees % 360.0).to_radians();
+    if x > core::f64::consts::PI {
+        x -= 2.0 * core::f64::consts::PI;
+    } else if x < -core::f64::consts::PI {
+        x += 2.0 * core::f64::consts::PI;
+    }
+
+    // the Taylor series is exact to a double's precision by this many
+    // terms for angles of at most pi
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..=14 {
+        term *= -x * x / ((2 * n - 1) * (2 * n)) as f64;
+        sum += term;
+    }
+    sum
+}
+
+/// returns the March equinox, June solstice, September equinox and
+/// December solstice of the given year, or None if the year is not from 1
+/// to 3000
+///
+/// these use the algorithm of Meeus's Astronomical Algorithms (chapter
+/// 27), which is accurate to a minute or so, and are rounded to the
+/// millisecond. it gives dynamical time, which is also taken as UTC; they
+/// differed by about a minute in 2000
+/// ```
+/// # use datetime::season::equinoxes_and_solstices;
+/// let [march, june, september, december] = equinoxes_and_solstices(2024).unwrap();
+///
+/// assert_eq!((march.date(), march.hour()), (20, 3));
+/// assert_eq!((june.date(), june.hour()), (20, 20));
+/// assert_eq!((september.date(), september.hour()), (22, 12));
+/// assert_eq!((december.date(), december.hour()), (21, 9));
+/// ```
+pub fn equinoxes_and_solstices(year: usize) -> Option<[DateTime; 4]> {
+    let (table, y) = match year {
+        1..=999 => (&MEAN_BEFORE_1000, year as f64 / 1000.0),
+        1000..=3000 => (&MEAN_AFTER_1000, (year as f64 - 2000.0) / 1000.0),
+        _ => return None,
+    };
+
+    let mut dates = [DateTime::from_secs(0); 4];
+    for (date, mean) in dates.iter_mut().zip(table) {
+        let jde0 = mean.iter().rev().fold(0.0, |sum, c| sum * y + c);
+
+        let t = (jde0 - 2451545.0) / 36525.0;
+        let w = 35999.373 * t - 2.47;
+        let dl = 1.0 + 0.0334 * cos_degrees(w) + 0.0007 * cos_degrees(2.0 * w);
+        let s: f64 = PERIODIC
+            .iter()
+            .map(|&(a, b, c)| a * cos_degrees(b + c * t))
+            .sum();
+
+        *date = DateTime::from_julian_date(jde0 + 0.00001 * s / dl)?;
+    }
+
+    Some(dates)
+}
+
+impl DateTime {
+    /// returns the meteorological season of the DateTime's month in the
+    /// given hemisphere
+    /// ```
+    /// # use datetime::season::{Hemisphere, Season};
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc3339("2024-12-01T00:00:00Z").unwrap();
+    ///
+    /// assert_eq!(date.season(Hemisphere::Northern), Season::Winter);
+    /// assert_eq!(date.season(Hemisphere::Southern), Season::Summer);
+    /// ```
+    pub fn season(&self, hemisphere: Hemisphere) -> Season {
+        let northern = match self.month() {
+            Month::March | Month::April | Month::May => Season::Spring,
+            Month::June | Month::July | Month::August => Season::Summer,
+            Month::September | Month::October | Month::November => Season::Autumn,
+            Month::December | Month::January | Month::February => Season::Winter,
+        };
+        northern.in_hemisphere(hemisphere)
+    }
+
+    /// returns the astronomical season of the DateTime in the given
+    /// hemisphere, which starts at an equinox or solstice, or None if its
+    /// year is after 3000 (see [`equinoxes_and_solstices`])
+    /// ```
+    /// # use datetime::season::{Hemisphere, Season};
+    /// # use datetime::DateTime;
+    /// let date = DateTime::parse_rfc3339("2024-03-20T03:00:00Z").unwrap();
+    /// assert_eq!(date.astronomical_season(Hemisphere::Northern), Some(Season::Winter));
+    ///
+    /// let date = DateTime::parse_rfc3339("2024-03-20T04:00:00Z").unwrap();
+    /// assert_eq!(date.astronomical_season(Hemisphere::Northern), Some(Season::Spring));
+    /// ```
+    pub fn astronomical_season(&self, hemisphere: Hemisphere) -> Option<Season> {
+        let starts = equinoxes_and_solstices(self.year())?;
+        let northern = match starts.iter().rposition(|start| start <= self) {
+            Some(0) => Season::Spring,
+            Some(1) => Season::Summer,
+            Some(2) => Season::Autumn,
+            _ => Season::Winter,
+        };
+        Some(northern.in_hemisphere(hemisphere))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cos_degrees, equinoxes_and_solstices, Hemisphere, Season};
+    use crate::DateTime;
+
+    #[test]
+    fn test_equinoxes_and_solstices() {
+        // Meeus's example 27.a: the June solstice of 1962 was at JDE
+        // 2437837.39245, given to five places
+        let june = equinoxes_and_solstices(1962).unwrap()[1];
+        assert!((june.julian_date() - 2437837.39245).abs() < 0.00001);
+        assert_eq!(june.to_rfc3339(), "1962-06-21T21:25:07.528Z");
+
+        let [march, _, september, december] = equinoxes_and_solstices(1).unwrap();
+        assert_eq!((march.year(), march.month() as usize + 1), (1, 3));
+        assert_eq!(september.month() as usize + 1, 9);
+        assert_eq!(december.month() as usize + 1, 12);
+
+        assert!(equinoxes_and_solstices(0).is_none());
+        assert!(equinoxes_and_solstices(3001).is_none());
+
+        for &(degrees, cos) in &[(0.0, 1.0), (60.0, 0.5), (180.0, -1.0), (-240.0, -0.5)] {
+            assert!((cos_degrees(degrees) - cos).abs() < 1e-12);
+        }
+        assert!((cos_degrees(3690.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_seasons() {
+        let date = |s: &str| DateTime::parse_rfc3339(s).unwrap();
+        let (north, south) = (Hemisphere::Northern, Hemisphere::Southern);
+
+        assert_eq!(date("2024-02-29T23:59:59Z").season(north), Season::Winter);
+        assert_eq!(date("2024-03-01T00:00:00Z").season(north), Season::Spring);
+        assert_eq!(date("2024-08-31T00:00:00Z").season(south), Season::Winter);
+        assert_eq!(
+            date("2024-11-30T00:00:00+02:00").season(north),
+            Season::Autumn
+        );
+
+        // the December solstice of 2024 was at 09:20 UTC on the 21st
+        let before = date("2024-12-21T09:00:00Z");
+        let after = date("2024-12-21T09:40:00Z");
+        assert_eq!(before.astronomical_season(north), Some(Season::Autumn));
+        assert_eq!(after.astronomical_season(north), Some(Season::Winter));
+        assert_eq!(after.astronomical_season(south), Some(Season::Summer));
+        assert_eq!(
+            date("2024-01-01T00:00:00Z").astronomical_season(south),
+            Some(Season::Summer)
+        );
+        assert!(DateTime::from_ordinal(3001, 1)
+            .unwrap()
+            .astronomical_season(north)
+            .is_none());
+    }
+}
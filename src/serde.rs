@@ -0,0 +1,453 @@
+//! serde support for DateTimes, Days and Months, enabled with the `serde`
+//! feature
+//!
+//! by default a DateTime is serialized as an RFC 3339 string, and a Day or
+//! Month as its English name. the [`ts_seconds`] and [`rfc3339`] modules
+//! pick a DateTime representation explicitly with `#[serde(with = ...)]`,
+//! as [`month_as_number`], [`month_as_name`] and [`month_as_abbreviation`]
+//! and their `day_as_` counterparts do for Days and Months
+//! ```
+//! # use datetime::DateTime;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "datetime::serde::ts_seconds")]
+//!     created: DateTime,
+//!     updated: DateTime,
+//! }
+//!
+//! let json = r#"{"created":842282624,"updated":"1996-09-09T15:23:44Z"}"#;
+//! let event: Event = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(event.created.year(), 1996);
+//! assert_eq!(serde_json::to_string(&event).unwrap(), json);
+//! ```
+//! ```
+//! # use datetime::{Day, Month};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Billing {
+//!     #[serde(with = "datetime::serde::month_as_number")]
+//!     renews: Month,
+//!     #[serde(with = "datetime::serde::day_as_abbreviation")]
+//!     invoiced: Day,
+//! }
+//!
+//! let billing = Billing { renews: Month::September, invoiced: Day::Friday };
+//! let json = serde_json::to_string(&billing).unwrap();
+//! assert_eq!(json, r#"{"renews":9,"invoiced":"FRI"}"#);
+//! ```
+use crate::{DateTime, Day, Month, MAX_SECS, MIN_SECS};
+use ::serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+/// (de)serializes a DateTime as an RFC 3339 string such as
+/// `1996-09-09T15:23:44.500+02:00`; this is the default representation
+///
+/// the string is written in the DateTime's own offset, while a
+/// deserialized DateTime is always in UTC
+pub mod rfc3339 {
+    use super::Rfc3339Visitor;
+    use crate::DateTime;
+    use ::serde::{Deserializer, Serializer};
+
+    /// serializes a DateTime as an RFC 3339 string
+    pub fn serialize<S: Serializer>(date: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date.to_rfc3339())
+    }
+
+    /// deserializes a DateTime from an RFC 3339 string
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        deserializer.deserialize_str(Rfc3339Visitor)
+    }
+}
+
+/// (de)serializes a DateTime as an integer number of seconds since the
+/// Unix epoch
+///
+/// any fraction of a second and the DateTime's offset are not kept
+pub mod ts_seconds {
+    use super::SecondsVisitor;
+    use crate::DateTime;
+    use ::serde::{Deserializer, Serializer};
+
+    /// serializes a DateTime as seconds since the Unix epoch
+    pub fn serialize<S: Serializer>(date: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(date.secs)
+    }
+
+    /// deserializes a DateTime from seconds since the Unix epoch
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        deserializer.deserialize_i64(SecondsVisitor)
+    }
+}
+
+/// (de)serializes a Month as its number, from 1 for January to 12 for
+/// December
+pub mod month_as_number {
+    use super::NumberVisitor;
+    use crate::Month;
+    use ::serde::{Deserializer, Serializer};
+
+    /// serializes a Month as its number
+    pub fn serialize<S: Serializer>(month: &Month, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(month.number() as u8)
+    }
+
+    /// deserializes a Month from its number
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Month, D::Error> {
+        deserializer.deserialize_u64(NumberVisitor {
+            what: "month from 1 to 12",
+            from_number: Month::from_number,
+        })
+    }
+}
+
+/// (de)serializes a Month as its English name, such as `"September"`;
+/// this is the default representation
+///
+/// any abbreviation, in any case, is accepted when deserializing
+pub mod month_as_name {
+    use crate::Month;
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// serializes a Month as its English name
+    pub fn serialize<S: Serializer>(month: &Month, serializer: S) -> Result<S::Ok, S::Error> {
+        month.serialize(serializer)
+    }
+
+    /// deserializes a Month from its English name or abbreviation
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Month, D::Error> {
+        Month::deserialize(deserializer)
+    }
+}
+
+/// (de)serializes a Month as its three-letter English abbreviation in
+/// capitals, such as `"SEP"`
+///
+/// the full name, in any case, is accepted when deserializing
+pub mod month_as_abbreviation {
+    use super::upper_abbreviation;
+    use crate::Month;
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    /// serializes a Month as its abbreviation
+    pub fn serialize<S: Serializer>(month: &Month, serializer: S) -> Result<S::Ok, S::Error> {
+        upper_abbreviation(month.abbreviation(), serializer)
+    }
+
+    /// deserializes a Month from its abbreviation or English name
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Month, D::Error> {
+        Month::deserialize(deserializer)
+    }
+}
+
+/// (de)serializes a Day as its ISO 8601 number, from 1 for Monday to 7
+/// for Sunday
+pub mod day_as_number {
+    use super::NumberVisitor;
+    use crate::Day;
+    use ::serde::{Deserializer, Serializer};
+
+    /// serializes a Day as its number
+    pub fn serialize<S: Serializer>(day: &Day, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(day.number() as u8)
+    }
+
+    /// deserializes a Day from its number
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Day, D::Error> {
+        deserializer.deserialize_u64(NumberVisitor {
+            what: "day from 1 for Monday to 7 for Sunday",
+            from_number: Day::from_number,
+        })
+    }
+}
+
+/// (de)serializes a Day as its English name, such as `"Monday"`; this is
+/// the default representation
+///
+/// any abbreviation, in any case, is accepted when deserializing
+pub mod day_as_name {
+    use crate::Day;
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// serializes a Day as its English name
+    pub fn serialize<S: Serializer>(day: &Day, serializer: S) -> Result<S::Ok, S::Error> {
+        day.serialize(serializer)
+    }
+
+    /// deserializes a Day from its English name or abbreviation
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Day, D::Error> {
+        Day::deserialize(deserializer)
+    }
+}
+
+/// (de)serializes a Day as its three-letter English abbreviation in
+/// capitals, such as `"MON"`
+///
+/// the full name, in any case, is accepted when deserializing
+pub mod day_as_abbreviation {
+    use super::upper_abbreviation;
+    use crate::Day;
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    /// serializes a Day as its abbreviation
+    pub fn serialize<S: Serializer>(day: &Day, serializer: S) -> Result<S::Ok, S::Error> {
+        upper_abbreviation(day.abbreviation(), serializer)
+    }
+
+    /// deserializes a Day from its abbreviation or English name
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Day, D::Error> {
+        Day::deserialize(deserializer)
+    }
+}
+
+// serializes a three-letter abbreviation in capitals, without allocating
+fn upper_abbreviation<S: Serializer>(name: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut upper = [0; 3];
+    upper.copy_from_slice(name.as_bytes());
+    upper.make_ascii_uppercase();
+    let upper = core::str::from_utf8(&upper).expect("uppercased ASCII is still UTF-8");
+    serializer.serialize_str(upper)
+}
+
+struct Rfc3339Visitor;
+
+impl<'de> Visitor<'de> for Rfc3339Visitor {
+    type Value = DateTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an RFC 3339 timestamp")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<DateTime, E> {
+        DateTime::parse_rfc3339(value).map_err(E::custom)
+    }
+}
+
+struct SecondsVisitor;
+
+impl<'de> Visitor<'de> for SecondsVisitor {
+    type Value = DateTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "seconds since the Unix epoch, no earlier than the year 1"
+        )
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<DateTime, E> {
+        if !(MIN_SECS..=MAX_SECS).contains(&value) {
+            return Err(E::invalid_value(Unexpected::Signed(value), &self));
+        }
+
+        Ok(DateTime::from_signed_secs(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<DateTime, E> {
+        match i64::try_from(value) {
+            Ok(value) => self.visit_i64(value),
+            Err(_) => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
+        }
+    }
+}
+
+impl Serialize for DateTime {
+    /// serializes the DateTime as an RFC 3339 string; see [`rfc3339`]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        rfc3339::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    /// deserializes a DateTime from an RFC 3339 string; see [`rfc3339`]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        rfc3339::deserialize(deserializer)
+    }
+}
+
+// a visitor for enums written as their English name, accepting anything
+// their FromStr impls do
+struct NameVisitor<T> {
+    what: &'static str,
+    value: PhantomData<T>,
+}
+
+impl<'de, T: FromStr> Visitor<'de> for NameVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the name of a {}", self.what)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value
+            .parse()
+            .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+    }
+}
+
+// a visitor for enums written as a number, such as the number of a month
+struct NumberVisitor<T> {
+    what: &'static str,
+    from_number: fn(usize) -> Option<T>,
+}
+
+impl<'de, T> Visitor<'de> for NumberVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the number of a {}", self.what)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        usize::try_from(value)
+            .ok()
+            .and_then(self.from_number)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+}
+
+impl Serialize for Day {
+    /// serializes the Day as its English name, e.g. `"Monday"`
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Day {
+    /// deserializes a Day from its English name or abbreviation
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(NameVisitor {
+            what: "day",
+            value: PhantomData,
+        })
+    }
+}
+
+impl Serialize for Month {
+    /// serializes the Month as its English name, e.g. `"September"`
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Month {
+    /// deserializes a Month from its English name or abbreviation
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(NameVisitor {
+            what: "month",
+            value: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{day_as_abbreviation, day_as_number, month_as_abbreviation, month_as_number};
+    use super::{month_as_name, ts_seconds};
+    use crate::{DateTime, Day, FixedOffset, Month};
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn test_rfc3339_round_trip() {
+        let date = DateTime::from_millis(842282624500);
+        let json = serde_json::to_string(&date).unwrap();
+        assert_eq!(json, r#""1996-09-09T15:23:44.500Z""#);
+
+        let parsed: DateTime = serde_json::from_str(&json).unwrap();
+        assert_eq!((parsed.secs, parsed.nanos), (842282624, 500_000_000));
+
+        let date = date.with_offset(FixedOffset::west(16200).unwrap());
+        let json = serde_json::to_string(&date).unwrap();
+        assert_eq!(json, r#""1996-09-09T10:53:44.500-04:30""#);
+
+        let parsed: DateTime = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.secs, 842282624);
+
+        assert!(serde_json::from_str::<DateTime>(r#""1996-09-09""#).is_err());
+    }
+
+    #[test]
+    fn test_ts_seconds() {
+        let date = DateTime::from_millis(-1500);
+
+        let mut json = Vec::new();
+        ts_seconds::serialize(&date, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(json, b"-2");
+
+        let mut de = serde_json::Deserializer::from_str("842282624");
+        let date = ts_seconds::deserialize(&mut de).unwrap();
+        assert_eq!(date.secs, 842282624);
+
+        let mut de = serde_json::Deserializer::from_str("-62135596801");
+        assert!(ts_seconds::deserialize(&mut de).is_err());
+    }
+
+    #[test]
+    fn test_day_and_month_names() {
+        assert_eq!(serde_json::to_string(&Day::Monday).unwrap(), r#""Monday""#);
+        assert_eq!(serde_json::to_string(&Month::May).unwrap(), r#""May""#);
+
+        let day: Day = serde_json::from_str(r#""tue""#).unwrap();
+        assert_eq!(day, Day::Tuesday);
+        let month: Month = serde_json::from_str(r#""SEPTEMBER""#).unwrap();
+        assert_eq!(month, Month::September);
+
+        assert!(serde_json::from_str::<Month>(r#""Septem""#).is_err());
+    }
+
+    #[test]
+    fn test_day_and_month_helpers() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Renewal {
+            #[serde(with = "month_as_number")]
+            month: Month,
+            #[serde(with = "month_as_name")]
+            name: Month,
+            #[serde(with = "month_as_abbreviation")]
+            short: Month,
+            #[serde(with = "day_as_number")]
+            day: Day,
+            #[serde(with = "day_as_abbreviation")]
+            short_day: Day,
+        }
+
+        let renewal = Renewal {
+            month: Month::September,
+            name: Month::May,
+            short: Month::December,
+            day: Day::Sunday,
+            short_day: Day::Thursday,
+        };
+        let json = serde_json::to_string(&renewal).unwrap();
+        assert_eq!(
+            json,
+            r#"{"month":9,"name":"May","short":"DEC","day":7,"short_day":"THU"}"#
+        );
+        assert_eq!(serde_json::from_str::<Renewal>(&json).unwrap(), renewal);
+
+        // names are accepted by the abbreviations and the other way around
+        let json = r#"{"month":9,"name":"may","short":"December","day":7,"short_day":"thu"}"#;
+        assert_eq!(serde_json::from_str::<Renewal>(json).unwrap(), renewal);
+
+        for bad in &["0", "13", "-1", r#""9""#] {
+            let mut de = serde_json::Deserializer::from_str(bad);
+            assert!(month_as_number::deserialize(&mut de).is_err());
+        }
+        let mut de = serde_json::Deserializer::from_str("8");
+        assert!(day_as_number::deserialize(&mut de).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! destinations that formatted timestamps can be written to
+//!
+//! every formatter in the crate writes its output through a
+//! [`TimestampSink`], which takes text and zero-padded numbers. it is
+//! implemented for `String`, `Vec<u8>` and `fmt::Formatter`, for any
+//! `fmt::Write` wrapped in a [`FmtSink`], and for any `io::Write` wrapped in
+//! an [`IoSink`], and can be implemented for binary loggers or arena
+//! buffers so that they receive the output directly
+//! ```
+//! # use datetime::{DateTime, TimestampSink};
+//! # use std::fmt;
+//! // a sink which only counts the bytes it would have written
+//! struct Counter(usize);
+//!
+//! impl TimestampSink for Counter {
+//!     fn write_str(&mut self, s: &str) -> fmt::Result {
+//!         self.0 += s.len();
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut counter = Counter(0);
+//! DateTime::from_secs(842282624).write_rfc3339_to(&mut counter).unwrap();
+//! assert_eq!(counter.0, "1996-09-09T15:23:44Z".len());
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// somewhere a formatted timestamp can be written
+pub trait TimestampSink {
+    /// writes a piece of text
+    fn write_str(&mut self, s: &str) -> fmt::Result;
+
+    /// writes a number in decimal, padded with zeros to at least `width`
+    /// digits
+    ///
+    /// the default writes the digits with [`TimestampSink::write_str`]
+    fn write_u32_padded(&mut self, value: u32, width: usize) -> fmt::Result {
+        let mut buf = [0; 10];
+        let mut start = buf.len();
+        let mut value = value;
+        loop {
+            start -= 1;
+            buf[start] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+
+        for _ in buf.len() - start..width {
+            self.write_str("0")?;
+        }
+        let digits = core::str::from_utf8(&buf[start..]).expect("digits are ASCII");
+        self.write_str(digits)
+    }
+}
+
+impl TimestampSink for String {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl TimestampSink for Vec<u8> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl TimestampSink for fmt::Formatter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        fmt::Write::write_str(self, s)
+    }
+}
+
+impl<S: TimestampSink + ?Sized> TimestampSink for &mut S {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        (**self).write_str(s)
+    }
+
+    fn write_u32_padded(&mut self, value: u32, width: usize) -> fmt::Result {
+        (**self).write_u32_padded(value, width)
+    }
+}
+
+/// a [`TimestampSink`] that writes to any `fmt::Write`
+#[derive(Debug)]
+pub struct FmtSink<W>(pub W);
+
+impl<W: fmt::Write> TimestampSink for FmtSink<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+}
+
+/// a [`TimestampSink`] that writes to any `io::Write`
+///
+/// `fmt::Result` can't carry an I/O error, so the first one is kept to be
+/// returned by [`IoSink::into_inner`]
+/// ```
+/// # use datetime::sink::IoSink;
+/// # use datetime::DateTime;
+/// let mut sink = IoSink::new(Vec::new());
+/// DateTime::from_secs(842282624).write_http_date_to(&mut sink).unwrap();
+///
+/// assert_eq!(sink.into_inner().unwrap(), b"Mon, 09 Sep 1996 15:23:44 GMT");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoSink<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> IoSink<W> {
+    /// returns a sink writing to `inner`
+    pub fn new(inner: W) -> Self {
+        IoSink { inner, error: None }
+    }
+
+    /// returns the writer, or the first error it returned
+    pub fn into_inner(self) -> io::Result<W> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.inner),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> TimestampSink for IoSink<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FmtSink, TimestampSink};
+    use crate::DateTime;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_write_u32_padded() {
+        let mut s = String::new();
+        s.write_u32_padded(7, 2).unwrap();
+        s.write_u32_padded(1996, 2).unwrap();
+        s.write_u32_padded(0, 0).unwrap();
+        s.write_u32_padded(u32::MAX, 12).unwrap();
+
+        assert_eq!(s, "0719960004294967295");
+    }
+
+    #[test]
+    fn test_sinks_agree() {
+        let date = DateTime::from_millis(842282624500);
+        let expected = date.to_rfc3339();
+
+        let mut bytes = Vec::new();
+        date.write_rfc3339_to(&mut bytes).unwrap();
+        assert_eq!(bytes, expected.as_bytes());
+
+        let mut s = String::new();
+        date.write_rfc3339_to(&mut FmtSink(&mut s)).unwrap();
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_io_sink_error() {
+        use super::IoSink;
+        use std::io::{self, Write};
+
+        struct Full;
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::WriteZero.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut sink = IoSink::new(Full);
+        assert!(DateTime::from_secs(0).write_rfc2822_to(&mut sink).is_err());
+        let error = sink.into_inner().err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+}
@@ -0,0 +1,485 @@
+//! signed lengths of time between DateTimes
+//!
+//! adding two DateTimes together isn't meaningful, so arithmetic goes
+//! through a [`Span`] instead: a DateTime plus or minus a Span is a
+//! DateTime, and one DateTime minus another is the Span between them.
+//! unlike a [`Duration`], a Span can be negative
+//! ```
+//! # use datetime::{DateTime, Span};
+//! let start = DateTime::from_secs(842282624);
+//! let end = DateTime::from_secs(842286224);
+//!
+//! let span = &end - &start;
+//! assert_eq!(span, Span::from_secs(3600));
+//! assert_eq!(&start - &end, -span);
+//! assert_eq!(start + span, end);
+//! ```
+use crate::{DateTime, RangeError, MAX_SECS, MIN_SECS};
+use core::convert::TryFrom;
+use core::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use core::time::Duration;
+
+/// a signed length of time, with nanosecond precision
+///
+/// Spans are stored as whole seconds, rounded towards the past, and the
+/// nanoseconds after that, so -1.5 seconds is -2 seconds and 500,000,000
+/// nanoseconds
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    secs: i64,
+    nanos: u32,
+}
+
+impl Span {
+    /// a Span of no time at all
+    pub const ZERO: Span = Span { secs: 0, nanos: 0 };
+
+    /// returns the Span of the given number of seconds and nanoseconds
+    /// after that, or None if `nanos` is not less than a second
+    pub fn new(secs: i64, nanos: u32) -> Option<Self> {
+        if nanos < 1_000_000_000 {
+            Some(Span { secs, nanos })
+        } else {
+            None
+        }
+    }
+
+    /// returns the Span of the given number of seconds
+    pub const fn from_secs(secs: i64) -> Self {
+        Span { secs, nanos: 0 }
+    }
+
+    /// returns the Span of the given number of milliseconds
+    /// ```
+    /// # use datetime::Span;
+    /// let span = Span::from_millis(-1500);
+    ///
+    /// assert_eq!((span.seconds(), span.subsec_nanos()), (-2, 500_000_000));
+    /// ```
+    pub const fn from_millis(millis: i64) -> Self {
+        Span {
+            secs: millis.div_euclid(1000),
+            nanos: millis.rem_euclid(1000) as u32 * 1_000_000,
+        }
+    }
+
+    /// returns the whole seconds in the Span, rounded towards the past
+    pub fn seconds(&self) -> i64 {
+        self.secs
+    }
+
+    /// returns the nanoseconds after [`Span::seconds`]
+    pub fn subsec_nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    /// returns true if the Span is less than zero
+    pub fn is_negative(&self) -> bool {
+        self.secs < 0
+    }
+
+    /// returns the length of the Span, ignoring its sign
+    pub fn unsigned_abs(&self) -> Duration {
+        match (self.secs, self.nanos) {
+            (secs, nanos) if secs >= 0 => Duration::new(secs as u64, nanos),
+            (secs, 0) => Duration::from_secs(secs.unsigned_abs()),
+            (secs, nanos) => Duration::new((secs + 1).unsigned_abs(), 1_000_000_000 - nanos),
+        }
+    }
+
+    /// returns `-self`, or None if that overflows
+    pub fn checked_neg(&self) -> Option<Span> {
+        match self.nanos {
+            0 => Some(Span::from_secs(self.secs.checked_neg()?)),
+            nanos => Some(Span {
+                secs: (-1i64).checked_sub(self.secs)?,
+                nanos: 1_000_000_000 - nanos,
+            }),
+        }
+    }
+
+    /// returns `self + other`, or None if that overflows
+    pub fn checked_add(&self, other: Span) -> Option<Span> {
+        let nanos = self.nanos + other.nanos;
+        let secs = self
+            .secs
+            .checked_add(other.secs)?
+            .checked_add((nanos / 1_000_000_000) as i64)?;
+
+        Some(Span {
+            secs,
+            nanos: nanos % 1_000_000_000,
+        })
+    }
+
+    /// returns `self - other`, or None if that overflows
+    pub fn checked_sub(&self, other: Span) -> Option<Span> {
+        self.checked_add(other.checked_neg()?)
+    }
+}
+
+impl TryFrom<Duration> for Span {
+    type Error = RangeError;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let secs = i64::try_from(duration.as_secs()).map_err(|_| RangeError)?;
+        Ok(Span {
+            secs,
+            nanos: duration.subsec_nanos(),
+        })
+    }
+}
+
+impl TryFrom<Span> for Duration {
+    type Error = RangeError;
+
+    /// fails if the Span is negative
+    fn try_from(span: Span) -> Result<Self, Self::Error> {
+        if span.is_negative() {
+            Err(RangeError)
+        } else {
+            Ok(span.unsigned_abs())
+        }
+    }
+}
+
+impl Neg for Span {
+    type Output = Span;
+
+    /// # Panics
+    ///
+    /// panics if the result overflows
+    fn neg(self) -> Span {
+        self.checked_neg().expect("overflow when negating a Span")
+    }
+}
+
+impl Add for Span {
+    type Output = Span;
+
+    /// # Panics
+    ///
+    /// panics if the result overflows
+    fn add(self, other: Span) -> Span {
+        self.checked_add(other).expect("overflow when adding Spans")
+    }
+}
+
+impl Sub for Span {
+    type Output = Span;
+
+    /// # Panics
+    ///
+    /// panics if the result overflows
+    fn sub(self, other: Span) -> Span {
+        self.checked_sub(other)
+            .expect("overflow when subtracting Spans")
+    }
+}
+
+impl DateTime {
+    /// returns the DateTime the given Span later, or None if that is
+    /// outside the range a DateTime can represent
+    pub fn checked_add_span(&self, span: Span) -> Option<DateTime> {
+        let nanos = self.nanos + span.nanos;
+        let secs = self
+            .secs
+            .checked_add(span.secs)?
+            .checked_add((nanos / 1_000_000_000) as i64)?;
+
+        if (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_parts(
+                secs,
+                nanos % 1_000_000_000,
+                self.offset,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// returns the DateTime the given Span earlier, or None if that is
+    /// outside the range a DateTime can represent
+    pub fn checked_sub_span(&self, span: Span) -> Option<DateTime> {
+        self.checked_add_span(span.checked_neg()?)
+    }
+
+    /// like `self - earlier`, the Span from `earlier` to this DateTime
+    /// (negative if `earlier` is actually later), but returns None instead
+    /// of panicking if that is too long for a Span
+    /// ```
+    /// # use datetime::{DateTime, Span};
+    /// let date = DateTime::from_millis(1500);
+    /// let epoch = DateTime::from_secs(0);
+    ///
+    /// assert_eq!(date.checked_span_since(&epoch), Some(Span::from_millis(1500)));
+    /// assert_eq!(epoch.checked_span_since(&date), Some(Span::from_millis(-1500)));
+    /// ```
+    pub fn checked_span_since(&self, earlier: &DateTime) -> Option<Span> {
+        // borrow a second if the nanoseconds would go negative
+        let (secs, nanos) = match self.nanos.checked_sub(earlier.nanos) {
+            Some(nanos) => (self.secs.checked_sub(earlier.secs)?, nanos),
+            None => (
+                self.secs.checked_sub(earlier.secs)?.checked_sub(1)?,
+                self.nanos + 1_000_000_000 - earlier.nanos,
+            ),
+        };
+
+        Some(Span { secs, nanos })
+    }
+
+    // the nanoseconds from `earlier` to this DateTime, which always fit in
+    // an i128
+    fn nanos_since(&self, earlier: &DateTime) -> i128 {
+        (self.secs as i128 - earlier.secs as i128) * 1_000_000_000 + self.nanos as i128
+            - earlier.nanos as i128
+    }
+
+    /// returns the time from `earlier` to this DateTime, or the time the
+    /// other way as an error if `earlier` is actually later, like
+    /// `SystemTime::duration_since`
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::Duration;
+    /// let deadline = DateTime::from_secs(842282624);
+    /// let now = DateTime::from_secs(842279024);
+    ///
+    /// assert_eq!(deadline.duration_since(&now), Ok(Duration::from_secs(3600)));
+    /// assert_eq!(now.duration_since(&deadline), Err(Duration::from_secs(3600)));
+    /// ```
+    pub fn duration_since(&self, earlier: &DateTime) -> Result<Duration, Duration> {
+        let nanos = self.nanos_since(earlier);
+        let length = nanos.unsigned_abs();
+        let duration = Duration::new(
+            (length / 1_000_000_000) as u64,
+            (length % 1_000_000_000) as u32,
+        );
+
+        if nanos >= 0 {
+            Ok(duration)
+        } else {
+            Err(duration)
+        }
+    }
+
+    /// returns the whole seconds from this DateTime until `other`, which
+    /// is negative if `other` is earlier, rounded towards zero and
+    /// saturating at the limits of an i64
+    pub fn seconds_until(&self, other: &DateTime) -> i64 {
+        let secs = other.nanos_since(self) / 1_000_000_000;
+        i64::try_from(secs).unwrap_or(if secs < 0 { i64::MIN } else { i64::MAX })
+    }
+
+    /// returns the whole 24-hour days from this DateTime until `other`,
+    /// which is negative if `other` is earlier, rounded towards zero
+    /// ```
+    /// # use datetime::DateTime;
+    /// let now = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+    /// let deadline = DateTime::parse_rfc3339("1996-09-12T09:00:00Z").unwrap();
+    ///
+    /// assert_eq!(now.days_until(&deadline), 2);
+    /// assert_eq!(deadline.days_until(&now), -2);
+    /// ```
+    pub fn days_until(&self, other: &DateTime) -> i64 {
+        (other.nanos_since(self) / (86_400 * 1_000_000_000)) as i64
+    }
+
+    /// returns true if this DateTime is an earlier instant than `other`
+    pub fn is_before(&self, other: &DateTime) -> bool {
+        self.nanos_since(other) < 0
+    }
+
+    /// returns true if this DateTime is a later instant than `other`
+    pub fn is_after(&self, other: &DateTime) -> bool {
+        self.nanos_since(other) > 0
+    }
+}
+
+impl Add<Span> for DateTime {
+    type Output = DateTime;
+
+    /// returns the DateTime the given Span later
+    ///
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent;
+    /// see [`DateTime::checked_add_span`]
+    fn add(self, span: Span) -> DateTime {
+        self.checked_add_span(span)
+            .expect("overflow when adding a Span to a DateTime")
+    }
+}
+
+impl AddAssign<Span> for DateTime {
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent
+    fn add_assign(&mut self, span: Span) {
+        *self = self
+            .checked_add_span(span)
+            .expect("overflow when adding a Span to a DateTime");
+    }
+}
+
+impl Sub<Span> for DateTime {
+    type Output = DateTime;
+
+    /// returns the DateTime the given Span earlier
+    ///
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent;
+    /// see [`DateTime::checked_sub_span`]
+    fn sub(self, span: Span) -> DateTime {
+        self.checked_sub_span(span)
+            .expect("overflow when subtracting a Span from a DateTime")
+    }
+}
+
+impl SubAssign<Span> for DateTime {
+    /// # Panics
+    ///
+    /// panics if the result is outside the range a DateTime can represent
+    fn sub_assign(&mut self, span: Span) {
+        *self = self
+            .checked_sub_span(span)
+            .expect("overflow when subtracting a Span from a DateTime");
+    }
+}
+
+impl Sub<&DateTime> for &DateTime {
+    type Output = Span;
+
+    /// returns the Span from `other` to `self`
+    ///
+    /// # Panics
+    ///
+    /// panics if the result is too long for a Span; see
+    /// [`DateTime::checked_span_since`]
+    fn sub(self, other: &DateTime) -> Span {
+        self.checked_span_since(other)
+            .expect("overflow when subtracting DateTimes")
+    }
+}
+
+impl Sub<&DateTime> for DateTime {
+    type Output = Span;
+
+    /// returns the Span from `other` to `self`
+    ///
+    /// # Panics
+    ///
+    /// panics if the result is too long for a Span; see
+    /// [`DateTime::checked_span_since`]
+    fn sub(self, other: &DateTime) -> Span {
+        self.checked_span_since(other)
+            .expect("overflow when subtracting DateTimes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Span;
+    use crate::{DateTime, FixedOffset, MAX_SECS, MIN_SECS};
+    use core::convert::TryFrom;
+    use std::time::Duration;
+
+    #[test]
+    fn test_span() {
+        let span = Span::from_millis(-1500);
+        assert!(span.is_negative());
+        assert_eq!(-span, Span::from_millis(1500));
+        assert_eq!(span.unsigned_abs(), Duration::from_millis(1500));
+        assert_eq!(span + Span::from_secs(2), Span::from_millis(500));
+        assert_eq!(span - span, Span::ZERO);
+        assert!(Span::from_millis(-1) < Span::ZERO);
+
+        assert!(Span::from_secs(i64::MIN).checked_neg().is_none());
+        assert!(Span::from_secs(i64::MAX)
+            .checked_add(Span::from_millis(1000))
+            .is_none());
+        assert!(Span::new(0, 1_000_000_000).is_none());
+
+        assert_eq!(
+            Duration::try_from(Span::from_millis(2500)),
+            Ok(Duration::from_millis(2500))
+        );
+        assert!(Duration::try_from(Span::from_millis(-1)).is_err());
+        assert_eq!(
+            Span::try_from(Duration::from_millis(1)),
+            Ok(Span::from_millis(1))
+        );
+        assert!(Span::try_from(Duration::from_secs(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_directional_helpers() {
+        let earlier = DateTime::from_millis(-1500);
+        let later = DateTime::from_millis(86_400_000 * 3 - 1);
+
+        assert_eq!(
+            later.duration_since(&earlier),
+            Ok(Duration::from_millis(86_400_000 * 3 + 1499))
+        );
+        assert_eq!(
+            earlier.duration_since(&later),
+            Err(Duration::from_millis(86_400_000 * 3 + 1499))
+        );
+        assert_eq!(earlier.duration_since(&earlier), Ok(Duration::ZERO));
+        assert_eq!(earlier.seconds_until(&later), 259_201);
+        assert_eq!(later.seconds_until(&earlier), -259_201);
+        assert_eq!(earlier.days_until(&later), 3);
+        assert_eq!(later.days_until(&DateTime::from_secs(1)), -2);
+
+        // instants compare the same whatever their offsets
+        let shifted = later.with_offset(FixedOffset::west(3600).unwrap());
+        assert!(earlier.is_before(&shifted) && shifted.is_after(&earlier));
+        assert!(!later.is_before(&shifted) && !later.is_after(&shifted));
+
+        let (first, last) = (
+            DateTime::from_signed_secs(MIN_SECS),
+            DateTime::from_signed_secs(MAX_SECS),
+        );
+        assert_eq!(first.seconds_until(&last), MAX_SECS - MIN_SECS);
+        assert_eq!(last.seconds_until(&first), MIN_SECS - MAX_SECS);
+        assert!(last.duration_since(&first).is_ok());
+    }
+
+    #[test]
+    fn test_datetime_arithmetic() {
+        let offset = FixedOffset::east(3600).unwrap();
+        let mut date = DateTime::from_millis(1500).with_offset(offset);
+
+        date += Span::from_millis(-2700);
+        assert_eq!((date.secs, date.millisecond()), (-2, 800));
+        assert_eq!(date.offset(), offset);
+        date -= Span::from_millis(-200);
+        assert_eq!(date, DateTime::from_signed_secs(-1));
+
+        let later = DateTime::from_millis(1500);
+        assert_eq!(&later - &date, Span::from_millis(2500));
+        assert_eq!(
+            date.checked_span_since(&later),
+            Some(Span::from_millis(-2500))
+        );
+        assert_eq!(DateTime::from_millis(1500) - &date, Span::from_millis(2500));
+
+        let latest = DateTime::from_secs(0).checked_add_span(Span::from_secs(MAX_SECS));
+        assert_eq!(latest.unwrap().secs, MAX_SECS);
+        assert!(DateTime::from_secs(1)
+            .checked_add_span(Span::from_secs(MAX_SECS))
+            .is_none());
+        assert!(DateTime::from_secs(0)
+            .checked_sub_span(Span::from_secs(i64::MIN))
+            .is_none());
+
+        let earliest = DateTime::from_signed_secs(MIN_SECS);
+        let latest = DateTime::from_signed_secs(MAX_SECS);
+        assert_eq!(
+            latest.checked_span_since(&earliest),
+            Some(Span::from_secs(MAX_SECS - MIN_SECS))
+        );
+        let span = DateTime::from_secs(0) - &earliest;
+        assert_eq!(earliest + span, DateTime::from_secs(0));
+    }
+}
@@ -0,0 +1,177 @@
+//! binding and reading DateTimes with `sqlx`
+//!
+//! - with the `sqlx-postgres` feature, DateTimes are `TIMESTAMPTZ` values.
+//!   `TIMESTAMP` columns can be read too, and are taken to be in UTC.
+//!   Postgres stores microseconds, so any finer fraction is truncated
+//! - with the `sqlx-sqlite` feature, DateTimes are `INTEGER` Unix seconds,
+//!   and any fraction of a second is dropped
+//!
+//! the `sqlx` feature enables both. values a DateTime can't hold, such as
+//! Postgres's `infinity`, fail to decode with [`RangeError`]
+use crate::{AbsoluteTime, DateTime, FixedOffset, RangeError};
+use ::sqlx::encode::IsNull;
+use ::sqlx::error::BoxDynError;
+use ::sqlx::{Database, Decode, Encode, Type};
+
+#[cfg(feature = "sqlx-postgres")]
+mod postgres {
+    use super::*;
+    use ::sqlx::postgres::{PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+    // 2000-01-01, the epoch Postgres counts timestamps from
+    const POSTGRES_EPOCH: i64 = 946_684_800;
+
+    // the microseconds since the Postgres epoch, or None if that doesn't
+    // fit in an i64
+    pub(super) fn to_micros(date: &DateTime) -> Option<i64> {
+        (date.secs - POSTGRES_EPOCH)
+            .checked_mul(1_000_000)?
+            .checked_add((date.nanos / 1000) as i64)
+    }
+
+    // the DateTime the given microseconds since the Postgres epoch are,
+    // or None for `infinity` and `-infinity` or if it's out of range
+    pub(super) fn from_micros(micros: i64) -> Option<DateTime> {
+        if micros == i64::MAX || micros == i64::MIN {
+            return None;
+        }
+
+        let secs = micros.div_euclid(1_000_000) + POSTGRES_EPOCH;
+        let nanos = micros.rem_euclid(1_000_000) as u32 * 1000;
+
+        AbsoluteTime::new(secs, nanos).map(|time| time.to_datetime(FixedOffset::utc()))
+    }
+
+    // parses a timestamp in Postgres's text format, e.g.
+    // `1996-09-09 15:23:44.5+00`, where the offset may leave out its minutes
+    // or, for TIMESTAMP columns, be missing altogether
+    pub(super) fn from_text(text: &str) -> Result<DateTime, BoxDynError> {
+        let bytes = text.as_bytes();
+        let has_offset = text.get(10..).is_some_and(|time| time.contains(['+', '-']));
+        let short_offset = bytes.len() >= 3 && matches!(bytes[bytes.len() - 3], b'+' | b'-');
+
+        let date = if !has_offset {
+            DateTime::parse_rfc3339(&format!("{}Z", text))?
+        } else if short_offset {
+            DateTime::parse_rfc3339(&format!("{}:00", text))?
+        } else {
+            DateTime::parse_rfc3339(text)?
+        };
+
+        Ok(date)
+    }
+
+    impl Type<Postgres> for DateTime {
+        fn type_info() -> PgTypeInfo {
+            PgTypeInfo::with_name("TIMESTAMPTZ")
+        }
+
+        fn compatible(ty: &PgTypeInfo) -> bool {
+            *ty == PgTypeInfo::with_name("TIMESTAMPTZ") || *ty == PgTypeInfo::with_name("TIMESTAMP")
+        }
+    }
+
+    impl PgHasArrayType for DateTime {
+        fn array_type_info() -> PgTypeInfo {
+            PgTypeInfo::with_name("_TIMESTAMPTZ")
+        }
+    }
+
+    impl<'q> Encode<'q, Postgres> for DateTime {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Postgres as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            let micros = to_micros(self).ok_or(RangeError)?;
+            Encode::<Postgres>::encode(micros, buf)
+        }
+
+        fn size_hint(&self) -> usize {
+            8
+        }
+    }
+
+    impl<'r> Decode<'r, Postgres> for DateTime {
+        fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+            match value.format() {
+                PgValueFormat::Binary => {
+                    let micros = <i64 as Decode<Postgres>>::decode(value)?;
+                    Ok(from_micros(micros).ok_or(RangeError)?)
+                }
+                PgValueFormat::Text => from_text(value.as_str()?),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+mod sqlite {
+    use super::*;
+    use ::sqlx::sqlite::{Sqlite, SqliteTypeInfo, SqliteValueRef};
+
+    impl Type<Sqlite> for DateTime {
+        fn type_info() -> SqliteTypeInfo {
+            <i64 as Type<Sqlite>>::type_info()
+        }
+
+        fn compatible(ty: &SqliteTypeInfo) -> bool {
+            <i64 as Type<Sqlite>>::compatible(ty)
+        }
+    }
+
+    impl<'q> Encode<'q, Sqlite> for DateTime {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Sqlite as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            Encode::<Sqlite>::encode(self.secs, buf)
+        }
+    }
+
+    impl<'r> Decode<'r, Sqlite> for DateTime {
+        fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+            let secs = <i64 as Decode<Sqlite>>::decode(value)?;
+            let time = AbsoluteTime::new(secs, 0).ok_or(RangeError)?;
+
+            Ok(time.to_datetime(FixedOffset::utc()))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sqlx-postgres"))]
+mod tests {
+    use super::postgres::{from_micros, from_text, to_micros};
+    use crate::DateTime;
+
+    #[test]
+    fn test_postgres_micros() {
+        assert_eq!(to_micros(&DateTime::from_secs(946_684_800)), Some(0));
+
+        let date = DateTime::from_nanos(842282624123456789);
+        let micros = to_micros(&date).unwrap();
+        assert_eq!(micros, -104_402_175_876_544);
+
+        let back = from_micros(micros).unwrap();
+        assert_eq!((back.secs, back.nanos), (842282624, 123_456_000));
+
+        // infinity and -infinity
+        assert!(from_micros(i64::MAX).is_none());
+        assert!(from_micros(i64::MIN).is_none());
+        assert!(from_micros(i64::MAX - 1).is_some());
+        assert!(to_micros(&DateTime::MAX).is_none());
+    }
+
+    #[test]
+    fn test_postgres_text() {
+        let date = from_text("1996-09-09 17:23:44.5+02").unwrap();
+        assert_eq!((date.secs, date.nanos), (842282624, 500_000_000));
+
+        let date = from_text("1996-09-09 10:53:44-04:30").unwrap();
+        assert_eq!(date.secs, 842282624);
+
+        let date = from_text("1969-12-31 23:59:59").unwrap();
+        assert_eq!(date.secs, -1);
+
+        assert!(from_text("infinity").is_err());
+    }
+}
@@ -0,0 +1,310 @@
+//! the layout of [`DateTime::as_time_stamp`]
+//!
+//! time stamps look like `Mon Sep 9, 1996  15:23:44 (UTC)`. the pieces
+//! that callers most often need to change (the gap between the date and
+//! the time, and the digits of fractional seconds) are set with
+//! [`StampOptions`] and [`DateTime::as_time_stamp_with`]
+//! ```
+//! # use datetime::{DateTime, StampOptions};
+//! let date = DateTime::from_millis(842282624123);
+//! let options = StampOptions::new().separator(" ").fraction_digits(3);
+//!
+//! assert_eq!(
+//!     date.as_time_stamp_with(&options),
+//!     "Mon Sep 9, 1996 15:23:44.123 (UTC)"
+//! );
+//! ```
+//!
+//! the hour can be zero-padded or on a 12-hour clock, and the weekday and
+//! the zone left out, to match the stamps of other log formats
+//! ```
+//! # use datetime::{DateTime, StampOptions};
+//! let date = DateTime::from_secs(842261043);
+//! let options = StampOptions::new()
+//!     .separator(" ")
+//!     .pad_hour(true)
+//!     .twelve_hour(true)
+//!     .show_weekday(false)
+//!     .show_zone(false);
+//!
+//! assert_eq!(date.as_time_stamp_with(&options), "Sep 9, 1996 09:24:03 AM");
+//! ```
+use crate::sink::FmtSink;
+use crate::{DateTime, Locale, TimestampSink};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::fmt;
+
+/// options for laying out a time stamp
+///
+/// the defaults give the same stamp as [`DateTime::as_time_stamp`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StampOptions {
+    // borrowed when set from a literal, so the defaults don't allocate
+    separator: Cow<'static, str>,
+    digits: usize,
+    locale: Locale,
+    pad_hour: bool,
+    twelve_hour: bool,
+    weekday: bool,
+    zone: bool,
+}
+
+impl StampOptions {
+    /// returns the default options: the weekday, two spaces between the
+    /// date and the time, an unpadded 24-hour clock, no fractional seconds
+    /// and the zone
+    pub fn new() -> Self {
+        StampOptions {
+            separator: Cow::Borrowed("  "),
+            digits: 0,
+            locale: Locale::En,
+            pad_hour: false,
+            twelve_hour: false,
+            weekday: true,
+            zone: true,
+        }
+    }
+
+    /// sets the text written between the date and the time, either a
+    /// `&'static str` or an owned String
+    pub fn separator(mut self, separator: impl Into<Cow<'static, str>>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// sets the number of digits (at most 9) of fractional seconds
+    pub fn fraction_digits(mut self, digits: usize) -> Self {
+        self.digits = digits.min(9);
+        self
+    }
+
+    /// sets whether the hour is padded to two digits with a zero, as in
+    /// `09:05:03`
+    pub fn pad_hour(mut self, pad: bool) -> Self {
+        self.pad_hour = pad;
+        self
+    }
+
+    /// sets whether the time is on a 12-hour clock, followed by `AM` or
+    /// `PM`
+    pub fn twelve_hour(mut self, twelve_hour: bool) -> Self {
+        self.twelve_hour = twelve_hour;
+        self
+    }
+
+    /// sets whether the stamp starts with the abbreviated weekday
+    pub fn show_weekday(mut self, show: bool) -> Self {
+        self.weekday = show;
+        self
+    }
+
+    /// sets whether the stamp ends with the zone, such as `(UTC)`
+    pub fn show_zone(mut self, show: bool) -> Self {
+        self.zone = show;
+        self
+    }
+
+    /// sets the language of the day and month abbreviations
+    /// ```
+    /// # use datetime::{DateTime, Locale, StampOptions};
+    /// let date = DateTime::from_secs(842282624);
+    /// let options = StampOptions::new().locale(Locale::De);
+    ///
+    /// assert_eq!(date.as_time_stamp_with(&options), "Mo Sep 9, 1996  15:23:44 (UTC)");
+    /// ```
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+impl Default for StampOptions {
+    fn default() -> Self {
+        StampOptions::new()
+    }
+}
+
+impl DateTime {
+    /// returns a String representing the time stamp of a DateTime, laid
+    /// out according to `options`
+    /// ```
+    /// # use datetime::{DateTime, StampOptions};
+    /// let date = DateTime::from_secs(842282624);
+    /// let options = StampOptions::new().separator(" at ");
+    ///
+    /// assert_eq!(
+    ///     date.as_time_stamp_with(&options),
+    ///     "Mon Sep 9, 1996 at 15:23:44 (UTC)"
+    /// );
+    /// ```
+    pub fn as_time_stamp_with(&self, options: &StampOptions) -> String {
+        let mut stamp = String::new();
+        self.write_time_stamp_with(&mut stamp, options)
+            .expect("writing to a String cannot fail");
+
+        stamp
+    }
+
+    /// writes the time stamp returned by [`DateTime::as_time_stamp_with`]
+    /// into `w`, without allocating
+    /// ```
+    /// # use datetime::{DateTime, StampOptions};
+    /// # use std::fmt::Write;
+    /// let mut line = String::from("[");
+    /// let options = StampOptions::new().fraction_digits(3);
+    /// DateTime::from_millis(842282624123).write_time_stamp_with(&mut line, &options).unwrap();
+    ///
+    /// assert_eq!(line, "[Mon Sep 9, 1996  15:23:44.123 (UTC)");
+    /// ```
+    pub fn write_time_stamp_with<W: fmt::Write>(
+        &self,
+        w: &mut W,
+        options: &StampOptions,
+    ) -> fmt::Result {
+        self.write_stamp(&mut FmtSink(w), None, options)
+    }
+
+    /// writes the time stamp returned by [`DateTime::as_time_stamp_with`]
+    /// into a [`TimestampSink`]
+    pub fn write_time_stamp_to<S: TimestampSink + ?Sized>(
+        &self,
+        w: &mut S,
+        options: &StampOptions,
+    ) -> fmt::Result {
+        self.write_stamp(w, None, options)
+    }
+
+    // like as_time_stamp_with, but with `zone` as the time zone name
+    #[cfg(feature = "tz")]
+    pub(crate) fn time_stamp_in_zone(&self, zone: &str, options: &StampOptions) -> String {
+        let mut stamp = String::new();
+        self.write_stamp(&mut stamp, Some(zone), options)
+            .expect("writing to a String cannot fail");
+
+        stamp
+    }
+
+    // writes the time stamp, with `zone` as the time zone name, or the
+    // DateTime's own zone name (as given by zone_name) if it is None
+    pub(crate) fn write_stamp<S: TimestampSink + ?Sized>(
+        &self,
+        w: &mut S,
+        zone: Option<&str>,
+        options: &StampOptions,
+    ) -> fmt::Result {
+        let fields = self.fields();
+        if options.weekday {
+            w.write_str(options.locale.day_abbreviation(fields.day))?;
+            w.write_str(" ")?;
+        }
+        w.write_str(options.locale.month_abbreviation(fields.month))?;
+        w.write_str(" ")?;
+        w.write_u32_padded(fields.date as u32, 0)?;
+        w.write_str(", ")?;
+        w.write_u32_padded(fields.year as u32, 0)?;
+        w.write_str(&options.separator)?;
+        let hour = match fields.hour {
+            hour if !options.twelve_hour => hour,
+            0 => 12,
+            hour if hour > 12 => hour - 12,
+            hour => hour,
+        };
+        w.write_u32_padded(hour as u32, if options.pad_hour { 2 } else { 0 })?;
+        w.write_str(":")?;
+        w.write_u32_padded(fields.minute as u32, 2)?;
+        w.write_str(":")?;
+        w.write_u32_padded(fields.second as u32, 2)?;
+
+        let digits = options.digits;
+        if digits > 0 {
+            w.write_str(".")?;
+            w.write_u32_padded(self.nanos / 10u32.pow(9 - digits as u32), digits)?;
+        }
+        if options.twelve_hour {
+            w.write_str(if fields.hour < 12 { " AM" } else { " PM" })?;
+        }
+
+        if !options.zone {
+            return Ok(());
+        }
+        match zone {
+            Some(zone) => {
+                w.write_str(" (")?;
+                w.write_str(zone)?;
+            }
+            None => {
+                w.write_str(" (UTC")?;
+                if !self.offset.is_utc() {
+                    self.offset.write(w, true)?;
+                }
+            }
+        }
+        w.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StampOptions;
+    use crate::{DateTime, FixedOffset};
+
+    #[test]
+    fn test_default_options() {
+        let date = DateTime::from_nanos(842282624987654321);
+
+        assert_eq!(StampOptions::default(), StampOptions::new());
+        assert_eq!(
+            date.as_time_stamp_with(&StampOptions::new()),
+            date.as_time_stamp()
+        );
+        assert_eq!(
+            date.as_time_stamp_with(&StampOptions::new().fraction_digits(12)),
+            date.as_time_stamp_with_fraction(9)
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        let midnight = DateTime::from_millis(842227200250);
+        let options = StampOptions::new().twelve_hour(true).fraction_digits(1);
+        assert_eq!(
+            midnight.as_time_stamp_with(&options),
+            "Mon Sep 9, 1996  12:00:00.2 AM (UTC)"
+        );
+
+        let evening = DateTime::from_secs(842282624).with_offset(FixedOffset::east(3600).unwrap());
+        let options = options.pad_hour(true).show_weekday(false);
+        assert_eq!(
+            evening.as_time_stamp_with(&options),
+            "Sep 9, 1996  04:23:44.0 PM (UTC+01:00)"
+        );
+        assert_eq!(
+            evening.as_time_stamp_with(&StampOptions::new().pad_hour(true).show_zone(false)),
+            "Mon Sep 9, 1996  16:23:44"
+        );
+    }
+
+    #[test]
+    fn test_separator() {
+        let date = DateTime::from_secs(842282624).with_offset(FixedOffset::east(3600).unwrap());
+        let owned = StampOptions::new().separator(alloc::format!(" {} ", "at"));
+        assert_eq!(
+            date.as_time_stamp_with(&owned),
+            "Mon Sep 9, 1996 at 16:23:44 (UTC+01:00)"
+        );
+
+        let options = StampOptions::new().separator("T").fraction_digits(2);
+
+        assert_eq!(
+            date.as_time_stamp_with(&options),
+            "Mon Sep 9, 1996T16:23:44.00 (UTC+01:00)"
+        );
+        assert_eq!(
+            date.as_time_stamp_with(&StampOptions::new().separator(""))
+                .split_whitespace()
+                .count(),
+            5
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! systemd calendar events, as used by `OnCalendar=` in timer units
+//!
+//! a [`CalendarEvent`] is parsed from up to four parts, separated by
+//! spaces: the days of the week, such as `Mon..Fri` or `Sat,Sun`; the date
+//! as `year-month-day` or `month-day`; the time as `hour:minute` or
+//! `hour:minute:second`; and `UTC`. a part that is left out matches any
+//! value, except the time, which defaults to midnight
+//!
+//! each number can be `*`, a value, a range such as `9..17`, any of those
+//! with a step such as `0/15`, or a comma-separated list of them. a `~`
+//! in place of the `-` before the day counts days from the end of the
+//! month, so `*-*~1` is the last day of every month. the shorthands
+//! `minutely`, `hourly`, `daily`, `weekly`, `monthly`, `quarterly`,
+//! `semiannually`, `yearly` and `annually` are accepted too
+//! ```
+//! # use datetime::systemd::CalendarEvent;
+//! # use datetime::DateTime;
+//! let event: CalendarEvent = "Mon..Fri *-*-* 10:00".parse().unwrap();
+//!
+//! // Friday 1996-09-13 at 15:23
+//! let friday = DateTime::parse_rfc3339("1996-09-13T15:23:44Z").unwrap();
+//! let next = event.next_elapse_after(&friday).unwrap();
+//! assert_eq!(next.to_rfc3339(), "1996-09-16T10:00:00Z");
+//! ```
+//!
+//! as in systemd, years from 1970 to 2199 can be matched. times are
+//! matched in the offset of the DateTime they are found after, unless
+//! the event ends in `UTC`
+use crate::convert::{civil_from_days, days_in_month, weekday_from_days};
+use crate::{DateTime, ParseError};
+use core::str::FromStr;
+
+/// a parsed calendar event; see the [module docs](self)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CalendarEvent {
+    // a bit for each allowed value of each field; years count from 1970
+    years: [u64; 4],
+    months: u16,
+    dates: u32,
+    // indexed by `Day as usize`
+    days: u8,
+    hours: u32,
+    minutes: u64,
+    seconds: u64,
+    // whether `dates` counts from the end of the month, with 1 the last day
+    from_end: bool,
+    utc: bool,
+}
+
+const FIRST_YEAR: u32 = 1970;
+const LAST_YEAR: u32 = 2199;
+
+impl CalendarEvent {
+    /// returns the first time after `date` that the event elapses, in
+    /// `date`'s offset, or None if it never does (such as on 30 February)
+    /// or only after 2199
+    /// ```
+    /// # use datetime::systemd::CalendarEvent;
+    /// # use datetime::{DateTime, FixedOffset};
+    /// let event: CalendarEvent = "*-*~1 23:30 UTC".parse().unwrap();
+    /// let date = DateTime::parse_rfc3339("1996-02-10T12:00:00Z").unwrap();
+    /// let date = date.with_offset(FixedOffset::east(7200).unwrap());
+    ///
+    /// let next = event.next_elapse_after(&date).unwrap();
+    /// assert_eq!(next.to_rfc3339(), "1996-03-01T01:30:00+02:00");
+    /// ```
+    pub fn next_elapse_after(&self, date: &DateTime) -> Option<DateTime> {
+        let offset = if self.utc {
+            0
+        } else {
+            date.offset.seconds() as i64
+        };
+        // whole seconds, so the first one after `date` is the next
+        let next = date.secs + offset + 1;
+        let (first_day, first_second) = if next < 0 {
+            (0, 0)
+        } else {
+            (next / 86_400, (next % 86_400) as u32)
+        };
+
+        for day in first_day.. {
+            let (year, month, dom) = civil_from_days(day);
+            if year > LAST_YEAR as i64 {
+                return None;
+            }
+            if !self.matches_day(day, year as u32, month, dom) {
+                continue;
+            }
+
+            let from = if day == first_day { first_second } else { 0 };
+            if let Some(second) = self.first_second(from) {
+                let secs = day * 86_400 + second as i64 - offset;
+                return Some(DateTime::from_parts(secs, 0, date.offset));
+            }
+        }
+
+        None
+    }
+
+    // whether a local day, in days since the epoch, matches the date fields
+    fn matches_day(&self, day: i64, year: u32, month: usize, date: usize) -> bool {
+        let year = (year - FIRST_YEAR) as usize;
+        if self.years[year / 64] & 1 << (year % 64) == 0 || self.months & 1 << month == 0 {
+            return false;
+        }
+
+        let date = if self.from_end {
+            days_in_month(year + FIRST_YEAR as usize, month) - date + 1
+        } else {
+            date
+        };
+        self.dates & 1 << date != 0 && self.days & 1 << weekday_from_days(day) as usize != 0
+    }
+
+    // the first matching second of the day at or after `from`
+    fn first_second(&self, from: u32) -> Option<u32> {
+        let (hour, minute, second) = (from / 3600, from / 60 % 60, from % 60);
+        (hour..24)
+            .filter(|&h| self.hours & 1 << h != 0)
+            .find_map(|h| {
+                let start = if h == hour { minute } else { 0 };
+                (start..60)
+                    .filter(|&m| self.minutes & 1 << m != 0)
+                    .find_map(|m| {
+                        let start = if h == hour && m == minute { second } else { 0 };
+                        match self.seconds >> start << start {
+                            0 => None,
+                            seconds => Some(h * 3600 + m * 60 + seconds.trailing_zeros()),
+                        }
+                    })
+            })
+    }
+}
+
+const DAY_NAMES: [(&str, &str); 7] = [
+    ("sun", "sunday"),
+    ("mon", "monday"),
+    ("tue", "tuesday"),
+    ("wed", "wednesday"),
+    ("thu", "thursday"),
+    ("fri", "friday"),
+    ("sat", "saturday"),
+];
+
+impl FromStr for CalendarEvent {
+    type Err = ParseError;
+
+    /// parses a calendar event, or one of the shorthands
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = match input.trim() {
+            "minutely" => "*-*-* *:*:00",
+            "hourly" => "*-*-* *:00:00",
+            "daily" => "*-*-* 00:00:00",
+            "weekly" => "Mon *-*-* 00:00:00",
+            "monthly" => "*-*-01 00:00:00",
+            "quarterly" => "*-01,04,07,10-01 00:00:00",
+            "semiannually" => "*-01,07-01 00:00:00",
+            "yearly" | "annually" => "*-01-01 00:00:00",
+            _ => input,
+        };
+
+        let mut event = CalendarEvent {
+            years: [!0; 4],
+            months: !0,
+            dates: !0,
+            days: !0,
+            hours: 1,
+            minutes: 1,
+            seconds: 1,
+            from_end: false,
+            utc: false,
+        };
+
+        // the parts must come in order: days, date, time, zone
+        let mut stage = 0;
+        let mut start = None;
+        for (i, c) in input.char_indices().chain(Some((input.len(), ' '))) {
+            let from = match (c.is_ascii_whitespace(), start) {
+                (false, None) => {
+                    start = Some(i);
+                    continue;
+                }
+                (true, Some(from)) => from,
+                _ => continue,
+            };
+            start = None;
+
+            let part = &input[from..i];
+            let kind = if part.eq_ignore_ascii_case("utc") {
+                3
+            } else if part.contains(':') {
+                2
+            } else if part.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                0
+            } else {
+                1
+            };
+            if kind < stage {
+                return Err(ParseError::Invalid(from));
+            }
+            stage = kind + 1;
+
+            match kind {
+                0 => {
+                    let mut days = 0;
+                    field(part, from, 0, 6, true, &mut |n| days |= 1 << n)?;
+                    event.days = days;
+                }
+                1 => event.parse_date(part, from)?,
+                2 => event.parse_time(part, from)?,
+                _ => event.utc = true,
+            }
+        }
+        if stage == 0 {
+            return Err(ParseError::TooShort);
+        }
+
+        Ok(event)
+    }
+}
+
+impl CalendarEvent {
+    // parses `year-month-day` or `month-day`, with `~` before a day
+    // counted from the end of the month
+    fn parse_date(&mut self, part: &str, pos: usize) -> Result<(), ParseError> {
+        let mut pieces = [""; 3];
+        let mut starts = [0; 3];
+        let mut count = 0;
+        let mut from = 0;
+        for (i, b) in part.bytes().enumerate().chain(Some((part.len(), b'-'))) {
+            if b == b'-' || b == b'~' {
+                if count == 2 && i < part.len() {
+                    return Err(ParseError::Invalid(pos + i));
+                }
+                pieces[count] = &part[from..i];
+                starts[count] = pos + from;
+                count += 1;
+                from = i + 1;
+                self.from_end |= b == b'~' && i < part.len();
+            }
+        }
+        if count < 2 {
+            return Err(ParseError::TooShort);
+        }
+
+        let first = count - 2;
+        if count == 3 {
+            let mut years = [0; 4];
+            let min = FIRST_YEAR;
+            field(pieces[0], starts[0], min, LAST_YEAR, false, &mut |n| {
+                let n = n - min;
+                years[n as usize / 64] |= 1 << (n % 64);
+            })?;
+            self.years = years;
+        }
+
+        let mut months = 0;
+        field(pieces[first], starts[first], 1, 12, false, &mut |n| {
+            months |= 1 << n
+        })?;
+        let mut dates = 0;
+        let (text, pos) = (pieces[first + 1], starts[first + 1]);
+        field(text, pos, 1, 31, false, &mut |n| dates |= 1 << n)?;
+        self.months = months;
+        self.dates = dates;
+        Ok(())
+    }
+
+    // parses `hour:minute` or `hour:minute:second`
+    fn parse_time(&mut self, part: &str, pos: usize) -> Result<(), ParseError> {
+        let mut fields = [0; 3];
+        let mut count = 0;
+        let mut from = 0;
+        for (i, b) in part.bytes().enumerate().chain(Some((part.len(), b':'))) {
+            if b == b':' {
+                if count == 2 && i < part.len() {
+                    return Err(ParseError::Invalid(pos + i));
+                }
+                let max = if count == 0 { 23 } else { 59 };
+                let bits = &mut fields[count];
+                field(&part[from..i], pos + from, 0, max, false, &mut |n| {
+                    *bits |= 1 << n
+                })?;
+                count += 1;
+                from = i + 1;
+            }
+        }
+        if count < 2 {
+            return Err(ParseError::TooShort);
+        }
+
+        self.hours = fields[0] as u32;
+        self.minutes = fields[1];
+        self.seconds = if count == 3 { fields[2] } else { 1 };
+        Ok(())
+    }
+}
+
+// calls `set` with each of a field's allowed values
+fn field(
+    text: &str,
+    pos: usize,
+    min: u32,
+    max: u32,
+    names: bool,
+    set: &mut dyn FnMut(u32),
+) -> Result<(), ParseError> {
+    let mut item_pos = pos;
+    for item in text.split(',') {
+        let (range, step) = match item.find('/') {
+            Some(i) => {
+                let step = value(&item[i + 1..], item_pos + i + 1, 1, max, false)?;
+                (&item[..i], Some(step))
+            }
+            None => (item, None),
+        };
+
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some(i) = range.find("..") {
+            let from = value(&range[..i], item_pos, min, max, names)?;
+            let to = value(&range[i + 2..], item_pos + i + 2, min, max, names)?;
+            if from > to {
+                return Err(ParseError::OutOfRange);
+            }
+            (from, to)
+        } else {
+            let from = value(range, item_pos, min, max, names)?;
+            // a single value with a step runs to the end of the field
+            (from, if step.is_some() { max } else { from })
+        };
+
+        for n in (from..=to).step_by(step.unwrap_or(1) as usize) {
+            set(n);
+        }
+        item_pos += item.len() + 1;
+    }
+
+    Ok(())
+}
+
+// a number from `min` to `max`, or the name of a day of the week
+fn value(text: &str, pos: usize, min: u32, max: u32, names: bool) -> Result<u32, ParseError> {
+    if names {
+        return DAY_NAMES
+            .iter()
+            .position(|(short, long)| {
+                short.eq_ignore_ascii_case(text) || long.eq_ignore_ascii_case(text)
+            })
+            .map(|i| i as u32)
+            .ok_or(ParseError::Invalid(pos));
+    }
+
+    match text.bytes().position(|b| !b.is_ascii_digit()) {
+        Some(i) => Err(ParseError::Invalid(pos + i)),
+        None if text.is_empty() => Err(ParseError::TooShort),
+        None => match text.parse() {
+            Ok(n) if (min..=max).contains(&n) => Ok(n),
+            _ => Err(ParseError::OutOfRange),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CalendarEvent;
+    use crate::{DateTime, FixedOffset, ParseError};
+    use alloc::string::String;
+
+    fn next(event: &str, after: &str) -> Option<String> {
+        let event: CalendarEvent = event.parse().unwrap();
+        let date = DateTime::parse_rfc3339(after).unwrap();
+        event.next_elapse_after(&date).map(|date| date.to_rfc3339())
+    }
+
+    #[test]
+    fn test_next_elapse_after() {
+        let at = "1996-09-09T15:23:44Z";
+        assert_eq!(next("*-*-* *:*:*", at).unwrap(), "1996-09-09T15:23:45Z");
+        assert_eq!(next("minutely", at).unwrap(), "1996-09-09T15:24:00Z");
+        assert_eq!(next("daily", at).unwrap(), "1996-09-10T00:00:00Z");
+        assert_eq!(next("weekly", at).unwrap(), "1996-09-16T00:00:00Z");
+        assert_eq!(next("quarterly", at).unwrap(), "1996-10-01T00:00:00Z");
+        assert_eq!(next("Sat,Sun", at).unwrap(), "1996-09-14T00:00:00Z");
+        assert_eq!(next("*-*-* 15:23:44", at).unwrap(), "1996-09-10T15:23:44Z");
+        assert_eq!(next("*:0/20", at).unwrap(), "1996-09-09T15:40:00Z");
+        assert_eq!(next("12-25 08:00", at).unwrap(), "1996-12-25T08:00:00Z");
+        assert_eq!(
+            next("1999..2001-02-29", at).unwrap(),
+            "2000-02-29T00:00:00Z"
+        );
+        assert_eq!(next("*-02~1", at).unwrap(), "1997-02-28T00:00:00Z");
+
+        // Friday the 13th
+        assert_eq!(next("Fri *-*-13", at).unwrap(), "1996-09-13T00:00:00Z");
+        assert_eq!(
+            next("Fri *-*-13", "1996-09-13T00:00:00Z").unwrap(),
+            "1996-12-13T00:00:00Z"
+        );
+        assert_eq!(next("*-02-30", at), None);
+        assert_eq!(next("2199-12-31 23:59:59", "2199-12-31T23:59:59Z"), None);
+
+        // in the DateTime's offset, unless the event is in UTC
+        let offset = FixedOffset::east(5 * 3600 + 45 * 60).unwrap();
+        let date = DateTime::parse_rfc3339(at).unwrap().with_offset(offset);
+        let event: CalendarEvent = "09:00".parse().unwrap();
+        let next = event.next_elapse_after(&date).unwrap();
+        assert_eq!(next.to_rfc3339(), "1996-09-10T09:00:00+05:45");
+        let event: CalendarEvent = "09:00 UTC".parse().unwrap();
+        let next = event.next_elapse_after(&date).unwrap();
+        assert_eq!(next.to_rfc3339(), "1996-09-10T14:45:00+05:45");
+
+        // before 1970, the first match is after it starts
+        assert_eq!(next_before_epoch("Thu 12:00"), "1970-01-01T12:00:00Z");
+    }
+
+    fn next_before_epoch(event: &str) -> String {
+        let date = DateTime::from_signed_secs(-86_400 * 365);
+        let event: CalendarEvent = event.parse().unwrap();
+        event.next_elapse_after(&date).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input: &str| input.parse::<CalendarEvent>();
+
+        assert!(parse("  mon..FRI\t2024-*-01  10:00:30 utc ").is_ok());
+        assert_eq!(parse(""), Err(ParseError::TooShort));
+        assert_eq!(parse("10"), Err(ParseError::TooShort));
+        assert_eq!(parse("Funday"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("10:00 Mon"), Err(ParseError::Invalid(6)));
+        assert_eq!(parse("*-13-01"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("1969-01-01"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("24:00"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("10:00:00:00"), Err(ParseError::Invalid(8)));
+        assert_eq!(parse("*-*~1-1"), Err(ParseError::Invalid(5)));
+        assert_eq!(parse("Fri..Mon"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("*:*/0"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("10:x0"), Err(ParseError::Invalid(3)));
+    }
+}
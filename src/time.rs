@@ -0,0 +1,141 @@
+//! conversions to and from the `time` crate, enabled with the `time`
+//! feature
+//!
+//! - `time::OffsetDateTime` converts into a DateTime with `TryFrom`,
+//!   keeping its offset, and a DateTime converts into an `OffsetDateTime`
+//!   the same way. either fails with [`RangeError`] if the other side
+//!   can't hold the value
+//! - [`Day`] and [`Month`] convert to and from `time::Weekday` and
+//!   `time::Month` with `From`
+//! ```
+//! # use datetime::DateTime;
+//! # use std::convert::TryFrom;
+//! use time::OffsetDateTime;
+//!
+//! let theirs = OffsetDateTime::from_unix_timestamp(842282624).unwrap();
+//! let ours = DateTime::try_from(theirs).unwrap();
+//!
+//! assert_eq!(ours.as_time_stamp(), "Mon Sep 9, 1996  15:23:44 (UTC)");
+//! assert_eq!(OffsetDateTime::try_from(&ours).unwrap(), theirs);
+//! ```
+use crate::{AbsoluteTime, DateTime, Day, FixedOffset, Month, RangeError, MONTHS};
+use ::time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
+use core::convert::TryFrom;
+
+impl TryFrom<OffsetDateTime> for DateTime {
+    type Error = RangeError;
+
+    /// converts an OffsetDateTime, keeping its offset from UTC
+    fn try_from(date: OffsetDateTime) -> Result<Self, Self::Error> {
+        let offset = FixedOffset::east(date.offset().whole_seconds()).ok_or(RangeError)?;
+        let instant =
+            AbsoluteTime::new(date.unix_timestamp(), date.nanosecond()).ok_or(RangeError)?;
+
+        Ok(instant.to_datetime(offset))
+    }
+}
+
+impl TryFrom<&DateTime> for OffsetDateTime {
+    type Error = RangeError;
+
+    /// converts a DateTime, keeping its offset from UTC
+    fn try_from(date: &DateTime) -> Result<Self, Self::Error> {
+        // building from the local fields, rather than converting from UTC,
+        // means the result can't overflow when the offset is applied
+        let year = i32::try_from(date.year()).map_err(|_| RangeError)?;
+        let day = Date::from_calendar_date(year, date.month().into(), date.date() as u8)
+            .map_err(|_| RangeError)?;
+        let time = Time::from_hms_nano(
+            date.hour() as u8,
+            date.minute() as u8,
+            date.second() as u8,
+            date.nanos,
+        )
+        .map_err(|_| RangeError)?;
+        let offset =
+            UtcOffset::from_whole_seconds(date.offset.seconds()).map_err(|_| RangeError)?;
+
+        Ok(PrimitiveDateTime::new(day, time).assume_offset(offset))
+    }
+}
+
+impl From<Weekday> for Day {
+    fn from(day: Weekday) -> Self {
+        match day {
+            Weekday::Sunday => Day::Sunday,
+            Weekday::Monday => Day::Monday,
+            Weekday::Tuesday => Day::Tuesday,
+            Weekday::Wednesday => Day::Wednesday,
+            Weekday::Thursday => Day::Thursday,
+            Weekday::Friday => Day::Friday,
+            Weekday::Saturday => Day::Saturday,
+        }
+    }
+}
+
+impl From<Day> for Weekday {
+    fn from(day: Day) -> Self {
+        match day {
+            Day::Sunday => Weekday::Sunday,
+            Day::Monday => Weekday::Monday,
+            Day::Tuesday => Weekday::Tuesday,
+            Day::Wednesday => Weekday::Wednesday,
+            Day::Thursday => Weekday::Thursday,
+            Day::Friday => Weekday::Friday,
+            Day::Saturday => Weekday::Saturday,
+        }
+    }
+}
+
+impl From<::time::Month> for Month {
+    fn from(month: ::time::Month) -> Self {
+        MONTHS[month as usize - 1]
+    }
+}
+
+impl From<Month> for ::time::Month {
+    fn from(month: Month) -> Self {
+        ::time::Month::try_from(month as u8 + 1).expect("every Month is a time Month")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, Day, FixedOffset, Month, RangeError};
+    use ::time::{OffsetDateTime, UtcOffset, Weekday};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_datetime_round_trip() {
+        let offset = UtcOffset::from_whole_seconds(-16200).unwrap();
+        let theirs = OffsetDateTime::from_unix_timestamp_nanos(-999_999_500)
+            .unwrap()
+            .to_offset(offset);
+
+        let ours = DateTime::try_from(theirs).unwrap();
+        assert_eq!((ours.secs, ours.nanos), (-1, 500));
+        assert_eq!(ours.offset(), FixedOffset::west(16200).unwrap());
+        assert_eq!(ours.hour(), 19);
+
+        let back = OffsetDateTime::try_from(&ours).unwrap();
+        assert_eq!(back, theirs);
+        assert_eq!(back.offset(), offset);
+
+        // the time crate stops at the year 9999, or 999999 with its
+        // large-dates feature, long before a DateTime does
+        let year = ::time::Date::MAX.year() as usize + 1;
+        let far = DateTime::from_ordinal(year, 1).unwrap();
+        assert_eq!(OffsetDateTime::try_from(&far), Err(RangeError));
+    }
+
+    #[test]
+    fn test_day_and_month() {
+        assert_eq!(Day::from(Weekday::Sunday), Day::Sunday);
+        assert_eq!(Weekday::from(Day::Saturday), Weekday::Saturday);
+        assert_eq!(Month::from(::time::Month::January), Month::January);
+        assert_eq!(
+            ::time::Month::from(Month::December),
+            ::time::Month::December
+        );
+    }
+}
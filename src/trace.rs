@@ -0,0 +1,20 @@
+// spans and events recorded with the `tracing` feature, around the work
+// that can be slow: loading time zones, converting large batches, and
+// parsers falling back to other formats. without the feature these expand
+// to nothing
+
+// enters a debug-level span until the end of the enclosing block
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+// records a debug-level event
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($arg)*);
+    };
+}
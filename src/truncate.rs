@@ -0,0 +1,433 @@
+//! snapping DateTimes to the boundaries of calendar units
+//!
+//! the `start_of_*` methods round a DateTime down to the start of its
+//! minute, hour, day, week, month or year, and the `end_of_*` methods up to
+//! the last nanosecond before the next one. boundaries are found in the
+//! DateTime's own offset, and the result keeps that offset
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_millis(842282624123);
+//!
+//! assert_eq!(date.start_of_day().as_time_stamp(), "Mon Sep 9, 1996  0:00:00 (UTC)");
+//! assert_eq!(
+//!     date.end_of_month().as_time_stamp_with_fraction(3),
+//!     "Mon Sep 30, 1996  23:59:59.999 (UTC)"
+//! );
+//! ```
+//!
+//! a boundary which falls outside the range a DateTime can represent, such
+//! as the start of 0001-01-01 in an offset ahead of UTC, is clamped to the
+//! nearest representable time
+//!
+//! the ISO week and month a DateTime falls in can also be written as
+//! strings such as `1996-W37` and `1996-09`, the usual keys for weekly and
+//! monthly aggregates, and parsed back to the start of that week or month
+//! ```
+//! # use datetime::DateTime;
+//! let date = DateTime::from_secs(842282624);
+//! assert_eq!(date.to_iso_week_string(), "1996-W37");
+//! assert_eq!(date.to_iso_month_string(), "1996-09");
+//!
+//! let week = DateTime::parse_iso_week_string("1996-W37").unwrap();
+//! assert_eq!(week, DateTime::from_secs(842282624).start_of_week(datetime::Day::Monday));
+//! ```
+//!
+//! for buckets that aren't calendar units, such as five minutes for
+//! metrics or a rate-limiting window, [`DateTime::floor_to`],
+//! [`DateTime::ceil_to`] and [`DateTime::round_to`] snap to a multiple of
+//! any interval
+use crate::convert::{days_from_civil, is_valid_date};
+use crate::parse::{Cursor, ParseError};
+use crate::{DateTime, Day, MAX_SECS, MIN_SECS};
+use alloc::format;
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::time::Duration;
+
+impl DateTime {
+    // the DateTime at `local` seconds on the clocks of self's offset, with
+    // the given fraction, clamped to the representable range
+    fn at_local(&self, local: i64, nanos: u32) -> DateTime {
+        let secs = local.saturating_sub(self.offset.seconds() as i64);
+        if secs < MIN_SECS {
+            DateTime::from_parts(MIN_SECS, 0, self.offset)
+        } else if secs > MAX_SECS {
+            DateTime::from_parts(MAX_SECS, 999_999_999, self.offset)
+        } else {
+            DateTime::from_parts(secs, nanos, self.offset)
+        }
+    }
+
+    // the start of the unit `len` seconds long containing self, in local
+    // seconds
+    fn local_floor(&self, len: i64) -> i64 {
+        let local = self.secs + self.offset.seconds() as i64;
+        local - local.rem_euclid(len)
+    }
+
+    // the last nanosecond before `next` local seconds
+    fn last_before(&self, next: i64) -> DateTime {
+        self.at_local(next.saturating_sub(1), 999_999_999)
+    }
+
+    // the local start of the day containing self, in days since the epoch
+    fn local_day(&self) -> i64 {
+        self.local_floor(86_400) / 86_400
+    }
+
+    /// returns the start of the DateTime's minute
+    pub fn start_of_minute(&self) -> DateTime {
+        self.at_local(self.local_floor(60), 0)
+    }
+
+    /// returns the last nanosecond of the DateTime's minute
+    pub fn end_of_minute(&self) -> DateTime {
+        self.last_before(self.local_floor(60).saturating_add(60))
+    }
+
+    /// returns the start of the DateTime's hour
+    /// ```
+    /// # use datetime::{DateTime, FixedOffset};
+    /// // offsets needn't be whole hours
+    /// let offset = FixedOffset::east(5 * 3600 + 1800).unwrap();
+    /// let date = DateTime::from_secs(842282624).with_offset(offset);
+    ///
+    /// assert_eq!(date.start_of_hour().as_time_stamp(), "Mon Sep 9, 1996  20:00:00 (UTC+05:30)");
+    /// ```
+    pub fn start_of_hour(&self) -> DateTime {
+        self.at_local(self.local_floor(3600), 0)
+    }
+
+    /// returns the last nanosecond of the DateTime's hour
+    pub fn end_of_hour(&self) -> DateTime {
+        self.last_before(self.local_floor(3600).saturating_add(3600))
+    }
+
+    /// returns the start of the DateTime's day
+    pub fn start_of_day(&self) -> DateTime {
+        self.at_local(self.local_floor(86_400), 0)
+    }
+
+    /// returns the last nanosecond of the DateTime's day
+    pub fn end_of_day(&self) -> DateTime {
+        self.last_before(self.local_floor(86_400).saturating_add(86_400))
+    }
+
+    /// returns the start of the DateTime's week, taking weeks to begin on
+    /// `first`
+    /// ```
+    /// # use datetime::{DateTime, Day};
+    /// // a Monday
+    /// let date = DateTime::from_secs(842282624);
+    ///
+    /// assert_eq!(date.start_of_week(Day::Monday).date(), 9);
+    /// assert_eq!(date.start_of_week(Day::Sunday).date(), 8);
+    /// ```
+    pub fn start_of_week(&self, first: Day) -> DateTime {
+        self.at_local(self.week_start(first), 0)
+    }
+
+    /// returns the last nanosecond of the DateTime's week, taking weeks to
+    /// begin on `first`
+    pub fn end_of_week(&self, first: Day) -> DateTime {
+        self.last_before(self.week_start(first).saturating_add(7 * 86_400))
+    }
+
+    // the local start of the week beginning on `first` containing self
+    fn week_start(&self, first: Day) -> i64 {
+        let days_since = (self.day() as i64 - first as i64).rem_euclid(7);
+        (self.local_day() - days_since) * 86_400
+    }
+
+    /// returns the start of the DateTime's month
+    pub fn start_of_month(&self) -> DateTime {
+        let days = days_from_civil(self.year() as i64, self.month() as usize + 1, 1);
+        self.at_local(days.saturating_mul(86_400), 0)
+    }
+
+    /// returns the last nanosecond of the DateTime's month
+    pub fn end_of_month(&self) -> DateTime {
+        let days = self.local_day() - self.date() as i64 + self.days_in_month() as i64 + 1;
+        self.last_before(days.saturating_mul(86_400))
+    }
+
+    /// returns the start of the DateTime's year
+    pub fn start_of_year(&self) -> DateTime {
+        let days = days_from_civil(self.year() as i64, 1, 1);
+        self.at_local(days.saturating_mul(86_400), 0)
+    }
+
+    /// returns the last nanosecond of the DateTime's year
+    /// ```
+    /// # use datetime::DateTime;
+    /// let date = DateTime::from_secs(842282624).end_of_year();
+    ///
+    /// assert_eq!(date.as_time_stamp(), "Tue Dec 31, 1996  23:59:59 (UTC)");
+    /// assert_eq!(date.nanosecond(), 999_999_999);
+    /// ```
+    pub fn end_of_year(&self) -> DateTime {
+        let days = days_from_civil(self.year() as i64 + 1, 1, 1);
+        self.last_before(days.saturating_mul(86_400))
+    }
+
+    /// returns the latest multiple of `interval` at or before the DateTime
+    ///
+    /// multiples are counted from midnight on 1970-01-01 in the DateTime's
+    /// offset, so that an interval which divides a day, such as 15 minutes,
+    /// lines up with the local clock
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use std::time::Duration;
+    /// let date = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+    ///
+    /// let quarter = Duration::from_secs(15 * 60);
+    /// assert_eq!(date.floor_to(quarter).to_rfc3339(), "1996-09-09T15:15:00Z");
+    /// assert_eq!(date.ceil_to(quarter).to_rfc3339(), "1996-09-09T15:30:00Z");
+    /// assert_eq!(date.round_to(quarter).to_rfc3339(), "1996-09-09T15:30:00Z");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// panics if `interval` is zero
+    pub fn floor_to(&self, interval: Duration) -> DateTime {
+        let (local, len) = self.local_nanos(interval);
+        self.at_local_nanos(local - local.rem_euclid(len))
+    }
+
+    /// returns the earliest multiple of `interval` at or after the
+    /// DateTime; see [`DateTime::floor_to`]
+    ///
+    /// # Panics
+    ///
+    /// panics if `interval` is zero
+    pub fn ceil_to(&self, interval: Duration) -> DateTime {
+        let (local, len) = self.local_nanos(interval);
+        match local.rem_euclid(len) {
+            0 => *self,
+            rem => self.at_local_nanos(local - rem + len),
+        }
+    }
+
+    /// returns the nearest multiple of `interval` to the DateTime, taking
+    /// the later one when it is halfway between; see
+    /// [`DateTime::floor_to`]
+    ///
+    /// # Panics
+    ///
+    /// panics if `interval` is zero
+    pub fn round_to(&self, interval: Duration) -> DateTime {
+        let (local, len) = self.local_nanos(interval);
+        let rem = local.rem_euclid(len);
+        if rem * 2 < len {
+            self.at_local_nanos(local - rem)
+        } else {
+            self.at_local_nanos(local - rem + len)
+        }
+    }
+
+    // self in local nanoseconds, and the interval in nanoseconds
+    fn local_nanos(&self, interval: Duration) -> (i128, i128) {
+        assert!(interval != Duration::ZERO, "interval must not be zero");
+
+        let local = (self.secs + self.offset.seconds() as i64) as i128 * 1_000_000_000;
+        (local + self.nanos as i128, interval.as_nanos() as i128)
+    }
+
+    // like at_local, for a time in local nanoseconds
+    fn at_local_nanos(&self, local: i128) -> DateTime {
+        let secs = local.div_euclid(1_000_000_000);
+        let secs = i64::try_from(secs).unwrap_or(if secs < 0 { i64::MIN } else { i64::MAX });
+        self.at_local(secs, local.rem_euclid(1_000_000_000) as u32)
+    }
+
+    /// returns the ISO 8601 week the DateTime falls in, in its own offset,
+    /// written `yyyy-Www` with the week-based year
+    /// ```
+    /// # use datetime::DateTime;
+    /// // Sunday, January 3rd 2010
+    /// assert_eq!(DateTime::from_secs(1262476800).to_iso_week_string(), "2009-W53");
+    /// ```
+    pub fn to_iso_week_string(&self) -> String {
+        let (year, week) = self.iso_week();
+        format!("{:04}-W{:02}", year, week)
+    }
+
+    /// returns the month the DateTime falls in, in its own offset, written
+    /// `yyyy-mm`
+    pub fn to_iso_month_string(&self) -> String {
+        format!("{:04}-{:02}", self.year(), self.month() as usize + 1)
+    }
+
+    /// parses an ISO 8601 week written `yyyy-Www`, returning the start of
+    /// its Monday in UTC
+    /// ```
+    /// # use datetime::{DateTime, ParseError};
+    /// let week = DateTime::parse_iso_week_string("2009-W53").unwrap();
+    /// assert_eq!(week.to_rfc3339(), "2009-12-28T00:00:00Z");
+    ///
+    /// assert_eq!(DateTime::parse_iso_week_string("2010-W53"), Err(ParseError::OutOfRange));
+    /// ```
+    pub fn parse_iso_week_string(input: &str) -> Result<DateTime, ParseError> {
+        let mut cursor = Cursor::new(input);
+        let year = cursor.digits(4)?;
+        cursor.expect(b'-')?;
+        cursor.expect(b'W')?;
+        let week = cursor.digits(2)?;
+        cursor.finish()?;
+
+        DateTime::from_iso_week(year, week, Day::Monday).ok_or(ParseError::OutOfRange)
+    }
+
+    /// parses a month written `yyyy-mm`, returning the start of its first
+    /// day in UTC
+    pub fn parse_iso_month_string(input: &str) -> Result<DateTime, ParseError> {
+        let mut cursor = Cursor::new(input);
+        let year = cursor.digits(4)?;
+        cursor.expect(b'-')?;
+        let month = cursor.digits(2)?;
+        cursor.finish()?;
+
+        is_valid_date(year, month, 1).map_err(|_| ParseError::OutOfRange)?;
+        Ok(DateTime::from_signed_secs(
+            days_from_civil(year as i64, month, 1) * 86_400,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, Day, FixedOffset, ParseError, MAX_SECS, MIN_SECS};
+    use std::time::Duration;
+
+    #[test]
+    fn test_start_of() {
+        // 1996-09-09 15:23:44.123, shown at -04:00
+        let offset = FixedOffset::west(4 * 3600).unwrap();
+        let date = DateTime::from_millis(842282624123).with_offset(offset);
+        let stamp = |date: DateTime| date.as_time_stamp_with_fraction(3);
+
+        assert_eq!(
+            stamp(date.start_of_minute()),
+            "Mon Sep 9, 1996  11:23:00.000 (UTC-04:00)"
+        );
+        assert_eq!(
+            stamp(date.start_of_day()),
+            "Mon Sep 9, 1996  0:00:00.000 (UTC-04:00)"
+        );
+        assert_eq!(
+            stamp(date.start_of_week(Day::Wednesday)),
+            "Wed Sep 4, 1996  0:00:00.000 (UTC-04:00)"
+        );
+        assert_eq!(
+            stamp(date.start_of_month()),
+            "Sun Sep 1, 1996  0:00:00.000 (UTC-04:00)"
+        );
+        assert_eq!(
+            stamp(date.start_of_year()),
+            "Mon Jan 1, 1996  0:00:00.000 (UTC-04:00)"
+        );
+    }
+
+    #[test]
+    fn test_end_of() {
+        // 1969-12-31 23:59:59.999999999 UTC
+        let date = DateTime::from_nanos(-1);
+        assert_eq!(date.end_of_minute(), date);
+        assert_eq!(date.end_of_day(), date);
+        assert_eq!(date.end_of_month(), date);
+        assert_eq!(date.end_of_year(), date);
+
+        let date = DateTime::from_secs(951782400);
+        assert_eq!(date.end_of_month().date(), 29);
+        assert_eq!(date.end_of_hour().minute(), 59);
+        assert_eq!(date.end_of_week(Day::Monday).day(), Day::Sunday);
+    }
+
+    #[test]
+    fn test_iso_week_and_month_strings() {
+        // Monday 1996-09-09 at 02:00, which is still Sunday at -04:00
+        let offset = FixedOffset::west(4 * 3600).unwrap();
+        let date = DateTime::from_secs(842234400).with_offset(offset);
+        assert_eq!(date.to_iso_week_string(), "1996-W36");
+        assert_eq!(
+            DateTime::from_secs(842234400).to_iso_week_string(),
+            "1996-W37"
+        );
+        assert_eq!(
+            DateTime::from_secs(951782400).to_iso_month_string(),
+            "2000-02"
+        );
+
+        let month = DateTime::parse_iso_month_string("2000-02").unwrap();
+        assert_eq!(month, DateTime::from_secs(951782400).start_of_month());
+        assert_eq!(
+            DateTime::parse_iso_month_string("2000-13"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_iso_month_string("0000-01"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_iso_week_string("2000-W00"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            DateTime::parse_iso_week_string("2000-w01"),
+            Err(ParseError::Invalid(5))
+        );
+        assert_eq!(
+            DateTime::parse_iso_week_string("2000-W01-1"),
+            Err(ParseError::TooLong)
+        );
+    }
+
+    #[test]
+    fn test_round_to_interval() {
+        let date = DateTime::from_millis(842282624250);
+        let second = Duration::from_secs(1);
+        assert_eq!(date.floor_to(second), DateTime::from_secs(842282624));
+        assert_eq!(date.ceil_to(second), DateTime::from_secs(842282625));
+        assert_eq!(date.round_to(second), DateTime::from_secs(842282624));
+        assert_eq!(
+            date.round_to(Duration::from_millis(500)),
+            DateTime::from_millis(842282624500)
+        );
+        assert_eq!(date.ceil_to(Duration::from_millis(250)), date);
+
+        // multiples are counted in the DateTime's own offset
+        let offset = FixedOffset::east(5 * 3600 + 1800).unwrap();
+        let local = date
+            .with_offset(offset)
+            .floor_to(Duration::from_secs(86_400));
+        assert_eq!(
+            local.as_time_stamp(),
+            "Mon Sep 9, 1996  0:00:00 (UTC+05:30)"
+        );
+        assert_eq!(local.offset(), offset);
+
+        // before the epoch, floor still goes back
+        let date = DateTime::from_millis(-1500);
+        assert_eq!(date.floor_to(second), DateTime::from_signed_secs(-2));
+        assert_eq!(date.round_to(second), DateTime::from_signed_secs(-1));
+
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert_eq!(last.ceil_to(Duration::from_secs(7 * 86_400)).secs, MAX_SECS);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_round_to_zero() {
+        DateTime::from_secs(0).round_to(Duration::ZERO);
+    }
+
+    #[test]
+    fn test_clamped() {
+        let offset = FixedOffset::east(3600).unwrap();
+        let first = DateTime::from_signed_secs(MIN_SECS).with_offset(offset);
+        assert_eq!(first.start_of_year().secs, MIN_SECS);
+
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert_eq!(last.end_of_year().secs, MAX_SECS);
+    }
+}
@@ -0,0 +1,1807 @@
+//! time zones described by their history of UTC offsets
+//!
+//! a [`Tz`] is a named zone together with the list of instants at which
+//! its offset from UTC (or its abbreviation) changed. zones can be loaded
+//! from the system's IANA time zone database with [`Tz::load`], or from
+//! the bytes of any TZif file (e.g. one embedded with `include_bytes!`)
+//! with [`Tz::from_tzif`]
+//!
+//! zones can also be given by a POSIX TZ string such as
+//! `EST5EDT,M3.2.0,M11.1.0` with [`Tz::from_posix`], for systems without
+//! the database; TZif files end with one of these, giving the rules after
+//! their last transition
+//!
+//! a [`ZonedDateTime`] pairs a DateTime with the rules of the zone in
+//! effect at that instant
+//!
+//! zones are used through the [`TimeZone`] trait, which needs only the
+//! rules at an instant and the transitions between two, so that zones
+//! from other sources, such as another crate's copy of the database, can
+//! be used in place of a `Tz`
+//! ```no_run
+//! # use datetime::DateTime;
+//! # use datetime::tz::Tz;
+//! let tz = Tz::load("America/New_York").unwrap();
+//! let date = tz.from_utc(&DateTime::from_secs(842282624));
+//!
+//! assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  11:23:44 (EDT)");
+//! ```
+//!
+//! the zone the operating system is set to is loaded with [`Tz::local`],
+//! and [`DateTime::to_local`] and [`DateTime::now_local`] give times in it
+//! ```no_run
+//! # use datetime::DateTime;
+//! let now = DateTime::now_local().unwrap();
+//! println!("it is {} here", now.format("%H:%M %Z"));
+//! ```
+use crate::convert::{
+    civil_from_days, days_from_civil, days_in_month, is_leap_year, weekday_from_days,
+};
+use crate::{DateTime, FixedOffset, StampOptions, MAX_SECS, MIN_SECS, MONTHS};
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// the rules in effect in a time zone between two transitions
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocalTimeType {
+    offset: i32,
+    is_dst: bool,
+    abbreviation: String,
+}
+
+impl LocalTimeType {
+    /// returns a LocalTimeType with the given offset from UTC (in seconds,
+    /// positive east of Greenwich), daylight-saving flag and abbreviation
+    ///
+    /// # Panics
+    ///
+    /// panics if the offset is a day or more in either direction
+    pub fn new(offset: i32, is_dst: bool, abbreviation: &str) -> Self {
+        assert!(offset.unsigned_abs() < 86_400, "offset out of range");
+
+        LocalTimeType {
+            offset,
+            is_dst,
+            abbreviation: abbreviation.to_string(),
+        }
+    }
+
+    /// returns the offset from UTC in seconds
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// returns the offset from UTC as a FixedOffset
+    pub fn fixed_offset(&self) -> FixedOffset {
+        FixedOffset::east(self.offset).expect("offset checked on construction")
+    }
+
+    /// returns true if this is daylight-saving (summer) time
+    pub fn is_dst(&self) -> bool {
+        self.is_dst
+    }
+
+    /// returns the abbreviation of the zone, e.g. `EST`
+    pub fn abbreviation(&self) -> &str {
+        &self.abbreviation
+    }
+}
+
+/// a change in a time zone's rules at a given instant
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Transition {
+    at: i64,
+    before: LocalTimeType,
+    after: LocalTimeType,
+}
+
+impl Transition {
+    /// returns a transition at `at` seconds since the Unix epoch from the
+    /// rules `before` to the rules `after`
+    pub fn new(at: i64, before: LocalTimeType, after: LocalTimeType) -> Self {
+        Transition { at, before, after }
+    }
+
+    /// returns the instant of the transition in seconds since the Unix
+    /// epoch (negative for transitions before 1970)
+    pub fn unix_time(&self) -> i64 {
+        self.at
+    }
+
+    /// returns the rules in effect just before the transition
+    pub fn before(&self) -> &LocalTimeType {
+        &self.before
+    }
+
+    /// returns the rules in effect from the transition onwards
+    pub fn after(&self) -> &LocalTimeType {
+        &self.after
+    }
+
+    /// returns how far the clocks moved at this transition, in seconds
+    /// (positive when they went forward)
+    pub fn delta(&self) -> i32 {
+        self.after.offset - self.before.offset
+    }
+
+    /// returns a human-readable notice describing the clock change, with
+    /// the date and time given in local time as it was before the change
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use datetime::tz::{LocalTimeType, Tz};
+    /// let mut tz = Tz::new("Europe/Paris", LocalTimeType::new(3600, false, "CET"));
+    /// tz.add_transition(1743296400, LocalTimeType::new(7200, true, "CEST"));
+    ///
+    /// let a = DateTime::from_secs(1735689600); // 2025-01-01
+    /// let b = DateTime::from_secs(1767225600); // 2026-01-01
+    /// let transition = &tz.transitions_between(&a, &b)[0];
+    ///
+    /// assert_eq!(
+    ///     transition.clock_change_notice(),
+    ///     "Clocks go forward 1 hour on Sun, Mar 30 at 02:00"
+    /// );
+    /// ```
+    pub fn clock_change_notice(&self) -> String {
+        let local = self.at + self.before.offset as i64;
+        let days = local.div_euclid(86_400);
+        let secs = local.rem_euclid(86_400);
+        let (_, month, date) = civil_from_days(days);
+
+        let when = format!(
+            "{}, {} {} at {:02}:{:02}",
+            weekday_from_days(days).abbreviation(),
+            MONTHS[month - 1].abbreviation(),
+            date,
+            secs / 3600,
+            secs / 60 % 60
+        );
+
+        let delta = self.delta();
+        if delta == 0 {
+            format!(
+                "Time zone changes from {} to {} on {}",
+                self.before.abbreviation, self.after.abbreviation, when
+            )
+        } else {
+            let direction = if delta > 0 { "forward" } else { "back" };
+            format!(
+                "Clocks go {} {} on {}",
+                direction,
+                describe_offset(delta.abs()),
+                when
+            )
+        }
+    }
+}
+
+// describes a whole number of seconds as e.g. "1 hour" or "2 hours 30 minutes"
+fn describe_offset(secs: i32) -> String {
+    fn plural(n: i32, unit: &str) -> String {
+        if n == 1 {
+            format!("{} {}", n, unit)
+        } else {
+            format!("{} {}s", n, unit)
+        }
+    }
+
+    let hours = secs / 3600;
+    let minutes = secs / 60 % 60;
+
+    match (hours, minutes) {
+        (0, m) => plural(m, "minute"),
+        (h, 0) => plural(h, "hour"),
+        (h, m) => format!("{} {}", plural(h, "hour"), plural(m, "minute")),
+    }
+}
+
+/// an error returned when a time zone could not be loaded
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TzError {
+    /// the zone name is not a valid IANA name
+    InvalidName,
+    /// the zone file could not be read
+    Io(io::Error),
+    /// the zone file is not valid TZif data
+    InvalidData(&'static str),
+    /// the operating system's time zone could not be found
+    NoLocalZone,
+    /// the string is not a valid POSIX TZ string
+    InvalidPosix,
+}
+
+impl fmt::Display for TzError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TzError::InvalidName => write!(f, "invalid time zone name"),
+            TzError::Io(e) => write!(f, "could not read time zone file: {}", e),
+            TzError::InvalidData(reason) => write!(f, "invalid time zone file: {}", reason),
+            TzError::NoLocalZone => write!(f, "could not find the system's time zone"),
+            TzError::InvalidPosix => write!(f, "invalid POSIX TZ string"),
+        }
+    }
+}
+
+impl Error for TzError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TzError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TzError {
+    fn from(e: io::Error) -> Self {
+        TzError::Io(e)
+    }
+}
+
+// a reader over the big-endian fields of a TZif file
+struct TzifReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> TzifReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TzError> {
+        if self.data.len() < len {
+            return Err(TzError::InvalidData("unexpected end of file"));
+        }
+
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, TzError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i32(&mut self) -> Result<i32, TzError> {
+        let b = self.take(4)?;
+        Ok(i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i64(&mut self) -> Result<i64, TzError> {
+        let b = self.take(8)?;
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(b);
+        Ok(i64::from_be_bytes(bytes))
+    }
+
+    fn count(&mut self) -> Result<usize, TzError> {
+        Ok(self.i32()? as u32 as usize)
+    }
+}
+
+// the counts found in a TZif header
+struct TzifHeader {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl TzifHeader {
+    fn read(reader: &mut TzifReader) -> Result<Self, TzError> {
+        if reader.take(4)? != b"TZif" {
+            return Err(TzError::InvalidData("missing TZif magic"));
+        }
+        let version = reader.u8()?;
+        reader.take(15)?;
+
+        Ok(TzifHeader {
+            version,
+            isutcnt: reader.count()?,
+            isstdcnt: reader.count()?,
+            leapcnt: reader.count()?,
+            timecnt: reader.count()?,
+            typecnt: reader.count()?,
+            charcnt: reader.count()?,
+        })
+    }
+
+    // the length of the data block following the header, given the size
+    // of a transition time
+    fn data_len(&self, time_size: usize) -> usize {
+        // saturating, since the counts can be anything up to 2^32 - 1
+        self.timecnt
+            .saturating_mul(time_size + 1)
+            .saturating_add(self.typecnt.saturating_mul(6))
+            .saturating_add(self.charcnt)
+            .saturating_add(self.leapcnt.saturating_mul(time_size + 4))
+            .saturating_add(self.isstdcnt)
+            .saturating_add(self.isutcnt)
+    }
+}
+
+/// a named time zone and its history of transitions
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tz {
+    name: String,
+    initial: LocalTimeType,
+    // sorted by instant; each entry is the rules in effect from that instant
+    changes: Vec<(i64, LocalTimeType)>,
+    // the rules after the last change, if they follow a POSIX TZ string
+    rule: Option<PosixRule>,
+}
+
+impl Tz {
+    /// returns a zone that has always used the given rules
+    /// ```
+    /// # use datetime::tz::{LocalTimeType, Tz};
+    /// let utc = Tz::new("UTC", LocalTimeType::new(0, false, "UTC"));
+    ///
+    /// assert_eq!(utc.name(), "UTC");
+    /// ```
+    pub fn new(name: &str, initial: LocalTimeType) -> Self {
+        Tz {
+            name: name.to_string(),
+            initial,
+            changes: Vec::new(),
+            rule: None,
+        }
+    }
+
+    /// loads a zone from the system's IANA time zone database, e.g.
+    /// `America/New_York`
+    ///
+    /// the database is read from the directory named by the `TZDIR`
+    /// environment variable, or `/usr/share/zoneinfo` if it is not set
+    pub fn load(name: &str) -> Result<Tz, TzError> {
+        load_in(&zoneinfo_dir(), name)
+    }
+
+    /// loads a zone like [`Tz::load`], but from a name as a user might
+    /// type it: case is ignored, spaces may stand in for underscores, and
+    /// old names kept as links for backward compatibility are replaced by
+    /// the zone they link to (see [`resolve_alias`])
+    ///
+    /// the zone returned has its canonical name
+    /// ```no_run
+    /// # use datetime::tz::Tz;
+    /// assert_eq!(Tz::find("asia/calcutta").unwrap().name(), "Asia/Kolkata");
+    /// assert_eq!(Tz::find("America/new york").unwrap().name(), "America/New_York");
+    /// ```
+    pub fn find(name: &str) -> Result<Tz, TzError> {
+        find_in(&zoneinfo_dir(), name)
+    }
+
+    /// loads the zone the operating system is set to, the same one C
+    /// programs use for local time
+    ///
+    /// this is the zone named by the `TZ` environment variable, which is
+    /// either an IANA name, loaded as by [`Tz::load`], a POSIX TZ string,
+    /// read as by [`Tz::from_posix`], or the path of a TZif file, and may
+    /// start with a `:`. without `TZ`, Unix-like systems
+    /// use `/etc/localtime`, named after the database entry it links to;
+    /// elsewhere, this returns [`TzError::NoLocalZone`]
+    pub fn local() -> Result<Tz, TzError> {
+        local_zone(std::env::var_os("TZ"), Path::new("/etc/localtime"))
+    }
+
+    /// parses a zone from the contents of a TZif file (RFC 8536)
+    ///
+    /// local time after the last transition in the file follows the POSIX
+    /// TZ string at its end, or the last transition's rules in version 1
+    /// files, which don't have one
+    pub fn from_tzif(name: &str, data: &[u8]) -> Result<Tz, TzError> {
+        let mut reader = TzifReader { data };
+        let mut header = TzifHeader::read(&mut reader)?;
+        let mut time_size = 4;
+
+        // version 2 and later files repeat the data with 64-bit times
+        if header.version >= b'2' {
+            reader.take(header.data_len(4))?;
+            header = TzifHeader::read(&mut reader)?;
+            time_size = 8;
+        }
+
+        if header.typecnt == 0 {
+            return Err(TzError::InvalidData("no local time types"));
+        }
+        // before allocating for the counts, which the header can overstate
+        if header.data_len(time_size) > reader.data.len() {
+            return Err(TzError::InvalidData("unexpected end of file"));
+        }
+
+        let mut times = Vec::with_capacity(header.timecnt);
+        for _ in 0..header.timecnt {
+            times.push(if time_size == 8 {
+                reader.i64()?
+            } else {
+                reader.i32()? as i64
+            });
+        }
+        let indices = reader.take(header.timecnt)?;
+
+        let mut raw_types = Vec::with_capacity(header.typecnt);
+        for _ in 0..header.typecnt {
+            let offset = reader.i32()?;
+            let is_dst = reader.u8()? != 0;
+            let abbreviation_index = reader.u8()? as usize;
+            if offset.unsigned_abs() >= 86_400 {
+                return Err(TzError::InvalidData("offset out of range"));
+            }
+            raw_types.push((offset, is_dst, abbreviation_index));
+        }
+
+        let chars = reader.take(header.charcnt)?;
+        let mut types = Vec::with_capacity(header.typecnt);
+        for (offset, is_dst, index) in raw_types {
+            let abbreviation = chars
+                .get(index..)
+                .and_then(|rest| rest.split(|&b| b == 0).next())
+                .and_then(|bytes| std::str::from_utf8(bytes).ok())
+                .ok_or(TzError::InvalidData("invalid abbreviation"))?;
+            types.push(LocalTimeType::new(offset, is_dst, abbreviation));
+        }
+
+        let mut tz = Tz::new(name, types[0].clone());
+        for (&at, &index) in times.iter().zip(indices) {
+            let rules = types
+                .get(index as usize)
+                .ok_or(TzError::InvalidData("invalid local time type index"))?;
+            tz.changes.push((at, rules.clone()));
+        }
+
+        if tz.changes.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(TzError::InvalidData("transitions out of order"));
+        }
+
+        // the footer of version 2 and later files is a TZ string between
+        // newlines, which may be empty
+        if header.version >= b'2' {
+            reader.take(header.leapcnt * 12 + header.isstdcnt + header.isutcnt)?;
+            let footer = reader
+                .data
+                .strip_prefix(b"\n")
+                .and_then(|rest| rest.split(|&b| b == b'\n').next())
+                .and_then(|bytes| std::str::from_utf8(bytes).ok())
+                .ok_or(TzError::InvalidData("missing footer"))?;
+            if !footer.is_empty() {
+                let rule = PosixRule::parse(footer)
+                    .ok_or(TzError::InvalidData("invalid TZ string in footer"))?;
+                tz.rule = Some(rule);
+            }
+        }
+
+        trace_event!(
+            name,
+            transitions = tz.changes.len(),
+            footer = tz.rule.is_some(),
+            "parsed TZif data"
+        );
+        Ok(tz)
+    }
+
+    /// returns a zone following a POSIX TZ string, as used in the `TZ`
+    /// environment variable, such as `EST5EDT,M3.2.0,M11.1.0` or
+    /// `<+0530>-5:30`; the zone is named by the string
+    ///
+    /// offsets in TZ strings are hours west of Greenwich, so `EST5` is five
+    /// hours behind UTC. a zone with daylight-saving time but no rule for
+    /// when it applies follows the current US rule, `M3.2.0,M11.1.0`
+    /// ```
+    /// # use datetime::tz::Tz;
+    /// # use datetime::DateTime;
+    /// let tz = Tz::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    ///
+    /// let date = tz.from_utc(&DateTime::from_secs(842282624));
+    /// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  17:23:44 (CEST)");
+    /// ```
+    pub fn from_posix(spec: &str) -> Result<Tz, TzError> {
+        let rule = PosixRule::parse(spec).ok_or(TzError::InvalidPosix)?;
+
+        Ok(Tz {
+            name: spec.to_string(),
+            initial: rule.std.clone(),
+            changes: Vec::new(),
+            rule: Some(rule),
+        })
+    }
+
+    /// records that the zone switched to the given rules at `at` seconds
+    /// since the Unix epoch
+    pub fn add_transition(&mut self, at: i64, rules: LocalTimeType) {
+        let index = self.changes.partition_point(|&(t, _)| t <= at);
+        self.changes.insert(index, (at, rules));
+    }
+
+    /// returns the name of the zone, e.g. `America/New_York`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// returns the rules in effect at the given instant (in seconds since
+    /// the Unix epoch)
+    pub fn rules_at(&self, unix_time: i64) -> &LocalTimeType {
+        let index = self.changes.partition_point(|&(t, _)| t <= unix_time);
+        if let Some(rule) = &self.rule {
+            if index == self.changes.len() {
+                return rule.rules_at(unix_time);
+            }
+        }
+
+        if index == 0 {
+            &self.initial
+        } else {
+            &self.changes[index - 1].1
+        }
+    }
+
+    /// returns every transition `t` with `a <= t < b`, in order
+    ///
+    /// transitions after the last one a zone lists are worked out from its
+    /// POSIX TZ string, so this takes time proportional to the number of
+    /// years between `a` and `b`
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use datetime::tz::{LocalTimeType, Tz};
+    /// let mut tz = Tz::new("Europe/London", LocalTimeType::new(0, false, "GMT"));
+    /// tz.add_transition(828234000, LocalTimeType::new(3600, true, "BST"));
+    /// tz.add_transition(846378000, LocalTimeType::new(0, false, "GMT"));
+    ///
+    /// let a = DateTime::from_secs(820454400); // 1996-01-01
+    /// let b = DateTime::from_secs(842282624); // 1996-09-09
+    /// let transitions = tz.transitions_between(&a, &b);
+    ///
+    /// assert_eq!(transitions.len(), 1);
+    /// assert_eq!(transitions[0].after().abbreviation(), "BST");
+    /// assert_eq!(transitions[0].delta(), 3600);
+    /// ```
+    pub fn transitions_between(&self, a: &DateTime, b: &DateTime) -> Vec<Transition> {
+        TimeZone::transitions_between(self, a, b)
+    }
+
+    /// returns the given UTC instant as a ZonedDateTime in this zone
+    /// ```
+    /// # use datetime::DateTime;
+    /// # use datetime::tz::{LocalTimeType, Tz};
+    /// let mut tz = Tz::new("Europe/London", LocalTimeType::new(0, false, "GMT"));
+    /// tz.add_transition(828234000, LocalTimeType::new(3600, true, "BST"));
+    ///
+    /// let date = tz.from_utc(&DateTime::from_secs(842282624));
+    ///
+    /// assert_eq!(date.hour(), 16);
+    /// assert_eq!(date.abbreviation(), "BST");
+    /// ```
+    pub fn from_utc(&self, date: &DateTime) -> ZonedDateTime {
+        TimeZone::from_utc(self, date)
+    }
+
+    /// resolves a local (wall-clock) date and time in this zone; `month`
+    /// is 1-12
+    ///
+    /// returns None if the fields are out of range or the time is before
+    /// the year 1 in UTC
+    /// ```
+    /// # use datetime::tz::{LocalResult, LocalTimeType, Tz};
+    /// let mut tz = Tz::new("Europe/London", LocalTimeType::new(0, false, "GMT"));
+    /// tz.add_transition(828234000, LocalTimeType::new(3600, true, "BST"));
+    /// tz.add_transition(846378000, LocalTimeType::new(0, false, "GMT"));
+    ///
+    /// match tz.from_local(1996, 10, 27, 1, 30, 0).unwrap() {
+    ///     LocalResult::Ambiguous(earlier, later) => {
+    ///         assert_eq!(earlier.abbreviation(), "BST");
+    ///         assert_eq!(later.abbreviation(), "GMT");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn from_local(
+        &self,
+        year: usize,
+        month: usize,
+        date: usize,
+        hour: usize,
+        minute: usize,
+        second: usize,
+    ) -> Option<LocalResult> {
+        TimeZone::from_local(self, year, month, date, hour, minute, second)
+    }
+
+    // like transitions_between, but over raw seconds since the epoch
+    pub(crate) fn transitions_in(&self, a: i64, b: i64) -> Vec<Transition> {
+        let mut before = &self.initial;
+        let mut transitions = Vec::new();
+
+        for (at, after) in &self.changes {
+            if *at >= b {
+                break;
+            }
+            if *at >= a {
+                transitions.push(Transition {
+                    at: *at,
+                    before: before.clone(),
+                    after: after.clone(),
+                });
+            }
+            before = after;
+        }
+
+        // then those of the rule, which continue for as long as asked, up
+        // to the last DateTime
+        let b = b.min(MAX_SECS + 1);
+        if let Some(rule) = &self.rule {
+            let from = match self.changes.last() {
+                Some(&(at, _)) => a.max(at + 1),
+                None => a,
+            };
+            if from < b {
+                transitions.extend(rule.transitions_in(from, b));
+            }
+        }
+
+        transitions
+    }
+}
+
+/// a time zone: the rules in effect at each instant, and the transitions
+/// between them
+///
+/// only [`rules_at`](TimeZone::rules_at) and
+/// [`transitions_in`](TimeZone::transitions_in) need to be written to back
+/// a zone with other data; conversions between UTC and local time come
+/// from those
+/// ```
+/// # use datetime::tz::{LocalTimeType, TimeZone, Transition};
+/// # use datetime::DateTime;
+/// // UTC+1 in winter and UTC+2 from March to October, every year
+/// struct Simple;
+///
+/// impl TimeZone for Simple {
+///     fn rules_at(&self, unix_time: i64) -> LocalTimeType {
+///         let date = DateTime::from_signed_secs(unix_time);
+///         if (3..=10).contains(&date.month().number()) {
+///             LocalTimeType::new(7200, true, "SUMMER")
+///         } else {
+///             LocalTimeType::new(3600, false, "WINTER")
+///         }
+///     }
+///
+///     fn transitions_in(&self, a: i64, b: i64) -> Vec<Transition> {
+///         Vec::new()
+///     }
+/// }
+///
+/// let date = Simple.from_utc(&DateTime::from_secs(842282624));
+/// assert_eq!(date.as_time_stamp(), "Mon Sep 9, 1996  17:23:44 (SUMMER)");
+/// ```
+pub trait TimeZone {
+    /// returns the rules in effect at the given instant (in seconds since
+    /// the Unix epoch)
+    fn rules_at(&self, unix_time: i64) -> LocalTimeType;
+
+    /// returns every transition `t` with `a <= t < b` (in seconds since the
+    /// Unix epoch), in order
+    ///
+    /// a zone with a recurring rule, such as a [`Tz`] with a POSIX TZ
+    /// string, has two transitions every year up to the last DateTime, and
+    /// builds every one of them, so `b` should be kept to the years that
+    /// are needed rather than `i64::MAX`
+    fn transitions_in(&self, a: i64, b: i64) -> Vec<Transition>;
+
+    /// returns every transition between two DateTimes, as
+    /// [`Tz::transitions_between`] does
+    fn transitions_between(&self, a: &DateTime, b: &DateTime) -> Vec<Transition> {
+        self.transitions_in(a.secs, b.secs)
+    }
+
+    /// returns the given UTC instant as a ZonedDateTime in this zone, as
+    /// [`Tz::from_utc`] does
+    #[allow(clippy::wrong_self_convention)]
+    fn from_utc(&self, date: &DateTime) -> ZonedDateTime {
+        let rules = self.rules_at(date.secs);
+
+        ZonedDateTime {
+            datetime: date.with_offset(rules.fixed_offset()),
+            rules,
+        }
+    }
+
+    /// resolves a local date and time in this zone, as [`Tz::from_local`]
+    /// does
+    #[allow(clippy::wrong_self_convention)]
+    fn from_local(
+        &self,
+        year: usize,
+        month: usize,
+        date: usize,
+        hour: usize,
+        minute: usize,
+        second: usize,
+    ) -> Option<LocalResult> {
+        if !(1..=12).contains(&month) || date < 1 || date > days_in_month(year, month) {
+            return None;
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        let local = days_from_civil(year as i64, month, date) * 86_400
+            + (hour * 3600 + minute * 60 + second) as i64;
+
+        // the rules in effect a day either side cover every offset that
+        // could apply to this local time
+        let before = self.rules_at(local - 86_400).offset as i64;
+        let after = self.rules_at(local + 86_400).offset as i64;
+
+        let mut instants: Vec<i64> = vec![local - before, local - after]
+            .into_iter()
+            .filter(|&t| self.rules_at(t).offset as i64 == local - t)
+            .collect();
+        instants.sort_unstable();
+        instants.dedup();
+
+        let zoned = |t: i64| {
+            if t < MIN_SECS {
+                None
+            } else {
+                Some(self.from_utc(&DateTime::from_signed_secs(t)))
+            }
+        };
+
+        match instants[..] {
+            [t] => Some(LocalResult::Single(zoned(t)?)),
+            [earlier, later] => Some(LocalResult::Ambiguous(zoned(earlier)?, zoned(later)?)),
+            // in a gap the local time doesn't exist: interpreting it with
+            // the rules from before the gap moves it forward past the gap
+            _ => Some(LocalResult::Gap(zoned(local - before)?)),
+        }
+    }
+}
+
+impl TimeZone for Tz {
+    fn rules_at(&self, unix_time: i64) -> LocalTimeType {
+        Tz::rules_at(self, unix_time).clone()
+    }
+
+    fn transitions_in(&self, a: i64, b: i64) -> Vec<Transition> {
+        Tz::transitions_in(self, a, b)
+    }
+}
+
+impl TimeZone for FixedOffset {
+    /// returns the offset, abbreviated `UTC` or like `+05:45`
+    fn rules_at(&self, _unix_time: i64) -> LocalTimeType {
+        if self.seconds() == 0 {
+            LocalTimeType::new(0, false, "UTC")
+        } else {
+            LocalTimeType::new(self.seconds(), false, &self.to_string())
+        }
+    }
+
+    /// a fixed offset never changes, so this is always empty
+    fn transitions_in(&self, _a: i64, _b: i64) -> Vec<Transition> {
+        Vec::new()
+    }
+}
+
+// the directory holding the tz database
+fn zoneinfo_dir() -> PathBuf {
+    std::env::var_os("TZDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/usr/share/zoneinfo"))
+}
+
+// loads the zone with the given name from the database in `dir`
+fn load_in(dir: &Path, name: &str) -> Result<Tz, TzError> {
+    trace_span!("tz_load", name);
+    if !is_valid_name(name) {
+        return Err(TzError::InvalidName);
+    }
+
+    let data = std::fs::read(dir.join(name))?;
+    trace_event!(bytes = data.len(), "read zone file");
+    Tz::from_tzif(name, &data)
+}
+
+// whether a zone name is relative and stays within the database
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('/')
+        && name
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..")
+}
+
+// loads a zone from the database in `dir` by a loosely written name
+fn find_in(dir: &Path, name: &str) -> Result<Tz, TzError> {
+    trace_span!("tz_find", name);
+    let name = name.trim().replace(' ', "_");
+    let name = resolve_alias(&name).unwrap_or(&name);
+    if !is_valid_name(name) {
+        return Err(TzError::InvalidName);
+    }
+
+    // find each part of the name in the directory above it, ignoring case
+    // unless there is an exact match
+    let mut path = dir.to_path_buf();
+    for part in name.split('/') {
+        if path.join(part).exists() {
+            path.push(part);
+            continue;
+        }
+
+        let entry = std::fs::read_dir(&path)?
+            .filter_map(Result::ok)
+            .find(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(part)
+            })
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        path.push(entry.file_name());
+    }
+
+    // links in the database itself lead to the canonical name, which may
+    // also be an alias in a different case
+    // (a link leading out of the database keeps the name it was found by)
+    let target = std::fs::canonicalize(&path)?;
+    let relative = match target.strip_prefix(std::fs::canonicalize(dir)?) {
+        Ok(relative) => relative,
+        Err(_) => path.strip_prefix(dir).expect("found within dir"),
+    };
+    let found = relative.to_string_lossy().replace('\\', "/");
+    let found = resolve_alias(&found).unwrap_or(&found);
+
+    load_in(dir, found)
+}
+
+// links from the tz database's `backward` file, as (old name, zone)
+const ALIASES: &[(&str, &str)] = &[
+    ("America/Buenos_Aires", "America/Argentina/Buenos_Aires"),
+    ("America/Catamarca", "America/Argentina/Catamarca"),
+    ("America/Cordoba", "America/Argentina/Cordoba"),
+    ("America/Ensenada", "America/Tijuana"),
+    ("America/Fort_Wayne", "America/Indiana/Indianapolis"),
+    ("America/Godthab", "America/Nuuk"),
+    ("America/Indianapolis", "America/Indiana/Indianapolis"),
+    ("America/Jujuy", "America/Argentina/Jujuy"),
+    ("America/Knox_IN", "America/Indiana/Knox"),
+    ("America/Louisville", "America/Kentucky/Louisville"),
+    ("America/Mendoza", "America/Argentina/Mendoza"),
+    ("America/Montreal", "America/Toronto"),
+    ("America/Porto_Acre", "America/Rio_Branco"),
+    ("America/Santa_Isabel", "America/Tijuana"),
+    ("America/Shiprock", "America/Denver"),
+    ("Asia/Ashkhabad", "Asia/Ashgabat"),
+    ("Asia/Calcutta", "Asia/Kolkata"),
+    ("Asia/Chongqing", "Asia/Shanghai"),
+    ("Asia/Chungking", "Asia/Shanghai"),
+    ("Asia/Dacca", "Asia/Dhaka"),
+    ("Asia/Harbin", "Asia/Shanghai"),
+    ("Asia/Istanbul", "Europe/Istanbul"),
+    ("Asia/Katmandu", "Asia/Kathmandu"),
+    ("Asia/Macao", "Asia/Macau"),
+    ("Asia/Rangoon", "Asia/Yangon"),
+    ("Asia/Saigon", "Asia/Ho_Chi_Minh"),
+    ("Asia/Tel_Aviv", "Asia/Jerusalem"),
+    ("Asia/Thimbu", "Asia/Thimphu"),
+    ("Asia/Ulan_Bator", "Asia/Ulaanbaatar"),
+    ("Atlantic/Faeroe", "Atlantic/Faroe"),
+    ("Australia/ACT", "Australia/Sydney"),
+    ("Australia/Canberra", "Australia/Sydney"),
+    ("Australia/NSW", "Australia/Sydney"),
+    ("Australia/North", "Australia/Darwin"),
+    ("Australia/Queensland", "Australia/Brisbane"),
+    ("Australia/South", "Australia/Adelaide"),
+    ("Australia/Tasmania", "Australia/Hobart"),
+    ("Australia/Victoria", "Australia/Melbourne"),
+    ("Australia/West", "Australia/Perth"),
+    ("Brazil/East", "America/Sao_Paulo"),
+    ("Brazil/West", "America/Manaus"),
+    ("Canada/Atlantic", "America/Halifax"),
+    ("Canada/Central", "America/Winnipeg"),
+    ("Canada/Eastern", "America/Toronto"),
+    ("Canada/Mountain", "America/Edmonton"),
+    ("Canada/Newfoundland", "America/St_Johns"),
+    ("Canada/Pacific", "America/Vancouver"),
+    ("Chile/Continental", "America/Santiago"),
+    ("Cuba", "America/Havana"),
+    ("Egypt", "Africa/Cairo"),
+    ("Eire", "Europe/Dublin"),
+    ("Europe/Belfast", "Europe/London"),
+    ("Europe/Kiev", "Europe/Kyiv"),
+    ("Europe/Nicosia", "Asia/Nicosia"),
+    ("Europe/Tiraspol", "Europe/Chisinau"),
+    ("GB", "Europe/London"),
+    ("GMT", "Etc/GMT"),
+    ("Greenwich", "Etc/GMT"),
+    ("Hongkong", "Asia/Hong_Kong"),
+    ("Iceland", "Atlantic/Reykjavik"),
+    ("Iran", "Asia/Tehran"),
+    ("Israel", "Asia/Jerusalem"),
+    ("Jamaica", "America/Jamaica"),
+    ("Japan", "Asia/Tokyo"),
+    ("Libya", "Africa/Tripoli"),
+    ("Mexico/General", "America/Mexico_City"),
+    ("NZ", "Pacific/Auckland"),
+    ("Navajo", "America/Denver"),
+    ("PRC", "Asia/Shanghai"),
+    ("Pacific/Enderbury", "Pacific/Kanton"),
+    ("Pacific/Ponape", "Pacific/Pohnpei"),
+    ("Pacific/Samoa", "Pacific/Pago_Pago"),
+    ("Pacific/Truk", "Pacific/Chuuk"),
+    ("Pacific/Yap", "Pacific/Chuuk"),
+    ("Poland", "Europe/Warsaw"),
+    ("Portugal", "Europe/Lisbon"),
+    ("ROC", "Asia/Taipei"),
+    ("ROK", "Asia/Seoul"),
+    ("Singapore", "Asia/Singapore"),
+    ("Turkey", "Europe/Istanbul"),
+    ("UCT", "Etc/UTC"),
+    ("US/Alaska", "America/Anchorage"),
+    ("US/Aleutian", "America/Adak"),
+    ("US/Arizona", "America/Phoenix"),
+    ("US/Central", "America/Chicago"),
+    ("US/East-Indiana", "America/Indiana/Indianapolis"),
+    ("US/Eastern", "America/New_York"),
+    ("US/Hawaii", "Pacific/Honolulu"),
+    ("US/Michigan", "America/Detroit"),
+    ("US/Mountain", "America/Denver"),
+    ("US/Pacific", "America/Los_Angeles"),
+    ("US/Samoa", "Pacific/Pago_Pago"),
+    ("UTC", "Etc/UTC"),
+    ("Universal", "Etc/UTC"),
+    ("W-SU", "Europe/Moscow"),
+    ("Zulu", "Etc/UTC"),
+];
+
+/// returns the zone an old or alternative zone name links to in the tz
+/// database, such as `Asia/Kolkata` for `Asia/Calcutta`, ignoring case, or
+/// None if the name isn't a known link
+/// ```
+/// # use datetime::tz::resolve_alias;
+/// assert_eq!(resolve_alias("US/Pacific"), Some("America/Los_Angeles"));
+/// assert_eq!(resolve_alias("asia/calcutta"), Some("Asia/Kolkata"));
+/// assert_eq!(resolve_alias("Asia/Kolkata"), None);
+/// ```
+pub fn resolve_alias(name: &str) -> Option<&'static str> {
+    ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map(|&(_, zone)| zone)
+}
+
+// the zone named by a value of the TZ environment variable, or in the
+// file at `default` if it isn't set
+fn local_zone(tz: Option<OsString>, default: &Path) -> Result<Tz, TzError> {
+    let path = match tz.filter(|tz| !tz.is_empty()) {
+        Some(tz) => {
+            let tz = tz.to_str().ok_or(TzError::InvalidName)?;
+            let tz = tz.strip_prefix(':').unwrap_or(tz);
+            if !tz.starts_with('/') {
+                // not a zone in the database, but maybe a TZ string
+                return Tz::load(tz).or_else(|e| {
+                    trace_event!(tz, error = %e, "TZ not in the database, reading it as a TZ string");
+                    Tz::from_posix(tz).map_err(|_| e)
+                });
+            }
+            PathBuf::from(tz)
+        }
+        None if cfg!(unix) => default.to_path_buf(),
+        None => return Err(TzError::NoLocalZone),
+    };
+    trace_event!(path = %path.display(), "loading the local zone");
+    let data = std::fs::read(&path)?;
+
+    // the file is usually a link into the database, whose path within it
+    // is the zone's name
+    let target = std::fs::canonicalize(&path).unwrap_or(path);
+    let target = target.to_string_lossy();
+    let name = match target.rfind("zoneinfo/") {
+        Some(index) => &target[index + "zoneinfo/".len()..],
+        None => &target,
+    };
+
+    Tz::from_tzif(name, &data)
+}
+
+// a day of the year on which a POSIX TZ rule changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RuleDay {
+    // `Jn`: day 1-365, never counting February 29th
+    Julian(i64),
+    // `n`: day 0-365, counting February 29th
+    Zero(i64),
+    // `Mm.w.d`: weekday d (Sunday being 0) of week w of month m, where
+    // week 5 is the last
+    Weekday {
+        month: usize,
+        week: i64,
+        weekday: i64,
+    },
+}
+
+impl RuleDay {
+    // the day in the given year, in days since the epoch
+    fn in_year(self, year: i64) -> i64 {
+        let leap = is_leap_year(year.rem_euclid(400) as usize);
+        match self {
+            RuleDay::Julian(day) => {
+                days_from_civil(year, 1, 1) + day - 1 + (leap && day >= 60) as i64
+            }
+            RuleDay::Zero(day) => days_from_civil(year, 1, 1) + day,
+            RuleDay::Weekday {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = days_from_civil(year, month, 1);
+                let length = days_in_month(year.rem_euclid(400) as usize, month) as i64;
+                let mut day = first + (weekday - weekday_from_days(first) as i64).rem_euclid(7);
+                day += (week - 1) * 7;
+                while day >= first + length {
+                    day -= 7;
+                }
+                day
+            }
+        }
+    }
+}
+
+// the daylight-saving part of a POSIX TZ string
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DstRule {
+    rules: LocalTimeType,
+    // the days daylight-saving time starts and ends, with the local time
+    // of day in seconds, which can be negative or past midnight
+    start: (RuleDay, i64),
+    end: (RuleDay, i64),
+}
+
+// the rules given by a POSIX TZ string
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PosixRule {
+    std: LocalTimeType,
+    dst: Option<DstRule>,
+}
+
+impl PosixRule {
+    // parses a TZ string, e.g. `EST5EDT,M3.2.0,M11.1.0`
+    fn parse(spec: &str) -> Option<PosixRule> {
+        let mut reader = PosixReader {
+            rest: spec.as_bytes(),
+        };
+
+        let std_name = reader.name()?;
+        let std_offset = -reader.time(24)?;
+        if std_offset.abs() >= 86_400 {
+            return None;
+        }
+        let std = LocalTimeType::new(std_offset as i32, false, std_name);
+        if reader.rest.is_empty() {
+            return Some(PosixRule { std, dst: None });
+        }
+
+        let dst_name = reader.name()?;
+        let dst_offset = match reader.rest.first() {
+            None | Some(b',') => std_offset + 3600,
+            Some(_) => -reader.time(24)?,
+        };
+        let (start, end) = if reader.rest.is_empty() {
+            let start = RuleDay::Weekday {
+                month: 3,
+                week: 2,
+                weekday: 0,
+            };
+            let end = RuleDay::Weekday {
+                month: 11,
+                week: 1,
+                weekday: 0,
+            };
+            ((start, 7200), (end, 7200))
+        } else {
+            reader.eat(b',')?;
+            let start = reader.transition()?;
+            reader.eat(b',')?;
+            (start, reader.transition()?)
+        };
+
+        if !reader.rest.is_empty() || dst_offset.abs() >= 86_400 {
+            return None;
+        }
+
+        Some(PosixRule {
+            std,
+            dst: Some(DstRule {
+                rules: LocalTimeType::new(dst_offset as i32, true, dst_name),
+                start,
+                end,
+            }),
+        })
+    }
+
+    // the instants daylight-saving time starts and ends in a year; each
+    // happens at a local time read in the rules before it
+    fn dst_instants(&self, dst: &DstRule, year: i64) -> (i64, i64) {
+        let (day, time) = dst.start;
+        let start = (day.in_year(year).saturating_mul(86_400))
+            .saturating_add(time - self.std.offset as i64);
+        let (day, time) = dst.end;
+        let end = (day.in_year(year).saturating_mul(86_400))
+            .saturating_add(time - dst.rules.offset as i64);
+        (start, end)
+    }
+
+    // the year an instant falls in, in standard time
+    fn year_of(&self, unix_time: i64) -> i64 {
+        let local = unix_time.saturating_add(self.std.offset as i64);
+        civil_from_days(local.div_euclid(86_400)).0
+    }
+
+    fn rules_at(&self, unix_time: i64) -> &LocalTimeType {
+        let dst = match &self.dst {
+            Some(dst) => dst,
+            None => return &self.std,
+        };
+
+        let (start, end) = self.dst_instants(dst, self.year_of(unix_time));
+        // in the southern hemisphere, daylight-saving time spans the new
+        // year
+        let in_dst = if start < end {
+            start <= unix_time && unix_time < end
+        } else {
+            unix_time < end || unix_time >= start
+        };
+
+        if in_dst {
+            &dst.rules
+        } else {
+            &self.std
+        }
+    }
+
+    // every transition `t` with `a <= t < b`, in order
+    fn transitions_in(&self, a: i64, b: i64) -> Vec<Transition> {
+        let dst = match &self.dst {
+            Some(dst) => dst,
+            None => return Vec::new(),
+        };
+
+        let mut transitions = Vec::new();
+        for year in self.year_of(a) - 1..=self.year_of(b) + 1 {
+            let (start, end) = self.dst_instants(dst, year);
+            transitions.push(Transition {
+                at: start,
+                before: self.std.clone(),
+                after: dst.rules.clone(),
+            });
+            transitions.push(Transition {
+                at: end,
+                before: dst.rules.clone(),
+                after: self.std.clone(),
+            });
+        }
+
+        transitions.retain(|transition| (a..b).contains(&transition.at));
+        transitions.sort_by_key(|transition| transition.at);
+        transitions
+    }
+}
+
+// a reader over the parts of a POSIX TZ string
+struct PosixReader<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> PosixReader<'a> {
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        self.rest = self.rest.strip_prefix(&[byte])?;
+        Some(())
+    }
+
+    // a zone abbreviation: three or more letters, or anything alphanumeric
+    // (or a sign) between angle brackets
+    fn name(&mut self) -> Option<&'a str> {
+        let (name, rest) = if self.eat(b'<').is_some() {
+            let end = self.rest.iter().position(|&b| b == b'>')?;
+            let name = &self.rest[..end];
+            if !name
+                .iter()
+                .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-')
+            {
+                return None;
+            }
+            (name, &self.rest[end + 1..])
+        } else {
+            let end = self
+                .rest
+                .iter()
+                .position(|b| !b.is_ascii_alphabetic())
+                .unwrap_or(self.rest.len());
+            self.rest.split_at(end)
+        };
+
+        if name.len() < 3 {
+            return None;
+        }
+        self.rest = rest;
+        std::str::from_utf8(name).ok()
+    }
+
+    // a number of up to `digits` digits
+    fn number(&mut self, digits: usize) -> Option<i64> {
+        let len = self
+            .rest
+            .iter()
+            .take(digits)
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if len == 0 {
+            return None;
+        }
+
+        let (number, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(number.iter().fold(0, |n, &b| n * 10 + (b - b'0') as i64))
+    }
+
+    // a signed time of `[+-]hh[:mm[:ss]]` in seconds, with up to `max`
+    // hours
+    fn time(&mut self, max: i64) -> Option<i64> {
+        let sign = match self.rest.first() {
+            Some(b'-') => -1,
+            _ => 1,
+        };
+        if let Some(b'+') | Some(b'-') = self.rest.first() {
+            self.rest = &self.rest[1..];
+        }
+
+        let hours = self.number(3)?;
+        let mut secs = hours * 3600;
+        for &unit in &[60, 1] {
+            if self.eat(b':').is_none() {
+                break;
+            }
+            match self.number(2)? {
+                value if value < 60 => secs += value * unit,
+                _ => return None,
+            }
+        }
+
+        if hours > max {
+            return None;
+        }
+        Some(sign * secs)
+    }
+
+    // a day on which the rules change, with the time of day after a `/`
+    fn transition(&mut self) -> Option<(RuleDay, i64)> {
+        let day = if self.eat(b'J').is_some() {
+            match self.number(3)? {
+                day @ 1..=365 => RuleDay::Julian(day),
+                _ => return None,
+            }
+        } else if self.eat(b'M').is_some() {
+            let month = self.number(2)?;
+            self.eat(b'.')?;
+            let week = self.number(1)?;
+            self.eat(b'.')?;
+            let weekday = self.number(1)?;
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return None;
+            }
+            RuleDay::Weekday {
+                month: month as usize,
+                week,
+                weekday,
+            }
+        } else {
+            match self.number(3)? {
+                day @ 0..=365 => RuleDay::Zero(day),
+                _ => return None,
+            }
+        };
+
+        let time = if self.eat(b'/').is_some() {
+            self.time(167)?
+        } else {
+            7200
+        };
+        Some((day, time))
+    }
+}
+
+/// the result of resolving a local date and time in a time zone
+#[derive(Debug)]
+pub enum LocalResult {
+    /// the local time occurs exactly once
+    Single(ZonedDateTime),
+    /// the local time occurs twice because the clocks went back; the
+    /// earlier and later instants are given in that order
+    Ambiguous(ZonedDateTime, ZonedDateTime),
+    /// the local time was skipped because the clocks went forward; the
+    /// instant given is the local time shifted forward by the length of
+    /// the gap (e.g. 02:30 becomes 03:30 when clocks go from 02:00 to 03:00)
+    Gap(ZonedDateTime),
+}
+
+impl LocalResult {
+    /// returns the single instant, the earlier of two ambiguous instants,
+    /// or the shifted instant for a gap
+    pub fn earliest(self) -> ZonedDateTime {
+        match self {
+            LocalResult::Single(date) | LocalResult::Gap(date) => date,
+            LocalResult::Ambiguous(earlier, _) => earlier,
+        }
+    }
+
+    /// returns the single instant, the later of two ambiguous instants,
+    /// or the shifted instant for a gap
+    pub fn latest(self) -> ZonedDateTime {
+        match self {
+            LocalResult::Single(date) | LocalResult::Gap(date) => date,
+            LocalResult::Ambiguous(_, later) => later,
+        }
+    }
+}
+
+/// a DateTime together with the time zone rules in effect at that instant
+///
+/// the calendar fields of the DateTime returned by
+/// [`datetime`](ZonedDateTime::datetime) are the wall-clock fields in the
+/// zone
+#[derive(Debug, Clone)]
+pub struct ZonedDateTime {
+    datetime: DateTime,
+    rules: LocalTimeType,
+}
+
+impl ZonedDateTime {
+    /// returns the DateTime, with its fields reported in the zone's offset
+    pub fn datetime(&self) -> &DateTime {
+        &self.datetime
+    }
+
+    /// returns the rules of the zone in effect at this instant
+    pub fn rules(&self) -> &LocalTimeType {
+        &self.rules
+    }
+
+    /// returns the zone abbreviation in effect, e.g. `EDT`
+    pub fn abbreviation(&self) -> &str {
+        self.rules.abbreviation()
+    }
+
+    /// returns true if daylight-saving time is in effect
+    pub fn is_dst(&self) -> bool {
+        self.rules.is_dst()
+    }
+
+    /// returns the offset from UTC in effect
+    pub fn offset(&self) -> FixedOffset {
+        self.datetime.offset()
+    }
+
+    /// returns the local hour
+    pub fn hour(&self) -> usize {
+        self.datetime.hour()
+    }
+
+    /// returns a String representing the time stamp in the zone, with the
+    /// zone abbreviation in place of the UTC offset
+    pub fn as_time_stamp(&self) -> String {
+        self.as_time_stamp_with(&StampOptions::new())
+    }
+
+    /// returns the time stamp laid out according to `options`, with the
+    /// zone abbreviation in place of the UTC offset
+    pub fn as_time_stamp_with(&self, options: &StampOptions) -> String {
+        self.datetime
+            .time_stamp_in_zone(self.abbreviation(), options)
+    }
+
+    /// formats the local date and time like [`DateTime::format`], with
+    /// `%Z` giving the zone abbreviation
+    pub fn format(&self, fmt: &str) -> String {
+        self.datetime.format_in_zone(fmt, self.abbreviation())
+    }
+}
+
+impl fmt::Display for ZonedDateTime {
+    /// writes the same time stamp as [`ZonedDateTime::as_time_stamp`],
+    /// with a precision adding digits of fractional seconds as for
+    /// DateTime
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = StampOptions::new().fraction_digits(f.precision().unwrap_or(0));
+        self.datetime
+            .write_stamp(f, Some(self.abbreviation()), &options)
+    }
+}
+
+impl DateTime {
+    /// returns the DateTime in the operating system's time zone, as given
+    /// by [`Tz::local`]
+    ///
+    /// the zone is loaded on every call, so to convert many DateTimes,
+    /// load it once and use [`Tz::from_utc`]
+    pub fn to_local(&self) -> Result<ZonedDateTime, TzError> {
+        Ok(Tz::local()?.from_utc(self))
+    }
+
+    /// returns the current time in the operating system's time zone
+    pub fn now_local() -> Result<ZonedDateTime, TzError> {
+        DateTime::now().to_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_in, local_zone, resolve_alias, LocalResult, LocalTimeType, TimeZone, Tz, TzError,
+    };
+    use crate::{DateTime, FixedOffset, MAX_SECS};
+    use std::path::Path;
+
+    // builds a version 2 TZif file with the given transitions and types
+    fn tzif(transitions: &[(i64, u8)], types: &[(i32, bool, &str)]) -> Vec<u8> {
+        let mut chars = Vec::new();
+        let mut ttinfo = Vec::new();
+        for &(offset, is_dst, abbreviation) in types {
+            ttinfo.extend_from_slice(&offset.to_be_bytes());
+            ttinfo.push(is_dst as u8);
+            ttinfo.push(chars.len() as u8);
+            chars.extend_from_slice(abbreviation.as_bytes());
+            chars.push(0);
+        }
+
+        let header = |data: &mut Vec<u8>, timecnt: usize| {
+            data.extend_from_slice(b"TZif2");
+            data.extend_from_slice(&[0; 15]);
+            for count in &[0, 0, 0, timecnt, types.len(), chars.len()] {
+                data.extend_from_slice(&(*count as u32).to_be_bytes());
+            }
+        };
+
+        // an empty version 1 block, as written by `zic -b slim`
+        let mut data = Vec::new();
+        header(&mut data, 0);
+        data.extend_from_slice(&ttinfo);
+        data.extend_from_slice(&chars);
+
+        header(&mut data, transitions.len());
+        for &(at, _) in transitions {
+            data.extend_from_slice(&at.to_be_bytes());
+        }
+        data.extend(transitions.iter().map(|&(_, index)| index));
+        data.extend_from_slice(&ttinfo);
+        data.extend_from_slice(&chars);
+        data.extend_from_slice(b"\nEST5EDT,M3.2.0,M11.1.0\n");
+        data
+    }
+
+    fn new_york() -> Tz {
+        let est = LocalTimeType::new(-5 * 3600, false, "EST");
+        let edt = LocalTimeType::new(-4 * 3600, true, "EDT");
+
+        let mut tz = Tz::new("America/New_York", est.clone());
+        tz.add_transition(1710054000, edt.clone());
+        tz.add_transition(1730613600, est.clone());
+        tz.add_transition(1678604400, edt);
+        tz.add_transition(1699164000, est);
+        tz
+    }
+
+    #[test]
+    fn test_rules_at() {
+        let tz = new_york();
+
+        assert_eq!(tz.rules_at(0).abbreviation(), "EST");
+        assert_eq!(tz.rules_at(1678604400).abbreviation(), "EDT");
+        assert_eq!(tz.rules_at(1699163999).abbreviation(), "EDT");
+        assert_eq!(tz.rules_at(1699164000).abbreviation(), "EST");
+    }
+
+    #[test]
+    fn test_transitions_in() {
+        let tz = new_york();
+        let transitions = tz.transitions_in(1690000000, 1720000000);
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].unix_time(), 1699164000);
+        assert_eq!(transitions[0].before().abbreviation(), "EDT");
+        assert_eq!(transitions[0].delta(), -3600);
+        assert_eq!(transitions[1].unix_time(), 1710054000);
+        assert!(transitions[1].after().is_dst());
+    }
+
+    #[test]
+    fn test_clock_change_notice() {
+        let tz = new_york();
+        let transitions = tz.transitions_in(1690000000, 1720000000);
+
+        assert_eq!(
+            transitions[0].clock_change_notice(),
+            "Clocks go back 1 hour on Sun, Nov 5 at 02:00"
+        );
+        assert_eq!(
+            transitions[1].clock_change_notice(),
+            "Clocks go forward 1 hour on Sun, Mar 10 at 02:00"
+        );
+
+        let mut lord_howe = Tz::new(
+            "Australia/Lord_Howe",
+            LocalTimeType::new(37800, false, "+1030"),
+        );
+        lord_howe.add_transition(1696087800, LocalTimeType::new(39600, true, "+11"));
+        let transition = &lord_howe.transitions_in(0, i64::MAX)[0];
+
+        assert_eq!(
+            transition.clock_change_notice(),
+            "Clocks go forward 30 minutes on Sun, Oct 1 at 02:00"
+        );
+    }
+
+    #[test]
+    fn test_time_zone_trait() {
+        fn zoned<Z: TimeZone>(tz: &Z, secs: i64) -> String {
+            tz.from_utc(&DateTime::from_signed_secs(secs))
+                .as_time_stamp()
+        }
+
+        let tz = new_york();
+        assert_eq!(
+            zoned(&tz, 1690000000),
+            tz.from_utc(&DateTime::from_secs(1690000000))
+                .as_time_stamp()
+        );
+        assert_eq!(
+            TimeZone::transitions_in(&tz, 1690000000, 1720000000),
+            tz.transitions_in(1690000000, 1720000000)
+        );
+
+        let offset = FixedOffset::east(5 * 3600 + 45 * 60).unwrap();
+        assert_eq!(zoned(&offset, 0), "Thu Jan 1, 1970  5:45:00 (+05:45)");
+        assert_eq!(
+            zoned(&FixedOffset::utc(), 0),
+            "Thu Jan 1, 1970  0:00:00 (UTC)"
+        );
+        assert!(offset.transitions_in(i64::MIN, i64::MAX).is_empty());
+        assert!(matches!(
+            offset.from_local(2024, 3, 10, 2, 30, 0),
+            Some(LocalResult::Single(_))
+        ));
+
+        // a zone can be used through a trait object
+        let zones: Vec<Box<dyn TimeZone>> = vec![Box::new(tz), Box::new(offset)];
+        assert_eq!(zones[0].rules_at(1690000000).abbreviation(), "EDT");
+        assert_eq!(zones[1].rules_at(1690000000).offset(), 20700);
+    }
+
+    #[test]
+    fn test_from_posix() {
+        let tz = Tz::from_posix("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!(tz.name(), "EST5EDT,M3.2.0,M11.1.0");
+        assert_eq!(tz.rules_at(1710053999).abbreviation(), "EST");
+        assert_eq!(tz.rules_at(1710054000).abbreviation(), "EDT");
+        assert_eq!(tz.rules_at(1710054000).offset(), -14400);
+        assert_eq!(tz.rules_at(1730613599).abbreviation(), "EDT");
+        assert_eq!(tz.rules_at(1730613600).abbreviation(), "EST");
+
+        let a = DateTime::from_secs(1704067200); // 2024-01-01
+        let b = DateTime::from_secs(1735689600); // 2025-01-01
+        let transitions = tz.transitions_between(&a, &b);
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].unix_time(), 1710054000);
+        assert_eq!(transitions[1].unix_time(), 1730613600);
+        assert_eq!(transitions[1].delta(), -3600);
+
+        // the default rule is the same
+        let tz = Tz::from_posix("EST5EDT").unwrap();
+        assert_eq!(tz.transitions_between(&a, &b), transitions);
+
+        // daylight-saving time across the new year
+        let tz = Tz::from_posix("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(tz.rules_at(1704067200).abbreviation(), "AEDT");
+        assert_eq!(tz.rules_at(1704067200).offset(), 11 * 3600);
+        assert_eq!(tz.rules_at(1720000000).abbreviation(), "AEST");
+        // 2024-04-07 03:00 AEDT and 2024-10-06 02:00 AEST
+        assert_eq!(tz.rules_at(1712419199).abbreviation(), "AEDT");
+        assert_eq!(tz.rules_at(1712419200).abbreviation(), "AEST");
+        assert_eq!(tz.rules_at(1728144000).abbreviation(), "AEDT");
+
+        let tz = Tz::from_posix("<+0530>-5:30").unwrap();
+        assert_eq!(tz.rules_at(0).abbreviation(), "+0530");
+        assert_eq!(tz.rules_at(0).offset(), 19800);
+        assert!(tz.transitions_between(&a, &b).is_empty());
+    }
+
+    #[test]
+    fn test_from_posix_invalid() {
+        for spec in &[
+            "",
+            "EST",
+            "E5",
+            "EST5EDT,M13.1.0,M11.1.0",
+            "EST5EDT,M3.2.0",
+            "EST5EDT,M3.2.0,M11.1.0x",
+            "<+05-5",
+            "EST25",
+            "EST24",
+            "EST-24",
+            "EST24:59:59",
+        ] {
+            assert!(
+                matches!(Tz::from_posix(spec), Err(TzError::InvalidPosix)),
+                "{}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_tzif() {
+        let data = tzif(
+            &[(1678604400, 1), (1699164000, 0)],
+            &[(-18000, false, "EST"), (-14400, true, "EDT")],
+        );
+        let tz = Tz::from_tzif("America/New_York", &data).unwrap();
+
+        assert_eq!(tz.name(), "America/New_York");
+        assert_eq!(tz.rules_at(0).abbreviation(), "EST");
+        assert_eq!(tz.rules_at(1690000000).abbreviation(), "EDT");
+        assert_eq!(tz.rules_at(1690000000).offset(), -14400);
+        assert_eq!(tz.transitions_in(0, 1_700_000_000).len(), 2);
+        // the rule stops at the last DateTime
+        let transitions = tz.transitions_in(MAX_SECS - 10 * 365 * 86_400, i64::MAX);
+        assert_eq!(transitions.len(), 20);
+        assert!(transitions[19].unix_time() <= MAX_SECS);
+        // after the last transition, the footer's rule applies
+        assert_eq!(tz.rules_at(1900000000).abbreviation(), "EDT");
+        assert_eq!(tz.rules_at(1920000000).abbreviation(), "EST");
+        assert_eq!(
+            tz.transitions_in(1699164000, 1730613601)
+                .iter()
+                .map(|t| t.unix_time())
+                .collect::<Vec<_>>(),
+            [1699164000, 1710054000, 1730613600]
+        );
+
+        assert!(matches!(
+            Tz::from_tzif("bad", &data[..30]),
+            Err(TzError::InvalidData(_))
+        ));
+
+        // a version 1 header claiming 2^32 - 1 transitions
+        let mut huge = tzif(&[], &[(0, false, "UTC")]);
+        huge[4] = 0;
+        huge[32..36].copy_from_slice(&[0xff; 4]);
+        assert!(matches!(
+            Tz::from_tzif("huge", &huge[..59]),
+            Err(TzError::InvalidData("unexpected end of file"))
+        ));
+        let min = tzif(&[], &[(i32::MIN, false, "MIN")]);
+        assert!(matches!(
+            Tz::from_tzif("min", &min),
+            Err(TzError::InvalidData("offset out of range"))
+        ));
+        assert!(matches!(
+            Tz::load("../etc/passwd"),
+            Err(TzError::InvalidName)
+        ));
+    }
+
+    #[test]
+    fn test_from_utc() {
+        let tz = new_york();
+        let date = tz.from_utc(&DateTime::from_secs(1690000000));
+
+        assert_eq!(date.as_time_stamp(), "Sat Jul 22, 2023  0:26:40 (EDT)");
+        assert_eq!(date.to_string(), date.as_time_stamp());
+        assert_eq!(date.format("%H:%M %Z %z"), "00:26 EDT -0400");
+        assert!(date.is_dst());
+    }
+
+    #[test]
+    fn test_from_local() {
+        let tz = new_york();
+
+        match tz.from_local(2023, 7, 1, 12, 0, 0).unwrap() {
+            LocalResult::Single(date) => assert_eq!(date.datetime().secs, 1688227200),
+            _ => panic!("expected a single result"),
+        }
+
+        // 2023-03-12 02:30 doesn't exist in New York
+        match tz.from_local(2023, 3, 12, 2, 30, 0).unwrap() {
+            LocalResult::Gap(date) => {
+                assert_eq!(date.hour(), 3);
+                assert_eq!(date.abbreviation(), "EDT");
+            }
+            _ => panic!("expected a gap"),
+        }
+
+        // 2023-11-05 01:30 happens twice in New York
+        match tz.from_local(2023, 11, 5, 1, 30, 0).unwrap() {
+            LocalResult::Ambiguous(earlier, later) => {
+                assert_eq!(earlier.abbreviation(), "EDT");
+                assert_eq!(later.abbreviation(), "EST");
+                assert_eq!(later.datetime().secs - earlier.datetime().secs, 3600);
+            }
+            _ => panic!("expected an ambiguous result"),
+        }
+
+        assert!(tz.from_local(2023, 2, 29, 0, 0, 0).is_none());
+    }
+
+    #[test]
+    fn test_local_zone() {
+        let dir = std::env::temp_dir().join("datetime-test-local-zone/zoneinfo/America");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("New_York");
+        let data = tzif(
+            &[(1710054000, 1)],
+            &[(-5 * 3600, false, "EST"), (-4 * 3600, true, "EDT")],
+        );
+        std::fs::write(&path, data).unwrap();
+
+        let missing = Path::new("/nonexistent/localtime");
+        let tz = local_zone(Some(path.clone().into()), missing).unwrap();
+        assert_eq!(tz.name(), "America/New_York");
+        assert_eq!(tz.rules_at(1710054000).abbreviation(), "EDT");
+
+        let tz = local_zone(Some(format!(":{}", path.display()).into()), missing).unwrap();
+        assert_eq!(tz.name(), "America/New_York");
+        if cfg!(unix) {
+            assert_eq!(local_zone(None, &path).unwrap(), tz);
+            assert!(matches!(local_zone(None, missing), Err(TzError::Io(_))));
+        }
+        assert!(matches!(
+            local_zone(Some("../passwd".into()), missing),
+            Err(TzError::InvalidName)
+        ));
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = std::env::temp_dir().join("datetime-test-find/zoneinfo");
+        let data = tzif(&[], &[(19800, false, "IST")]);
+        for name in &["Asia/Kolkata", "America/New_York", "Etc/UTC"] {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, &data).unwrap();
+        }
+        // a link in the database under a name with no entry in the table
+        #[cfg(unix)]
+        {
+            let link = dir.join("Asia/Old_Kolkata");
+            let _ = std::fs::remove_file(&link);
+            std::os::unix::fs::symlink("Kolkata", &link).unwrap();
+            assert_eq!(
+                find_in(&dir, "asia/old_kolkata").unwrap().name(),
+                "Asia/Kolkata"
+            );
+        }
+
+        assert_eq!(
+            find_in(&dir, "Asia/Calcutta").unwrap().name(),
+            "Asia/Kolkata"
+        );
+        assert_eq!(
+            find_in(&dir, " ASIA/KOLKATA ").unwrap().name(),
+            "Asia/Kolkata"
+        );
+        assert_eq!(
+            find_in(&dir, "america/new york").unwrap().name(),
+            "America/New_York"
+        );
+        assert_eq!(find_in(&dir, "zulu").unwrap().name(), "Etc/UTC");
+        assert!(matches!(find_in(&dir, "Asia/Nowhere"), Err(TzError::Io(_))));
+        assert!(matches!(
+            find_in(&dir, "../etc/passwd"),
+            Err(TzError::InvalidName)
+        ));
+
+        assert_eq!(resolve_alias("us/eastern"), Some("America/New_York"));
+        assert_eq!(resolve_alias("America/New_York"), None);
+    }
+}
@@ -0,0 +1,18 @@
+//! experimental APIs, enabled with the `unstable` feature
+//!
+//! everything outside this module follows semantic versioning. the
+//! subsystems here are new enough that their APIs may still change in
+//! any release, including patch releases, so they are kept apart until
+//! they settle
+//!
+//! each is reached through a versioned module: an incompatible revision
+//! is added next to the old one as `v1`, `v2` and so on, so that code
+//! written against `v0` keeps compiling for at least one release after
+//! that. a subsystem that becomes stable moves to the top level of the
+//! crate
+//! ```
+//! # use datetime::unstable::v0::recurrence::{Frequency, Recurrence};
+//! let rule = Recurrence::new(Frequency::Weekly);
+//! ```
+
+pub mod v0;
@@ -0,0 +1,3 @@
+//! the first version of each experimental API
+pub mod escalation;
+pub mod recurrence;
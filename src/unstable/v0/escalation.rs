@@ -0,0 +1,191 @@
+//! escalation timing for on-call incidents
+//!
+//! an [`EscalationPolicy`] is a list of stages, each paging someone new
+//! after a delay from the stage before (or from the start of the
+//! incident). a delay can run on the clock, for anything that needs an
+//! answer at 3am, or only during the working hours of a [`WorkCalendar`]
+//! ```
+//! # use datetime::business::WorkCalendar;
+//! # use datetime::unstable::v0::escalation::EscalationPolicy;
+//! # use datetime::DateTime;
+//! # use std::time::Duration;
+//! let policy = EscalationPolicy::new(WorkCalendar::new())
+//!     .with_stage(Duration::from_secs(15 * 60))
+//!     .with_working_stage(Duration::from_secs(4 * 3600));
+//!
+//! // raised on Friday 1996-09-13 at 16:30
+//! let start = DateTime::parse_rfc3339("1996-09-13T16:30:00Z").unwrap();
+//! let now = DateTime::parse_rfc3339("1996-09-13T17:00:00Z").unwrap();
+//!
+//! assert_eq!(policy.stage_at(&start, &now), 1);
+//! let next = policy.next_escalation_at(&start, &now).unwrap();
+//! assert_eq!(next.to_rfc3339(), "1996-09-16T12:45:00Z");
+//! ```
+use crate::business::{add_working_duration, WorkCalendar};
+use crate::DateTime;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+// a stage of a policy: how long after the last stage it is reached, and
+// whether only working hours count towards that
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stage {
+    delay: Duration,
+    working_hours: bool,
+}
+
+/// the stages an incident escalates through, and the working hours that
+/// some of them wait for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscalationPolicy {
+    stages: Vec<Stage>,
+    calendar: WorkCalendar,
+}
+
+impl EscalationPolicy {
+    /// returns a policy with no stages, whose working-hours stages follow
+    /// `calendar`
+    pub fn new(calendar: WorkCalendar) -> Self {
+        EscalationPolicy {
+            stages: Vec::new(),
+            calendar,
+        }
+    }
+
+    /// returns the policy with a stage added, reached `delay` after the
+    /// one before it
+    pub fn with_stage(mut self, delay: Duration) -> Self {
+        self.stages.push(Stage {
+            delay,
+            working_hours: false,
+        });
+        self
+    }
+
+    /// returns the policy with a stage added, reached once `delay` of
+    /// working hours has passed since the one before it
+    pub fn with_working_stage(mut self, delay: Duration) -> Self {
+        self.stages.push(Stage {
+            delay,
+            working_hours: true,
+        });
+        self
+    }
+
+    /// returns the number of stages
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// returns true if the policy has no stages
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// returns the time each stage is reached for an incident starting at
+    /// `incident_start`, stopping at the first that is out of range
+    pub fn escalation_times(&self, incident_start: &DateTime) -> Vec<DateTime> {
+        let mut times = Vec::with_capacity(self.stages.len());
+        let mut last = *incident_start;
+
+        for stage in &self.stages {
+            let next = if stage.working_hours {
+                add_working_duration(&last, stage.delay, &self.calendar)
+            } else {
+                last.checked_add_duration(stage.delay)
+            };
+            last = match next {
+                Some(next) => next,
+                None => break,
+            };
+            times.push(last);
+        }
+
+        times
+    }
+
+    /// returns the number of stages an incident starting at
+    /// `incident_start` has reached by `now`
+    pub fn stage_at(&self, incident_start: &DateTime, now: &DateTime) -> usize {
+        self.escalation_times(incident_start)
+            .iter()
+            .take_while(|&time| time <= now)
+            .count()
+    }
+
+    /// returns when an incident starting at `incident_start` next
+    /// escalates after `now`, or None if it has reached every stage
+    pub fn next_escalation_at(
+        &self,
+        incident_start: &DateTime,
+        now: &DateTime,
+    ) -> Option<DateTime> {
+        self.escalation_times(incident_start)
+            .into_iter()
+            .find(|time| time > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EscalationPolicy;
+    use crate::business::WorkCalendar;
+    use crate::{DateTime, MAX_SECS};
+    use core::time::Duration;
+
+    fn date(input: &str) -> DateTime {
+        DateTime::parse_rfc3339(input).unwrap()
+    }
+
+    #[test]
+    fn test_escalation_times() {
+        let policy = EscalationPolicy::new(WorkCalendar::new())
+            .with_stage(Duration::from_secs(600))
+            .with_working_stage(Duration::from_secs(3600))
+            .with_stage(Duration::from_secs(1800));
+        assert_eq!(policy.len(), 3);
+
+        // raised on a Saturday night
+        let start = date("1996-09-14T22:00:00Z");
+        let times: Vec<_> = policy
+            .escalation_times(&start)
+            .iter()
+            .map(|time| time.to_rfc3339())
+            .collect();
+        assert_eq!(
+            times,
+            [
+                "1996-09-14T22:10:00Z",
+                "1996-09-16T10:00:00Z",
+                "1996-09-16T10:30:00Z"
+            ]
+        );
+
+        assert_eq!(policy.stage_at(&start, &start), 0);
+        assert_eq!(policy.stage_at(&start, &date("1996-09-16T10:00:00Z")), 2);
+        assert_eq!(
+            policy.next_escalation_at(&start, &date("1996-09-16T10:00:00Z")),
+            Some(date("1996-09-16T10:30:00Z"))
+        );
+        assert_eq!(
+            policy.next_escalation_at(&start, &date("1996-09-17T00:00:00Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let policy = EscalationPolicy::new(WorkCalendar::new().with_days(&[]))
+            .with_stage(Duration::from_secs(60))
+            .with_working_stage(Duration::from_secs(60))
+            .with_stage(Duration::from_secs(60));
+        let start = date("1996-09-14T22:00:00Z");
+        assert_eq!(policy.escalation_times(&start).len(), 1);
+
+        let late = DateTime::from_signed_secs(MAX_SECS);
+        let policy = EscalationPolicy::new(WorkCalendar::new()).with_stage(Duration::from_secs(1));
+        assert!(policy.escalation_times(&late).is_empty());
+        assert!(policy.next_escalation_at(&late, &late).is_none());
+        assert!(EscalationPolicy::new(WorkCalendar::new()).is_empty());
+    }
+}
@@ -0,0 +1,725 @@
+//! recurring events, following a subset of iCalendar's RRULE
+//!
+//! a [`Recurrence`] describes when an event repeats: its frequency, how
+//! many periods apart occurrences are, and optionally which days of the
+//! week, days of the month and months they fall on. as in
+//! [RFC 5545 §3.3.10](https://www.rfc-editor.org/rfc/rfc5545#section-3.3.10),
+//! anything not given is taken from the start, including the time of day
+//! ```
+//! # use datetime::unstable::v0::recurrence::{Frequency, Recurrence};
+//! # use datetime::Day;
+//! // every second Tuesday of the month, from 1996-09-09 09:00
+//! let rule = Recurrence::new(Frequency::Monthly).on_nth(2, Day::Tuesday);
+//! let start = datetime::DateTime::from_secs(842259600);
+//!
+//! let dates: Vec<_> = rule.occurrences(&start).take(3).map(|date| date.to_rfc3339()).collect();
+//! assert_eq!(
+//!     dates,
+//!     ["1996-09-10T09:00:00Z", "1996-10-08T09:00:00Z", "1996-11-12T09:00:00Z"]
+//! );
+//! ```
+//!
+//! rules can also be read from and written as RRULE values, supporting the
+//! `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`, `BYMONTHDAY` and
+//! `BYMONTH` parts
+//! ```
+//! # use datetime::unstable::v0::recurrence::Recurrence;
+//! let rule: Recurrence = "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH".parse().unwrap();
+//!
+//! assert_eq!(rule.to_string(), "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH");
+//! ```
+use crate::convert::{civil_from_days, days_from_civil, days_in_month};
+use crate::convert::{is_valid_date, is_valid_time, weekday_from_days};
+use crate::{AbsoluteTime, DateTime, Day, FixedOffset, Month, ParseError, DAYS, MONTHS};
+use crate::{MAX_SECS, MIN_SECS};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::str::FromStr;
+
+/// how often a [`Recurrence`] repeats
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// a rule for a recurring event; see the [module docs](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<AbsoluteTime>,
+    // 0 for every such day of the period, n for the nth, or -n for the nth
+    // from the end
+    by_day: Vec<(i32, Day)>,
+    // negative days count from the end of the month
+    by_month_day: Vec<i32>,
+    by_month: Vec<Month>,
+}
+
+impl Recurrence {
+    /// returns a rule repeating every period of the given frequency
+    pub fn new(frequency: Frequency) -> Self {
+        Recurrence {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+        }
+    }
+
+    /// repeats every `interval` periods instead of every one
+    ///
+    /// # Panics
+    ///
+    /// panics if `interval` is zero
+    pub fn interval(mut self, interval: u32) -> Self {
+        assert!(interval > 0, "a recurrence interval must not be zero");
+        self.interval = interval;
+        self
+    }
+
+    /// stops after `count` occurrences
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// stops after `until`, which is included if it is an occurrence
+    pub fn until(mut self, until: &DateTime) -> Self {
+        self.until = Some(AbsoluteTime::from(until));
+        self
+    }
+
+    /// limits occurrences to the given day of the week; may be called more
+    /// than once
+    pub fn on(self, day: Day) -> Self {
+        self.on_nth(0, day)
+    }
+
+    /// limits occurrences to the nth given day of the week in the month
+    /// (or, for yearly rules without [`in_month`](Recurrence::in_month),
+    /// the year); negative numbers count from the end, so -1 is the last
+    ///
+    /// # Panics
+    ///
+    /// panics if `n` is more than 53 either way
+    pub fn on_nth(mut self, n: i32, day: Day) -> Self {
+        assert!((-53..=53).contains(&n), "no year has {} weeks", n);
+        self.by_day.push((n, day));
+        self
+    }
+
+    /// limits occurrences to the given day of the month; negative days
+    /// count from the end, so -1 is the last day
+    ///
+    /// # Panics
+    ///
+    /// panics if `date` is 0 or more than 31 either way
+    pub fn on_date(mut self, date: i32) -> Self {
+        assert!(
+            date != 0 && (-31..=31).contains(&date),
+            "no month has a day {}",
+            date
+        );
+        self.by_month_day.push(date);
+        self
+    }
+
+    /// limits occurrences to the given month
+    pub fn in_month(mut self, month: Month) -> Self {
+        self.by_month.push(month);
+        self
+    }
+
+    /// returns an iterator over the occurrences of the rule at or after
+    /// `start`, in `start`'s offset
+    ///
+    /// a rule which matches no days at all, such as February 30, stops
+    /// after 10,000 periods without an occurrence
+    pub fn occurrences(&self, start: &DateTime) -> Occurrences<'_> {
+        let offset = start.offset.seconds() as i64;
+        let local = start.secs + offset;
+
+        Occurrences {
+            rule: self,
+            nanos: start.nanos,
+            start_day: local.div_euclid(86_400),
+            time_of_day: local.rem_euclid(86_400),
+            offset: start.offset,
+            period: 0,
+            pending: Vec::new(),
+            emitted: 0,
+            done: false,
+        }
+    }
+
+    // the local days of the period `index` periods after the one holding
+    // `start_day` which match the rule, in order, or None once the periods
+    // run past the years a DateTime can represent
+    fn days_in_period(&self, start_day: i64, index: u64) -> Option<Vec<i64>> {
+        let step = i64::try_from(index.checked_mul(u64::from(self.interval))?).ok()?;
+        let (start_year, start_month, start_date) = civil_from_days(start_day);
+
+        let mut days = Vec::new();
+        match self.frequency {
+            Frequency::Daily => {
+                let day = start_day.checked_add(step)?;
+                check_day(day)?;
+                let (year, month, date) = civil_from_days(day);
+                let weekday = weekday_from_days(day);
+
+                if self.month_matches(month)
+                    && self.month_day_matches(date, days_in_month(year as usize, month))
+                    && (self.by_day.is_empty() || self.by_day.iter().any(|&(_, d)| d == weekday))
+                {
+                    days.push(day);
+                }
+            }
+            Frequency::Weekly => {
+                // weeks start on Monday
+                let monday = start_day - (start_day + 3).rem_euclid(7);
+                let monday = monday.checked_add(step.checked_mul(7)?)?;
+                check_day(monday)?;
+
+                for day in monday..monday + 7 {
+                    let (_, month, _) = civil_from_days(day);
+                    let weekday = weekday_from_days(day);
+                    let on_day = if self.by_day.is_empty() {
+                        weekday == weekday_from_days(start_day)
+                    } else {
+                        self.by_day.iter().any(|&(_, d)| d == weekday)
+                    };
+
+                    if on_day && self.month_matches(month) {
+                        days.push(day);
+                    }
+                }
+            }
+            Frequency::Monthly => {
+                let months = (start_year * 12 + start_month as i64 - 1).checked_add(step)?;
+                let (year, month) = (months / 12, (months % 12) as usize + 1);
+                check_day(days_from_civil(year, 1, 1))?;
+
+                if self.month_matches(month) {
+                    self.push_days_in_month(year, month, start_date, &mut days);
+                }
+            }
+            Frequency::Yearly => {
+                let year = start_year.checked_add(step)?;
+                check_day(days_from_civil(year, 1, 1))?;
+
+                if !self.by_day.is_empty() && self.by_month.is_empty() {
+                    // BYDAY counts weeks in the whole year
+                    let first = days_from_civil(year, 1, 1);
+                    let last = days_from_civil(year + 1, 1, 1) - 1;
+                    for day in first..=last {
+                        let (_, month, date) = civil_from_days(day);
+                        if self.weekday_matches(day, first, last)
+                            && self.month_day_matches(date, days_in_month(year as usize, month))
+                        {
+                            days.push(day);
+                        }
+                    }
+                } else {
+                    for month in 1..=12 {
+                        // without BYMONTH, BYMONTHDAY picks days from every
+                        // month, and otherwise only the start's month is used
+                        let wanted = if !self.by_month.is_empty() {
+                            self.month_matches(month)
+                        } else {
+                            !self.by_month_day.is_empty() || month == start_month
+                        };
+                        if wanted {
+                            self.push_days_in_month(year, month, start_date, &mut days);
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(days)
+    }
+
+    // pushes the days of the given month matching the rule's days of the
+    // week and month, or the start's day of the month if it has neither
+    fn push_days_in_month(&self, year: i64, month: usize, start_date: usize, days: &mut Vec<i64>) {
+        let len = days_in_month(year as usize, month);
+        let first = days_from_civil(year, month, 1);
+        let last = first + len as i64 - 1;
+
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            // months without the start's date are skipped
+            if start_date <= len {
+                days.push(first + start_date as i64 - 1);
+            }
+            return;
+        }
+
+        for day in first..=last {
+            let date = (day - first) as usize + 1;
+            if self.weekday_matches(day, first, last) && self.month_day_matches(date, len) {
+                days.push(day);
+            }
+        }
+    }
+
+    fn month_matches(&self, month: usize) -> bool {
+        self.by_month.is_empty() || self.by_month.iter().any(|&m| m as usize + 1 == month)
+    }
+
+    fn month_day_matches(&self, date: usize, len: usize) -> bool {
+        let (date, len) = (date as i32, len as i32);
+        self.by_month_day.is_empty()
+            || self
+                .by_month_day
+                .iter()
+                .any(|&d| d == date || d < 0 && len + 1 + d == date)
+    }
+
+    // whether the day matches the rule's days of the week, counting the nth
+    // one from `first` or back from `last`
+    fn weekday_matches(&self, day: i64, first: i64, last: i64) -> bool {
+        let weekday = weekday_from_days(day);
+        self.by_day.is_empty()
+            || self.by_day.iter().any(|&(n, d)| {
+                d == weekday
+                    && match n {
+                        0 => true,
+                        n if n > 0 => (day - first) / 7 + 1 == n as i64,
+                        n => (last - day) / 7 + 1 == -n as i64,
+                    }
+            })
+    }
+}
+
+// fails once a day is past the last a DateTime can represent
+fn check_day(day: i64) -> Option<()> {
+    if day > MAX_SECS / 86_400 + 1 {
+        None
+    } else {
+        Some(())
+    }
+}
+
+// the most periods in a row without an occurrence before giving up
+const MAX_EMPTY_PERIODS: u64 = 10_000;
+
+/// an iterator over the occurrences of a [`Recurrence`]; see
+/// [`Recurrence::occurrences`]
+#[derive(Debug, Clone)]
+pub struct Occurrences<'a> {
+    rule: &'a Recurrence,
+    nanos: u32,
+    start_day: i64,
+    time_of_day: i64,
+    offset: FixedOffset,
+    // the next period to expand
+    period: u64,
+    // days of the current period not yet returned, latest first
+    pending: Vec<i64>,
+    emitted: usize,
+    done: bool,
+}
+
+impl Occurrences<'_> {
+    // the DateTime on the given local day, or None if it is out of range
+    fn at_day(&self, day: i64) -> Option<DateTime> {
+        let secs = day
+            .checked_mul(86_400)?
+            .checked_add(self.time_of_day - self.offset.seconds() as i64)?;
+        if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+            return None;
+        }
+
+        Some(DateTime::from_parts(secs, self.nanos, self.offset))
+    }
+
+    // refills `pending` from the following periods, returning false if no
+    // more occurrences can be found
+    fn refill(&mut self) -> bool {
+        for _ in 0..MAX_EMPTY_PERIODS {
+            let days = match self.rule.days_in_period(self.start_day, self.period) {
+                Some(days) => days,
+                None => return false,
+            };
+            self.period += 1;
+
+            // days before the start don't count, and every day has the
+            // start's time of day
+            self.pending = days
+                .into_iter()
+                .rev()
+                .filter(|&day| day >= self.start_day)
+                .collect();
+            if !self.pending.is_empty() {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        if self.done || self.rule.count.is_some_and(|count| self.emitted >= count) {
+            self.done = true;
+            return None;
+        }
+
+        if self.pending.is_empty() && !self.refill() {
+            self.done = true;
+            return None;
+        }
+
+        let day = self.pending.pop().expect("refilled above");
+        match self.at_day(day) {
+            Some(date)
+                if self
+                    .rule
+                    .until
+                    .is_none_or(|until| AbsoluteTime::from(&date) <= until) =>
+            {
+                self.emitted += 1;
+                Some(date)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl FusedIterator for Occurrences<'_> {}
+
+// the RRULE codes for each day, in the order of the Day enum
+const DAY_CODES: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+impl FromStr for Recurrence {
+    type Err = ParseError;
+
+    /// parses an RRULE value such as `FREQ=MONTHLY;BYDAY=-1FR`, with or
+    /// without the `RRULE:` prefix
+    ///
+    /// `UNTIL` must be a date or a date and time in UTC, and is read as
+    /// the end of the day for a date
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let prefix = if input.starts_with("RRULE:") { 6 } else { 0 };
+        let mut frequency = None;
+        let mut rule = Recurrence::new(Frequency::Daily);
+
+        let mut pos = prefix;
+        for part in input[prefix..].split(';') {
+            let (name, value) = match part.find('=') {
+                Some(i) => (&part[..i], &part[i + 1..]),
+                None => return Err(ParseError::Invalid(pos)),
+            };
+            let value_pos = pos + name.len() + 1;
+
+            match name {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(ParseError::Invalid(value_pos)),
+                    })
+                }
+                "INTERVAL" => match number(value, value_pos)? {
+                    0 => return Err(ParseError::OutOfRange),
+                    n => rule.interval = u32::try_from(n).map_err(|_| ParseError::OutOfRange)?,
+                },
+                "COUNT" => {
+                    let count = number(value, value_pos)?;
+                    rule.count = Some(usize::try_from(count).map_err(|_| ParseError::OutOfRange)?);
+                }
+                "UNTIL" => rule.until = Some(until(value, value_pos)?),
+                "BYDAY" => {
+                    for (item, item_pos) in items(value, value_pos) {
+                        let split = item.len().saturating_sub(2);
+                        let (n, code) = match (item.get(..split), item.get(split..)) {
+                            (Some(n), Some(code)) => (n, code),
+                            _ => return Err(ParseError::Invalid(item_pos)),
+                        };
+                        let code = DAY_CODES
+                            .iter()
+                            .position(|&c| c == code)
+                            .ok_or(ParseError::Invalid(item_pos + split))?;
+                        let n = match n {
+                            "" => 0,
+                            n => signed(n, item_pos, 53)?,
+                        };
+                        rule.by_day.push((n, DAYS[code]));
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for (item, item_pos) in items(value, value_pos) {
+                        rule.by_month_day.push(signed(item, item_pos, 31)?);
+                    }
+                }
+                "BYMONTH" => {
+                    for (item, item_pos) in items(value, value_pos) {
+                        match number(item, item_pos)? {
+                            month @ 1..=12 => rule.by_month.push(MONTHS[month as usize - 1]),
+                            _ => return Err(ParseError::OutOfRange),
+                        }
+                    }
+                }
+                _ => return Err(ParseError::Invalid(pos)),
+            }
+
+            pos += part.len() + 1;
+        }
+
+        rule.frequency = frequency.ok_or(ParseError::Invalid(prefix))?;
+        Ok(rule)
+    }
+}
+
+// the comma-separated items of a value, with their byte offsets
+fn items(value: &str, pos: usize) -> impl Iterator<Item = (&str, usize)> {
+    value.split(',').scan(pos, |pos, item| {
+        let start = *pos;
+        *pos += item.len() + 1;
+        Some((item, start))
+    })
+}
+
+// an unsigned decimal number
+fn number(value: &str, pos: usize) -> Result<u64, ParseError> {
+    match value.bytes().position(|b| !b.is_ascii_digit()) {
+        Some(i) => Err(ParseError::Invalid(pos + i)),
+        None if value.is_empty() => Err(ParseError::TooShort),
+        None => value.parse().map_err(|_| ParseError::OutOfRange),
+    }
+}
+
+// a non-zero number with an optional sign, at most `max` either way
+fn signed(value: &str, pos: usize, max: u64) -> Result<i32, ParseError> {
+    let (negative, digits) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+
+    match number(digits, pos + value.len() - digits.len())? {
+        n if n == 0 || n > max => Err(ParseError::OutOfRange),
+        n if negative => Ok(-(n as i32)),
+        n => Ok(n as i32),
+    }
+}
+
+// an UNTIL value, `yyyymmdd` or `yyyymmddThhmmssZ`
+fn until(value: &str, pos: usize) -> Result<AbsoluteTime, ParseError> {
+    let field = |from: usize, to: usize| match value.get(from..to) {
+        Some(digits) => number(digits, pos + from).map(|n| n as usize),
+        None => Err(ParseError::TooShort),
+    };
+
+    let (year, month, date) = (field(0, 4)?, field(4, 6)?, field(6, 8)?);
+    is_valid_date(year, month, date).map_err(|_| ParseError::OutOfRange)?;
+    let day_secs = days_from_civil(year as i64, month, date) * 86_400;
+
+    let (secs, nanos) = match value.len() {
+        8 => (day_secs + 86_399, 999_999_999),
+        16 if value.as_bytes()[8] == b'T' && value.as_bytes()[15] == b'Z' => {
+            let (hour, minute, second) = (field(9, 11)?, field(11, 13)?, field(13, 15)?);
+            is_valid_time(hour, minute, second).map_err(|_| ParseError::OutOfRange)?;
+            (day_secs + (hour * 3600 + minute * 60 + second) as i64, 0)
+        }
+        len if len < 16 => return Err(ParseError::TooShort),
+        _ => return Err(ParseError::TooLong),
+    };
+
+    AbsoluteTime::new(secs, nanos).ok_or(ParseError::OutOfRange)
+}
+
+impl fmt::Display for Recurrence {
+    /// writes the rule as an RRULE value, without the `RRULE:` prefix
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            let date = until.to_datetime(FixedOffset::utc());
+            write!(
+                f,
+                ";UNTIL={:04}{:02}{:02}T{:02}{:02}{:02}Z",
+                date.year(),
+                date.month() as usize + 1,
+                date.date(),
+                date.hour(),
+                date.minute(),
+                date.second()
+            )?;
+        }
+
+        // writes a part with a list of numbers, each followed by a day code
+        let mut list = |name: &str, items: &mut dyn Iterator<Item = (i32, &str)>| -> fmt::Result {
+            for (i, (n, code)) in items.enumerate() {
+                match i {
+                    0 => write!(f, ";{}=", name)?,
+                    _ => f.write_str(",")?,
+                }
+                if n != 0 {
+                    write!(f, "{}", n)?;
+                }
+                f.write_str(code)?;
+            }
+            Ok(())
+        };
+        list(
+            "BYMONTH",
+            &mut self.by_month.iter().map(|&m| (m as i32 + 1, "")),
+        )?;
+        list(
+            "BYMONTHDAY",
+            &mut self.by_month_day.iter().map(|&d| (d, "")),
+        )?;
+        list(
+            "BYDAY",
+            &mut self.by_day.iter().map(|&(n, d)| (n, DAY_CODES[d as usize])),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Frequency, Recurrence};
+    use crate::{DateTime, Day, FixedOffset, Month, ParseError};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    fn dates(rule: &Recurrence, start: &DateTime, n: usize) -> Vec<String> {
+        rule.occurrences(start)
+            .take(n)
+            .map(|date| date.to_rfc3339())
+            .collect()
+    }
+
+    #[test]
+    fn test_weekly_and_daily() {
+        // Monday 1996-09-09 15:23:44
+        let start = DateTime::from_secs(842282624);
+
+        let rule = Recurrence::new(Frequency::Weekly)
+            .interval(2)
+            .on(Day::Monday)
+            .on(Day::Friday);
+        assert_eq!(
+            dates(&rule, &start, 3),
+            [
+                "1996-09-09T15:23:44Z",
+                "1996-09-13T15:23:44Z",
+                "1996-09-23T15:23:44Z"
+            ]
+        );
+
+        let rule = Recurrence::new(Frequency::Daily).on(Day::Saturday).count(2);
+        assert_eq!(
+            dates(&rule, &start, 5),
+            ["1996-09-14T15:23:44Z", "1996-09-21T15:23:44Z"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_and_yearly() {
+        // 2000-01-31 09:00 at +01:00
+        let offset = FixedOffset::east(3600).unwrap();
+        let start = DateTime::from_secs(949305600).with_offset(offset);
+
+        // months without a 31st are skipped
+        let rule = Recurrence::new(Frequency::Monthly);
+        assert_eq!(
+            dates(&rule, &start, 3),
+            [
+                "2000-01-31T09:00:00+01:00",
+                "2000-03-31T09:00:00+01:00",
+                "2000-05-31T09:00:00+01:00"
+            ]
+        );
+
+        let rule = Recurrence::new(Frequency::Monthly).on_date(-1);
+        assert_eq!(dates(&rule, &start, 2)[1], "2000-02-29T09:00:00+01:00");
+
+        let rule = Recurrence::new(Frequency::Yearly)
+            .in_month(Month::November)
+            .on_nth(4, Day::Thursday)
+            .until(&DateTime::from_secs(10_000_000_000));
+        let thanksgivings = dates(&rule, &start, 1000);
+        assert_eq!(thanksgivings[0], "2000-11-23T09:00:00+01:00");
+        assert_eq!(thanksgivings.len(), 286);
+
+        // the last Friday of each year
+        let rule = Recurrence::new(Frequency::Yearly).on_nth(-1, Day::Friday);
+        assert_eq!(dates(&rule, &start, 1), ["2000-12-29T09:00:00+01:00"]);
+
+        let never = Recurrence::new(Frequency::Yearly)
+            .in_month(Month::February)
+            .on_date(30);
+        assert_eq!(never.occurrences(&start).count(), 0);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let rule: Recurrence = "RRULE:FREQ=MONTHLY;INTERVAL=2;COUNT=10;BYDAY=1SU,-1SU"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rule,
+            Recurrence::new(Frequency::Monthly)
+                .interval(2)
+                .count(10)
+                .on_nth(1, Day::Sunday)
+                .on_nth(-1, Day::Sunday)
+        );
+        assert_eq!(
+            rule.to_string(),
+            "FREQ=MONTHLY;INTERVAL=2;COUNT=10;BYDAY=1SU,-1SU"
+        );
+
+        let rule: Recurrence = "FREQ=DAILY;UNTIL=19971224T000000Z;BYMONTHDAY=1,-1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rule.to_string(),
+            "FREQ=DAILY;UNTIL=19971224T000000Z;BYMONTHDAY=1,-1"
+        );
+        let rule: Recurrence = "FREQ=DAILY;UNTIL=19971224".parse().unwrap();
+        assert!(rule.to_string().ends_with("UNTIL=19971224T235959Z"));
+
+        let parse = |input: &str| input.parse::<Recurrence>();
+        assert_eq!(parse("INTERVAL=2"), Err(ParseError::Invalid(0)));
+        assert_eq!(parse("FREQ=HOURLY"), Err(ParseError::Invalid(5)));
+        assert_eq!(
+            parse("FREQ=DAILY;BYDAY=MO,XX"),
+            Err(ParseError::Invalid(20))
+        );
+        assert_eq!(parse("FREQ=DAILY;BYMONTH=13"), Err(ParseError::OutOfRange));
+        assert_eq!(parse("FREQ=DAILY;WKST=MO"), Err(ParseError::Invalid(11)));
+    }
+}
@@ -0,0 +1,133 @@
+//! moving between days of the week
+//!
+//! [`DateTime::next_weekday`] and [`DateTime::prev_weekday`] step to the
+//! nearest given day of the week, keeping the time of day, and
+//! [`DateTime::nth_weekday_of_month`] finds dates like the third Thursday
+//! of November that holidays and meetings are often set on
+//! ```
+//! # use datetime::{DateTime, Day, Month};
+//! // Monday 1996-09-09 15:23:44
+//! let date = DateTime::from_secs(842282624);
+//!
+//! assert_eq!(date.next_weekday(Day::Friday).unwrap().date(), 13);
+//! assert_eq!(date.prev_weekday(Day::Monday).unwrap().date(), 2);
+//!
+//! // Thanksgiving, the fourth Thursday of November
+//! let thanksgiving = DateTime::nth_weekday_of_month(1996, Month::November, Day::Thursday, 4);
+//! assert_eq!(thanksgiving.unwrap().date(), 28);
+//! ```
+use crate::convert::{days_from_civil, days_in_month, weekday_from_days};
+use crate::{DateTime, Day, Month, MAX_SECS, MIN_SECS};
+use core::convert::TryFrom;
+
+impl DateTime {
+    /// returns the DateTime on the next given day of the week after this
+    /// one, at the same time of day, or None if that is out of range
+    ///
+    /// this is always one to seven days later, so a Monday's next Monday is
+    /// a week away
+    pub fn next_weekday(&self, day: Day) -> Option<DateTime> {
+        let days = (day as i64 - self.day() as i64 - 1).rem_euclid(7) + 1;
+        self.checked_add_secs(days * 86_400)
+    }
+
+    /// returns the DateTime on the previous given day of the week before
+    /// this one, at the same time of day, or None if that is out of range
+    ///
+    /// this is always one to seven days earlier, so a Monday's previous
+    /// Monday is a week ago
+    pub fn prev_weekday(&self, day: Day) -> Option<DateTime> {
+        let days = (self.day() as i64 - day as i64 - 1).rem_euclid(7) + 1;
+        self.checked_sub_secs(days * 86_400)
+    }
+
+    /// returns the start, in UTC, of the nth given day of the week in a
+    /// month, counting from 1, or from the end of the month if n is
+    /// negative (so -1 is the last), or None if the month has no such day
+    /// or the year is 0
+    /// ```
+    /// # use datetime::{DateTime, Day, Month};
+    /// // Memorial Day, the last Monday of May
+    /// let date = DateTime::nth_weekday_of_month(2024, Month::May, Day::Monday, -1).unwrap();
+    /// assert_eq!(date.to_rfc3339(), "2024-05-27T00:00:00Z");
+    ///
+    /// // February 2024 had only four Fridays
+    /// assert!(DateTime::nth_weekday_of_month(2024, Month::February, Day::Friday, 5).is_none());
+    /// ```
+    pub fn nth_weekday_of_month(year: usize, month: Month, day: Day, n: i32) -> Option<DateTime> {
+        // well past the last year a DateTime can reach, but small enough
+        // for the arithmetic below
+        if year > 300_000_000_000 {
+            return None;
+        }
+
+        let month = month as usize + 1;
+        let first = days_from_civil(i64::try_from(year).ok()?, month, 1);
+        let length = days_in_month(year, month) as i64;
+
+        let date = if n > 0 {
+            let first_match = (day as i64 - weekday_from_days(first) as i64).rem_euclid(7);
+            first_match + (n as i64 - 1) * 7
+        } else if n < 0 {
+            let last = first + length - 1;
+            let last_match =
+                length - 1 - (weekday_from_days(last) as i64 - day as i64).rem_euclid(7);
+            last_match + (n as i64 + 1) * 7
+        } else {
+            return None;
+        };
+
+        let secs = (first + date) * 86_400;
+        if (0..length).contains(&date) && year >= 1 && (MIN_SECS..=MAX_SECS).contains(&secs) {
+            Some(DateTime::from_signed_secs(secs))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DateTime, Day, FixedOffset, Month, MAX_SECS};
+
+    #[test]
+    fn test_next_and_prev_weekday() {
+        // Sunday 1996-09-08 23:00 at -05:00, which is Monday in UTC
+        let offset = FixedOffset::west(5 * 3600).unwrap();
+        let date = DateTime::from_secs(842241600).with_offset(offset);
+        assert_eq!(date.day(), Day::Sunday);
+
+        let monday = date.next_weekday(Day::Monday).unwrap();
+        assert_eq!((monday.date(), monday.hour()), (9, 23));
+        assert_eq!(date.next_weekday(Day::Sunday).unwrap().date(), 15);
+        assert_eq!(date.prev_weekday(Day::Sunday).unwrap().date(), 1);
+        assert_eq!(date.prev_weekday(Day::Saturday).unwrap().date(), 7);
+
+        let last = DateTime::from_signed_secs(MAX_SECS);
+        assert!(last.next_weekday(Day::Monday).is_none());
+        assert!(DateTime::from_signed_secs(-62_135_596_800)
+            .prev_weekday(Day::Monday)
+            .is_none());
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month() {
+        let nth = |year, month, day, n| {
+            DateTime::nth_weekday_of_month(year, month, day, n).map(|date| date.date())
+        };
+
+        // September 2024 started on a Sunday and ended on a Monday
+        assert_eq!(nth(2024, Month::September, Day::Sunday, 1), Some(1));
+        assert_eq!(nth(2024, Month::September, Day::Monday, 1), Some(2));
+        assert_eq!(nth(2024, Month::September, Day::Sunday, 5), Some(29));
+        assert_eq!(nth(2024, Month::September, Day::Monday, 5), Some(30));
+        assert_eq!(nth(2024, Month::September, Day::Tuesday, 5), None);
+        assert_eq!(nth(2024, Month::September, Day::Monday, -1), Some(30));
+        assert_eq!(nth(2024, Month::September, Day::Sunday, -1), Some(29));
+        assert_eq!(nth(2024, Month::September, Day::Sunday, -5), Some(1));
+        assert_eq!(nth(2024, Month::September, Day::Saturday, -5), None);
+        assert_eq!(nth(2024, Month::September, Day::Sunday, 0), None);
+        assert_eq!(nth(0, Month::January, Day::Sunday, 1), None);
+        assert_eq!(nth(usize::MAX, Month::January, Day::Sunday, 1), None);
+    }
+}
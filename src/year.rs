@@ -0,0 +1,205 @@
+//! whole years of the calendar
+//!
+//! a [`Year`] answers the questions reports ask of a year: whether it is
+//! a leap year, how many ISO 8601 weeks it has, which DateTimes fall in
+//! it, and what its months and days are
+//! ```
+//! # use datetime::{DateTime, Month, Year};
+//! let year = Year(1996);
+//!
+//! assert!(year.is_leap());
+//! assert_eq!(year.num_weeks(), 52);
+//! assert_eq!(year.days().count(), 366);
+//! assert_eq!(year.months().last(), Some(Month::December));
+//! assert!(year.contains(&DateTime::from_secs(842282624)));
+//! ```
+use crate::convert::{days_in_month, is_leap_year};
+use crate::{iso_weeks_in_year, Date, DateTime, Month, RangeError};
+use core::convert::TryFrom;
+use core::fmt;
+
+/// a year of the proleptic Gregorian calendar, which can be before the
+/// year 1
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(pub i32);
+
+impl Year {
+    // a year from 1 to 400 that the calendar repeats this one in, for the
+    // functions that take a positive year
+    fn in_cycle(self) -> usize {
+        match self.0.rem_euclid(400) {
+            0 => 400,
+            year => year as usize,
+        }
+    }
+
+    /// returns true if the year is a leap year
+    /// ```
+    /// # use datetime::Year;
+    /// assert!(Year(2000).is_leap());
+    /// assert!(!Year(1900).is_leap());
+    /// assert!(Year(-4).is_leap());
+    /// ```
+    pub fn is_leap(&self) -> bool {
+        is_leap_year(self.in_cycle())
+    }
+
+    /// returns the number of days in the year, 365 or 366
+    pub fn num_days(&self) -> usize {
+        if self.is_leap() {
+            366
+        } else {
+            365
+        }
+    }
+
+    /// returns the number of ISO 8601 weeks in the year, 52 or 53
+    /// ```
+    /// # use datetime::Year;
+    /// assert_eq!(Year(2020).num_weeks(), 53);
+    /// assert_eq!(Year(2021).num_weeks(), 52);
+    /// ```
+    pub fn num_weeks(&self) -> usize {
+        iso_weeks_in_year(self.in_cycle())
+    }
+
+    /// returns an iterator over the months of the year, from January to
+    /// December
+    pub fn months(&self) -> impl DoubleEndedIterator<Item = Month> + ExactSizeIterator {
+        Month::iter()
+    }
+
+    /// returns an iterator over the days of the year, from 1 January to 31
+    /// December; a Date can't be before the year 1, so this is empty for
+    /// earlier years
+    /// ```
+    /// # use datetime::{Date, Year};
+    /// let leap_day = Year(2024).days().nth(59).unwrap();
+    /// assert_eq!(leap_day, Date::new(2024, 2, 29).unwrap());
+    /// assert_eq!(Year(0).days().count(), 0);
+    /// ```
+    pub fn days(&self) -> impl Iterator<Item = Date> {
+        let year = usize::try_from(self.0).ok().filter(|&year| year > 0);
+        year.into_iter().flat_map(|year| {
+            (1..=12).flat_map(move |month| {
+                (1..=days_in_month(year, month))
+                    .map(move |date| Date::new(year, month, date).expect("a day of the year"))
+            })
+        })
+    }
+
+    /// returns true if `date` is in the year, in its own offset
+    /// ```
+    /// # use datetime::{DateTime, FixedOffset, Year};
+    /// let date = DateTime::parse_rfc3339("1996-12-31T23:30:00Z").unwrap();
+    ///
+    /// assert!(Year(1996).contains(&date));
+    /// assert!(Year(1997).contains(&date.with_offset(FixedOffset::east(3600).unwrap())));
+    /// ```
+    pub fn contains(&self, date: &DateTime) -> bool {
+        date.year() as i64 == self.0 as i64
+    }
+
+    /// returns the next year, or None after `Year(i32::MAX)`
+    pub fn succ(&self) -> Option<Year> {
+        self.0.checked_add(1).map(Year)
+    }
+
+    /// returns the previous year, or None before `Year(i32::MIN)`
+    pub fn pred(&self) -> Option<Year> {
+        self.0.checked_sub(1).map(Year)
+    }
+}
+
+impl TryFrom<&DateTime> for Year {
+    type Error = RangeError;
+
+    /// returns the year of the DateTime, in its own offset, or an error if
+    /// it is too large for an i32, as the latest DateTimes are
+    fn try_from(date: &DateTime) -> Result<Self, Self::Error> {
+        i32::try_from(date.year()).map(Year).map_err(|_| RangeError)
+    }
+}
+
+impl fmt::Display for Year {
+    /// writes the year as a number, e.g. `1996`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Year;
+    use crate::{Date, DateTime, Month, RangeError};
+    use alloc::string::ToString;
+    use core::convert::TryFrom;
+
+    #[test]
+    fn test_calendar() {
+        for &(year, leap, weeks) in &[
+            (1996, true, 52),
+            (1998, false, 53),
+            (2000, true, 52),
+            (2004, true, 53),
+            (2100, false, 52),
+            (2015, false, 53),
+            (1, false, 52),
+            (0, true, 52),
+            (-1, false, 52),
+            (-400, true, 52),
+        ] {
+            let year = Year(year);
+            assert_eq!(year.is_leap(), leap, "{}", year);
+            assert_eq!(year.num_weeks(), weeks, "{}", year);
+            assert_eq!(year.num_days(), if leap { 366 } else { 365 });
+        }
+    }
+
+    #[test]
+    fn test_cycle_boundaries() {
+        for &(year, in_cycle) in &[
+            (399, 399),
+            (400, 400),
+            (401, 1),
+            (0, 400),
+            (-1, 399),
+            (-400, 400),
+            (i32::MIN, 352),
+        ] {
+            assert_eq!(Year(year).in_cycle(), in_cycle, "{}", year);
+        }
+        assert_eq!(Year(400).num_weeks(), Year(2000).num_weeks());
+        assert_eq!(Year(401).num_weeks(), Year(1).num_weeks());
+    }
+
+    #[test]
+    fn test_iteration() {
+        let year = Year(1900);
+        assert_eq!(year.months().len(), 12);
+        assert_eq!(year.months().next(), Some(Month::January));
+
+        let mut days = year.days();
+        assert_eq!(days.next(), Date::new(1900, 1, 1).ok());
+        assert_eq!(days.nth(58), Date::new(1900, 3, 1).ok());
+        assert_eq!(days.last(), Date::new(1900, 12, 31).ok());
+        assert_eq!(year.days().count(), 365);
+        assert_eq!(Year(-5).days().count(), 0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let date = DateTime::parse_rfc3339("1996-09-09T15:23:44Z").unwrap();
+        assert!(Year(1996).contains(&date));
+        assert!(!Year(1995).contains(&date));
+        assert_eq!(Year::try_from(&date), Ok(Year(1996)));
+        assert_eq!(Year::try_from(&DateTime::MAX), Err(RangeError));
+        assert!(!Year(-1).contains(&DateTime::MIN));
+
+        assert_eq!(Year(1996).succ().unwrap().to_string(), "1997");
+        assert_eq!(Year(0).pred(), Some(Year(-1)));
+        assert_eq!(Year(i32::MAX).succ(), None);
+        assert_eq!(Year(i32::MIN).pred(), None);
+        assert!(Year(-1) < Year(1));
+    }
+}